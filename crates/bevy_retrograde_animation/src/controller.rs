@@ -0,0 +1,347 @@
+use bevy::utils::{HashMap, HashSet};
+
+/// An animation made of frames of a sprite sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteAnimation {
+    /// The tile indexes in the sprite sheet of each frame of the animation
+    pub frames: Vec<u32>,
+    /// The number of frames to play per second
+    pub fps: f32,
+    /// Whether or not to start over after the last frame, instead of stopping on it
+    pub looping: bool,
+}
+
+impl SpriteAnimation {
+    /// Create an animation that starts over after its last frame
+    pub fn looping<F: IntoIterator<Item = u32>>(frames: F, fps: f32) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            fps,
+            looping: true,
+        }
+    }
+
+    /// Create an animation that plays once and stops on its last frame
+    pub fn once<F: IntoIterator<Item = u32>>(frames: F, fps: f32) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            fps,
+            looping: false,
+        }
+    }
+}
+
+/// A condition that switches an [`AnimationController`] to another state
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationCondition {
+    /// The trigger with the given name has been [set][AnimationController::trigger] since the
+    /// last update of the controller
+    Trigger(String),
+    /// The bool parameter with the given name has the given value
+    Bool(String, bool),
+    /// The float parameter with the given name is greater than the given value
+    Greater(String, f32),
+    /// The float parameter with the given name is less than the given value
+    Less(String, f32),
+    /// The animation of the current state isn't looping and has played its last frame
+    Finished,
+    /// All of the conditions are true
+    All(Vec<AnimationCondition>),
+}
+
+impl AnimationCondition {
+    /// Create a [`Trigger`][AnimationCondition::Trigger] condition
+    pub fn trigger<N: Into<String>>(name: N) -> Self {
+        AnimationCondition::Trigger(name.into())
+    }
+
+    /// Create a [`Bool`][AnimationCondition::Bool] condition
+    pub fn bool<N: Into<String>>(name: N, value: bool) -> Self {
+        AnimationCondition::Bool(name.into(), value)
+    }
+
+    /// Create a [`Greater`][AnimationCondition::Greater] condition
+    pub fn greater<N: Into<String>>(name: N, value: f32) -> Self {
+        AnimationCondition::Greater(name.into(), value)
+    }
+
+    /// Create a [`Less`][AnimationCondition::Less] condition
+    pub fn less<N: Into<String>>(name: N, value: f32) -> Self {
+        AnimationCondition::Less(name.into(), value)
+    }
+
+    /// Get whether or not the condition is true for the controller
+    fn is_met(&self, controller: &AnimationController) -> bool {
+        match self {
+            AnimationCondition::Trigger(name) => controller.triggers.contains(name),
+            AnimationCondition::Bool(name, value) => controller.get_bool(name) == *value,
+            AnimationCondition::Greater(name, value) => controller.get_float(name) > *value,
+            AnimationCondition::Less(name, value) => controller.get_float(name) < *value,
+            AnimationCondition::Finished => controller.finished,
+            AnimationCondition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.is_met(controller)),
+        }
+    }
+}
+
+/// A transition of an [`AnimationController`] from one state to another
+#[derive(Debug, Clone, PartialEq)]
+struct AnimationTransition {
+    /// The state that the transition is from, or [`None`] if it can be taken from any state
+    from: Option<String>,
+    to: String,
+    condition: AnimationCondition,
+}
+
+/// A state machine that plays [`SpriteAnimation`]s on the sprite sheet of an entity
+///
+/// Each state has a name and an animation, and the controller switches between states when the
+/// conditions of its transitions are met. Gameplay systems drive the transitions by setting the
+/// bool and float parameters and the triggers of the controller, or they can switch states
+/// directly with [`play`][AnimationController::play].
+///
+/// The controller sets the tile index of the entity's
+/// [`SpriteSheet`][bevy_retrograde_core::prelude::SpriteSheet], so each animated entity needs a
+/// sprite sheet asset of its own.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_animation::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// fn spawn_player(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     mut sprite_sheet_assets: ResMut<Assets<SpriteSheet>>,
+/// ) {
+///     commands
+///         .spawn_bundle(SpriteSheetBundle {
+///             sprite_bundle: SpriteBundle {
+///                 image: asset_server.load("player.png"),
+///                 ..Default::default()
+///             },
+///             sprite_sheet: sprite_sheet_assets.add(SpriteSheet {
+///                 grid_size: UVec2::splat(16),
+///                 tile_index: 0,
+///             }),
+///         })
+///         .insert(
+///             AnimationController::new("idle")
+///                 .with_state("idle", SpriteAnimation::looping(0..4, 6.))
+///                 .with_state("run", SpriteAnimation::looping(4..10, 12.))
+///                 .with_state("attack", SpriteAnimation::once(10..14, 12.))
+///                 .with_transition("idle", "run", AnimationCondition::bool("running", true))
+///                 .with_transition("run", "idle", AnimationCondition::bool("running", false))
+///                 .with_any_transition("attack", AnimationCondition::trigger("attack"))
+///                 .with_transition("attack", "idle", AnimationCondition::Finished),
+///         );
+/// }
+///
+/// fn control_player(
+///     keyboard: Res<Input<KeyCode>>,
+///     mut players: Query<&mut AnimationController>,
+/// ) {
+///     for mut controller in players.iter_mut() {
+///         controller.set_bool("running", keyboard.pressed(KeyCode::Right));
+///         if keyboard.just_pressed(KeyCode::Space) {
+///             controller.trigger("attack");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnimationController {
+    states: HashMap<String, SpriteAnimation>,
+    transitions: Vec<AnimationTransition>,
+    current: String,
+    /// The index of the current frame in the animation of the current state
+    frame: usize,
+    /// The time in seconds since the current frame started
+    elapsed: f32,
+    finished: bool,
+    bools: HashMap<String, bool>,
+    floats: HashMap<String, f32>,
+    triggers: HashSet<String>,
+    /// Whether or not the animation is paused
+    ///
+    /// Transitions are still taken while the animation is paused.
+    pub paused: bool,
+    /// The speed that the animations are played at, where `1.0` is the normal speed
+    pub speed: f32,
+}
+
+impl AnimationController {
+    /// Create a controller that starts in the given state
+    pub fn new<N: Into<String>>(initial_state: N) -> Self {
+        Self {
+            states: Default::default(),
+            transitions: Default::default(),
+            current: initial_state.into(),
+            frame: 0,
+            elapsed: 0.,
+            finished: false,
+            bools: Default::default(),
+            floats: Default::default(),
+            triggers: Default::default(),
+            paused: false,
+            speed: 1.,
+        }
+    }
+
+    /// Add a state with the animation that is played while it is the current state
+    pub fn with_state<N: Into<String>>(mut self, name: N, animation: SpriteAnimation) -> Self {
+        self.states.insert(name.into(), animation);
+        self
+    }
+
+    /// Add a transition from one state to another that is taken when its condition is met
+    ///
+    /// Transitions are checked in the order that they are added, and only the first one with a
+    /// met condition is taken.
+    pub fn with_transition<F: Into<String>, T: Into<String>>(
+        mut self,
+        from: F,
+        to: T,
+        condition: AnimationCondition,
+    ) -> Self {
+        self.transitions.push(AnimationTransition {
+            from: Some(from.into()),
+            to: to.into(),
+            condition,
+        });
+        self
+    }
+
+    /// Add a transition from any state other than `to` that is taken when its condition is met
+    pub fn with_any_transition<T: Into<String>>(
+        mut self,
+        to: T,
+        condition: AnimationCondition,
+    ) -> Self {
+        self.transitions.push(AnimationTransition {
+            from: None,
+            to: to.into(),
+            condition,
+        });
+        self
+    }
+
+    /// Get the name of the current state
+    pub fn current_state(&self) -> &str {
+        &self.current
+    }
+
+    /// Get the index of the current frame in the animation of the current state
+    pub fn current_frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Get the animation of the current state, if the state has been added
+    pub fn current_animation(&self) -> Option<&SpriteAnimation> {
+        self.states.get(&self.current)
+    }
+
+    /// Get whether or not the animation of the current state isn't looping and has played its
+    /// last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Switch to a state, restarting its animation if it is already the current state
+    pub fn play<N: Into<String>>(&mut self, state: N) {
+        self.current = state.into();
+        self.frame = 0;
+        self.elapsed = 0.;
+        self.finished = false;
+    }
+
+    /// Set a bool parameter that is checked by the transition conditions
+    pub fn set_bool<N: Into<String>>(&mut self, name: N, value: bool) {
+        self.bools.insert(name.into(), value);
+    }
+
+    /// Get a bool parameter, which is `false` if it hasn't been set
+    pub fn get_bool(&self, name: &str) -> bool {
+        self.bools.get(name).copied().unwrap_or(false)
+    }
+
+    /// Set a float parameter that is checked by the transition conditions
+    pub fn set_float<N: Into<String>>(&mut self, name: N, value: f32) {
+        self.floats.insert(name.into(), value);
+    }
+
+    /// Get a float parameter, which is `0.0` if it hasn't been set
+    pub fn get_float(&self, name: &str) -> f32 {
+        self.floats.get(name).copied().unwrap_or(0.)
+    }
+
+    /// Set a trigger that is checked by the transition conditions
+    ///
+    /// Triggers are cleared after the next update of the controller, whether or not they cause a
+    /// transition.
+    pub fn trigger<N: Into<String>>(&mut self, name: N) {
+        self.triggers.insert(name.into());
+    }
+
+    /// Advance the animation by the given time in seconds and take the first transition with a met
+    /// condition
+    ///
+    /// Returns the state whose animation finished if it finished, and the state that was left if
+    /// there was a transition.
+    pub(crate) fn update(&mut self, delta: f32) -> (Option<String>, Option<String>) {
+        let mut finished = None;
+        if let Some(animation) = self.states.get(&self.current) {
+            if !self.paused && !self.finished && !animation.frames.is_empty() && animation.fps > 0.
+            {
+                let frame_duration = 1. / animation.fps;
+                self.elapsed += delta * self.speed;
+                while self.elapsed >= frame_duration {
+                    self.elapsed -= frame_duration;
+                    self.frame += 1;
+
+                    if self.frame >= animation.frames.len() {
+                        if animation.looping {
+                            self.frame = 0;
+                        } else {
+                            self.frame = animation.frames.len() - 1;
+                            self.elapsed = 0.;
+                            self.finished = true;
+                            finished = Some(self.current.clone());
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let current = &self.current;
+        let transition = self
+            .transitions
+            .iter()
+            .filter(|transition| match &transition.from {
+                Some(from) => from == current,
+                None => &transition.to != current,
+            })
+            .find(|transition| transition.condition.is_met(self))
+            .cloned();
+
+        let previous = transition.map(|transition| {
+            let previous = self.current.clone();
+            self.play(transition.to);
+            previous
+        });
+
+        self.triggers.clear();
+
+        (finished, previous)
+    }
+
+    /// Get the tile index of the current frame in the sprite sheet
+    pub(crate) fn current_tile(&self) -> Option<u32> {
+        self.current_animation()
+            .and_then(|animation| animation.frames.get(self.frame))
+            .copied()
+    }
+}
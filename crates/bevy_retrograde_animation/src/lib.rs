@@ -0,0 +1,89 @@
+//! Bevy Retrograde sprite animation plugin
+//!
+//! An [`AnimationController`] is a state machine that plays a [`SpriteAnimation`] from the sprite
+//! sheet of an entity for each of its named states, such as `"idle"`, `"run"`, and `"attack"`.
+//! Gameplay systems set the parameters and triggers of the controller to make it transition
+//! between states, and an [`AnimationFinished`] event is sent when an animation that doesn't loop
+//! plays its last frame.
+//!
+//! Animations are played with the [`GameTime`], so they freeze while the game is paused and speed
+//! up or slow down with its time scale.
+
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::{GameTime, SpriteSheet};
+
+mod controller;
+pub use controller::{AnimationCondition, AnimationController, SpriteAnimation};
+
+/// Sprite animation plugin for Bevy Retrograde
+pub struct RetroAnimationPlugin;
+
+impl Plugin for RetroAnimationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<AnimationFinished>()
+            .add_event::<AnimationStateChanged>()
+            .add_system_to_stage(CoreStage::PostUpdate, animate_sprites.system());
+    }
+}
+
+/// Bevy event sent when the animation of the current state of an [`AnimationController`] isn't
+/// looping and has played its last frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationFinished {
+    /// The entity with the controller
+    pub entity: Entity,
+    /// The state that the animation was played for
+    pub state: String,
+}
+
+/// Bevy event sent when an [`AnimationController`] takes a transition to another state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationStateChanged {
+    /// The entity with the controller
+    pub entity: Entity,
+    /// The state that was left
+    pub from: String,
+    /// The state that was entered
+    pub to: String,
+}
+
+/// Update the animation controllers and set the current frames on their sprite sheets
+fn animate_sprites(
+    time: Res<GameTime>,
+    mut sprite_sheet_assets: ResMut<Assets<SpriteSheet>>,
+    mut controllers: Query<(Entity, &mut AnimationController, &Handle<SpriteSheet>)>,
+    mut finished_events: EventWriter<AnimationFinished>,
+    mut changed_events: EventWriter<AnimationStateChanged>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut controller, sprite_sheet_handle) in controllers.iter_mut() {
+        let (finished, previous) = controller.update(delta);
+
+        if let Some(state) = finished {
+            finished_events.send(AnimationFinished { entity, state });
+        }
+        if let Some(from) = previous {
+            changed_events.send(AnimationStateChanged {
+                entity,
+                from,
+                to: controller.current_state().into(),
+            });
+        }
+
+        // Only get the sprite sheet mutably if the frame changed to avoid sending asset events
+        let tile_index = if let Some(tile_index) = controller.current_tile() {
+            tile_index
+        } else {
+            continue;
+        };
+        if sprite_sheet_assets
+            .get(sprite_sheet_handle)
+            .map(|sprite_sheet| sprite_sheet.tile_index != tile_index)
+            .unwrap_or(false)
+        {
+            if let Some(sprite_sheet) = sprite_sheet_assets.get_mut(sprite_sheet_handle) {
+                sprite_sheet.tile_index = tile_index;
+            }
+        }
+    }
+}
@@ -2,13 +2,51 @@ use bevy::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
     prelude::*,
     reflect::TypeUuid,
-    utils::BoxedFuture,
+    utils::{BoxedFuture, HashMap},
 };
-use kira::sound::{handle::SoundHandle as KiraSoundHandle, Sound as KiraSound};
+use kira::sound::{
+    handle::SoundHandle as KiraSoundHandle, Sound as KiraSound, SoundSettings as KiraSoundSettings,
+};
+
+use crate::Sound;
 
 pub(crate) fn add_assets(app: &mut AppBuilder) {
-    app.add_asset::<SoundData>()
-        .add_asset_loader(SoundDataLoader);
+    // Get the settings for loading sounds
+    let settings = app
+        .world_mut()
+        .get_resource_or_insert_with(SoundLoadSettings::default)
+        .clone();
+
+    app.init_resource::<SoundLoadStates>()
+        .add_asset::<SoundData>()
+        .add_asset_loader(SoundDataLoader { settings });
+}
+
+/// Bevy resource used to configure how sound files are loaded
+///
+/// Insert this resource before adding the [`RetroAudioPlugin`][crate::RetroAudioPlugin] to change
+/// the settings.
+#[derive(Debug, Clone)]
+pub struct SoundLoadSettings {
+    /// The size in bytes above which sound files are not decoded while they are loaded
+    ///
+    /// Decoding a long piece of music can take a while, so instead of decoding large files while
+    /// loading them, their encoded bytes are kept and they are decoded in the background the first
+    /// time a sound is created from them. This makes loading faster and keeps music that is never
+    /// played out of memory, at the cost of a short delay before the sound can first be played.
+    ///
+    /// Sounds are always fully decoded into memory before playing, because the audio backend
+    /// doesn't support streaming audio from disk. Set this to [`None`] to decode all sounds while
+    /// loading them.
+    pub background_decoding_threshold: Option<usize>,
+}
+
+impl Default for SoundLoadSettings {
+    fn default() -> Self {
+        Self {
+            background_decoding_threshold: Some(1024 * 1024),
+        }
+    }
 }
 
 /// An asset that holds the data necessary to create a sound using the [`SoundController`][`crate::SoundController`] resource
@@ -20,6 +58,154 @@ pub(crate) fn add_assets(app: &mut AppBuilder) {
 pub enum SoundData {
     Sound(KiraSound),
     SoundHandle(KiraSoundHandle),
+    /// A sound file that will be decoded in the background when a sound is created from it
+    BackgroundDecoded(BackgroundDecodedSound),
+}
+
+/// The bytes of a sound file that is decoded in the background when it is first used
+///
+/// The whole sound is still decoded into memory before it plays; this only moves the decoding
+/// out of asset loading.
+#[derive(Clone, Debug)]
+pub struct BackgroundDecodedSound {
+    pub bytes: Vec<u8>,
+    /// The file extension of the sound, which determines its format
+    pub extension: String,
+}
+
+impl BackgroundDecodedSound {
+    /// Decode the sound
+    pub fn decode(&self) -> Result<KiraSound, SoundDataLoaderError> {
+        decode_sound(&self.bytes, &self.extension)
+    }
+}
+
+/// A position in a sound used for [`LoopPoints`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopPosition {
+    /// A position in seconds from the start of the sound
+    Seconds(f64),
+    /// A position in samples from the start of the sound
+    Samples(u64),
+}
+
+impl LoopPosition {
+    fn to_seconds(self, sample_rate: u32) -> f64 {
+        match self {
+            LoopPosition::Seconds(seconds) => seconds,
+            LoopPosition::Samples(samples) => samples as f64 / sample_rate as f64,
+        }
+    }
+}
+
+/// The loop region of a sound, such as a piece of music with an intro
+///
+/// The part of the sound before `start` plays once, after which the region from `start` to `end`
+/// loops seamlessly. Anything after `end` is never played.
+///
+/// Loop points are set when creating a sound with
+/// [`create_sound_with_loop_points`][crate::SoundController::create_sound_with_loop_points], and
+/// sounds with loop points loop by default when played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopPoints {
+    /// The start of the loop region
+    pub start: LoopPosition,
+    /// The end of the loop region, or [`None`] to loop at the end of the sound
+    pub end: Option<LoopPosition>,
+}
+
+impl LoopPoints {
+    /// Loop from `start` until the end of the sound
+    pub fn from_start(start: LoopPosition) -> Self {
+        Self { start, end: None }
+    }
+
+    /// Create a copy of a sound that loops between the loop points
+    pub(crate) fn apply(&self, sound: &KiraSound) -> KiraSound {
+        let sample_rate = sound.sample_rate();
+        let end = self
+            .end
+            .map(|end| end.to_seconds(sample_rate).min(sound.duration()))
+            .unwrap_or_else(|| sound.duration());
+        let start = self.start.to_seconds(sample_rate).clamp(0., end);
+
+        // Copy the frames of the sound up to the end of the loop
+        let frame_count = (end * sample_rate as f64).round() as usize;
+        let frames = (0..frame_count)
+            .map(|i| sound.get_frame_at_position(i as f64 / sample_rate as f64))
+            .collect();
+
+        KiraSound::from_frames(
+            sample_rate,
+            frames,
+            KiraSoundSettings::new().default_loop_start(start),
+        )
+    }
+}
+
+/// The loading state of the data of a [`Sound`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundLoadState {
+    /// The sound data is still being loaded
+    Loading,
+    /// The sound data is being decoded in the background
+    Decoding,
+    /// The sound is ready to be played
+    Ready,
+    /// The sound data could not be loaded or decoded, so the sound will never play
+    Failed,
+}
+
+/// Bevy resource with the loading state of every [`Sound`]
+///
+/// Sounds that are played before they are ready start playing as soon as they are ready, which may
+/// be too late for sounds that need to play at a specific time, such as music that gameplay is
+/// synced to. The load states can be used to wait for those sounds before starting a level, and
+/// the [`SoundReady`][crate::SoundReady] event is sent when a sound becomes ready.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// struct LevelSounds(Vec<Sound>);
+///
+/// fn wait_for_sounds(level_sounds: Res<LevelSounds>, load_states: Res<SoundLoadStates>) {
+///     if load_states.all_ready(&level_sounds.0) {
+///         // Start the level
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SoundLoadStates {
+    states: HashMap<Sound, SoundLoadState>,
+}
+
+impl SoundLoadStates {
+    /// Get the loading state of a sound
+    ///
+    /// Sounds that were created this frame are [`Loading`][SoundLoadState::Loading] until the
+    /// audio system has seen them.
+    pub fn get(&self, sound: Sound) -> SoundLoadState {
+        self.states
+            .get(&sound)
+            .copied()
+            .unwrap_or(SoundLoadState::Loading)
+    }
+
+    /// Get whether or not a sound is ready to be played
+    pub fn is_ready(&self, sound: Sound) -> bool {
+        self.get(sound) == SoundLoadState::Ready
+    }
+
+    /// Get whether or not all of the sounds are ready to be played
+    pub fn all_ready<'a, I: IntoIterator<Item = &'a Sound>>(&self, sounds: I) -> bool {
+        sounds.into_iter().all(|&sound| self.is_ready(sound))
+    }
+
+    pub(crate) fn set(&mut self, sound: Sound, state: SoundLoadState) {
+        self.states.insert(sound, state);
+    }
 }
 
 /// An error that occurs when loading a sound asset
@@ -29,11 +215,17 @@ pub enum SoundDataLoaderError {
     NonUnicodeFilename,
     #[error("Error loading sound from file: {0}")]
     FileError(#[from] kira::sound::error::SoundFromFileError),
+    #[error("Support for `.{extension}` sounds is disabled, enable the `{feature}` feature to load them")]
+    UnsupportedFormat {
+        extension: String,
+        feature: &'static str,
+    },
 }
 
 /// An LDTK map asset loader
-#[derive(Default)]
-struct SoundDataLoader;
+struct SoundDataLoader {
+    settings: SoundLoadSettings,
+}
 
 impl AssetLoader for SoundDataLoader {
     fn load<'a>(
@@ -42,51 +234,86 @@ impl AssetLoader for SoundDataLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
         // Create a future for the load function
-        Box::pin(async move { Ok(load_sound(bytes, load_context).await?) })
+        Box::pin(async move { Ok(load_sound(bytes, load_context, &self.settings).await?) })
     }
 
     fn extensions(&self) -> &[&str] {
-        &[
-            #[cfg(feature = "mp3")]
-            "mp3",
-            #[cfg(feature = "ogg")]
-            "ogg",
-            #[cfg(feature = "flac")]
-            "flac",
-            #[cfg(feature = "wav")]
-            "wav",
-        ]
+        // Formats that are disabled are still registered so that loading them gives a helpful error
+        &["mp3", "ogg", "flac", "wav"]
     }
 }
 
 async fn load_sound<'a, 'b>(
     bytes: &'a [u8],
     load_context: &'a mut LoadContext<'b>,
+    settings: &'a SoundLoadSettings,
 ) -> Result<(), SoundDataLoaderError> {
-    let sound = match load_context.path().extension() {
-        Some(ext) => match ext
+    let extension = match load_context.path().extension() {
+        Some(ext) => ext
             .to_str()
-            .ok_or(SoundDataLoaderError::NonUnicodeFilename)?
-        {
-            #[cfg(feature = "mp3")]
-            "mp3" => KiraSound::from_mp3_reader(bytes, Default::default()),
-            #[cfg(feature = "flac")]
-            "flac" => KiraSound::from_flac_reader(bytes, Default::default()),
-            #[cfg(feature = "ogg")]
-            "ogg" => {
-                let reader = std::io::Cursor::new(bytes);
-                KiraSound::from_ogg_reader(reader, Default::default())
-            }
-            #[cfg(feature = "wav")]
-            "wav" => KiraSound::from_wav_reader(bytes, Default::default()),
-            _ => panic!("Unsupported sound extension, bevy should have caught this"),
-        },
+            .ok_or(SoundDataLoaderError::NonUnicodeFilename)?,
         None => {
             panic!("File does not have extension, bevy should have caught this")
         }
-    }?;
+    };
+
+    // Make sure the format is enabled before keeping the sound to decode later
+    check_format_enabled(extension)?;
+
+    // Keep large files encoded so that they can be decoded in the background later
+    let sound_data = match settings.background_decoding_threshold {
+        Some(threshold) if bytes.len() > threshold => {
+            SoundData::BackgroundDecoded(BackgroundDecodedSound {
+                bytes: bytes.to_vec(),
+                extension: extension.to_string(),
+            })
+        }
+        _ => SoundData::Sound(decode_sound(bytes, extension)?),
+    };
 
-    load_context.set_default_asset(LoadedAsset::new(SoundData::Sound(sound)));
+    load_context.set_default_asset(LoadedAsset::new(sound_data));
 
     Ok(())
 }
+
+/// Check that the feature for decoding a sound format is enabled
+fn check_format_enabled(extension: &str) -> Result<(), SoundDataLoaderError> {
+    let (enabled, feature) = match extension {
+        "mp3" => (cfg!(feature = "mp3"), "mp3"),
+        "flac" => (cfg!(feature = "flac"), "flac"),
+        "ogg" => (cfg!(feature = "ogg"), "ogg"),
+        "wav" => (cfg!(feature = "wav"), "wav"),
+        _ => panic!("Unsupported sound extension, bevy should have caught this"),
+    };
+
+    if enabled {
+        Ok(())
+    } else {
+        Err(SoundDataLoaderError::UnsupportedFormat {
+            extension: extension.to_string(),
+            feature,
+        })
+    }
+}
+
+/// Decode a sound file with the given file extension
+fn decode_sound(bytes: &[u8], extension: &str) -> Result<KiraSound, SoundDataLoaderError> {
+    check_format_enabled(extension)?;
+
+    let sound: Result<KiraSound, kira::sound::error::SoundFromFileError> = match extension {
+        #[cfg(feature = "mp3")]
+        "mp3" => KiraSound::from_mp3_reader(bytes, Default::default()),
+        #[cfg(feature = "flac")]
+        "flac" => KiraSound::from_flac_reader(bytes, Default::default()),
+        #[cfg(feature = "ogg")]
+        "ogg" => {
+            let reader = std::io::Cursor::new(bytes);
+            KiraSound::from_ogg_reader(reader, Default::default())
+        }
+        #[cfg(feature = "wav")]
+        "wav" => KiraSound::from_wav_reader(bytes, Default::default()),
+        _ => unreachable!("Sound format was checked above"),
+    };
+
+    Ok(sound?)
+}
@@ -0,0 +1,116 @@
+use kira::Value;
+use rand::Rng;
+
+use super::*;
+
+/// A set of variations of a sound, such as footsteps or hits, that are picked from at random
+/// every time the bank is played
+///
+/// Each time a sound is played from the bank, its volume and playback rate are also varied by a
+/// random amount, so that sounds that are played over and over again don't sound robotic.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// struct Footsteps(SoundBank);
+///
+/// fn setup(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+/// ) {
+///     let variants = (1..=4)
+///         .map(|i| sound_controller.create_sound(&asset_server.load(&format!("step{}.ogg", i)[..])))
+///         .collect();
+///
+///     commands.insert_resource(Footsteps(
+///         SoundBank::new(variants)
+///             .with_pitch_jitter(0.1)
+///             .with_bus(SFX_BUS),
+///     ));
+/// }
+///
+/// fn play_footstep(mut footsteps: ResMut<Footsteps>, mut sound_controller: SoundController) {
+///     sound_controller.play_from_bank(&mut footsteps.0);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundBank {
+    /// The sounds to pick from
+    pub variants: Vec<Sound>,
+    /// The most that the volume is raised or lowered by each time a sound is played
+    pub volume_jitter: f64,
+    /// The most that the playback rate, and therefore the pitch, is raised or lowered by each time
+    /// a sound is played
+    pub pitch_jitter: f64,
+    /// Whether or not to avoid playing the same variant twice in a row
+    pub avoid_repeats: bool,
+    /// The [`AudioMixer`] bus to play the sounds on
+    pub bus: Option<String>,
+    last_variant: Option<usize>,
+}
+
+impl SoundBank {
+    /// Create a sound bank that picks from the variants without any jitter
+    pub fn new(variants: Vec<Sound>) -> Self {
+        Self {
+            variants,
+            volume_jitter: 0.,
+            pitch_jitter: 0.,
+            avoid_repeats: true,
+            bus: None,
+            last_variant: None,
+        }
+    }
+
+    /// Set the volume jitter
+    pub fn with_volume_jitter(mut self, jitter: f64) -> Self {
+        self.volume_jitter = jitter;
+        self
+    }
+
+    /// Set the pitch jitter
+    pub fn with_pitch_jitter(mut self, jitter: f64) -> Self {
+        self.pitch_jitter = jitter;
+        self
+    }
+
+    /// Set the bus to play the sounds on
+    pub fn with_bus<N: Into<String>>(mut self, bus: N) -> Self {
+        self.bus = Some(bus.into());
+        self
+    }
+
+    /// Pick the next variant to play, and apply the jitter to the play settings
+    ///
+    /// Returns [`None`] if the bank doesn't have any variants.
+    pub(crate) fn choose(
+        &mut self,
+        mut settings: PlaySoundSettings,
+    ) -> Option<(Sound, PlaySoundSettings)> {
+        if self.variants.is_empty() {
+            return None;
+        }
+        let mut rng = rand::thread_rng();
+
+        // Pick a variant, skipping the last one if there are others to choose from
+        let mut index = rng.gen_range(0..self.variants.len());
+        if self.avoid_repeats && self.variants.len() > 1 && Some(index) == self.last_variant {
+            index = (index + rng.gen_range(1..self.variants.len())) % self.variants.len();
+        }
+        self.last_variant = Some(index);
+
+        let mut jitter = |value: Value<f64>, amount: f64| match value {
+            Value::Fixed(value) if amount > 0. => {
+                Value::Fixed((value + rng.gen_range(-amount..=amount)).max(0.))
+            }
+            value => value,
+        };
+        settings.volume = jitter(settings.volume, self.volume_jitter);
+        settings.playback_rate = jitter(settings.playback_rate, self.pitch_jitter);
+
+        Some((self.variants[index], settings))
+    }
+}
@@ -0,0 +1,531 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use kira::{sound::Sound as KiraSound, Frame};
+
+use super::*;
+
+/// The sample rate that chiptune sounds are rendered at
+const SAMPLE_RATE: u32 = 44100;
+
+/// The number of seconds that it takes for a note to fade in or out, to avoid clicks
+const NOTE_FADE_DURATION: f32 = 0.002;
+
+pub(crate) fn add_chiptune_assets(app: &mut AppBuilder) {
+    app.add_asset_loader(ChiptuneLoader);
+}
+
+/// The waveform of a chiptune channel or [`Tone`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    /// A square wave, where `duty` is the fraction of each cycle that the wave is high, such as
+    /// `0.5` for a hollow sound or `0.125` for a thin, buzzy sound
+    Square { duty: f32 },
+    /// A triangle wave, with a soft sound that is often used for bass
+    Triangle,
+    /// A sawtooth wave, with a bright sound
+    Sawtooth,
+    /// Noise, often used for drums and explosions, which changes faster at higher pitches
+    Noise,
+}
+
+impl Default for Waveform {
+    fn default() -> Self {
+        Waveform::Square { duty: 0.5 }
+    }
+}
+
+/// An oscillator that generates a waveform
+struct Oscillator {
+    waveform: Waveform,
+    /// The position in the current cycle of the wave, from `0.0` to `1.0`
+    phase: f32,
+    /// The shift register used to generate noise
+    noise: u16,
+    noise_value: f32,
+}
+
+impl Oscillator {
+    fn new(waveform: Waveform) -> Self {
+        Self {
+            waveform,
+            phase: 0.,
+            noise: 1,
+            noise_value: 1.,
+        }
+    }
+
+    /// Get the next sample of the waveform at the given frequency
+    fn next(&mut self, frequency: f32) -> f32 {
+        let value = match self.waveform {
+            Waveform::Square { duty } => {
+                if self.phase < duty {
+                    1.
+                } else {
+                    -1.
+                }
+            }
+            Waveform::Triangle => 1. - 4. * (self.phase - 0.5).abs(),
+            Waveform::Sawtooth => 2. * self.phase - 1.,
+            Waveform::Noise => self.noise_value,
+        };
+
+        self.phase += frequency / SAMPLE_RATE as f32;
+        while self.phase >= 1. {
+            self.phase -= 1.;
+
+            // Step the noise shift register once per cycle, like the noise channel of the NES
+            let bit = (self.noise ^ (self.noise >> 1)) & 1;
+            self.noise = (self.noise >> 1) | (bit << 14);
+            self.noise_value = if self.noise & 1 == 0 { 1. } else { -1. };
+        }
+
+        value
+    }
+}
+
+/// Get the frequency of a MIDI note number, where `69` is the A above middle C
+fn note_frequency(note: u8) -> f32 {
+    440. * 2f32.powf((note as f32 - 69.) / 12.)
+}
+
+/// Create a sound from mono samples
+fn sound_from_samples(samples: Vec<f32>) -> KiraSound {
+    KiraSound::from_frames(
+        SAMPLE_RATE,
+        samples
+            .into_iter()
+            .map(|sample| Frame::from_mono(sample.clamp(-1., 1.)))
+            .collect(),
+        Default::default(),
+    )
+}
+
+/// A single synthesized note, such as a sound effect for jumping or picking up a coin
+///
+/// Tones are synthesized when they are turned into [`SoundData`], which can be added to the
+/// sound data assets and played like any other sound.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// struct JumpSound(Sound);
+///
+/// fn setup(
+///     mut commands: Commands,
+///     mut sound_data_assets: ResMut<Assets<SoundData>>,
+///     mut sound_controller: SoundController,
+/// ) {
+///     let jump = Tone::new(Waveform::Square { duty: 0.25 }, 220., 0.2).with_slide(880.);
+///     let jump = sound_controller.create_sound(&sound_data_assets.add(jump.to_sound_data()));
+///     commands.insert_resource(JumpSound(jump));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub waveform: Waveform,
+    /// The frequency at the start of the tone in hertz
+    pub frequency: f32,
+    /// The frequency at the end of the tone in hertz, which the pitch slides to over the tone
+    pub end_frequency: f32,
+    /// The length of the tone in seconds
+    pub duration: f32,
+    /// The volume of the tone, where `1.0` is full volume
+    pub volume: f32,
+    /// The number of seconds that it takes for the tone to fade in
+    pub attack: f32,
+    /// The number of seconds that it takes for the tone to fade out at the end
+    pub release: f32,
+}
+
+impl Tone {
+    /// Create a tone that stays at the same pitch
+    pub fn new(waveform: Waveform, frequency: f32, duration: f32) -> Self {
+        Self {
+            waveform,
+            frequency,
+            end_frequency: frequency,
+            duration,
+            volume: 0.5,
+            attack: 0.005,
+            release: 0.05,
+        }
+    }
+
+    /// Slide the pitch to `end_frequency` over the length of the tone
+    pub fn with_slide(mut self, end_frequency: f32) -> Self {
+        self.end_frequency = end_frequency;
+        self
+    }
+
+    /// Set the volume
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Set how long it takes for the tone to fade in and out
+    pub fn with_envelope(mut self, attack: f32, release: f32) -> Self {
+        self.attack = attack;
+        self.release = release;
+        self
+    }
+
+    /// Synthesize the tone
+    pub fn render(&self) -> KiraSound {
+        let mut oscillator = Oscillator::new(self.waveform);
+        let sample_count = (self.duration.max(0.) * SAMPLE_RATE as f32) as usize;
+
+        let samples = (0..sample_count)
+            .map(|i| {
+                let time = i as f32 / SAMPLE_RATE as f32;
+                let progress = time / self.duration;
+                let frequency = self.frequency + (self.end_frequency - self.frequency) * progress;
+
+                let fade_in = (time / self.attack.max(f32::EPSILON)).min(1.);
+                let fade_out = ((self.duration - time) / self.release.max(f32::EPSILON)).min(1.);
+
+                oscillator.next(frequency) * fade_in * fade_out * self.volume
+            })
+            .collect();
+
+        sound_from_samples(samples)
+    }
+
+    /// Synthesize the tone into sound data
+    pub fn to_sound_data(&self) -> SoundData {
+        SoundData::Sound(self.render())
+    }
+}
+
+/// A row of a [`ChiptuneChannel`] pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternRow {
+    /// Start playing a MIDI note number, where `60` is middle C
+    Note(u8),
+    /// Keep playing the current note, or keep silent if no note is playing
+    Hold,
+    /// Stop playing the current note
+    Off,
+}
+
+/// A channel of a [`ChiptuneSong`], which plays one note at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChiptuneChannel {
+    pub waveform: Waveform,
+    /// The volume of the channel, where `1.0` is full volume
+    pub volume: f32,
+    /// The rows of the channel, which are played one after the other
+    pub rows: Vec<PatternRow>,
+}
+
+impl ChiptuneChannel {
+    /// Create a channel without any rows
+    pub fn new(waveform: Waveform) -> Self {
+        Self {
+            waveform,
+            volume: 0.25,
+            rows: Vec::new(),
+        }
+    }
+}
+
+/// A song made of chiptune channels that play patterns of notes, like a simple tracker module
+///
+/// Songs can be built in code or loaded from `.chiptune` files, which are much smaller than
+/// recorded music. Songs are synthesized when they are loaded, after which they are played like any
+/// other sound.
+///
+/// # File Format
+///
+/// Chiptune files are text files with one command per line. Rows of notes are written as `C-4`
+/// for middle C or `C#4` for the note above it, `...` to hold the current note, and `===` to stop
+/// it. Lines may contain any number of rows, and everything after `//` is a comment.
+///
+/// ```text
+/// // The tempo and the number of rows in each beat
+/// bpm 120
+/// rows_per_beat 4
+///
+/// // A melody on a square wave
+/// channel square
+/// duty 0.25
+/// volume 0.3
+/// C-5 ... E-5 ... G-5 ... C-6 ... === ... ... ... ... ... ... ...
+///
+/// // A bass line on a triangle wave
+/// channel triangle
+/// C-3 ... ... ... ... ... ... ... G-2 ... ... ... ... ... ... ...
+///
+/// // A drum beat
+/// channel noise
+/// C-8 === ... ... C-4 === ... ... C-8 === ... ... C-4 === ... ...
+/// ```
+///
+/// The waveforms are `square`, `triangle`, `sawtooth`, and `noise`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn start_music(
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+///     mut music_controller: MusicController,
+/// ) {
+///     let music = sound_controller.create_sound(&asset_server.load("title.chiptune"));
+///     music_controller.play(music);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChiptuneSong {
+    /// The tempo of the song in beats per minute
+    pub bpm: f32,
+    /// The number of rows in each beat
+    pub rows_per_beat: u32,
+    pub channels: Vec<ChiptuneChannel>,
+}
+
+impl ChiptuneSong {
+    /// Create a song without any channels
+    pub fn new(bpm: f32) -> Self {
+        Self {
+            bpm,
+            rows_per_beat: 4,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Add a channel
+    pub fn with_channel(mut self, channel: ChiptuneChannel) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Parse a song from the `.chiptune` file format
+    pub fn parse(source: &str) -> Result<Self, ChiptuneError> {
+        let mut song = ChiptuneSong::new(120.);
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split("//").next().unwrap_or("").trim();
+            let mut words = line.split_whitespace();
+            let command = match words.next() {
+                Some(command) => command,
+                None => continue,
+            };
+
+            let invalid_value = || ChiptuneError::InvalidValue {
+                line: line_number,
+                command: command.to_string(),
+            };
+            let no_channel = || ChiptuneError::NoChannel {
+                line: line_number,
+                command: command.to_string(),
+            };
+            let mut value = || -> Result<f32, ChiptuneError> {
+                words
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .ok_or_else(invalid_value)
+            };
+
+            match command {
+                "bpm" => {
+                    let bpm = value()?;
+                    if !bpm.is_finite() || bpm <= 0. {
+                        return Err(invalid_value());
+                    }
+                    song.bpm = bpm;
+                }
+                "rows_per_beat" => song.rows_per_beat = value()?.max(1.) as u32,
+                "channel" => {
+                    let waveform = match words.next() {
+                        Some("square") => Waveform::default(),
+                        Some("triangle") => Waveform::Triangle,
+                        Some("sawtooth") => Waveform::Sawtooth,
+                        Some("noise") => Waveform::Noise,
+                        waveform => {
+                            return Err(ChiptuneError::UnknownWaveform {
+                                line: line_number,
+                                waveform: waveform.unwrap_or("").to_string(),
+                            })
+                        }
+                    };
+                    song.channels.push(ChiptuneChannel::new(waveform));
+                }
+                "volume" => {
+                    let volume = value()?;
+                    song.channels.last_mut().ok_or_else(no_channel)?.volume = volume;
+                }
+                "duty" => {
+                    let value = value()?;
+                    match &mut song.channels.last_mut().ok_or_else(no_channel)?.waveform {
+                        Waveform::Square { duty } => *duty = value,
+                        _ => return Err(invalid_value()),
+                    }
+                }
+                // Anything else is a line of rows
+                _ => {
+                    let channel = song.channels.last_mut().ok_or_else(no_channel)?;
+                    for word in line.split_whitespace() {
+                        channel.rows.push(parse_row(word).ok_or_else(|| {
+                            ChiptuneError::InvalidNote {
+                                line: line_number,
+                                word: word.to_string(),
+                            }
+                        })?);
+                    }
+                }
+            }
+        }
+
+        Ok(song)
+    }
+
+    /// Get the length of the song in seconds
+    pub fn duration(&self) -> f32 {
+        let row_count = self
+            .channels
+            .iter()
+            .map(|x| x.rows.len())
+            .max()
+            .unwrap_or(0);
+        row_count as f32 * self.row_duration()
+    }
+
+    fn row_duration(&self) -> f32 {
+        60. / (self.bpm.max(f32::EPSILON) * self.rows_per_beat.max(1) as f32)
+    }
+
+    /// Synthesize the song
+    ///
+    /// Songs without a positive tempo can't be played, so they are rendered as silence.
+    pub fn render(&self) -> KiraSound {
+        if !self.bpm.is_finite() || self.bpm <= 0. {
+            return sound_from_samples(Vec::new());
+        }
+
+        let samples_per_row = self.row_duration() * SAMPLE_RATE as f32;
+        let sample_count = (self.duration() * SAMPLE_RATE as f32).round() as usize;
+        let fade_step = 1. / (NOTE_FADE_DURATION * SAMPLE_RATE as f32);
+
+        let mut samples = vec![0.; sample_count];
+        for channel in &self.channels {
+            let mut oscillator = Oscillator::new(channel.waveform);
+            let mut frequency = 0.;
+            let mut playing = false;
+            let mut level = 0f32;
+
+            for (row_index, row) in channel.rows.iter().enumerate() {
+                match row {
+                    PatternRow::Note(note) => {
+                        frequency = note_frequency(*note);
+                        playing = true;
+                        // Restart the note so that repeated notes can be heard
+                        level = 0.;
+                    }
+                    PatternRow::Off => playing = false,
+                    PatternRow::Hold => (),
+                }
+
+                let start = (row_index as f32 * samples_per_row).round() as usize;
+                let end =
+                    (((row_index + 1) as f32 * samples_per_row).round() as usize).min(sample_count);
+                for sample in &mut samples[start.min(end)..end] {
+                    level = if playing {
+                        (level + fade_step).min(1.)
+                    } else {
+                        (level - fade_step).max(0.)
+                    };
+                    if level > 0. {
+                        *sample += oscillator.next(frequency) * level * channel.volume;
+                    }
+                }
+            }
+        }
+
+        sound_from_samples(samples)
+    }
+
+    /// Synthesize the song into sound data
+    pub fn to_sound_data(&self) -> SoundData {
+        SoundData::Sound(self.render())
+    }
+}
+
+/// Parse a row of a pattern, such as `C#4`, `...`, or `===`
+fn parse_row(word: &str) -> Option<PatternRow> {
+    match word {
+        "..." => return Some(PatternRow::Hold),
+        "===" => return Some(PatternRow::Off),
+        _ => (),
+    }
+
+    let mut chars = word.chars();
+    let semitone = match chars.next()? {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let accidental = match chars.next()? {
+        '-' => 0,
+        '#' => 1,
+        _ => return None,
+    };
+    let octave = chars.next()?.to_digit(10)? as u8;
+    if chars.next().is_some() {
+        return None;
+    }
+
+    Some(PatternRow::Note((octave + 1) * 12 + semitone + accidental))
+}
+
+/// An error that occurs when parsing a chiptune file
+#[derive(thiserror::Error, Debug)]
+pub enum ChiptuneError {
+    #[error("Chiptune file is not valid UTF-8")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    #[error("Line {line}: invalid note or command `{word}`")]
+    InvalidNote { line: usize, word: String },
+    #[error("Line {line}: invalid value for `{command}`")]
+    InvalidValue { line: usize, command: String },
+    #[error("Line {line}: unknown waveform `{waveform}`")]
+    UnknownWaveform { line: usize, waveform: String },
+    #[error("Line {line}: `{command}` must come after a `channel` line")]
+    NoChannel { line: usize, command: String },
+}
+
+/// A chiptune song asset loader
+struct ChiptuneLoader;
+
+impl AssetLoader for ChiptuneLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move { Ok(load_chiptune(bytes, load_context)?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["chiptune"]
+    }
+}
+
+fn load_chiptune(bytes: &[u8], load_context: &mut LoadContext) -> Result<(), ChiptuneError> {
+    let song = ChiptuneSong::parse(std::str::from_utf8(bytes)?)?;
+    load_context.set_default_asset(LoadedAsset::new(song.to_sound_data()));
+
+    Ok(())
+}
@@ -19,12 +19,35 @@ pub struct SoundController<'a> {
 impl<'a> SoundController<'a> {
     /// Create a new sound that can then be played, paused, resumed, or stopped using the other functions on [`SoundController`]
     pub fn create_sound(&mut self, sound_data: &Handle<SoundData>) -> Sound {
+        self.create_sound_internal(sound_data, None)
+    }
+
+    /// Create a new sound with an intro that plays once, followed by a loop region
+    ///
+    /// The loop points are stored with the sound data the first time that a sound is created from
+    /// it, so they can't be changed by creating another sound from the same sound data.
+    pub fn create_sound_with_loop_points(
+        &mut self,
+        sound_data: &Handle<SoundData>,
+        loop_points: LoopPoints,
+    ) -> Sound {
+        self.create_sound_internal(sound_data, Some(loop_points))
+    }
+
+    fn create_sound_internal(
+        &mut self,
+        sound_data: &Handle<SoundData>,
+        loop_points: Option<LoopPoints>,
+    ) -> Sound {
         // Create a sound handle
         let sound = Sound::new();
 
         // Send the sound create event
-        self.sound_event_writer
-            .send(SoundEvent::CreateSound(sound_data.clone(), sound));
+        self.sound_event_writer.send(SoundEvent::CreateSound(
+            sound_data.clone(),
+            sound,
+            loop_points,
+        ));
 
         // Return the sound handle
         sound
@@ -32,14 +55,65 @@ impl<'a> SoundController<'a> {
 
     /// Play a sound
     ///
-    /// This will play the sound using the default settings
-    pub fn play_sound(&mut self, sound: Sound) {
+    /// This will play the sound using the default settings. A sound can be played any number of
+    /// times at once, and the returned [`SoundInstance`] can be used to control this one playback
+    /// of the sound.
+    pub fn play_sound(&mut self, sound: Sound) -> SoundInstance {
         self.play_sound_with_settings(sound, Default::default())
     }
     /// Play a sound with customized settings
-    pub fn play_sound_with_settings(&mut self, sound: Sound, settings: PlaySoundSettings) {
+    pub fn play_sound_with_settings(
+        &mut self,
+        sound: Sound,
+        settings: PlaySoundSettings,
+    ) -> SoundInstance {
+        let instance = SoundInstance::new();
         self.sound_event_writer
-            .send(SoundEvent::PlaySound(sound, settings));
+            .send(SoundEvent::PlaySound(sound, instance, settings, None));
+        instance
+    }
+    /// Play a sound on one of the buses of the [`AudioMixer`]
+    ///
+    /// If the bus doesn't exist, the sound is played without a bus.
+    pub fn play_sound_on_bus(
+        &mut self,
+        sound: Sound,
+        bus: &str,
+        settings: PlaySoundSettings,
+    ) -> SoundInstance {
+        let instance = SoundInstance::new();
+        self.sound_event_writer.send(SoundEvent::PlaySound(
+            sound,
+            instance,
+            settings,
+            Some(bus.to_string()),
+        ));
+        instance
+    }
+    /// Play a random sound from a [`SoundBank`]
+    ///
+    /// If the bank doesn't have any sounds, nothing is played, but an instance is still returned.
+    pub fn play_from_bank(&mut self, bank: &mut SoundBank) -> SoundInstance {
+        self.play_from_bank_with_settings(bank, Default::default())
+    }
+    /// Play a random sound from a [`SoundBank`] with customized settings
+    ///
+    /// The jitter of the bank is applied on top of the volume and playback rate of the settings.
+    pub fn play_from_bank_with_settings(
+        &mut self,
+        bank: &mut SoundBank,
+        settings: PlaySoundSettings,
+    ) -> SoundInstance {
+        let instance = SoundInstance::new();
+        if let Some((sound, settings)) = bank.choose(settings) {
+            self.sound_event_writer.send(SoundEvent::PlaySound(
+                sound,
+                instance,
+                settings,
+                bank.bus.clone(),
+            ));
+        }
+        instance
     }
     /// Pause a sound
     pub fn pause_sound(&mut self, sound: Sound) {
@@ -68,6 +142,168 @@ impl<'a> SoundController<'a> {
         self.sound_event_writer
             .send(SoundEvent::StopSound(sound, settings));
     }
+
+    /// Pause one instance of a sound
+    pub fn pause_instance(&mut self, instance: SoundInstance) {
+        self.pause_instance_with_settings(instance, Default::default())
+    }
+    /// Pause one instance of a sound with customized settings
+    pub fn pause_instance_with_settings(
+        &mut self,
+        instance: SoundInstance,
+        settings: PauseSoundSettings,
+    ) {
+        self.sound_event_writer
+            .send(SoundEvent::PauseInstance(instance, settings));
+    }
+    /// Resume one instance of a sound
+    pub fn resume_instance(&mut self, instance: SoundInstance) {
+        self.resume_instance_with_settings(instance, Default::default())
+    }
+    /// Resume one instance of a sound with customized settings
+    pub fn resume_instance_with_settings(
+        &mut self,
+        instance: SoundInstance,
+        settings: ResumeSoundSettings,
+    ) {
+        self.sound_event_writer
+            .send(SoundEvent::ResumeInstance(instance, settings));
+    }
+    /// Stop one instance of a sound
+    pub fn stop_instance(&mut self, instance: SoundInstance) {
+        self.stop_instance_with_settings(instance, Default::default())
+    }
+    /// Stop one instance of a sound with customized settings
+    pub fn stop_instance_with_settings(
+        &mut self,
+        instance: SoundInstance,
+        settings: StopSoundSettings,
+    ) {
+        self.sound_event_writer
+            .send(SoundEvent::StopInstance(instance, settings));
+    }
+
+    /// Set the volume of a sound, where `1.0` is the volume that it was played with
+    ///
+    /// This changes the volume of all of the playing instances of the sound, as well as any
+    /// instances played later.
+    pub fn set_sound_volume(&mut self, sound: Sound, volume: f64) {
+        self.animate_sound_property(sound, SoundProperty::Volume, volume, 0.);
+    }
+    /// Set the playback rate of a sound, where `1.0` is the rate that it was played with
+    ///
+    /// Changing the playback rate also changes the pitch of the sound.
+    pub fn set_sound_playback_rate(&mut self, sound: Sound, playback_rate: f64) {
+        self.animate_sound_property(sound, SoundProperty::PlaybackRate, playback_rate, 0.);
+    }
+    /// Set the panning of a sound, where `0.0` is hard left, `0.5` is centered, and `1.0` is hard
+    /// right
+    pub fn set_sound_panning(&mut self, sound: Sound, panning: f64) {
+        self.animate_sound_property(sound, SoundProperty::Panning, panning, 0.);
+    }
+    /// Smoothly change a property of a sound to `value` over `duration` seconds
+    pub fn animate_sound_property(
+        &mut self,
+        sound: Sound,
+        property: SoundProperty,
+        value: f64,
+        duration: f32,
+    ) {
+        self.sound_event_writer
+            .send(SoundEvent::SetProperty(sound, property, value, duration));
+    }
+}
+
+/// Bevy resource for playing background music
+///
+/// Only one music track plays at a time. Changing the track fades out the old track while fading in
+/// the new one, and music loops from the start of its [`LoopPoints`], or from the beginning if it
+/// doesn't have any. Music is played on the [`MUSIC_BUS`] of the [`AudioMixer`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn enter_cave(
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+///     mut music_controller: MusicController,
+/// ) {
+///     let cave_music = sound_controller.create_sound(&asset_server.load("cave.ogg"));
+///     music_controller.crossfade(cave_music, 2.0);
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct MusicController<'a> {
+    sound_event_writer: EventWriter<'a, SoundEvent>,
+}
+
+impl<'a> MusicController<'a> {
+    /// Start playing a music track, fading out the current track while fading in the new one over
+    /// `duration` seconds
+    ///
+    /// Nothing happens if the track is already playing.
+    pub fn crossfade(&mut self, track: Sound, duration: f32) {
+        self.sound_event_writer
+            .send(SoundEvent::CrossfadeMusic(track, duration, None));
+    }
+    /// Crossfade to a music track with a tempo, sending [`Beat`] and [`Bar`] events in time with
+    /// the track from the moment that it starts
+    pub fn crossfade_with_tempo(&mut self, track: Sound, duration: f32, tempo: MusicTempo) {
+        self.sound_event_writer
+            .send(SoundEvent::CrossfadeMusic(track, duration, Some(tempo)));
+    }
+    /// Start playing a music track immediately, stopping the current track
+    pub fn play(&mut self, track: Sound) {
+        self.crossfade(track, 0.);
+    }
+    /// Fade out the current music track over `duration` seconds
+    pub fn stop(&mut self, duration: f32) {
+        self.sound_event_writer
+            .send(SoundEvent::StopMusic(duration));
+    }
+
+    /// Start playing [`LayeredMusic`]
+    ///
+    /// Layered music plays alongside the current music track, so the current track should usually
+    /// be stopped first.
+    pub fn play_layered(&mut self, music: LayeredMusic) -> LayeredMusicHandle {
+        let handle = LayeredMusicHandle::new();
+        self.sound_event_writer
+            .send(SoundEvent::PlayLayeredMusic(handle, music));
+        handle
+    }
+    /// Fade a stem of layered music in or out
+    ///
+    /// The fade starts on the next multiple of `interval` beats since the music started, such as
+    /// `4.0` to wait for the next bar in 4/4 time, or immediately if `interval` is `0.0`.
+    pub fn set_stem_enabled(
+        &mut self,
+        music: LayeredMusicHandle,
+        stem: usize,
+        enabled: bool,
+        interval: f64,
+    ) {
+        self.sound_event_writer
+            .send(SoundEvent::SetStemEnabled(music, stem, enabled, interval));
+    }
+    /// Fade out layered music over `duration` seconds
+    pub fn stop_layered(&mut self, music: LayeredMusicHandle, duration: f32) {
+        self.sound_event_writer
+            .send(SoundEvent::StopLayeredMusic(music, duration));
+    }
+}
+
+/// A property of a sound that can be changed while it is playing with the [`SoundController`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundProperty {
+    /// The volume multiplier of the sound
+    Volume,
+    /// The playback rate multiplier of the sound, which also changes its pitch
+    PlaybackRate,
+    /// The panning of the sound, where `0.5` is centered
+    Panning,
 }
 
 /// A Handle to a sound that can be played, paused, etc. using the [`SoundController`] resource
@@ -81,6 +317,19 @@ impl Sound {
     }
 }
 
+/// A handle to one playback of a [`Sound`], returned when playing the sound
+///
+/// The instance can be paused, resumed, or stopped using the [`SoundController`] without affecting
+/// any other instances of the same sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundInstance(Uuid);
+
+impl SoundInstance {
+    pub(crate) fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
 /// The audio manager
 pub(crate) struct AudioManager(pub(crate) KiraAudioManager);
 
@@ -0,0 +1,138 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::*;
+
+pub(crate) fn add_emitter_systems(app: &mut AppBuilder) {
+    app.add_system_to_stage(CoreStage::PostUpdate, update_audio_emitters.system());
+}
+
+/// Component that plays a sound from an entity
+///
+/// The sound is created when the component is added, played while `playing` is `true`, and stopped
+/// when `playing` is set to `false` or the component or its entity is removed. Sounds that don't
+/// loop set `playing` back to `false` once they finish, so setting it to `true` again plays the
+/// sound again.
+///
+/// This is a simpler alternative to creating and playing sounds with the [`SoundController`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn spawn_waterfall(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.spawn().insert(AudioEmitter {
+///         looped: true,
+///         ..AudioEmitter::new(asset_server.load("waterfall.ogg"))
+///     });
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioEmitter {
+    /// The sound data to play
+    pub sound: Handle<SoundData>,
+    /// Whether or not the sound is playing
+    pub playing: bool,
+    /// Whether or not the sound loops
+    ///
+    /// Changing this while the sound is playing takes effect the next time it is played.
+    pub looped: bool,
+    /// The volume of the sound, where `1.0` is full volume
+    pub volume: f64,
+}
+
+impl AudioEmitter {
+    /// Create an emitter that starts playing the sound once at full volume
+    pub fn new(sound: Handle<SoundData>) -> Self {
+        Self {
+            sound,
+            playing: true,
+            looped: false,
+            volume: 1.,
+        }
+    }
+}
+
+/// The sound that an [`AudioEmitter`] has created
+struct EmitterState {
+    /// The emitter settings as of the last update
+    emitter: AudioEmitter,
+    sound: Sound,
+    instance: Option<SoundInstance>,
+}
+
+fn update_audio_emitters(
+    mut sound_controller: SoundController,
+    mut emitters: Query<(Entity, &mut AudioEmitter)>,
+    removed_emitters: RemovedComponents<AudioEmitter>,
+    mut finished_events: EventReader<SoundFinished>,
+    mut emitter_states: Local<HashMap<Entity, EmitterState>>,
+) {
+    // Stop the sounds of removed emitters
+    for ent in removed_emitters.iter() {
+        if let Some(instance) = emitter_states.remove(&ent).and_then(|x| x.instance) {
+            sound_controller.stop_instance(instance);
+        }
+    }
+
+    // Stop playing emitters whose sounds have finished
+    for event in finished_events.iter() {
+        for (ent, state) in emitter_states.iter_mut() {
+            if state.instance == Some(event.instance) {
+                state.instance = None;
+                if let Ok((_, mut emitter)) = emitters.get_mut(*ent) {
+                    emitter.playing = false;
+                }
+            }
+        }
+    }
+
+    for (ent, emitter) in emitters.iter_mut() {
+        // Skip emitters that haven't changed
+        if let Some(state) = emitter_states.get(&ent) {
+            if state.emitter == *emitter {
+                continue;
+            }
+        }
+
+        // Create the sound if the emitter is new or its sound data has changed
+        let state = match emitter_states.remove(&ent) {
+            Some(state) if state.emitter.sound == emitter.sound => state,
+            previous_state => {
+                if let Some(instance) = previous_state.and_then(|x| x.instance) {
+                    sound_controller.stop_instance(instance);
+                }
+
+                EmitterState {
+                    emitter: emitter.clone(),
+                    sound: sound_controller.create_sound(&emitter.sound),
+                    instance: None,
+                }
+            }
+        };
+        let mut state = EmitterState {
+            emitter: emitter.clone(),
+            ..state
+        };
+
+        sound_controller.set_sound_volume(state.sound, emitter.volume);
+
+        // Start or stop the sound
+        if emitter.playing && state.instance.is_none() {
+            state.instance = Some(sound_controller.play_sound_with_settings(
+                state.sound,
+                PlaySoundSettings::new().loop_start(if emitter.looped {
+                    LoopStart::Custom(0.)
+                } else {
+                    LoopStart::None
+                }),
+            ));
+        } else if !emitter.playing {
+            if let Some(instance) = state.instance.take() {
+                sound_controller.stop_instance(instance);
+            }
+        }
+
+        emitter_states.insert(ent, state);
+    }
+}
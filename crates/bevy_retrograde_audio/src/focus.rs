@@ -0,0 +1,19 @@
+/// Bevy resource that sets what happens to audio when the window loses focus
+///
+/// On the web, the window loses focus when the browser tab is hidden or the player clicks outside of
+/// the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioFocusPolicy {
+    /// Keep playing audio as normal
+    KeepPlaying,
+    /// Pause all playing sounds and resume them when the window gets focus again
+    Pause,
+    /// Turn the volume of all sounds down to the given volume, where `1.0` is full volume
+    Duck(f64),
+}
+
+impl Default for AudioFocusPolicy {
+    fn default() -> Self {
+        AudioFocusPolicy::Pause
+    }
+}
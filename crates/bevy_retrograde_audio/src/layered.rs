@@ -0,0 +1,79 @@
+use uuid::Uuid;
+
+use super::*;
+
+/// A piece of adaptive music made of stems that play in sync, such as drums, bass, and melody
+///
+/// Stems can be faded in and out while the music plays with the [`MusicController`], and the
+/// changes can wait for the next beat or bar, so that the music changes in time with itself. All of
+/// the stems should be the same length so that they loop together. Layered music also sends
+/// [`Beat`] and [`Bar`] events while it plays.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// struct BattleMusic(LayeredMusicHandle);
+///
+/// fn start_music(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+///     mut music_controller: MusicController,
+/// ) {
+///     let drums = sound_controller.create_sound(&asset_server.load("battle_drums.ogg"));
+///     let melody = sound_controller.create_sound(&asset_server.load("battle_melody.ogg"));
+///
+///     let music = music_controller.play_layered(
+///         LayeredMusic::new(120.)
+///             .with_stem(drums, true)
+///             .with_stem(melody, false),
+///     );
+///     commands.insert_resource(BattleMusic(music));
+/// }
+///
+/// fn boss_appeared(battle_music: Res<BattleMusic>, mut music_controller: MusicController) {
+///     // Bring in the melody at the start of the next 4 beat bar
+///     music_controller.set_stem_enabled(battle_music.0, 1, true, 4.);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredMusic {
+    /// The tempo of the music in beats per minute
+    pub tempo: f64,
+    /// The number of beats in each bar
+    pub beats_per_bar: u32,
+    /// The number of seconds that it takes to fade a stem in or out
+    pub fade_duration: f64,
+    /// The sounds of the stems, along with whether or not each stem starts out enabled
+    pub stems: Vec<(Sound, bool)>,
+}
+
+impl LayeredMusic {
+    /// Create layered music without any stems
+    pub fn new(tempo: f64) -> Self {
+        Self {
+            tempo,
+            beats_per_bar: 4,
+            fade_duration: 0.5,
+            stems: Vec::new(),
+        }
+    }
+
+    /// Add a stem
+    pub fn with_stem(mut self, sound: Sound, enabled: bool) -> Self {
+        self.stems.push((sound, enabled));
+        self
+    }
+}
+
+/// A handle to playing [`LayeredMusic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayeredMusicHandle(Uuid);
+
+impl LayeredMusicHandle {
+    pub(crate) fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
@@ -4,6 +4,49 @@
 //! in games using [Kira]. If more control is desired you may want to look into [`bevy_kira_audio`]
 //! which grants more control over audio playback.
 //!
+//! The volume, playback rate, and panning of sounds can be changed or animated while they are
+//! playing with the [`SoundController`].
+//!
+//! The [`SoundFinished`] and [`SoundLooped`] events are sent when sounds finish playing or loop,
+//! following the audio clock, which can be used to chain sounds together.
+//!
+//! Large sound files, such as music, are decoded in the background the first time a sound is
+//! created from them, which can be configured with the [`SoundLoadSettings`] resource.
+//!
+//! The loading state of each sound is kept in the [`SoundLoadStates`] resource, so that games can
+//! wait for important sounds to be ready before starting a level.
+//!
+//! Sounds can be routed to named buses, such as music and sound effects, whose volumes are set in
+//! the [`AudioMixer`] resource. The player's master volume, mute, and bus volume settings are set in
+//! the [`AudioSettings`] resource, and with the `save` feature enabled, they can be saved
+//! automatically with
+//! [`add_saved_audio_settings`][AppBuilderSavedAudioSettingsExt::add_saved_audio_settings].
+//!
+//! On web, sounds are queued up until the user interacts with the page, as described in
+//! [`AudioLockState`].
+//!
+//! By default, all sounds are paused while the window doesn't have focus, which can be changed with
+//! the [`AudioFocusPolicy`] resource.
+//!
+//! Background music is played with the [`MusicController`], which crossfades between tracks and
+//! plays adaptive [`LayeredMusic`] with stems that fade in and out on the beat.
+//!
+//! Music with a [`MusicTempo`] sends [`Beat`] and [`Bar`] events in time with the audio clock,
+//! which can be used for rhythm gameplay and animations that follow the music.
+//!
+//! Sounds with several variations, such as footsteps, can be put in a [`SoundBank`], which plays a
+//! random variation with a random volume and pitch every time it is played.
+//!
+//! Sounds can also be played by adding an [`AudioEmitter`] component to an entity, which creates,
+//! plays, and stops the sound for you.
+//!
+//! Sounds can be played from the position of an entity with the [`SpatialAudio`] component, which
+//! attenuates and pans them relative to the entity with the [`AudioListener`] component.
+//!
+//! With the `chiptune` feature enabled, retro sounds can be synthesized from square, triangle,
+//! sawtooth, and noise waveforms, either as a single [`Tone`][crate::Tone] or as a
+//! [`ChiptuneSong`][crate::ChiptuneSong] loaded from a tiny `.chiptune` file.
+//!
 //! [`bevy_kira_audio`]: https://github.com/NiklasEi/bevy_kira_audio
 //!
 //! [Kira]: https://docs.rs/kira
@@ -15,26 +58,71 @@ pub use kira;
 mod assets;
 pub use assets::*;
 
+mod bank;
+pub use bank::*;
+
+#[cfg(feature = "chiptune")]
+mod chiptune;
+#[cfg(feature = "chiptune")]
+pub use chiptune::*;
+
 mod components;
 pub use components::*;
 
+mod emitter;
+pub use emitter::*;
+
+mod focus;
+pub use focus::*;
+
+mod layered;
+pub use layered::*;
+
+mod mixer;
+pub use mixer::*;
+
+mod rhythm;
+pub use rhythm::*;
+
+#[cfg(feature = "save")]
+mod saved_settings;
+#[cfg(feature = "save")]
+pub use saved_settings::AppBuilderSavedAudioSettingsExt;
+
+mod spatial;
+pub use spatial::*;
+
 mod systems;
 pub(crate) use systems::*;
 
+mod unlock;
+pub use unlock::*;
+
 /// Bevy Retrograde audio plugin
 #[derive(Default)]
 pub struct RetroAudioPlugin;
 
 impl Plugin for RetroAudioPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app
-            // Add audio manager resource
-            .insert_non_send_resource(AudioManager::default())
-            .add_event::<SoundEvent>();
+        app.init_resource::<AudioMixer>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<AudioFocusPolicy>()
+            .add_event::<SoundEvent>()
+            .add_event::<SoundFinished>()
+            .add_event::<SoundLooped>()
+            .add_event::<Beat>()
+            .add_event::<Bar>()
+            .add_event::<AudioUnlocked>()
+            .add_event::<SoundReady>();
 
         // Add asssets and systems
         add_assets(app);
+        #[cfg(feature = "chiptune")]
+        add_chiptune_assets(app);
+        add_unlock_systems(app);
         add_systems(app);
+        add_spatial_systems(app);
+        add_emitter_systems(app);
     }
 }
 
@@ -47,10 +135,67 @@ mod events {
     #[derive(Debug, Clone)]
     #[allow(clippy::large_enum_variant)]
     pub enum SoundEvent {
-        CreateSound(Handle<SoundData>, Sound),
-        PlaySound(Sound, PlaySoundSettings),
+        CreateSound(Handle<SoundData>, Sound, Option<LoopPoints>),
+        PlaySound(Sound, SoundInstance, PlaySoundSettings, Option<String>),
         PauseSound(Sound, PauseSoundSettings),
         ResumeSound(Sound, ResumeSoundSettings),
         StopSound(Sound, StopSoundSettings),
+        PauseInstance(SoundInstance, PauseSoundSettings),
+        ResumeInstance(SoundInstance, ResumeSoundSettings),
+        StopInstance(SoundInstance, StopSoundSettings),
+        SetProperty(Sound, SoundProperty, f64, f32),
+        SetSpatial(Sound, Option<SpatialSoundState>),
+        CrossfadeMusic(Sound, f32, Option<MusicTempo>),
+        StopMusic(f32),
+        PlayLayeredMusic(LayeredMusicHandle, LayeredMusic),
+        SetStemEnabled(LayeredMusicHandle, usize, bool, f64),
+        StopLayeredMusic(LayeredMusicHandle, f32),
+    }
+
+    /// Event sent when the data of a sound has been loaded and decoded, and it is ready to be played
+    ///
+    /// See [`SoundLoadStates`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SoundReady {
+        pub sound: Sound,
+    }
+
+    /// Event sent when an instance of a sound finishes playing
+    ///
+    /// Sounds that are stopped with the [`SoundController`] don't send this event.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SoundFinished {
+        pub sound: Sound,
+        pub instance: SoundInstance,
+    }
+
+    /// Event sent when an instance of a looping sound jumps back to its loop start
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SoundLooped {
+        pub sound: Sound,
+        pub instance: SoundInstance,
+    }
+
+    /// Event sent when the audio is unlocked on web, once the user has interacted with the page
+    ///
+    /// See [`AudioLockState`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AudioUnlocked;
+
+    /// Event sent on every beat of music that has a [`MusicTempo`], including the first beat when
+    /// the music starts
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Beat {
+        /// The number of beats since the music started
+        pub beat: u64,
+        /// The beat within the current bar, where `0` is the first beat of the bar
+        pub beat_in_bar: u32,
+    }
+
+    /// Event sent on the first beat of every bar of music that has a [`MusicTempo`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Bar {
+        /// The number of bars since the music started
+        pub bar: u64,
     }
 }
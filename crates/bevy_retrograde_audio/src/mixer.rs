@@ -0,0 +1,146 @@
+use bevy::utils::HashMap;
+
+/// The name of the bus that music is played on
+pub const MUSIC_BUS: &str = "music";
+/// The name of the bus for sound effects
+pub const SFX_BUS: &str = "sfx";
+/// The name of the bus for UI sounds
+pub const UI_BUS: &str = "ui";
+
+/// Bevy resource with the player's audio settings, such as from an options menu
+///
+/// The settings are applied to all sounds as soon as they are changed, on top of the volumes set in
+/// the [`AudioMixer`], so the game can still mix its sounds independently of the player's settings.
+///
+/// With the `serde` feature enabled, the settings can be serialized so that they can be saved and
+/// restored between sessions. Missing fields are filled in with their defaults when deserializing.
+/// With the `save` feature enabled, they can be saved automatically with
+/// [`add_saved_audio_settings`][crate::AppBuilderSavedAudioSettingsExt::add_saved_audio_settings].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn toggle_mute(keyboard: Res<Input<KeyCode>>, mut audio_settings: ResMut<AudioSettings>) {
+///     if keyboard.just_pressed(KeyCode::M) {
+///         audio_settings.muted = !audio_settings.muted;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct AudioSettings {
+    /// The volume of all sounds, where `1.0` is full volume
+    pub master_volume: f64,
+    /// Whether or not all sounds are muted
+    pub muted: bool,
+    /// The volumes of the buses of the [`AudioMixer`] by name
+    ///
+    /// Buses that aren't in the map are at full volume.
+    pub bus_volumes: HashMap<String, f64>,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            muted: false,
+            bus_volumes: Default::default(),
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Get the volume that all sounds are multiplied by, including whether or not they are muted
+    pub fn volume(&self) -> f64 {
+        if self.muted {
+            0.
+        } else {
+            self.master_volume
+        }
+    }
+
+    /// Get the volume of a bus, which is `1.0` if it hasn't been set
+    pub fn bus_volume(&self, name: &str) -> f64 {
+        self.bus_volumes.get(name).copied().unwrap_or(1.)
+    }
+}
+
+/// Bevy resource used to control the volume of groups of sounds
+///
+/// Sounds are routed to a named bus when they are played with
+/// [`play_sound_on_bus`][crate::SoundController::play_sound_on_bus], and the volume of each bus can
+/// be changed independently, such as to turn down the music during dialogue. Music played with the
+/// [`MusicController`][crate::MusicController] is routed to the [`MUSIC_BUS`].
+///
+/// The mixer starts out with the [`MUSIC_BUS`], [`SFX_BUS`], and [`UI_BUS`] buses, and more can be
+/// added with [`add_bus`][Self::add_bus]. The player's volume settings are kept separately in the
+/// [`AudioSettings`] resource.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn turn_down_music(mut mixer: ResMut<AudioMixer>) {
+///     mixer.set_bus_volume(MUSIC_BUS, 0.5);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioMixer {
+    master_volume: f64,
+    buses: HashMap<String, f64>,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        let mut mixer = Self {
+            master_volume: 1.,
+            buses: Default::default(),
+        };
+        mixer.add_bus(MUSIC_BUS);
+        mixer.add_bus(SFX_BUS);
+        mixer.add_bus(UI_BUS);
+
+        mixer
+    }
+}
+
+impl AudioMixer {
+    /// Add a bus with a volume of `1.0`, if it doesn't already exist
+    pub fn add_bus<N: Into<String>>(&mut self, name: N) {
+        self.buses.entry(name.into()).or_insert(1.);
+    }
+
+    /// Get the volume of a bus, or [`None`] if the bus doesn't exist
+    pub fn bus_volume(&self, name: &str) -> Option<f64> {
+        self.buses.get(name).copied()
+    }
+
+    /// Set the volume of a bus, adding the bus if it doesn't exist
+    pub fn set_bus_volume<N: Into<String>>(&mut self, name: N, volume: f64) {
+        self.buses.insert(name.into(), volume);
+    }
+
+    /// Get the names and volumes of the buses
+    pub fn buses(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.buses
+            .iter()
+            .map(|(name, volume)| (name.as_str(), *volume))
+    }
+
+    /// Get the volume that all sounds are multiplied by
+    pub fn master_volume(&self) -> f64 {
+        self.master_volume
+    }
+
+    /// Set the volume that all sounds are multiplied by
+    pub fn set_master_volume(&mut self, volume: f64) {
+        self.master_volume = volume;
+    }
+}
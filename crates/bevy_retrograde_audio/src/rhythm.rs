@@ -0,0 +1,51 @@
+/// The tempo and time signature of a music track, used to send [`Beat`][crate::Beat] and
+/// [`Bar`][crate::Bar] events in time with the music
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn start_music(
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+///     mut music_controller: MusicController,
+/// ) {
+///     let music = sound_controller.create_sound(&asset_server.load("dance.ogg"));
+///     music_controller.crossfade_with_tempo(music, 0., MusicTempo::new(128.));
+/// }
+///
+/// struct Dancer;
+///
+/// fn pulse_on_beat(mut beats: EventReader<Beat>, mut dancers: Query<&mut Transform, With<Dancer>>) {
+///     for beat in beats.iter() {
+///         let scale = if beat.beat_in_bar == 0 { 1.5 } else { 1.2 };
+///         for mut transform in dancers.iter_mut() {
+///             transform.scale = Vec3::splat(scale);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MusicTempo {
+    /// The tempo in beats per minute
+    pub bpm: f64,
+    /// The number of beats in each bar
+    pub beats_per_bar: u32,
+}
+
+impl MusicTempo {
+    /// Create a tempo with four beats per bar
+    pub fn new(bpm: f64) -> Self {
+        Self {
+            bpm,
+            beats_per_bar: 4,
+        }
+    }
+
+    /// Set the number of beats in each bar
+    pub fn with_beats_per_bar(mut self, beats_per_bar: u32) -> Self {
+        self.beats_per_bar = beats_per_bar;
+        self
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use bevy_retrograde_save::AppBuilderSettingsExt;
+
+use crate::AudioSettings;
+
+/// Bevy [`AppBuilder`] extension for saving the player's [`AudioSettings`]
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// # use bevy_retrograde_save::RetroSavePlugin;
+/// fn main() {
+///     App::build()
+///         .add_plugin(RetroSavePlugin)
+///         .add_plugin(RetroAudioPlugin)
+///         .add_saved_audio_settings("audio_settings");
+/// }
+/// ```
+pub trait AppBuilderSavedAudioSettingsExt {
+    /// Load the [`AudioSettings`] from the save slot with the given name, or use the default
+    /// settings if nothing has been saved yet, and save them whenever they change
+    ///
+    /// The settings are kept as [`Settings<AudioSettings>`][bevy_retrograde_save::Settings], which
+    /// are copied to the [`AudioSettings`] resource, so either resource can be changed to change
+    /// the settings.
+    fn add_saved_audio_settings(self, slot: &'static str) -> Self;
+}
+
+impl AppBuilderSavedAudioSettingsExt for &mut AppBuilder {
+    fn add_saved_audio_settings(self, slot: &'static str) -> Self {
+        self.add_synced_settings(slot, AudioSettings::default())
+    }
+}
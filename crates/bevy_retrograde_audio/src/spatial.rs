@@ -0,0 +1,195 @@
+use bevy::{prelude::*, transform::TransformSystem, utils::HashMap};
+
+use super::*;
+
+pub(crate) fn add_spatial_systems(app: &mut AppBuilder) {
+    app.add_system_to_stage(
+        CoreStage::PostUpdate,
+        update_spatial_audio
+            .system()
+            .after(TransformSystem::TransformPropagate),
+    );
+}
+
+/// Component that marks the entity that spatial sounds are heard from, usually the camera
+///
+/// If there is more than one listener, the first one found is used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioListener;
+
+/// Component that makes a sound play from the position of its entity
+///
+/// Every frame, all of the playing instances of the sound are attenuated by the distance from the
+/// entity to the [`AudioListener`], and panned to the left or the right depending on which side of
+/// the listener the entity is on. If there is no listener, the sound is played at full volume and
+/// isn't panned.
+///
+/// The volume follows the [`SpatialFalloff`] curve from full volume at `min_distance` to the edge of
+/// the `range`. With `cutoff` enabled, the instances of the sound are paused while the entity is
+/// out of range, so that distant sounds don't use any processing, and resume once it comes back in
+/// range.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// fn setup(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     mut sound_controller: SoundController,
+/// ) {
+///     let sound = sound_controller.create_sound(&asset_server.load("waterfall.ogg"));
+///     sound_controller.play_sound(sound);
+///
+///     commands
+///         .spawn()
+///         .insert(Transform::from_xyz(100., 0., 0.))
+///         .insert(GlobalTransform::default())
+///         .insert(SpatialAudio::new(sound));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialAudio {
+    /// The sound played from the entity
+    pub sound: Sound,
+    /// The distance in pixels from the listener at which the sound can no longer be heard
+    pub range: f32,
+    /// The distance in pixels from the listener within which the sound plays at full volume
+    pub min_distance: f32,
+    /// How the volume decreases between `min_distance` and `range`
+    pub falloff: SpatialFalloff,
+    /// Whether or not to pause the sound while it is out of range
+    pub cutoff: bool,
+    /// The distance in pixels to the left or the right of the listener at which the sound is
+    /// panned all the way to one side
+    pub pan_range: f32,
+}
+
+impl SpatialAudio {
+    /// Play a sound from the entity with the default ranges
+    pub fn new(sound: Sound) -> Self {
+        Self {
+            sound,
+            range: 300.,
+            min_distance: 0.,
+            falloff: SpatialFalloff::Linear,
+            cutoff: true,
+            pan_range: 200.,
+        }
+    }
+
+    /// Get the volume of the sound at a distance from the listener, from `0.0` to `1.0`
+    pub fn volume_at(&self, distance: f32) -> f32 {
+        if self.cutoff && distance > self.range {
+            return 0.;
+        }
+
+        let min = self.min_distance.max(0.);
+        let max = self.range.max(min + f32::EPSILON);
+        let distance = distance.clamp(min, max);
+
+        match self.falloff {
+            SpatialFalloff::Linear => 1. - (distance - min) / (max - min),
+            SpatialFalloff::Inverse { rolloff } => {
+                let min = min.max(1.);
+                min / (min + rolloff * (distance - min).max(0.))
+            }
+            SpatialFalloff::Custom(curve) => curve((distance - min) / (max - min)),
+        }
+        .clamp(0., 1.)
+    }
+}
+
+/// How the volume of a [`SpatialAudio`] sound decreases with its distance from the listener
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpatialFalloff {
+    /// The volume decreases evenly until it is silent at the edge of the range
+    Linear,
+    /// The volume decreases quickly at first and then more slowly, like sound in the real world
+    ///
+    /// Higher `rolloff` values make the volume decrease faster. Because the volume never reaches
+    /// zero, the sound should usually have `cutoff` enabled.
+    Inverse { rolloff: f32 },
+    /// A custom curve that takes the distance from `0.0` at the minimum distance to `1.0` at the
+    /// edge of the range, and returns the volume
+    Custom(fn(f32) -> f32),
+}
+
+impl Default for SpatialFalloff {
+    fn default() -> Self {
+        SpatialFalloff::Linear
+    }
+}
+
+/// The volume and panning of a spatial sound, relative to the listener
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialSoundState {
+    /// The volume multiplier from the distance to the listener, from `0.0` to `1.0`
+    pub volume: f64,
+    /// The panning of the sound, where `0.0` is hard left, `0.5` is centered, and `1.0` is hard
+    /// right
+    pub panning: f64,
+    /// Whether or not the sound is within range of the listener, or not cut off
+    pub audible: bool,
+}
+
+impl Default for SpatialSoundState {
+    fn default() -> Self {
+        Self {
+            volume: 1.,
+            panning: 0.5,
+            audible: true,
+        }
+    }
+}
+
+fn update_spatial_audio(
+    listeners: Query<&GlobalTransform, With<AudioListener>>,
+    sounds: Query<(Entity, &SpatialAudio, &GlobalTransform)>,
+    removed_sounds: RemovedComponents<SpatialAudio>,
+    mut entity_sounds: Local<HashMap<Entity, (Sound, SpatialSoundState)>>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    // Stop making sounds spatial when their component is removed
+    for ent in removed_sounds.iter() {
+        if let Some((sound, _)) = entity_sounds.remove(&ent) {
+            sound_events.send(SoundEvent::SetSpatial(sound, None));
+        }
+    }
+
+    let listener_pos = listeners.iter().next().map(|x| x.translation.truncate());
+
+    for (ent, spatial, transform) in sounds.iter() {
+        let state = if let Some(listener_pos) = listener_pos {
+            let offset = transform.translation.truncate() - listener_pos;
+            let distance = offset.length();
+
+            SpatialSoundState {
+                volume: spatial.volume_at(distance) as f64,
+                panning: 0.5
+                    + (offset.x / spatial.pan_range.max(f32::EPSILON)).clamp(-1., 1.) as f64 * 0.5,
+                audible: !spatial.cutoff || distance <= spatial.range,
+            }
+        } else {
+            SpatialSoundState::default()
+        };
+
+        // Keep track of which sound the entity plays and the state last sent for it, so that the
+        // sound is only updated when something actually changed
+        match entity_sounds.insert(ent, (spatial.sound, state)) {
+            Some((previous_sound, previous_state))
+                if previous_sound == spatial.sound && previous_state == state =>
+            {
+                continue
+            }
+            Some((previous_sound, _)) if previous_sound != spatial.sound => {
+                sound_events.send(SoundEvent::SetSpatial(previous_sound, None));
+            }
+            _ => (),
+        }
+
+        sound_events.send(SoundEvent::SetSpatial(spatial.sound, Some(state)));
+    }
+}
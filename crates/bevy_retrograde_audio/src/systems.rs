@@ -1,9 +1,21 @@
 use bevy::{
     app::{Events, ManualEventReader},
+    asset::{HandleId, LoadState},
     prelude::*,
-    utils::HashMap,
+    tasks::{AsyncComputeTaskPool, Task},
+    utils::{HashMap, HashSet},
+    window::WindowFocused,
+};
+use futures_lite::future;
+use kira::{
+    instance::{handle::InstanceHandle, InstanceState},
+    metronome::{handle::MetronomeHandle, MetronomeSettings},
+    mixer::{SubTrackSettings, TrackHandle},
+    parameter::{handle::ParameterHandle, tween::Tween, ParameterSettings},
+    sequence::{Sequence, SequenceInstanceSettings, SequenceSettings},
+    sound::{handle::SoundHandle as KiraSoundHandle, Sound as KiraSound},
+    Tempo, Value,
 };
-use kira::sound::handle::SoundHandle as KiraSoundHandle;
 
 use super::*;
 
@@ -19,23 +31,535 @@ pub(crate) fn add_systems(app: &mut AppBuilder) {
     );
 }
 
+/// A playing instance of a sound
+struct PlayingInstance {
+    id: SoundInstance,
+    handle: InstanceHandle,
+    /// The volume that the instance was played with
+    volume: f64,
+    /// The playback rate that the instance was played with
+    playback_rate: f64,
+    /// The panning that the instance was played with
+    panning: f64,
+    /// Whether or not the instance plays backwards
+    reverse: bool,
+    /// The playback position of the instance the last time it was checked
+    position: f64,
+    /// Whether or not the instance was stopped with the [`SoundController`], in which case it
+    /// doesn't count as having finished
+    stopped: bool,
+    /// Whether or not the instance was paused because the window lost focus
+    focus_paused: bool,
+    /// Whether or not the instance was paused because its spatial sound is out of range
+    spatial_paused: bool,
+    /// Whether or not the volume of the instance is controlled by a kira parameter, in which case
+    /// the volume properties of its sound are ignored
+    volume_is_parameter: bool,
+    /// The volume, playback rate, and panning last applied to the instance handle, so that kira
+    /// is only told about values that changed
+    applied_volume: Option<f64>,
+    applied_playback_rate: Option<f64>,
+    applied_panning: Option<f64>,
+}
+
+impl PlayingInstance {
+    fn new(id: SoundInstance, handle: InstanceHandle, settings: &PlaySoundSettings) -> Self {
+        let fixed_or = |value: Value<f64>, default: f64| match value {
+            Value::Fixed(value) => value,
+            _ => default,
+        };
+
+        Self {
+            id,
+            handle,
+            volume: fixed_or(settings.volume, 1.),
+            playback_rate: fixed_or(settings.playback_rate, 1.),
+            panning: fixed_or(settings.panning, 0.5),
+            reverse: settings.reverse,
+            position: settings.start_position,
+            stopped: false,
+            focus_paused: false,
+            spatial_paused: false,
+            volume_is_parameter: matches!(settings.volume, Value::Parameter(..)),
+            applied_volume: None,
+            applied_playback_rate: None,
+            applied_panning: None,
+        }
+    }
+
+    /// Check whether or not the instance has jumped back to its loop start since the last time
+    /// this was called
+    fn check_looped(&mut self) -> bool {
+        let position = self.handle.position();
+        let looped = if self.reverse {
+            position > self.position
+        } else {
+            position < self.position
+        };
+        self.position = position;
+
+        looped
+    }
+
+    /// Update the volume, playback rate, and panning of the instance from the properties of its
+    /// sound
+    fn apply(&mut self, state: &SoundState) {
+        let spatial = state.spatial.unwrap_or_default();
+
+        if !self.volume_is_parameter {
+            let volume = self.volume * state.volume.value * spatial.volume;
+            if self.applied_volume != Some(volume) {
+                if let Err(e) = self.handle.set_volume(volume) {
+                    warn!("Could not set sound instance volume: {}", e);
+                }
+                self.applied_volume = Some(volume);
+            }
+        }
+
+        let playback_rate = self.playback_rate * state.playback_rate.value;
+        if self.applied_playback_rate != Some(playback_rate) {
+            if let Err(e) = self.handle.set_playback_rate(playback_rate) {
+                warn!("Could not set sound instance playback rate: {}", e);
+            }
+            self.applied_playback_rate = Some(playback_rate);
+        }
+
+        let panning =
+            (self.panning + (state.panning.value - 0.5) + (spatial.panning - 0.5)).clamp(0., 1.);
+        if self.applied_panning != Some(panning) {
+            if let Err(e) = self.handle.set_panning(panning) {
+                warn!("Could not set sound instance panning: {}", e);
+            }
+            self.applied_panning = Some(panning);
+        }
+
+        // Pause the instance while it is out of range of the listener, without resuming instances
+        // that are also paused because the window lost focus
+        if !spatial.audible
+            && !self.spatial_paused
+            && (self.focus_paused || matches!(self.handle.state(), InstanceState::Playing))
+        {
+            if !self.focus_paused {
+                if let Err(e) = self.handle.pause(Default::default()) {
+                    warn!("Could not pause out of range sound instance: {}", e);
+                }
+            }
+            self.spatial_paused = true;
+        } else if spatial.audible && self.spatial_paused {
+            if !self.focus_paused {
+                if let Err(e) = self.handle.resume(Default::default()) {
+                    warn!("Could not resume sound instance back in range: {}", e);
+                }
+            }
+            self.spatial_paused = false;
+        }
+    }
+}
+
+/// A sound property that may be animated towards a target value
+struct AnimatedProperty {
+    value: f64,
+    start: f64,
+    target: f64,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl AnimatedProperty {
+    fn new(value: f64) -> Self {
+        Self {
+            value,
+            start: value,
+            target: value,
+            elapsed: 0.,
+            duration: 0.,
+        }
+    }
+
+    /// Start animating the property towards `target` over `duration` seconds
+    fn animate_to(&mut self, target: f64, duration: f32) {
+        self.start = self.value;
+        self.target = target;
+        self.elapsed = 0.;
+        self.duration = duration;
+        self.advance(0.);
+    }
+
+    /// Advance the animation, returning whether or not the value has changed
+    fn advance(&mut self, delta: f32) -> bool {
+        let previous_value = self.value;
+
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+        self.value = if self.duration > 0. {
+            self.start + (self.target - self.start) * (self.elapsed / self.duration) as f64
+        } else {
+            self.target
+        };
+
+        (self.value - previous_value).abs() > f64::EPSILON
+    }
+}
+
+/// The properties and playing instances of a sound
+struct SoundState {
+    volume: AnimatedProperty,
+    playback_rate: AnimatedProperty,
+    panning: AnimatedProperty,
+    spatial: Option<SpatialSoundState>,
+    instances: Vec<PlayingInstance>,
+}
+
+impl Default for SoundState {
+    fn default() -> Self {
+        Self {
+            volume: AnimatedProperty::new(1.),
+            playback_rate: AnimatedProperty::new(1.),
+            panning: AnimatedProperty::new(0.5),
+            spatial: None,
+            instances: Vec::new(),
+        }
+    }
+}
+
+impl SoundState {
+    fn property_mut(&mut self, property: SoundProperty) -> &mut AnimatedProperty {
+        match property {
+            SoundProperty::Volume => &mut self.volume,
+            SoundProperty::PlaybackRate => &mut self.playback_rate,
+            SoundProperty::Panning => &mut self.panning,
+        }
+    }
+
+    /// Advance the property animations, returning whether or not any of the properties changed
+    fn advance(&mut self, delta: f32) -> bool {
+        // Advance all of the properties without short-circuiting
+        let volume_changed = self.volume.advance(delta);
+        let playback_rate_changed = self.playback_rate.advance(delta);
+        let panning_changed = self.panning.advance(delta);
+
+        volume_changed || playback_rate_changed || panning_changed
+    }
+
+    /// Play a new instance of the sound
+    fn play(
+        &mut self,
+        sound_handle: &mut KiraSoundHandle,
+        id: SoundInstance,
+        settings: &PlaySoundSettings,
+    ) {
+        let mut instance =
+            PlayingInstance::new(id, sound_handle.play(*settings).unwrap(), settings);
+        instance.apply(self);
+        self.instances.push(instance);
+    }
+
+    /// Stop all of the playing instances of the sound
+    fn stop(&mut self, sound_handle: &mut KiraSoundHandle, settings: &StopSoundSettings) {
+        sound_handle.stop(*settings).unwrap();
+        for instance in &mut self.instances {
+            instance.stopped = true;
+        }
+    }
+
+    /// Update all of the playing instances of the sound
+    fn apply(&mut self) {
+        let mut instances = std::mem::take(&mut self.instances);
+        for instance in &mut instances {
+            instance.apply(self);
+        }
+        self.instances = instances;
+    }
+}
+
+/// A metronome that sends [`Beat`] and [`Bar`] events in time with a piece of music
+struct MusicMetronome {
+    handle: MetronomeHandle,
+    beats_per_bar: u32,
+    /// The number of beats that have been sent
+    beats: u64,
+}
+
+impl MusicMetronome {
+    /// Add a metronome to the audio manager without starting it
+    fn new(audio_manager: &mut AudioManager, tempo: MusicTempo) -> Self {
+        let handle = audio_manager
+            .0
+            .add_metronome(
+                MetronomeSettings::new()
+                    .tempo(Tempo(tempo.bpm))
+                    .interval_events_to_emit(vec![1.]),
+            )
+            .unwrap();
+
+        Self {
+            handle,
+            beats_per_bar: tempo.beats_per_bar.max(1),
+            beats: 0,
+        }
+    }
+
+    /// Start the metronome, sending the first beat right away
+    ///
+    /// This should be called right after the music is played so that the metronome starts at the
+    /// same time as the music on the audio thread.
+    fn start(&mut self, beat_events: &mut Events<Beat>, bar_events: &mut Events<Bar>) {
+        self.handle.start().unwrap();
+        self.send_beat(beat_events, bar_events);
+    }
+
+    /// Send events for the beats that the metronome has passed on the audio thread
+    fn update(&mut self, beat_events: &mut Events<Beat>, bar_events: &mut Events<Bar>) {
+        while let Ok(Some(_)) = self.handle.pop_event() {
+            self.send_beat(beat_events, bar_events);
+        }
+    }
+
+    fn send_beat(&mut self, beat_events: &mut Events<Beat>, bar_events: &mut Events<Bar>) {
+        let beats_per_bar = self.beats_per_bar as u64;
+        let beat_in_bar = (self.beats % beats_per_bar) as u32;
+
+        beat_events.send(Beat {
+            beat: self.beats,
+            beat_in_bar,
+        });
+        if beat_in_bar == 0 {
+            bar_events.send(Bar {
+                bar: self.beats / beats_per_bar,
+            });
+        }
+
+        self.beats += 1;
+    }
+
+    /// Remove the metronome from the audio manager
+    fn remove(self, audio_manager: &mut AudioManager) {
+        audio_manager.0.remove_metronome(self.handle.id()).unwrap();
+    }
+}
+
+/// A playing piece of [`LayeredMusic`]
+struct LayeredMusicState {
+    metronome: MusicMetronome,
+    fade_duration: f64,
+    /// The sounds of the stems and the parameters that control their volumes
+    stems: Vec<(Sound, ParameterHandle)>,
+}
+
+/// Get the loop start for music, which loops from its loop points if it has any, or from the
+/// beginning otherwise
+fn music_loop_start(sound_handle: &KiraSoundHandle) -> LoopStart {
+    if sound_handle.default_loop_start().is_some() {
+        LoopStart::Default
+    } else {
+        LoopStart::Custom(0.)
+    }
+}
+
+/// Find a playing sound instance, returning [`None`] if it has already finished playing
+fn find_instance<'a>(
+    sound_states: &'a mut HashMap<Sound, SoundState>,
+    id: &SoundInstance,
+) -> Option<&'a mut PlayingInstance> {
+    sound_states
+        .values_mut()
+        .flat_map(|x| &mut x.instances)
+        .find(|x| x.id == *id)
+}
+
 fn get_handle_sound_events_system() -> impl FnMut(&mut World) {
     let mut audio_event_reader = ManualEventReader::<SoundEvent>::default();
     let mut sound_to_handle_map = HashMap::<Sound, KiraSoundHandle>::default();
+    let mut sound_states = HashMap::<Sound, SoundState>::default();
+    let mut current_music = None::<Sound>;
+    let mut music_metronome = None::<MusicMetronome>;
+    let mut bus_tracks = HashMap::<String, TrackHandle>::default();
+    let mut applied_mixer = None::<(AudioMixer, AudioSettings)>;
+    let mut decoding_tasks =
+        HashMap::<HandleId, Task<Result<KiraSound, SoundDataLoaderError>>>::default();
+    let mut failed_sound_data = HashSet::<HandleId>::default();
+    let mut pending_instances = HashSet::<SoundInstance>::default();
+    let mut layered_music = HashMap::<LayeredMusicHandle, LayeredMusicState>::default();
+    let mut pending_layered_music = HashSet::<LayeredMusicHandle>::default();
+    let mut focus_event_reader = ManualEventReader::<WindowFocused>::default();
+    let mut focus_volume = 1.;
     let mut pending_events = Vec::<SoundEvent>::new();
 
     move |world| {
         let world = world.cell();
-        let mut audio_manager = world.get_non_send_mut::<AudioManager>().unwrap();
         let audio_events = world.get_resource::<Events<SoundEvent>>().unwrap();
+
+        // Queue up sound events until the audio has been unlocked
+        let mut audio_manager = match world.get_non_send_mut::<AudioManager>() {
+            Some(audio_manager) => audio_manager,
+            None => {
+                pending_events.extend(audio_event_reader.iter(&audio_events).cloned());
+                return;
+            }
+        };
         let mut sound_data_assets = world.get_resource_mut::<Assets<SoundData>>().unwrap();
+        let time = world.get_resource::<Time>().unwrap();
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        let mut load_states = world.get_resource_mut::<SoundLoadStates>().unwrap();
+        let mut ready_events = world.get_resource_mut::<Events<SoundReady>>().unwrap();
+        let mut finished_events = world.get_resource_mut::<Events<SoundFinished>>().unwrap();
+        let mut looped_events = world.get_resource_mut::<Events<SoundLooped>>().unwrap();
+        let mixer = world.get_resource::<AudioMixer>().unwrap();
+        let audio_settings = world.get_resource::<AudioSettings>().unwrap();
+        let task_pool = world.get_resource::<AsyncComputeTaskPool>().unwrap();
+        let focus_policy = world.get_resource::<AudioFocusPolicy>().unwrap();
+        let focus_events = world.get_resource::<Events<WindowFocused>>().unwrap();
+        let mut beat_events = world.get_resource_mut::<Events<Beat>>().unwrap();
+        let mut bar_events = world.get_resource_mut::<Events<Bar>>().unwrap();
+
+        // Pause or duck the audio when the window loses focus, and restore it when the window gets
+        // focus again
+        if let Some(focused) = focus_event_reader
+            .iter(&focus_events)
+            .last()
+            .map(|x| x.focused)
+        {
+            for instance in sound_states.values_mut().flat_map(|x| &mut x.instances) {
+                if focused && instance.focus_paused {
+                    if !instance.spatial_paused {
+                        instance.handle.resume(Default::default()).unwrap();
+                    }
+                    instance.focus_paused = false;
+                } else if !focused
+                    && *focus_policy == AudioFocusPolicy::Pause
+                    && matches!(instance.handle.state(), InstanceState::Playing)
+                {
+                    instance.handle.pause(Default::default()).unwrap();
+                    instance.focus_paused = true;
+                }
+            }
+
+            focus_volume = match *focus_policy {
+                AudioFocusPolicy::Duck(volume) if !focused => volume,
+                _ => 1.,
+            };
+            // Make sure the new volume is applied to the mixer
+            applied_mixer = None;
+        }
+
+        // Replace sound data that has finished decoding in the background with the decoded sound
+        decoding_tasks.retain(
+            |&id, task| match future::block_on(future::poll_once(task)) {
+                Some(Ok(sound)) => {
+                    sound_data_assets.set_untracked(id, SoundData::Sound(sound));
+                    false
+                }
+                Some(Err(e)) => {
+                    error!("Could not decode sound: {}", e);
+                    failed_sound_data.insert(id);
+                    false
+                }
+                None => true,
+            },
+        );
+
+        // Update the mixer tracks if the mixer or the audio settings have changed, adding tracks for
+        // any new buses
+        if applied_mixer
+            .as_ref()
+            .map(|(mixer, settings)| (mixer, settings))
+            != Some((&*mixer, &*audio_settings))
+        {
+            audio_manager
+                .0
+                .main_track()
+                .set_volume(mixer.master_volume() * audio_settings.volume() * focus_volume)
+                .unwrap();
+
+            for (name, volume) in mixer.buses() {
+                let track = bus_tracks.entry(name.to_string()).or_insert_with(|| {
+                    audio_manager
+                        .0
+                        .add_sub_track(SubTrackSettings::new())
+                        .expect("Could not add audio bus")
+                });
+                track
+                    .set_volume(volume * audio_settings.bus_volume(name))
+                    .unwrap();
+            }
+
+            applied_mixer = Some((mixer.clone(), audio_settings.clone()));
+        }
+
+        // Send events for instances that have looped or finished playing, forget about the
+        // finished instances, and advance property animations
+        for (&sound, state) in sound_states.iter_mut() {
+            for instance in &mut state.instances {
+                if instance.check_looped() {
+                    looped_events.send(SoundLooped {
+                        sound,
+                        instance: instance.id,
+                    });
+                }
+            }
+
+            state.instances.retain(|instance| {
+                if !matches!(instance.handle.state(), InstanceState::Stopped) {
+                    return true;
+                }
+                if !instance.stopped {
+                    finished_events.send(SoundFinished {
+                        sound,
+                        instance: instance.id,
+                    });
+                }
+                false
+            });
+
+            if state.advance(time.delta_seconds()) {
+                state.apply();
+            }
+        }
+
+        // Send beat and bar events for the music that is playing
+        for metronome in music_metronome
+            .iter_mut()
+            .chain(layered_music.values_mut().map(|x| &mut x.metronome))
+        {
+            metronome.update(&mut beat_events, &mut bar_events);
+        }
 
         let mut handle_event = |event: &SoundEvent| match event {
-            SoundEvent::CreateSound(sound_data_asset_handle, sound) => {
+            SoundEvent::CreateSound(sound_data_asset_handle, sound, loop_points) => {
+                // Give up on sounds whose data can't be loaded or decoded
+                if failed_sound_data.contains(&sound_data_asset_handle.id)
+                    || asset_server.get_load_state(sound_data_asset_handle) == LoadState::Failed
+                {
+                    load_states.set(*sound, SoundLoadState::Failed);
+                    return true;
+                }
+
                 if let Some(sound_data) = sound_data_assets.remove(sound_data_asset_handle) {
                     let sound_handle = match sound_data {
-                        SoundData::Sound(sound) => audio_manager.0.add_sound(sound).unwrap(),
-                        SoundData::SoundHandle(handle) => handle,
+                        SoundData::Sound(sound) => {
+                            let sound = match loop_points {
+                                Some(loop_points) => loop_points.apply(&sound),
+                                None => sound,
+                            };
+                            audio_manager.0.add_sound(sound).unwrap()
+                        }
+                        // Decode the sound in the background and create the sound once it's done
+                        SoundData::BackgroundDecoded(undecoded) => {
+                            decoding_tasks.insert(
+                                sound_data_asset_handle.id,
+                                task_pool.spawn(async move { undecoded.decode() }),
+                            );
+                            load_states.set(*sound, SoundLoadState::Decoding);
+                            return false;
+                        }
+                        SoundData::SoundHandle(handle) => {
+                            if loop_points.is_some() {
+                                warn!(
+                                    "Ignoring loop points for sound data that has already been \
+                                    used to create a sound"
+                                );
+                            }
+                            handle
+                        }
                     };
 
                     sound_data_assets.set_untracked(
@@ -44,23 +568,52 @@ fn get_handle_sound_events_system() -> impl FnMut(&mut World) {
                     );
 
                     sound_to_handle_map.insert(*sound, sound_handle);
+                    load_states.set(*sound, SoundLoadState::Ready);
+                    ready_events.send(SoundReady { sound: *sound });
 
                     true
                 } else {
+                    // The sound data is either still loading or being decoded for another sound
+                    if decoding_tasks.contains_key(&sound_data_asset_handle.id) {
+                        load_states.set(*sound, SoundLoadState::Decoding);
+                    }
                     false
                 }
             }
-            SoundEvent::PlaySound(sound, settings) => {
+            SoundEvent::PlaySound(sound, instance, settings, bus) => {
                 if let Some(sound_handle) = sound_to_handle_map.get_mut(sound) {
-                    sound_handle.play(*settings).unwrap();
+                    // Route the sound to its bus
+                    let mut settings = *settings;
+                    if let Some(bus) = bus {
+                        if let Some(track) = bus_tracks.get(bus) {
+                            settings = settings.track(track.index());
+                        } else {
+                            warn!("Playing sound on non-existent audio bus: {}", bus);
+                        }
+                    }
+
+                    sound_states.entry(*sound).or_default().play(
+                        sound_handle,
+                        *instance,
+                        &settings,
+                    );
+                    pending_instances.remove(instance);
                     true
                 } else {
+                    // Keep track of the instance so that commands for it wait until it's played
+                    pending_instances.insert(*instance);
                     false
                 }
             }
             SoundEvent::PauseSound(sound, settings) => {
                 if let Some(sound_handle) = sound_to_handle_map.get_mut(sound) {
                     sound_handle.pause(*settings).unwrap();
+                    // Keep the instances paused even if they come back in range
+                    if let Some(state) = sound_states.get_mut(sound) {
+                        for instance in &mut state.instances {
+                            instance.spatial_paused = false;
+                        }
+                    }
                     true
                 } else {
                     false
@@ -69,6 +622,13 @@ fn get_handle_sound_events_system() -> impl FnMut(&mut World) {
             SoundEvent::ResumeSound(sound, settings) => {
                 if let Some(sound_handle) = sound_to_handle_map.get_mut(sound) {
                     sound_handle.resume(*settings).unwrap();
+                    // Pause the instances again if they are out of range
+                    if let Some(state) = sound_states.get_mut(sound) {
+                        for instance in &mut state.instances {
+                            instance.spatial_paused = false;
+                        }
+                        state.apply();
+                    }
                     true
                 } else {
                     false
@@ -76,12 +636,252 @@ fn get_handle_sound_events_system() -> impl FnMut(&mut World) {
             }
             SoundEvent::StopSound(sound, settings) => {
                 if let Some(sound_handle) = sound_to_handle_map.get_mut(sound) {
-                    sound_handle.stop(*settings).unwrap();
+                    sound_states
+                        .entry(*sound)
+                        .or_default()
+                        .stop(sound_handle, settings);
                     true
                 } else {
                     false
                 }
             }
+            SoundEvent::CrossfadeMusic(sound, duration, tempo) => {
+                // Wait for the new music to be created
+                if !sound_to_handle_map.contains_key(sound) {
+                    return false;
+                }
+
+                // Keep playing the music if it is already the current music
+                if current_music == Some(*sound) {
+                    return true;
+                }
+
+                let fade_tween = if *duration > 0. {
+                    Some(Tween::linear(*duration as f64))
+                } else {
+                    None
+                };
+
+                // Fade out the old music, after which kira will clean up its instances
+                if let Some(metronome) = music_metronome.take() {
+                    metronome.remove(&mut audio_manager);
+                }
+                if let Some(old_music) = current_music.take() {
+                    if let Some(sound_handle) = sound_to_handle_map.get_mut(&old_music) {
+                        sound_states.entry(old_music).or_default().stop(
+                            sound_handle,
+                            &StopSoundSettings::new().fade_tween(fade_tween),
+                        );
+                    }
+                }
+
+                // Fade in the new music, looping from its loop start if it has loop points, or
+                // from the beginning otherwise
+                let sound_handle = sound_to_handle_map.get_mut(sound).unwrap();
+                let mut settings = PlaySoundSettings::new()
+                    .loop_start(music_loop_start(sound_handle))
+                    .fade_in_tween(fade_tween);
+                if let Some(track) = bus_tracks.get(MUSIC_BUS) {
+                    settings = settings.track(track.index());
+                }
+                sound_states.entry(*sound).or_default().play(
+                    sound_handle,
+                    SoundInstance::new(),
+                    &settings,
+                );
+                current_music = Some(*sound);
+
+                if let Some(tempo) = tempo {
+                    let mut metronome = MusicMetronome::new(&mut audio_manager, *tempo);
+                    metronome.start(&mut beat_events, &mut bar_events);
+                    music_metronome = Some(metronome);
+                }
+
+                true
+            }
+            SoundEvent::PlayLayeredMusic(handle, music) => {
+                // Wait for all of the stems to be created
+                if !music
+                    .stems
+                    .iter()
+                    .all(|(sound, _)| sound_to_handle_map.contains_key(sound))
+                {
+                    pending_layered_music.insert(*handle);
+                    return false;
+                }
+                pending_layered_music.remove(handle);
+
+                let mut metronome = MusicMetronome::new(
+                    &mut audio_manager,
+                    MusicTempo {
+                        bpm: music.tempo,
+                        beats_per_bar: music.beats_per_bar,
+                    },
+                );
+
+                // Start all of the stems together, with the volume of each stem controlled by a
+                // parameter so that it can be faded in and out
+                let mut stems = Vec::new();
+                for (sound, enabled) in &music.stems {
+                    let parameter = audio_manager
+                        .0
+                        .add_parameter(ParameterSettings::new().value(if *enabled {
+                            1.
+                        } else {
+                            0.
+                        }))
+                        .unwrap();
+
+                    let sound_handle = sound_to_handle_map.get_mut(sound).unwrap();
+                    let mut settings = PlaySoundSettings::new()
+                        .volume(Value::Parameter(parameter.id(), Default::default()))
+                        .loop_start(music_loop_start(sound_handle));
+                    if let Some(track) = bus_tracks.get(MUSIC_BUS) {
+                        settings = settings.track(track.index());
+                    }
+                    sound_states.entry(*sound).or_default().play(
+                        sound_handle,
+                        SoundInstance::new(),
+                        &settings,
+                    );
+
+                    stems.push((*sound, parameter));
+                }
+                metronome.start(&mut beat_events, &mut bar_events);
+
+                layered_music.insert(
+                    *handle,
+                    LayeredMusicState {
+                        metronome,
+                        fade_duration: music.fade_duration,
+                        stems,
+                    },
+                );
+
+                true
+            }
+            SoundEvent::SetStemEnabled(handle, ..) | SoundEvent::StopLayeredMusic(handle, _)
+                if pending_layered_music.contains(handle) =>
+            {
+                false
+            }
+            SoundEvent::SetStemEnabled(handle, stem, enabled, interval) => {
+                if let Some(music) = layered_music.get_mut(handle) {
+                    if let Some((_, parameter)) = music.stems.get_mut(*stem) {
+                        let target = if *enabled { 1. } else { 0. };
+                        let tween = Some(Tween::linear(music.fade_duration));
+
+                        if *interval > 0. {
+                            // Use a sequence to wait for the next interval on the audio thread
+                            let mut sequence = Sequence::<()>::new(SequenceSettings::new());
+                            sequence.wait_for_interval(*interval);
+                            sequence.set_parameter(parameter.id(), target, tween);
+                            audio_manager
+                                .0
+                                .start_sequence(
+                                    sequence,
+                                    SequenceInstanceSettings::new()
+                                        .metronome(&music.metronome.handle),
+                                )
+                                .unwrap();
+                        } else {
+                            parameter.set(target, tween).unwrap();
+                        }
+                    } else {
+                        warn!("Layered music doesn't have a stem with index {}", stem);
+                    }
+                }
+
+                true
+            }
+            SoundEvent::StopLayeredMusic(handle, duration) => {
+                if let Some(music) = layered_music.remove(handle) {
+                    let fade_tween = if *duration > 0. {
+                        Some(Tween::linear(*duration as f64))
+                    } else {
+                        None
+                    };
+
+                    for (sound, _) in &music.stems {
+                        if let Some(sound_handle) = sound_to_handle_map.get_mut(sound) {
+                            sound_states.entry(*sound).or_default().stop(
+                                sound_handle,
+                                &StopSoundSettings::new().fade_tween(fade_tween),
+                            );
+                        }
+                    }
+                    music.metronome.remove(&mut audio_manager);
+                }
+
+                true
+            }
+            SoundEvent::StopMusic(duration) => {
+                if let Some(metronome) = music_metronome.take() {
+                    metronome.remove(&mut audio_manager);
+                }
+                if let Some(old_music) = current_music.take() {
+                    if let Some(sound_handle) = sound_to_handle_map.get_mut(&old_music) {
+                        let fade_tween = if *duration > 0. {
+                            Some(Tween::linear(*duration as f64))
+                        } else {
+                            None
+                        };
+                        sound_states.entry(old_music).or_default().stop(
+                            sound_handle,
+                            &StopSoundSettings::new().fade_tween(fade_tween),
+                        );
+                    }
+                }
+
+                true
+            }
+            // Commands for instances that haven't been played yet wait until they are played
+            SoundEvent::PauseInstance(instance, _)
+            | SoundEvent::ResumeInstance(instance, _)
+            | SoundEvent::StopInstance(instance, _)
+                if pending_instances.contains(instance) =>
+            {
+                false
+            }
+            SoundEvent::PauseInstance(instance, settings) => {
+                if let Some(instance) = find_instance(&mut sound_states, instance) {
+                    instance.handle.pause(*settings).unwrap();
+                    instance.spatial_paused = false;
+                }
+                true
+            }
+            SoundEvent::ResumeInstance(instance, settings) => {
+                if let Some(instance) = find_instance(&mut sound_states, instance) {
+                    instance.handle.resume(*settings).unwrap();
+                }
+                true
+            }
+            SoundEvent::StopInstance(instance, settings) => {
+                if let Some(instance) = find_instance(&mut sound_states, instance) {
+                    instance.handle.stop(*settings).unwrap();
+                    instance.stopped = true;
+                }
+                true
+            }
+            // Sound properties are kept even if the sound hasn't been created yet, so that they can
+            // be applied once the sound is played
+            SoundEvent::SetProperty(sound, property, value, duration) => {
+                let state = sound_states.entry(*sound).or_default();
+                state.property_mut(*property).animate_to(*value, *duration);
+                state.apply();
+                true
+            }
+            SoundEvent::SetSpatial(sound, spatial) => {
+                let state = sound_states.entry(*sound).or_default();
+
+                // Only send commands to the audio thread if the spatial state has changed
+                if state.spatial != *spatial {
+                    state.spatial = *spatial;
+                    state.apply();
+                }
+
+                true
+            }
         };
 
         let mut new_pending_events = Vec::new();
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+#[cfg(wasm)]
+use bevy::app::Events;
+
+use super::*;
+
+pub(crate) fn add_unlock_systems(app: &mut AppBuilder) {
+    #[cfg(not(wasm))]
+    app.insert_non_send_resource(AudioManager::default())
+        .insert_resource(AudioLockState::Unlocked);
+
+    #[cfg(wasm)]
+    app.insert_resource(AudioLockState::Locked)
+        .add_system_to_stage(CoreStage::Last, unlock_audio.exclusive_system().at_end());
+}
+
+/// Bevy resource that tells whether or not sounds can be played yet
+///
+/// Browsers don't allow pages to play audio until the user has interacted with them, so on web the
+/// audio starts out locked, and is unlocked as soon as a mouse button, key, or touch is pressed.
+/// Sounds that are played while the audio is locked are queued up and start playing once it is
+/// unlocked, and the [`AudioUnlocked`][crate::AudioUnlocked] event is sent. This can be used to
+/// show a "click to start" screen until the player has interacted with the page.
+///
+/// On other platforms the audio is always unlocked.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// struct ClickToStart;
+///
+/// fn hide_click_to_start(
+///     mut commands: Commands,
+///     audio_lock: Res<AudioLockState>,
+///     screens: Query<Entity, With<ClickToStart>>,
+/// ) {
+///     if *audio_lock == AudioLockState::Unlocked {
+///         for screen in screens.iter() {
+///             commands.entity(screen).despawn();
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioLockState {
+    /// The user hasn't interacted with the page yet, so sounds are queued up
+    Locked,
+    /// Sounds can be played
+    Unlocked,
+}
+
+/// Start the audio manager once the user has interacted with the page
+#[cfg(wasm)]
+fn unlock_audio(world: &mut World) {
+    if *world.get_resource::<AudioLockState>().unwrap() == AudioLockState::Unlocked {
+        return;
+    }
+
+    let interacted = world
+        .get_resource::<Input<MouseButton>>()
+        .map(|x| x.get_just_pressed().next().is_some())
+        .unwrap_or(false)
+        || world
+            .get_resource::<Input<KeyCode>>()
+            .map(|x| x.get_just_pressed().next().is_some())
+            .unwrap_or(false)
+        || world
+            .get_resource::<Touches>()
+            .map(|x| x.iter_just_pressed().next().is_some())
+            .unwrap_or(false);
+    if !interacted {
+        return;
+    }
+
+    // Browsers let the audio context start once the page has had a user gesture, so creating the
+    // audio manager now starts it in the running state
+    world.insert_non_send(AudioManager::default());
+    world.insert_resource(AudioLockState::Unlocked);
+    world
+        .get_resource_mut::<Events<AudioUnlocked>>()
+        .unwrap()
+        .send(AudioUnlocked);
+}
@@ -5,8 +5,11 @@ use serde::{Deserialize, Serialize};
 
 pub(crate) fn add_components(app: &mut AppBuilder) {
     app.register_type::<Camera>()
+        .register_type::<CameraBounds>()
         .register_type::<Color>()
         .register_type::<CameraSize>()
+        .register_type::<ScreenAnchor>()
+        .register_type::<Anchor>()
         .register_type::<Sprite>()
         .register_type::<SpriteSheet>()
         .register_type::<Visible>();
@@ -110,6 +113,127 @@ impl Default for Camera {
     }
 }
 
+/// Limits the area of the world that a [`Camera`] can see
+///
+/// When added to a camera entity, the camera's [`Transform`] will be clamped so that the camera
+/// never shows anything outside of the bounds. If the camera is bigger than the bounds on an axis,
+/// it will be centered on the bounds on that axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct CameraBounds {
+    /// The top-left corner of the bounds in world space
+    pub min: Vec2,
+    /// The bottom-right corner of the bounds in world space
+    pub max: Vec2,
+}
+
+impl CameraBounds {
+    /// Get the position that a camera should be clamped to in order to stay inside the bounds
+    ///
+    /// `camera_size` is the size of the camera view in world pixels.
+    pub fn clamp_camera_position(&self, position: Vec2, camera_size: Vec2, centered: bool) -> Vec2 {
+        // Get the offset from the camera position to the top-left corner of the view
+        let offset = if centered {
+            camera_size / 2.
+        } else {
+            Vec2::ZERO
+        };
+
+        let clamp_axis = |pos: f32, size: f32, offset: f32, min: f32, max: f32| {
+            if size >= max - min {
+                // Center the camera if it is bigger than the bounds
+                (min + max) / 2. - size / 2. + offset
+            } else {
+                (pos - offset).max(min).min(max - size) + offset
+            }
+        };
+
+        Vec2::new(
+            clamp_axis(position.x, camera_size.x, offset.x, self.min.x, self.max.x),
+            clamp_axis(position.y, camera_size.y, offset.y, self.min.y, self.max.y),
+        )
+    }
+}
+
+/// Component that positions an entity relative to the camera view instead of the world
+///
+/// This is useful for HUD elements such as text labels and icons that should stay in the same
+/// place on screen as the camera moves, no matter which [`CameraSize`] mode is used. The x and y
+/// of the entity's [`Transform`] will be overwritten every frame, but the z is left as is.
+///
+/// If the entity has a [`Sprite`], the sprite will be aligned so that the same anchor point of the
+/// sprite is placed at the anchor point of the screen. For example, a sprite anchored to
+/// [`Anchor::BottomRight`] will have its bottom-right corner in the bottom-right corner of the
+/// screen.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// // Put a sprite 4 pixels away from the top-right corner of the screen
+/// let anchor = ScreenAnchor {
+///     anchor: Anchor::TopRight,
+///     offset: Vec2::new(-4., 4.),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct ScreenAnchor {
+    /// The point on the screen that the entity is positioned relative to
+    pub anchor: Anchor,
+    /// The offset in pixels from the anchor point, with positive y pointing down
+    pub offset: Vec2,
+}
+
+/// The size in pixels of a [`Sprite`] that is drawn by a render hook instead of from an image
+///
+/// Render hooks that draw sprites without a `Handle<Image>`, such as the text render hook, keep
+/// this up to date so that the sprite can still be aligned by a [`ScreenAnchor`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpriteSize(pub UVec2);
+
+/// An anchor point on a rectangle such as the screen or a sprite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[reflect_value(PartialEq, Serialize, Deserialize)]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::TopLeft
+    }
+}
+
+impl Anchor {
+    /// Get the position of the anchor point on a rectangle as a fraction of the rectangle's size,
+    /// where `(0, 0)` is the top-left corner and `(1, 1)` is the bottom-right corner
+    pub fn as_fraction(&self) -> Vec2 {
+        let (x, y) = match self {
+            Anchor::TopLeft => (0., 0.),
+            Anchor::TopCenter => (0.5, 0.),
+            Anchor::TopRight => (1., 0.),
+            Anchor::CenterLeft => (0., 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::CenterRight => (1., 0.5),
+            Anchor::BottomLeft => (0., 1.),
+            Anchor::BottomCenter => (0.5, 1.),
+            Anchor::BottomRight => (1., 1.),
+        };
+
+        Vec2::new(x, y)
+    }
+}
+
 /// The size of the 2D camera
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Reflect)]
 #[reflect_value(PartialEq, Serialize, Deserialize)]
@@ -180,6 +304,93 @@ impl Camera {
             high: high_res,
         }
     }
+
+    /// Get the position in the world under a position in the window, as seen by this camera
+    ///
+    /// The window position is in logical pixels from the bottom-left corner of the window, like
+    /// the position of the cursor, and the camera position is the translation of the camera's
+    /// [`Transform`]. Returns [`None`] if the position is outside of the camera view, such as in
+    /// the letter box.
+    pub fn window_to_world(
+        &self,
+        camera_position: Vec2,
+        window: &bevy::window::Window,
+        window_position: Vec2,
+    ) -> Option<Vec2> {
+        let target_size = self.get_target_sizes(window).low;
+        let camera_size = Vec2::new(target_size.x as f32, target_size.y as f32);
+        let screen_aspect_ratio = window.width() / window.height();
+        let camera_aspect_ratio = camera_size.x / camera_size.y;
+
+        // Get the fraction of the window that the camera view covers on each axis, the same way
+        // that the screen shader does
+        let pillarboxed = Vec2::new(
+            camera_aspect_ratio / screen_aspect_ratio * self.pixel_aspect_ratio,
+            1.,
+        );
+        let letterboxed = Vec2::new(
+            1.,
+            screen_aspect_ratio / camera_aspect_ratio / self.pixel_aspect_ratio,
+        );
+        let view_scale = match self.size {
+            CameraSize::LetterBoxed { .. } => {
+                if screen_aspect_ratio > camera_aspect_ratio * self.pixel_aspect_ratio {
+                    pillarboxed
+                } else {
+                    letterboxed
+                }
+            }
+            CameraSize::FixedWidth(_) => pillarboxed,
+            CameraSize::FixedHeight(_) => letterboxed,
+        };
+
+        // Map the window position to the camera view, from 0 to 1 with y pointing down
+        let window_fraction = Vec2::new(
+            window_position.x / window.width(),
+            window_position.y / window.height(),
+        );
+        let view_fraction = ((window_fraction * 2. - Vec2::ONE) / view_scale + Vec2::ONE) / 2.;
+        let view_fraction = Vec2::new(view_fraction.x, 1. - view_fraction.y);
+        if view_fraction.x < 0.
+            || view_fraction.x > 1.
+            || view_fraction.y < 0.
+            || view_fraction.y > 1.
+        {
+            return None;
+        }
+
+        let camera_top_left = if self.centered {
+            camera_position - camera_size / 2.
+        } else {
+            camera_position
+        };
+
+        Some(camera_top_left + view_fraction * camera_size)
+    }
+}
+
+/// This resource has the position in the world of the mouse cursor in the primary window
+///
+/// The position is updated at the start of every frame from the first [`Camera`], and is [`None`]
+/// while the cursor is outside of the window or over the letter box.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// fn place_block(cursor: Res<CursorWorldPosition>, mouse_buttons: Res<Input<MouseButton>>) {
+///     if let (Some(pixel), true) = (cursor.pixel, mouse_buttons.just_pressed(MouseButton::Left)) {
+///         info!("Placing a block at {:?}", pixel);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CursorWorldPosition {
+    /// The exact position in the world under the cursor
+    pub position: Option<Vec2>,
+    /// The world pixel under the cursor
+    pub pixel: Option<IVec2>,
 }
 
 /// Sprite options
@@ -0,0 +1,165 @@
+//! Renderer diagnostics
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+
+/// Plugin that reports the time taken by the Bevy Retrograde renderer and each of its
+/// [`RenderHook`][crate::graphics::RenderHook]s through the Bevy [`Diagnostics`] resource
+///
+/// The CPU time of the renderer is always reported. The GPU time is reported where GPU timer
+/// queries are available, which is currently only in browsers that support the
+/// `EXT_disjoint_timer_query` WebGL extension, and lags a few frames behind the CPU time.
+///
+/// The diagnostics of each render hook are identified by the type name of the hook and are added
+/// when the hook is first timed. Use [`hook_cpu_time`][Self::hook_cpu_time] and
+/// [`hook_gpu_time`][Self::hook_gpu_time] to get their ids.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{diagnostic::Diagnostics, prelude::*};
+/// # use bevy_retrograde_core::diagnostics::*;
+/// fn log_render_time(diagnostics: Res<Diagnostics>) {
+///     if let Some(time) = diagnostics
+///         .get(RetroRenderDiagnosticsPlugin::RENDER_CPU_TIME)
+///         .and_then(|diagnostic| diagnostic.average())
+///     {
+///         info!("Rendering takes {:.2} ms", time * 1000.);
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct RetroRenderDiagnosticsPlugin;
+
+/// The number of frames of history kept for the renderer diagnostics
+const MAX_HISTORY_LENGTH: usize = 20;
+
+impl Plugin for RetroRenderDiagnosticsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<RenderTimings>()
+            .add_startup_system(Self::setup_system.system())
+            .add_system_to_stage(CoreStage::First, Self::diagnostic_system.system());
+    }
+}
+
+impl RetroRenderDiagnosticsPlugin {
+    /// The CPU time of the whole renderer in seconds
+    pub const RENDER_CPU_TIME: DiagnosticId =
+        DiagnosticId::from_u128(0x5e6d4a1b_9c3f_4d7e_a2b8_61f0c9e4d317);
+    /// The GPU time of the whole renderer in seconds
+    pub const RENDER_GPU_TIME: DiagnosticId =
+        DiagnosticId::from_u128(0x8b2e7f90_1a4c_4b63_9d05_e37a2c6f18b4);
+
+    /// Get the id of the CPU time diagnostic for the render hook with the given name
+    pub fn hook_cpu_time(hook_name: &str) -> DiagnosticId {
+        hashed_id("cpu", hook_name)
+    }
+
+    /// Get the id of the GPU time diagnostic for the render hook with the given name
+    pub fn hook_gpu_time(hook_name: &str) -> DiagnosticId {
+        hashed_id("gpu", hook_name)
+    }
+
+    fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(
+            Diagnostic::new(Self::RENDER_CPU_TIME, "render_cpu_time", MAX_HISTORY_LENGTH)
+                .with_suffix("s"),
+        );
+        diagnostics.add(
+            Diagnostic::new(Self::RENDER_GPU_TIME, "render_gpu_time", MAX_HISTORY_LENGTH)
+                .with_suffix("s"),
+        );
+    }
+
+    fn diagnostic_system(timings: Res<RenderTimings>, mut diagnostics: ResMut<Diagnostics>) {
+        // Only report frames that have been rendered
+        if !timings.is_changed() {
+            return;
+        }
+
+        diagnostics.add_measurement(Self::RENDER_CPU_TIME, timings.cpu_time);
+        if let Some(gpu_time) = timings.gpu_time {
+            diagnostics.add_measurement(Self::RENDER_GPU_TIME, gpu_time);
+        }
+
+        for hook in &timings.hooks {
+            let cpu_id = Self::hook_cpu_time(hook.name);
+            if diagnostics.get(cpu_id).is_none() {
+                diagnostics.add(
+                    Diagnostic::new(
+                        cpu_id,
+                        format!("{}_cpu_time", hook.name),
+                        MAX_HISTORY_LENGTH,
+                    )
+                    .with_suffix("s"),
+                );
+            }
+            diagnostics.add_measurement(cpu_id, hook.cpu_time);
+
+            if let Some(gpu_time) = hook.gpu_time {
+                let gpu_id = Self::hook_gpu_time(hook.name);
+                if diagnostics.get(gpu_id).is_none() {
+                    diagnostics.add(
+                        Diagnostic::new(
+                            gpu_id,
+                            format!("{}_gpu_time", hook.name),
+                            MAX_HISTORY_LENGTH,
+                        )
+                        .with_suffix("s"),
+                    );
+                }
+                diagnostics.add_measurement(gpu_id, gpu_time);
+            }
+        }
+    }
+}
+
+/// Resource containing the time taken to render the last frame
+///
+/// The renderer only measures its timings when this resource exists, which is added by the
+/// [`RetroRenderDiagnosticsPlugin`].
+#[derive(Debug, Clone, Default)]
+pub struct RenderTimings {
+    /// The CPU time of the whole renderer in seconds, not including waiting for the buffers to swap
+    pub cpu_time: f64,
+    /// The GPU time of the whole renderer in seconds, if GPU timer queries are available
+    ///
+    /// GPU timings are read back a few frames after they are measured, so this is the time of the
+    /// latest frame that has finished on the GPU.
+    pub gpu_time: Option<f64>,
+    /// The timings of each render hook, in the order that they were added
+    pub hooks: Vec<RenderHookTimings>,
+}
+
+/// The time taken by a [`RenderHook`][crate::graphics::RenderHook] to render the last frame
+#[derive(Debug, Clone)]
+pub struct RenderHookTimings {
+    /// The name of the render hook, which is the name of its type
+    pub name: &'static str,
+    /// The CPU time in seconds taken by the render hook's prepare, render, and overlay functions
+    pub cpu_time: f64,
+    /// The GPU time in seconds taken by the render hook's render and overlay functions, if GPU
+    /// timer queries are available
+    pub gpu_time: Option<f64>,
+}
+
+/// Create a diagnostic id by hashing a name with FNV-1a, so that the same render hook always gets
+/// the same id
+fn hashed_id(kind: &str, name: &str) -> DiagnosticId {
+    const OFFSET_BASIS: u128 = 0x6c62272e_07bb0142_62b82175_6295c58d;
+    const PRIME: u128 = 0x00000000_01000000_00000000_0000013b;
+
+    let mut hash = OFFSET_BASIS;
+    for byte in "bevy_retrograde_render_hook"
+        .bytes()
+        .chain(kind.bytes())
+        .chain(name.bytes())
+    {
+        hash ^= byte as u128;
+        hash = hash.wrapping_mul(PRIME);
+    }
+
+    DiagnosticId::from_u128(hash)
+}
@@ -0,0 +1,114 @@
+//! Pausable and scalable game time
+
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
+
+/// The time of the game world, which can be paused and slowed down or sped up
+///
+/// The core plugin adds a [`GameTime`] resource that is updated from Bevy's [`Time`] at the start
+/// of every frame. While it is paused its delta is zero, otherwise it is the real delta multiplied
+/// by the [`scale`][GameTime::scale]. The Bevy Retrograde systems that move things over time, such
+/// as sprite animations, tile animations, tweens, and path following, use this time, so pausing it
+/// freezes the game world while menus and UI keep running on the real [`Time`].
+///
+/// Game systems can use [`GameTime`] instead of [`Time`] to follow along, or only be run while the
+/// game is running with the [`run_if_not_paused`] run criteria.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// fn toggle_pause(keyboard: Res<Input<KeyCode>>, mut game_time: ResMut<GameTime>) {
+///     if keyboard.just_pressed(KeyCode::Escape) {
+///         game_time.paused = !game_time.paused;
+///     }
+///
+///     // Slow motion while shift is held
+///     game_time.scale = if keyboard.pressed(KeyCode::LShift) {
+///         0.25
+///     } else {
+///         1.
+///     };
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameTime {
+    /// Whether or not the game time is stopped
+    pub paused: bool,
+    /// The speed of the game time compared to real time
+    pub scale: f32,
+    delta: f32,
+    seconds_since_startup: f64,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            scale: 1.,
+            delta: 0.,
+            seconds_since_startup: 0.,
+        }
+    }
+}
+
+impl GameTime {
+    /// Get the game time in seconds that passed during the last frame
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta
+    }
+
+    /// Get the game time in seconds that has passed since the game started, not counting the time
+    /// that it was paused
+    pub fn seconds_since_startup(&self) -> f64 {
+        self.seconds_since_startup
+    }
+
+    /// Stop the game time
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Start the game time again after it was paused
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Get whether or not the game time is stopped
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Advance the game time by a frame that took the given real time in seconds
+    pub(crate) fn advance(&mut self, real_delta: f32) {
+        self.delta = if self.paused {
+            0.
+        } else {
+            real_delta * self.scale.max(0.)
+        };
+        self.seconds_since_startup += self.delta as f64;
+    }
+}
+
+/// Run criteria that only runs systems while the [`GameTime`] isn't paused
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// fn main() {
+///     App::build().add_system(move_enemies.system().with_run_criteria(run_if_not_paused.system()));
+/// }
+///
+/// fn move_enemies() {
+///     // Only moves the enemies while the game isn't paused
+/// }
+/// ```
+pub fn run_if_not_paused(game_time: Res<GameTime>) -> ShouldRun {
+    if game_time.paused {
+        ShouldRun::No
+    } else {
+        ShouldRun::Yes
+    }
+}
@@ -27,6 +27,19 @@ pub type Texture<D, P> = luminance::texture::Texture<Glow, D, P>;
 /// texture based on the associated image handle.
 pub type TextureCache = HashMap<Handle<Image>, Texture<Dim2, NormRGBA8UI>>;
 
+/// Resource containing statistics about the last frame rendered by the Bevy Retrograde renderer
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    /// The number of renderables returned by all of the render hooks
+    pub renderables: usize,
+    /// The number of batches of renderables that were passed to the render hooks to render
+    pub render_batches: usize,
+    /// The number of image textures uploaded to the GPU
+    pub cached_textures: usize,
+    /// The total size in bytes of the image textures uploaded to the GPU
+    pub cached_texture_bytes: usize,
+}
+
 #[cfg(not(wasm))]
 /// A [`luminance`] that is used as the render target for the Bevy Retrograde scene at the low-res camera
 /// resolution
@@ -34,6 +47,9 @@ pub type SceneFramebuffer = Framebuffer<Dim2, luminance::pixel::RGBA32F, ()>;
 #[cfg(wasm)]
 pub type SceneFramebuffer = Framebuffer<Dim2, luminance::pixel::RGBA8UI, ()>;
 
+/// The [`luminance`] back buffer of the window, which the scaled retro output is rendered to
+pub type ScreenFramebuffer = Framebuffer<Dim2, (), ()>;
+
 /// A trait that allows you hook custom functionality into the Bevy Retrograde renderer
 ///
 /// By implementing [`RenderHook`] you are able to use the raw [`luminance`] API to do fully custom
@@ -48,6 +64,9 @@ pub type SceneFramebuffer = Framebuffer<Dim2, luminance::pixel::RGBA8UI, ()>;
 /// configured at the resolution of the Bevy Retrograde camera, but in the future you will be able to
 /// render at the full resolution of the user's screen if desired, allowing you to selectively break
 /// out of the pixel-perfect, retro rendering.
+///
+/// Hooks can also draw on top of the scaled retro output at the full resolution of the window with
+/// [`render_overlay`][`RenderHook::render_overlay`], which is useful for debug UI and dev tools.
 pub trait RenderHook {
     /// Function called upon window creation to initialize the render hook
     fn init(window_id: bevy::window::WindowId, surface: &mut Surface) -> Box<dyn RenderHook>
@@ -84,6 +103,21 @@ pub trait RenderHook {
         renderables: &[RenderHookRenderableHandle],
     ) {
     }
+
+    /// This function is called once per frame, after the retro-resolution framebuffer has been
+    /// scaled onto the window, to draw on top of it at the full resolution of the window.
+    ///
+    /// The overlays of the hooks are drawn in the order that the hooks were added.
+    #[allow(unused_variables)]
+    fn render_overlay(
+        &mut self,
+        world: &mut World,
+        surface: &mut Surface,
+        texture_cache: &mut TextureCache,
+        frame_context: &FrameContext,
+        target_framebuffer: &ScreenFramebuffer,
+    ) {
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -171,13 +205,23 @@ type RenderHookInitFn =
 /// Bevy resource that can be used to add [`RenderHook`]s to the Bevy Retrograde renderer
 #[derive(Default)]
 pub struct RenderHooks {
-    pub(crate) new_hooks: Vec<Box<RenderHookInitFn>>,
+    /// The names and init functions of the render hooks that haven't been added to the renderer yet
+    pub(crate) new_hooks: Vec<(&'static str, Box<RenderHookInitFn>)>,
 }
 
 impl RenderHooks {
     /// Add a new [`RenderHook`] to the Bevy Retrograde renderer
     pub fn add_render_hook<T: RenderHook + 'static>(&mut self) {
-        self.new_hooks
-            .push(Box::new(T::init) as Box<RenderHookInitFn>);
+        self.new_hooks.push((
+            render_hook_name::<T>(),
+            Box::new(T::init) as Box<RenderHookInitFn>,
+        ));
     }
 }
+
+/// Get the name of a render hook, which is its type name without the module path or generics
+fn render_hook_name<T>() -> &'static str {
+    let type_name = std::any::type_name::<T>();
+    let path = type_name.split('<').next().unwrap_or(type_name);
+    path.rsplit("::").next().unwrap_or(path)
+}
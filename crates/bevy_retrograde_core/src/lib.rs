@@ -9,6 +9,8 @@ pub mod prelude {
     pub use crate::bevy_extensions::*;
     pub use crate::bundles::*;
     pub use crate::components::*;
+    pub use crate::game_time::*;
+    pub use crate::rng::*;
     pub use crate::shaders::*;
 }
 
@@ -22,10 +24,14 @@ pub mod assets;
 pub mod bevy_extensions;
 pub mod bundles;
 pub mod components;
+pub mod diagnostics;
+pub mod game_time;
 pub mod graphics;
+pub mod rng;
 pub mod shaders;
 
 mod renderer;
+mod systems;
 
 /// The ECS schedule stages that the Bevy Retrograde code is run in
 #[derive(Debug, Clone, Copy, StageLabel, Hash, PartialEq, Eq)]
@@ -43,8 +49,13 @@ impl Plugin for RetroCorePlugin {
     fn build(&self, app: &mut AppBuilder) {
         add_components(app);
         add_assets(app);
+        systems::add_systems(app);
 
         app.init_resource::<RenderHooks>()
+            .init_resource::<RenderStats>()
+            .init_resource::<CursorWorldPosition>()
+            .init_resource::<RetroRng>()
+            .init_resource::<GameTime>()
             .add_render_hook::<graphics::hooks::SpriteHook>()
             .add_stage_after(
                 CoreStage::Last,
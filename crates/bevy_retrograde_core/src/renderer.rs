@@ -8,6 +8,7 @@ use bevy::{
 };
 
 pub(crate) mod backend;
+mod timing;
 
 use self::backend::Renderer;
 
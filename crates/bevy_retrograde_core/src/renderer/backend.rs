@@ -3,6 +3,7 @@ use std::usize;
 use bevy::{
     app::{Events, ManualEventReader},
     prelude::*,
+    utils::Instant,
 };
 use luminance::{
     context::GraphicsContext,
@@ -14,7 +15,12 @@ use luminance::{
     Semantics, UniformInterface, Vertex,
 };
 
-use crate::{graphics::*, prelude::*};
+use super::timing::{FrameTimer, GpuTimer};
+use crate::{
+    diagnostics::{RenderHookTimings, RenderTimings},
+    graphics::*,
+    prelude::*,
+};
 
 /// The default custom camera shader string
 const DEFAULT_CUSTOM_SHADER: &str = r#"
@@ -105,6 +111,10 @@ pub(crate) struct Renderer {
 
     /// The list of render hooks
     render_hooks: Vec<Box<dyn RenderHook>>,
+    /// The names of the render hooks, which are used for the render timings
+    render_hook_names: Vec<&'static str>,
+    /// The GPU timer used for the render timings, if GPU timer queries are available
+    gpu_timer: Option<GpuTimer>,
 
     // The texture cache
     texture_cache: TextureCache,
@@ -144,6 +154,8 @@ impl Renderer {
             .build()
             .unwrap();
 
+        let gpu_timer = GpuTimer::new(&surface);
+
         Self {
             window_id,
             surface,
@@ -152,6 +164,8 @@ impl Renderer {
             staging_framebuffer: scene_framebuffer,
             custom_shader: None,
             render_hooks: Vec::new(),
+            render_hook_names: Vec::new(),
+            gpu_timer,
 
             texture_cache: Default::default(),
             image_asset_event_reader: Default::default(),
@@ -164,6 +178,10 @@ impl Renderer {
         // Check for any new render hooks and add them to our render hook list
         self.add_render_hooks(world);
 
+        // The frame is only timed if the render timings are being collected
+        let frame_start = Instant::now();
+        let timing_enabled = world.contains_resource::<RenderTimings>();
+
         let Self {
             screen_program,
             screen_tess,
@@ -171,6 +189,8 @@ impl Renderer {
             surface,
             window_id,
             render_hooks,
+            render_hook_names,
+            gpu_timer,
             pending_textures,
             texture_cache,
             image_asset_event_reader,
@@ -243,10 +263,18 @@ impl Renderer {
             target_sizes,
         };
 
+        // Time each render hook, and drawing the scene to the screen after them
+        let screen_section = render_hooks.len();
+        let mut timer = FrameTimer::new(timing_enabled, gpu_timer.as_mut(), screen_section + 1);
+
         let mut renderables = Vec::new();
         // Loop through our render hooks and run their prepare functions
         for (i, hook) in render_hooks.iter_mut().enumerate() {
-            for handle in hook.prepare(world, surface, texture_cache, &frame_context) {
+            timer.start(i);
+            let handles = hook.prepare(world, surface, texture_cache, &frame_context);
+            timer.stop();
+
+            for handle in handles {
                 // Add all the renderables from this render hook to our renderables list
                 renderables.push(Renderable {
                     hook_idx: i,
@@ -257,6 +285,8 @@ impl Renderer {
 
         // Sort renderables before rendering
         renderables.sort();
+        let renderable_count = renderables.len();
+        let mut render_batches = 0;
 
         // Loop through our renderers and render them
         let mut current_batch = Vec::new();
@@ -281,6 +311,8 @@ impl Renderer {
                     // Render the current batch
                     let batch_renderables: Vec<_> =
                         current_batch.iter().map(|x| x.handle).collect();
+                    render_batches += 1;
+                    timer.start(current_batch_render_hook_idx);
                     render_hooks
                         .get_mut(current_batch_render_hook_idx)
                         .unwrap()
@@ -292,6 +324,7 @@ impl Renderer {
                             staging_framebuffer,
                             &batch_renderables,
                         );
+                    timer.stop();
 
                     // And start a new batch
                     current_batch.clear();
@@ -303,6 +336,10 @@ impl Renderer {
 
         // Render the final batch
         let batch_renderables: Vec<_> = current_batch.iter().map(|x| x.handle).collect();
+        if !batch_renderables.is_empty() {
+            render_batches += 1;
+        }
+        timer.start(current_batch_render_hook_idx);
         render_hooks
             .get_mut(current_batch_render_hook_idx)
             .unwrap()
@@ -314,10 +351,28 @@ impl Renderer {
                 staging_framebuffer,
                 &batch_renderables,
             );
+        timer.stop();
+
+        // Update the render stats
+        if let Some(mut stats) = world.get_resource_mut::<RenderStats>() {
+            *stats = RenderStats {
+                renderables: renderable_count,
+                render_batches,
+                cached_textures: texture_cache.len(),
+                cached_texture_bytes: texture_cache
+                    .values()
+                    .map(|texture| {
+                        let [width, height] = texture.size();
+                        width as usize * height as usize * 4
+                    })
+                    .sum(),
+            };
+        }
 
         let bevy_time = world.get_resource::<Time>().unwrap();
 
         // Render the staging framebuffer to the back buffer on a quad
+        timer.start(screen_section);
         surface
             .new_pipeline_gate()
             .pipeline(
@@ -362,6 +417,33 @@ impl Renderer {
                 },
             )
             .assume();
+        timer.stop();
+
+        // Let the render hooks draw on top of the scaled scene
+        for (i, hook) in render_hooks.iter_mut().enumerate() {
+            timer.start(i);
+            hook.render_overlay(world, surface, texture_cache, &frame_context, &back_buffer);
+            timer.stop();
+        }
+
+        // Update the render timings
+        let (cpu_times, gpu_times) = timer.finish();
+        if let Some(mut timings) = world.get_resource_mut::<RenderTimings>() {
+            *timings = RenderTimings {
+                cpu_time: frame_start.elapsed().as_secs_f64(),
+                gpu_time: gpu_times.as_ref().map(|times| times.iter().sum()),
+                hooks: render_hook_names
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .map(|(i, name)| RenderHookTimings {
+                        name,
+                        cpu_time: cpu_times[i],
+                        gpu_time: gpu_times.as_ref().and_then(|times| times.get(i).copied()),
+                    })
+                    .collect(),
+            };
+        }
 
         #[cfg(not(wasm))]
         self.surface.swap_buffers().unwrap();
@@ -373,9 +455,10 @@ impl Renderer {
         let mut render_hooks = world.get_resource_mut::<RenderHooks>().unwrap();
 
         // Initialize each new render hook
-        for hook_init in render_hooks.new_hooks.drain(0..) {
+        for (name, hook_init) in render_hooks.new_hooks.drain(0..) {
             self.render_hooks
                 .push(hook_init(self.window_id, &mut self.surface));
+            self.render_hook_names.push(name);
         }
     }
 
@@ -40,7 +40,7 @@ use luminance_glow::{Context, Glow, StateQueryError};
 
 use std::fmt;
 use wasm_bindgen::JsCast as _;
-use web_sys::{Document, HtmlCanvasElement, Window};
+use web_sys::{Document, HtmlCanvasElement, WebGlRenderingContext, Window};
 
 /// web-sys errors that might occur while initializing and using the platform.
 #[non_exhaustive]
@@ -105,6 +105,8 @@ pub struct WebSysWebGLSurface {
     pub window: Window,
     pub document: Document,
     pub canvas: HtmlCanvasElement,
+    /// The WebGL context, which is used for the WebGL extensions that luminance doesn't expose
+    pub(crate) gl: WebGlRenderingContext,
     backend: Glow,
 }
 
@@ -116,12 +118,14 @@ impl WebSysWebGLSurface {
             .document()
             .ok_or_else(|| WebSysWebGLSurfaceError::cannot_grab_document())?;
 
-        let webgl = canvas
+        let gl: WebGlRenderingContext = canvas
             .get_context("webgl")
             .map_err(|_| WebSysWebGLSurfaceError::cannot_grab_webgl_context())?
-            .ok_or_else(|| WebSysWebGLSurfaceError::no_available_webgl_context())?;
+            .ok_or_else(|| WebSysWebGLSurfaceError::no_available_webgl_context())?
+            .dyn_into()
+            .unwrap();
 
-        let ctx = Context::from_webgl1_context(webgl.dyn_into().unwrap());
+        let ctx = Context::from_webgl1_context(gl.clone());
 
         // create the backend object and return the whole object
         let backend = Glow::from_context(ctx)?;
@@ -130,6 +134,7 @@ impl WebSysWebGLSurface {
             window,
             document,
             canvas,
+            gl,
             backend,
         })
     }
@@ -0,0 +1,217 @@
+//! Renderer timing used for the render diagnostics
+
+use bevy::utils::Instant;
+
+use super::Surface;
+
+/// Times the sections of a frame on the CPU and, if available, on the GPU
+///
+/// Every render hook is a section, and so is drawing the scene to the screen. Sections can't be
+/// nested because only one GPU timer query can be active at a time.
+pub(crate) struct FrameTimer<'a> {
+    enabled: bool,
+    gpu_timer: Option<&'a mut GpuTimer>,
+    cpu_times: Vec<f64>,
+    current: Option<(usize, Instant)>,
+}
+
+impl<'a> FrameTimer<'a> {
+    pub fn new(enabled: bool, gpu_timer: Option<&'a mut GpuTimer>, sections: usize) -> Self {
+        Self {
+            enabled,
+            gpu_timer: gpu_timer.filter(|_| enabled),
+            cpu_times: vec![0.; sections],
+            current: None,
+        }
+    }
+
+    /// Start timing a section
+    pub fn start(&mut self, section: usize) {
+        if !self.enabled {
+            return;
+        }
+
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.begin(section);
+        }
+        self.current = Some((section, Instant::now()));
+    }
+
+    /// Stop timing the current section
+    pub fn stop(&mut self) {
+        if let Some((section, start)) = self.current.take() {
+            self.cpu_times[section] += start.elapsed().as_secs_f64();
+
+            if let Some(gpu_timer) = &mut self.gpu_timer {
+                gpu_timer.end();
+            }
+        }
+    }
+
+    /// Finish timing the frame, returning the CPU time of each section and the GPU time of each
+    /// section for the latest frame that has finished on the GPU
+    pub fn finish(self) -> (Vec<f64>, Option<Vec<f64>>) {
+        let sections = self.cpu_times.len();
+        let gpu_times = self
+            .gpu_timer
+            .and_then(|gpu_timer| gpu_timer.finish_frame(sections));
+
+        (self.cpu_times, gpu_times)
+    }
+}
+
+#[cfg(not(wasm))]
+pub(crate) use native::GpuTimer;
+#[cfg(wasm)]
+pub(crate) use web::GpuTimer;
+
+#[cfg(not(wasm))]
+mod native {
+    use super::Surface;
+
+    /// GPU timer queries aren't available on native because the surfman surface doesn't expose
+    /// the OpenGL context, so this timer can't be created
+    pub(crate) enum GpuTimer {}
+
+    impl GpuTimer {
+        pub fn new(_surface: &Surface) -> Option<Self> {
+            None
+        }
+
+        pub fn begin(&mut self, _section: usize) {
+            match *self {}
+        }
+
+        pub fn end(&mut self) {
+            match *self {}
+        }
+
+        pub fn finish_frame(&mut self, _sections: usize) -> Option<Vec<f64>> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(wasm)]
+mod web {
+    use std::collections::VecDeque;
+
+    use wasm_bindgen::JsCast;
+    use web_sys::{ExtDisjointTimerQuery, WebGlRenderingContext, WebGlTimerQueryExt};
+
+    use super::Surface;
+
+    const TIME_ELAPSED_EXT: u32 = 0x88BF;
+    const QUERY_RESULT_EXT: u32 = 0x8866;
+    const QUERY_RESULT_AVAILABLE_EXT: u32 = 0x8867;
+    const GPU_DISJOINT_EXT: u32 = 0x8FBB;
+
+    /// The number of frames of queries that can wait for their results before they are dropped
+    const MAX_PENDING_FRAMES: usize = 8;
+
+    /// The queries of a frame, with the section that each one timed
+    type FrameQueries = Vec<(usize, WebGlTimerQueryExt)>;
+
+    /// Times sections of frames on the GPU with the `EXT_disjoint_timer_query` WebGL extension
+    pub(crate) struct GpuTimer {
+        gl: WebGlRenderingContext,
+        ext: ExtDisjointTimerQuery,
+        current: FrameQueries,
+        pending: VecDeque<FrameQueries>,
+        active: bool,
+    }
+
+    impl GpuTimer {
+        /// Create a GPU timer, if the browser supports timer queries
+        pub fn new(surface: &Surface) -> Option<Self> {
+            let ext = surface
+                .gl
+                .get_extension("EXT_disjoint_timer_query")
+                .ok()
+                .flatten()?
+                .unchecked_into::<ExtDisjointTimerQuery>();
+
+            Some(Self {
+                gl: surface.gl.clone(),
+                ext,
+                current: Vec::new(),
+                pending: VecDeque::new(),
+                active: false,
+            })
+        }
+
+        pub fn begin(&mut self, section: usize) {
+            if let Some(query) = self.ext.create_query_ext() {
+                self.ext.begin_query_ext(TIME_ELAPSED_EXT, &query);
+                self.current.push((section, query));
+                self.active = true;
+            }
+        }
+
+        pub fn end(&mut self) {
+            if self.active {
+                self.ext.end_query_ext(TIME_ELAPSED_EXT);
+                self.active = false;
+            }
+        }
+
+        /// Finish the frame and get the GPU time of each section for the latest frame whose query
+        /// results are available
+        pub fn finish_frame(&mut self, sections: usize) -> Option<Vec<f64>> {
+            self.pending.push_back(std::mem::take(&mut self.current));
+
+            let mut times = None;
+            while let Some(queries) = self.pending.front() {
+                let available = queries.iter().all(|(_, query)| {
+                    self.ext
+                        .get_query_object_ext(query, QUERY_RESULT_AVAILABLE_EXT)
+                        .as_bool()
+                        .unwrap_or(false)
+                });
+                if !available {
+                    break;
+                }
+
+                // The results are invalid if the GPU was disjoint, such as when it was throttled
+                let disjoint = self
+                    .gl
+                    .get_parameter(GPU_DISJOINT_EXT)
+                    .ok()
+                    .and_then(|disjoint| disjoint.as_bool())
+                    .unwrap_or(true);
+
+                let queries = self.pending.pop_front().unwrap();
+                let mut frame_times = vec![0.; sections];
+                for (section, query) in &queries {
+                    let nanoseconds = self
+                        .ext
+                        .get_query_object_ext(query, QUERY_RESULT_EXT)
+                        .as_f64()
+                        .unwrap_or(0.);
+                    if let Some(time) = frame_times.get_mut(*section) {
+                        *time += nanoseconds / 1_000_000_000.;
+                    }
+                }
+                self.delete_queries(queries);
+
+                if !disjoint {
+                    times = Some(frame_times);
+                }
+            }
+
+            // Drop the oldest queries if their results never become available
+            while self.pending.len() > MAX_PENDING_FRAMES {
+                let queries = self.pending.pop_front().unwrap();
+                self.delete_queries(queries);
+            }
+
+            times
+        }
+
+        fn delete_queries(&self, queries: FrameQueries) {
+            for (_, query) in &queries {
+                self.ext.delete_query_ext(Some(query));
+            }
+        }
+    }
+}
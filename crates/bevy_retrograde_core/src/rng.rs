@@ -0,0 +1,109 @@
+//! Deterministic random number generation
+
+use bevy::prelude::*;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A seeded random number generator that gives the same numbers on every platform
+///
+/// The core plugin adds a [`RetroRng`] resource with a random seed, unless one has already been
+/// inserted. Gameplay that only uses this generator can be played back exactly by starting it
+/// again with the same [`seed`][RetroRng::seed].
+///
+/// Entities that need random numbers of their own can get a fork of the generator with
+/// [`fork_entity`][RetroRng::fork_entity], which doesn't depend on the order that systems use the
+/// generator in, and insert it as a component.
+///
+/// The generator implements [`RngCore`], so it works with all of the functions of the [`rand::Rng`]
+/// trait.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// use rand::Rng;
+///
+/// fn main() {
+///     App::build()
+///         // Use the same seed every time the game is played
+///         .insert_resource(RetroRng::new(42))
+///         .add_system(roll_dice.system());
+/// }
+///
+/// fn roll_dice(mut rng: ResMut<RetroRng>) {
+///     info!("Rolled a {}", rng.gen_range(1..=6));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetroRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl Default for RetroRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl RetroRng {
+    /// Create a generator with a seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Create a generator with a random seed
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// Get the seed that the generator was started with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start the generator over with a new seed
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// Create a generator seeded with the next number of this one
+    pub fn fork(&mut self) -> Self {
+        Self::new(self.rng.next_u64())
+    }
+
+    /// Create a generator for an entity, seeded with the seed of this generator and the entity
+    ///
+    /// The fork only depends on the seed and the entity, so the same entity always gets the same
+    /// numbers, no matter how many numbers have been taken from this generator.
+    pub fn fork_entity(&self, entity: Entity) -> Self {
+        // Mix the bits of the entity into the seed with the SplitMix64 finalizer so that entities
+        // next to each other get unrelated seeds
+        let mut seed = self.seed ^ entity.to_bits().wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self::new(seed ^ (seed >> 31))
+    }
+}
+
+impl RngCore for RetroRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
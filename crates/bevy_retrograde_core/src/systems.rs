@@ -0,0 +1,168 @@
+//! ECS systems
+
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+struct ClampCamerasSystem;
+
+pub(crate) fn add_systems(app: &mut AppBuilder) {
+    app.add_system_to_stage(
+        CoreStage::First,
+        update_game_time
+            .system()
+            .after(bevy::core::CoreSystem::Time),
+    )
+    .add_system_to_stage(
+        CoreStage::PostUpdate,
+        clamp_cameras_to_bounds
+            .system()
+            .label(ClampCamerasSystem)
+            .before(TransformSystem::TransformPropagate),
+    )
+    .add_system_to_stage(CoreStage::PreUpdate, update_cursor_world_position.system())
+    .add_system_to_stage(
+        CoreStage::PostUpdate,
+        anchor_to_screen
+            .system()
+            .after(ClampCamerasSystem)
+            .before(TransformSystem::TransformPropagate),
+    );
+}
+
+/// This system advances the [`GameTime`] by the real time of the last frame
+fn update_game_time(time: Res<Time>, mut game_time: ResMut<GameTime>) {
+    game_time.advance(time.delta_seconds());
+}
+
+/// This system keeps cameras with [`CameraBounds`] inside of their bounds
+fn clamp_cameras_to_bounds(
+    windows: Res<Windows>,
+    mut cameras: Query<(&Camera, &CameraBounds, &mut Transform)>,
+) {
+    let window = if let Some(window) = windows.get_primary() {
+        window
+    } else {
+        return;
+    };
+
+    for (camera, bounds, mut transform) in cameras.iter_mut() {
+        let target_size = camera.get_target_sizes(window).low;
+        let camera_size = Vec2::new(target_size.x as f32, target_size.y as f32);
+        let position = bounds.clamp_camera_position(
+            transform.translation.truncate(),
+            camera_size,
+            camera.centered,
+        );
+
+        // Only update the transform if it has changed to avoid triggering change detection
+        if position != transform.translation.truncate() {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
+
+/// This system positions entities with a [`ScreenAnchor`] relative to the camera view
+fn anchor_to_screen(
+    windows: Res<Windows>,
+    image_assets: Res<Assets<Image>>,
+    sprite_sheet_assets: Res<Assets<SpriteSheet>>,
+    cameras: Query<(&Camera, &Transform)>,
+    mut anchored: Query<
+        (
+            &ScreenAnchor,
+            &mut Transform,
+            Option<&Sprite>,
+            Option<&Handle<Image>>,
+            Option<&Handle<SpriteSheet>>,
+            Option<&SpriteSize>,
+        ),
+        Without<Camera>,
+    >,
+) {
+    let window = if let Some(window) = windows.get_primary() {
+        window
+    } else {
+        return;
+    };
+    let (camera, camera_transform) = if let Some(camera) = cameras.iter().next() {
+        camera
+    } else {
+        return;
+    };
+
+    // Get the top-left corner and the size of the camera view
+    let target_size = camera.get_target_sizes(window).low;
+    let camera_size = Vec2::new(target_size.x as f32, target_size.y as f32);
+    let camera_top_left = if camera.centered {
+        camera_transform.translation.truncate() - camera_size / 2.
+    } else {
+        camera_transform.translation.truncate()
+    };
+
+    for (screen_anchor, mut transform, sprite, image, sprite_sheet, sprite_size) in
+        anchored.iter_mut()
+    {
+        let anchor = screen_anchor.anchor.as_fraction();
+        let mut position = camera_top_left + anchor * camera_size + screen_anchor.offset;
+
+        // Align the same anchor point of the sprite to the anchor point of the screen
+        if let Some(sprite) = sprite {
+            let sprite_size =
+                if let Some(sprite_sheet) = sprite_sheet.and_then(|x| sprite_sheet_assets.get(x)) {
+                    Some(sprite_sheet.grid_size)
+                } else if let Some(sprite_size) = sprite_size {
+                    Some(sprite_size.0)
+                } else {
+                    image
+                        .and_then(|x| image_assets.get(x))
+                        .map(|x| UVec2::new(x.width(), x.height()))
+                };
+
+            if let Some(sprite_size) = sprite_size {
+                let sprite_size = Vec2::new(sprite_size.x as f32, sprite_size.y as f32);
+                let sprite_offset = if sprite.centered {
+                    sprite_size / 2.
+                } else {
+                    Vec2::ZERO
+                };
+
+                position += sprite_offset - anchor * sprite_size;
+            }
+        }
+
+        // Only update the transform if it has changed to avoid triggering change detection
+        if position != transform.translation.truncate() {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
+
+/// This system updates the [`CursorWorldPosition`] from the cursor in the primary window
+fn update_cursor_world_position(
+    windows: Res<Windows>,
+    cameras: Query<(&Camera, &Transform)>,
+    mut cursor_world_position: ResMut<CursorWorldPosition>,
+) {
+    let position = windows.get_primary().and_then(|window| {
+        let (camera, camera_transform) = cameras.iter().next()?;
+        camera.window_to_world(
+            camera_transform.translation.truncate(),
+            window,
+            window.cursor_position()?,
+        )
+    });
+
+    let new_position = CursorWorldPosition {
+        position,
+        pixel: position.map(|x| IVec2::new(x.x.floor() as i32, x.y.floor() as i32)),
+    };
+
+    // Only update the resource if it has changed to avoid triggering change detection
+    if new_position != *cursor_world_position {
+        *cursor_world_position = new_position;
+    }
+}
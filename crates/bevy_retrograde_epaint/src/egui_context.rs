@@ -0,0 +1,206 @@
+use bevy::{
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+        ElementState,
+    },
+    prelude::*,
+    window::{CursorLeft, CursorMoved, ReceivedCharacter},
+};
+use egui::{ClippedMesh, CtxRef, Event, Modifiers, PointerButton, Pos2, RawInput, Rect};
+
+/// The number of points scrolled for every line that the mouse wheel is scrolled
+const SCROLL_LINE_POINTS: f32 = 24.;
+
+/// This resource is the [`egui`] context that is drawn on top of the game
+///
+/// A frame of the context is started before the [`CoreStage::Update`] stage with the input from the
+/// primary window, so systems can add windows and panels to it. It is drawn at the full resolution
+/// of the window on top of the scaled retro output.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_epaint::*;
+/// struct PlayerSpeed(f32);
+///
+/// fn debug_window(egui: Res<EguiContext>, mut speed: ResMut<PlayerSpeed>) {
+///     egui::Window::new("Debug").show(&egui, |ui| {
+///         ui.add(egui::Slider::new(&mut speed.0, 0.0..=10.0).text("Player speed"));
+///     });
+/// }
+/// ```
+#[derive(Default)]
+pub struct EguiContext {
+    ctx: CtxRef,
+    /// The meshes of the last finished frame, which are drawn by the render hook
+    pub(crate) meshes: Vec<ClippedMesh>,
+    /// The position of the pointer in points, which isn't sent with mouse button events
+    pointer_position: Pos2,
+    /// Whether or not a frame has been started and not finished yet
+    frame_started: bool,
+}
+
+impl std::ops::Deref for EguiContext {
+    type Target = CtxRef;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ctx
+    }
+}
+
+impl EguiContext {
+    /// Get the egui context
+    pub fn ctx(&self) -> &CtxRef {
+        &self.ctx
+    }
+}
+
+/// The input events that egui uses
+#[derive(bevy::ecs::system::SystemParam)]
+pub(crate) struct EguiInputEvents<'a> {
+    cursor_moved: EventReader<'a, CursorMoved>,
+    cursor_left: EventReader<'a, CursorLeft>,
+    mouse_buttons: EventReader<'a, MouseButtonInput>,
+    mouse_wheel: EventReader<'a, MouseWheel>,
+    characters: EventReader<'a, ReceivedCharacter>,
+    keyboard: EventReader<'a, KeyboardInput>,
+}
+
+/// Start a frame of the egui context with the input from the primary window
+pub(crate) fn begin_egui_frame(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    keys: Res<Input<KeyCode>>,
+    mut egui: ResMut<EguiContext>,
+    mut input: EguiInputEvents,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let pixels_per_point = window.scale_factor() as f32;
+
+    let modifiers = Modifiers {
+        alt: keys.pressed(KeyCode::LAlt) || keys.pressed(KeyCode::RAlt),
+        ctrl: keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl),
+        shift: keys.pressed(KeyCode::LShift) || keys.pressed(KeyCode::RShift),
+        mac_cmd: cfg!(target_os = "macos")
+            && (keys.pressed(KeyCode::LWin) || keys.pressed(KeyCode::RWin)),
+        command: if cfg!(target_os = "macos") {
+            keys.pressed(KeyCode::LWin) || keys.pressed(KeyCode::RWin)
+        } else {
+            keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl)
+        },
+    };
+
+    let mut raw_input = RawInput {
+        screen_rect: Some(Rect::from_min_size(
+            Pos2::ZERO,
+            egui::vec2(window.width(), window.height()),
+        )),
+        pixels_per_point: Some(pixels_per_point),
+        time: Some(time.seconds_since_startup()),
+        predicted_dt: time.delta_seconds(),
+        modifiers,
+        ..Default::default()
+    };
+
+    let egui = &mut *egui;
+    for event in input.cursor_moved.iter() {
+        if event.id != window.id() {
+            continue;
+        }
+
+        // Bevy's cursor positions start at the bottom of the window and egui's start at the top
+        egui.pointer_position = Pos2::new(event.position.x, window.height() - event.position.y);
+        raw_input
+            .events
+            .push(Event::PointerMoved(egui.pointer_position));
+    }
+    for event in input.cursor_left.iter() {
+        if event.id == window.id() {
+            raw_input.events.push(Event::PointerGone);
+        }
+    }
+    for event in input.mouse_buttons.iter() {
+        let button = match event.button {
+            MouseButton::Left => PointerButton::Primary,
+            MouseButton::Right => PointerButton::Secondary,
+            MouseButton::Middle => PointerButton::Middle,
+            MouseButton::Other(_) => continue,
+        };
+        raw_input.events.push(Event::PointerButton {
+            pos: egui.pointer_position,
+            button,
+            pressed: event.state == ElementState::Pressed,
+            modifiers,
+        });
+    }
+    for event in input.mouse_wheel.iter() {
+        let scale = match event.unit {
+            MouseScrollUnit::Line => SCROLL_LINE_POINTS,
+            MouseScrollUnit::Pixel => 1. / pixels_per_point,
+        };
+        raw_input.scroll_delta += egui::vec2(event.x, event.y) * scale;
+    }
+    for event in input.characters.iter() {
+        if event.id == window.id() && !event.char.is_control() {
+            raw_input.events.push(Event::Text(event.char.to_string()));
+        }
+    }
+    for event in input.keyboard.iter() {
+        if let Some(key) = event.key_code.and_then(egui_key) {
+            raw_input.events.push(Event::Key {
+                key,
+                pressed: event.state == ElementState::Pressed,
+                modifiers,
+            });
+        }
+    }
+
+    egui.ctx.begin_frame(raw_input);
+    egui.frame_started = true;
+}
+
+/// Finish the frame of the egui context and tessellate it for the render hook
+pub(crate) fn end_egui_frame(mut egui: ResMut<EguiContext>) {
+    // Skip frames where there was no window to start a frame with
+    if !egui.frame_started {
+        return;
+    }
+
+    egui.frame_started = false;
+    let (_output, shapes) = egui.ctx.end_frame();
+    egui.meshes = egui.ctx.tessellate(shapes);
+}
+
+/// Get the egui key for the keys that egui uses for navigation and editing
+fn egui_key(key: KeyCode) -> Option<egui::Key> {
+    use egui::Key;
+
+    Some(match key {
+        KeyCode::Down => Key::ArrowDown,
+        KeyCode::Left => Key::ArrowLeft,
+        KeyCode::Right => Key::ArrowRight,
+        KeyCode::Up => Key::ArrowUp,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Back => Key::Backspace,
+        KeyCode::Return | KeyCode::NumpadEnter => Key::Enter,
+        KeyCode::Space => Key::Space,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::A => Key::A,
+        KeyCode::K => Key::K,
+        KeyCode::U => Key::U,
+        KeyCode::W => Key::W,
+        KeyCode::Z => Key::Z,
+        _ => return None,
+    })
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::EguiContext;
+
+/// This resource controls the egui entity inspector, a dev tool window that lists the entities of
+/// the world and shows the components of the selected entity
+///
+/// The inspector is closed by default. The position of an entity with a [`Transform`] can be edited
+/// in it too.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_epaint::*;
+/// fn toggle_inspector(keyboard: Res<Input<KeyCode>>, mut inspector: ResMut<EguiInspector>) {
+///     if keyboard.just_pressed(KeyCode::F12) {
+///         inspector.open = !inspector.open;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EguiInspector {
+    /// Whether or not the inspector window is shown
+    pub open: bool,
+    /// The entity that is being inspected
+    pub selected: Option<Entity>,
+}
+
+/// Show the inspector window
+pub(crate) fn show_inspector(world: &mut World) {
+    let mut inspector = match world.get_resource::<EguiInspector>() {
+        Some(inspector) if inspector.open => inspector.clone(),
+        _ => return,
+    };
+    let ctx = match world.get_resource::<EguiContext>() {
+        Some(context) => context.ctx().clone(),
+        None => return,
+    };
+
+    // List the entities by name if they have one
+    let mut entities = world
+        .query::<(Entity, Option<&Name>)>()
+        .iter(world)
+        .map(|(entity, name)| {
+            let label = match name {
+                Some(name) => format!("{} ({})", name.as_str(), entity.id()),
+                None => format!("Entity {}", entity.id()),
+            };
+            (entity, label)
+        })
+        .collect::<Vec<_>>();
+    entities.sort_by_key(|(entity, _)| entity.id());
+
+    let mut open = inspector.open;
+    egui::Window::new("Inspector")
+        .open(&mut open)
+        .show(&ctx, |ui| {
+            egui::ScrollArea::from_max_height(200.).show(ui, |ui| {
+                for (entity, label) in &entities {
+                    if ui
+                        .selectable_label(inspector.selected == Some(*entity), label.as_str())
+                        .clicked()
+                    {
+                        inspector.selected = Some(*entity);
+                    }
+                }
+            });
+
+            let entity = match inspector.selected {
+                Some(entity) if world.get_entity(entity).is_some() => entity,
+                _ => return,
+            };
+            ui.separator();
+
+            // Edit the position of the entity, only setting it when it changes so that change
+            // detection isn't triggered
+            if let Some(transform) = world.get::<Transform>(entity) {
+                let mut translation = transform.translation;
+                ui.horizontal(|ui| {
+                    ui.label("Position");
+                    ui.add(egui::DragValue::new(&mut translation.x));
+                    ui.add(egui::DragValue::new(&mut translation.y));
+                    ui.add(egui::DragValue::new(&mut translation.z));
+                });
+                if translation != transform.translation {
+                    if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+                        transform.translation = translation;
+                    }
+                }
+            }
+
+            ui.label("Components:");
+            if let Some(entity_ref) = world.get_entity(entity) {
+                for component in entity_ref.archetype().components() {
+                    if let Some(info) = world.components().get_info(component) {
+                        ui.label(info.name());
+                    }
+                }
+            }
+        });
+    inspector.open = open;
+
+    // Only write the inspector back if it has changed to avoid triggering change detection
+    if let Some(mut current) = world.get_resource_mut::<EguiInspector>() {
+        if current.open != inspector.open || current.selected != inspector.selected {
+            *current = inspector;
+        }
+    }
+}
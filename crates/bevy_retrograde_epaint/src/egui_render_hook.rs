@@ -0,0 +1,207 @@
+use std::ops::Range;
+
+use bevy::prelude::World;
+use bevy_retrograde_core::{
+    graphics::{
+        FrameContext, Program, RenderHook, ScreenFramebuffer, Surface, Texture, TextureCache,
+    },
+    luminance::{
+        self,
+        blending::{Blending, Equation, Factor},
+        context::GraphicsContext,
+        pipeline::{PipelineState, TextureBinding},
+        pixel::{NormRGBA8UI, NormUnsigned},
+        render_state::RenderState,
+        shader::Uniform,
+        texture::{Dim2, GenMipmaps, MagFilter, MinFilter, Sampler, Wrap},
+        UniformInterface, Vertex,
+    },
+};
+
+use egui::ClippedMesh;
+
+use crate::{
+    render_hook::{VertexColor, VertexPosition, VertexSemantics, VertexUv},
+    EguiContext,
+};
+
+/// The sampler for the egui font texture, which is smoothly scaled unlike the retro textures
+const FONT_SAMPLER: Sampler = Sampler {
+    wrap_r: Wrap::ClampToEdge,
+    wrap_s: Wrap::ClampToEdge,
+    wrap_t: Wrap::ClampToEdge,
+    min_filter: MinFilter::Linear,
+    mag_filter: MagFilter::Linear,
+    depth_comparison: None,
+};
+
+/// The render hook that draws the [`EguiContext`] on top of the game
+pub struct EguiRenderHook {
+    program: Program<(), (), EguiUniformInterface>,
+    /// The font texture and the egui version of it that was uploaded
+    font_texture: Option<(u64, Texture<Dim2, NormRGBA8UI>)>,
+}
+
+impl RenderHook for EguiRenderHook {
+    fn init(_window_id: bevy::window::WindowId, surface: &mut Surface) -> Box<dyn RenderHook>
+    where
+        Self: Sized,
+    {
+        let program = surface
+            .new_shader_program::<(), (), EguiUniformInterface>()
+            .from_strings(
+                include_str!("egui_render_hook/egui.vert"),
+                None,
+                None,
+                include_str!("egui_render_hook/egui.frag"),
+            )
+            .unwrap()
+            .program;
+
+        Box::new(Self {
+            program,
+            font_texture: None,
+        })
+    }
+
+    fn render_overlay(
+        &mut self,
+        world: &mut World,
+        surface: &mut Surface,
+        _texture_cache: &mut TextureCache,
+        _frame_context: &FrameContext,
+        target_framebuffer: &ScreenFramebuffer,
+    ) {
+        let context = if let Some(context) = world.get_resource::<EguiContext>() {
+            context
+        } else {
+            return;
+        };
+        if context.meshes.is_empty() {
+            return;
+        }
+
+        // Upload the font texture if it has changed
+        let font = context.texture();
+        if self.font_texture.as_ref().map(|(version, _)| *version) != Some(font.version) {
+            // The font texture only has alpha, so make it white
+            let mut pixels = Vec::with_capacity(font.pixels.len() * 4);
+            for &alpha in &font.pixels {
+                pixels.extend_from_slice(&[255, 255, 255, alpha]);
+            }
+            let mut texture = surface
+                .new_texture::<Dim2, NormRGBA8UI>(
+                    [font.width as u32, font.height as u32],
+                    0,
+                    FONT_SAMPLER,
+                )
+                .unwrap();
+            texture.upload_raw(GenMipmaps::No, &pixels).unwrap();
+            self.font_texture = Some((font.version, texture));
+        }
+
+        // Collect the meshes into one tesselation, with the clip rectangle of each mesh in pixels
+        let pixels_per_point = context.pixels_per_point();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut batches: Vec<([f32; 4], Range<usize>)> = Vec::new();
+        for ClippedMesh(clip_rect, mesh) in &context.meshes {
+            let start = indices.len();
+            let offset = vertices.len() as u32;
+            indices.extend(mesh.indices.iter().map(|i| i + offset));
+            vertices.extend(mesh.vertices.iter().map(|v| EguiVert {
+                pos: VertexPosition::new([v.pos.x, v.pos.y]),
+                uv: VertexUv::new([v.uv.x, v.uv.y]),
+                color: VertexColor::new([
+                    v.color.r() as f32 / 255.,
+                    v.color.g() as f32 / 255.,
+                    v.color.b() as f32 / 255.,
+                    v.color.a() as f32 / 255.,
+                ]),
+            }));
+
+            batches.push((
+                [
+                    clip_rect.min.x * pixels_per_point,
+                    clip_rect.min.y * pixels_per_point,
+                    clip_rect.max.x * pixels_per_point,
+                    clip_rect.max.y * pixels_per_point,
+                ],
+                start..indices.len(),
+            ));
+        }
+
+        let tess = surface
+            .new_tess()
+            .set_mode(luminance::tess::Mode::Triangle)
+            .set_vertices(vertices)
+            .set_indices(indices)
+            .build()
+            .unwrap();
+
+        let [width, height] = target_framebuffer.size();
+        let screen_size = [
+            width as f32 / pixels_per_point,
+            height as f32 / pixels_per_point,
+        ];
+        let font_texture = &mut self.font_texture.as_mut().unwrap().1;
+        let program = &mut self.program;
+
+        // egui colors are premultiplied by their alpha
+        let render_state = &RenderState::default()
+            .set_face_culling(None)
+            .set_depth_test(None)
+            .set_blending(Blending {
+                equation: Equation::Additive,
+                src: Factor::One,
+                dst: Factor::SrcAlphaComplement,
+            });
+
+        surface
+            .new_pipeline_gate()
+            .pipeline(
+                target_framebuffer,
+                &PipelineState::default()
+                    .enable_clear_color(false)
+                    .enable_clear_depth(false),
+                |pipeline, mut shading_gate| {
+                    let bound_texture = pipeline.bind_texture(font_texture)?;
+
+                    shading_gate.shade(program, |mut interface, uniforms, mut render_gate| {
+                        interface.set(&uniforms.font_texture, bound_texture.binding());
+                        interface.set(&uniforms.screen_size, screen_size);
+                        interface.set(&uniforms.screen_height, height as f32);
+
+                        for (clip_rect, range) in &batches {
+                            interface.set(&uniforms.clip_rect, *clip_rect);
+                            render_gate.render(render_state, |mut tess_gate| {
+                                tess_gate.render(tess.view(range.clone()).unwrap())
+                            })?;
+                        }
+
+                        Ok(())
+                    })
+                },
+            )
+            .assume()
+            .into_result()
+            .expect("Could not render egui");
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Vertex)]
+#[vertex(sem = "VertexSemantics")]
+struct EguiVert {
+    pos: VertexPosition,
+    uv: VertexUv,
+    color: VertexColor,
+}
+
+#[derive(UniformInterface)]
+struct EguiUniformInterface {
+    font_texture: Uniform<TextureBinding<Dim2, NormUnsigned>>,
+    screen_size: Uniform<[f32; 2]>,
+    screen_height: Uniform<f32>,
+    clip_rect: Uniform<[f32; 4]>,
+}
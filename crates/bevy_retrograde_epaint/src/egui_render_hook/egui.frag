@@ -0,0 +1,18 @@
+varying vec2 uv;
+varying vec4 color;
+
+uniform sampler2D font_texture;
+// The min and max corners of the clip rectangle in pixels from the top-left of the screen
+uniform vec4 clip_rect;
+uniform float screen_height;
+
+void main() {
+  vec2 frag_pos = vec2(gl_FragCoord.x, screen_height - gl_FragCoord.y);
+  if (frag_pos.x < clip_rect.x || frag_pos.y < clip_rect.y ||
+      frag_pos.x > clip_rect.z || frag_pos.y > clip_rect.w) {
+    discard;
+  }
+
+  // The vertex colors are premultiplied and the font texture is white with alpha
+  gl_FragColor = color * texture2D(font_texture, uv).a;
+}
@@ -0,0 +1,19 @@
+attribute vec2 v_pos;
+attribute vec2 v_uv;
+attribute vec4 v_color;
+
+varying vec2 uv;
+varying vec4 color;
+
+// The size of the screen in egui points
+uniform vec2 screen_size;
+
+void main() {
+  uv = v_uv;
+  color = v_color;
+
+  // Convert from points with the origin in the top-left to normalized coordinates
+  vec2 norm_pos = v_pos / screen_size * 2.0 - 1.0;
+
+  gl_Position = vec4(norm_pos.x, -norm_pos.y, 0., 1.);
+}
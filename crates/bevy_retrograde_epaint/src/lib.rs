@@ -3,7 +3,12 @@
 //! The target use-case is easy drawing of primitives such as lines, circles, text, etc. for use in
 //! debug rendering and visualization.
 //!
+//! With the `egui` feature, the [`RetroEguiPlugin`] draws an [egui] context on top of the game at
+//! the full resolution of the window, for building dev tools such as the built-in
+//! [`EguiInspector`].
+//!
 //! [epaint]: https://docs.rs/epaint
+//! [egui]: https://docs.rs/egui
 
 use bevy::prelude::*;
 
@@ -14,10 +19,30 @@ pub use epaint::emath::*;
 pub use epaint::*;
 use render_hook::EpaintRenderHook;
 
+#[cfg(feature = "egui")]
+mod egui_context;
+#[cfg(feature = "egui")]
+pub use egui_context::EguiContext;
+
+#[cfg(feature = "egui")]
+mod egui_inspector;
+#[cfg(feature = "egui")]
+pub use egui_inspector::EguiInspector;
+
+#[cfg(feature = "egui")]
+mod egui_render_hook;
+
+/// Re-export of the [`egui`] crate
+#[cfg(feature = "egui")]
+pub use egui;
+
 /// Epaint plugin prelude
 pub mod prelude {
     pub use crate::ShapeBundle;
     pub use epaint::Shape;
+
+    #[cfg(feature = "egui")]
+    pub use crate::{egui, EguiContext, EguiInspector};
 }
 
 /// Text rendering plugin for Bevy Retrograde
@@ -32,6 +57,25 @@ impl Plugin for RetroEpaintPlugin {
     }
 }
 
+/// Egui plugin for Bevy Retrograde, which adds the [`EguiContext`] and the [`EguiInspector`]
+#[cfg(feature = "egui")]
+pub struct RetroEguiPlugin;
+
+#[cfg(feature = "egui")]
+impl Plugin for RetroEguiPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<EguiContext>()
+            .init_resource::<EguiInspector>()
+            .add_render_hook::<egui_render_hook::EguiRenderHook>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                egui_context::begin_egui_frame.system(),
+            )
+            .add_system(egui_inspector::show_inspector.exclusive_system())
+            .add_system_to_stage(CoreStage::Last, egui_context::end_egui_frame.system());
+    }
+}
+
 /// Bundle for rendering an [`epaint`] shape
 #[derive(Bundle, Debug, Clone)]
 pub struct ShapeBundle {
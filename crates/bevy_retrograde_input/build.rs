@@ -0,0 +1,5 @@
+fn main() {
+    cfg_aliases::cfg_aliases! {
+        wasm: { target_arch = "wasm32" },
+    }
+}
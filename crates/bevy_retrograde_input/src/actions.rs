@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    input::gamepad::{GamepadAxisType, GamepadButtonType},
+    prelude::*,
+};
+
+use crate::{
+    virtual_controls::VirtualControlState, AxisDirection, AxisSource, InputBindings, InputSource,
+};
+
+/// How far a gamepad axis has to be pushed to trigger an action bound to it
+const GAMEPAD_AXIS_THRESHOLD: f32 = 0.5;
+
+/// This resource has the state of the actions bound in the [`InputBindings`]
+///
+/// Like Bevy's [`Input`] resource, an action is pressed while any of its inputs are held, and is
+/// just pressed and just released on the frames that it starts and stops being pressed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_input::*;
+/// struct Player;
+///
+/// fn jump(actions: Res<InputActions>, query: Query<&Transform, With<Player>>) {
+///     if actions.just_pressed("jump") {
+///         for transform in query.iter() {
+///             info!("Jumped from {:?}", transform.translation);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputActions {
+    pressed: HashSet<String>,
+    just_pressed: HashSet<String>,
+    just_released: HashSet<String>,
+}
+
+impl InputActions {
+    /// Get whether or not an action is pressed
+    pub fn pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    /// Get whether or not an action started being pressed this frame
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.just_pressed.contains(action)
+    }
+
+    /// Get whether or not an action stopped being pressed this frame
+    pub fn just_released(&self, action: &str) -> bool {
+        self.just_released.contains(action)
+    }
+
+    /// Iterate over the actions that are pressed
+    pub fn get_pressed(&self) -> impl Iterator<Item = &str> {
+        self.pressed.iter().map(String::as_str)
+    }
+
+    /// Iterate over the actions that started being pressed this frame
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &str> {
+        self.just_pressed.iter().map(String::as_str)
+    }
+
+    /// Iterate over the actions that stopped being pressed this frame
+    pub fn get_just_released(&self) -> impl Iterator<Item = &str> {
+        self.just_released.iter().map(String::as_str)
+    }
+
+    /// Update the actions from the set of actions that are pressed this frame
+    fn update(&mut self, pressed: HashSet<String>) {
+        self.just_pressed = pressed.difference(&self.pressed).cloned().collect();
+        self.just_released = self.pressed.difference(&pressed).cloned().collect();
+        self.pressed = pressed;
+    }
+}
+
+/// This resource has the values of the axes bound in the [`InputBindings`]
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_input::*;
+/// struct Player;
+///
+/// fn walk(axes: Res<InputAxes>, mut query: Query<&mut Transform, With<Player>>) {
+///     for mut transform in query.iter_mut() {
+///         transform.translation.x += axes.value("move");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputAxes {
+    values: HashMap<String, f32>,
+}
+
+impl InputAxes {
+    /// Get the value of an axis, from `-1.0` to `1.0`, which is `0.0` if nothing is held
+    pub fn value(&self, axis: &str) -> f32 {
+        self.values.get(axis).copied().unwrap_or_default()
+    }
+}
+
+/// The input devices that actions and axes are read from
+pub(crate) struct InputDevices<'a> {
+    pub keys: &'a Input<KeyCode>,
+    pub mouse_buttons: &'a Input<MouseButton>,
+    pub gamepads: &'a Gamepads,
+    pub gamepad_buttons: &'a Input<GamepadButton>,
+    pub gamepad_axes: &'a Axis<GamepadAxis>,
+    pub touches: &'a Touches,
+    pub window: Option<&'a Window>,
+}
+
+impl<'a> InputDevices<'a> {
+    /// Get whether or not an input is pressed
+    pub fn pressed(&self, source: &InputSource) -> bool {
+        match *source {
+            InputSource::Key(key) => self.keys.pressed(key),
+            InputSource::MouseButton(button) => self.mouse_buttons.pressed(button),
+            InputSource::GamepadButton(button) => self.gamepad_button_pressed(button),
+            InputSource::GamepadAxis(axis, direction) => {
+                let value = self.gamepad_axis(axis);
+                match direction {
+                    AxisDirection::Positive => value >= GAMEPAD_AXIS_THRESHOLD,
+                    AxisDirection::Negative => value <= -GAMEPAD_AXIS_THRESHOLD,
+                }
+            }
+            InputSource::Touch(area) => {
+                let window = match self.window {
+                    Some(window) => window,
+                    None => return false,
+                };
+
+                // Touch positions start from the bottom left corner of the window
+                self.touches.iter().any(|touch| {
+                    let position = touch.position();
+                    area.contains(Vec2::new(
+                        position.x / window.width(),
+                        1. - position.y / window.height(),
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Get the value of an axis, from `-1.0` to `1.0`
+    pub fn axis(&self, source: &AxisSource) -> f32 {
+        match source {
+            AxisSource::Buttons { negative, positive } => {
+                let mut value = 0.;
+                if self.pressed(negative) {
+                    value -= 1.;
+                }
+                if self.pressed(positive) {
+                    value += 1.;
+                }
+                value
+            }
+            AxisSource::GamepadAxis(axis) => self.gamepad_axis(*axis),
+        }
+    }
+
+    fn gamepad_button_pressed(&self, button: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.gamepad_buttons
+                .pressed(GamepadButton(*gamepad, button))
+        })
+    }
+
+    /// Get the value of an axis of the gamepad that pushes it the furthest
+    fn gamepad_axis(&self, axis: GamepadAxisType) -> f32 {
+        self.gamepads
+            .iter()
+            .filter_map(|gamepad| self.gamepad_axes.get(GamepadAxis(*gamepad, axis)))
+            .fold(
+                0.,
+                |value: f32, x| if x.abs() > value.abs() { x } else { value },
+            )
+    }
+}
+
+/// Update the actions and axes from the input devices
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_input_actions(
+    bindings: Res<InputBindings>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    virtual_controls: Res<VirtualControlState>,
+    mut actions: ResMut<InputActions>,
+    mut axes: ResMut<InputAxes>,
+) {
+    let devices = InputDevices {
+        keys: &keys,
+        mouse_buttons: &mouse_buttons,
+        gamepads: &gamepads,
+        gamepad_buttons: &gamepad_buttons,
+        gamepad_axes: &gamepad_axes,
+        touches: &touches,
+        window: windows.get_primary(),
+    };
+
+    actions.update(
+        bindings
+            .actions()
+            .filter(|action| {
+                bindings
+                    .action_sources(action)
+                    .iter()
+                    .any(|source| devices.pressed(source))
+            })
+            .map(String::from)
+            .chain(virtual_controls.pressed.iter().cloned())
+            .collect(),
+    );
+
+    axes.values = bindings
+        .axes()
+        .map(|axis| {
+            let value = bindings
+                .axis_sources(axis)
+                .iter()
+                .map(|source| devices.axis(source))
+                .sum::<f32>();
+            (axis.to_string(), value)
+        })
+        .collect();
+    for (axis, value) in &virtual_controls.axes {
+        *axes.values.entry(axis.clone()).or_default() += value;
+    }
+    for value in axes.values.values_mut() {
+        *value = value.max(-1.).min(1.);
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::gamepad::{GamepadAxisType, GamepadButtonType},
+    prelude::*,
+};
+
+/// A button-like input that can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputSource {
+    /// A key on the keyboard
+    Key(KeyCode),
+    /// A mouse button
+    MouseButton(MouseButton),
+    /// A button on any of the connected gamepads
+    GamepadButton(GamepadButtonType),
+    /// An axis of any of the connected gamepads, such as a stick, pushed more than half way in a
+    /// direction
+    GamepadAxis(GamepadAxisType, AxisDirection),
+    /// A touch in an area of the primary window
+    Touch(TouchArea),
+}
+
+/// A direction along an axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// An area of the window, as fractions of the size of the window from its top left corner
+///
+/// For example, the left half of the window is `TouchArea { left: 0., top: 0., right: 0.5, bottom:
+/// 1. }`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchArea {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl TouchArea {
+    /// The whole window
+    pub const WINDOW: TouchArea = TouchArea {
+        left: 0.,
+        top: 0.,
+        right: 1.,
+        bottom: 1.,
+    };
+
+    /// Get whether or not a position, as a fraction of the window size from its top left corner,
+    /// is in the area
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.x >= self.left
+            && position.x < self.right
+            && position.y >= self.top
+            && position.y < self.bottom
+    }
+}
+
+/// An input that sets the value of an axis, from `-1.0` to `1.0`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisSource {
+    /// A pair of inputs that set the axis to `-1.0` and `1.0` while they are pressed, such as the
+    /// left and right arrow keys
+    Buttons {
+        negative: InputSource,
+        positive: InputSource,
+    },
+    /// An axis of any of the connected gamepads, such as a stick
+    GamepadAxis(GamepadAxisType),
+}
+
+/// This resource binds the names of actions and axes to the inputs that trigger them
+///
+/// Games read the actions and axes from the [`InputActions`][crate::InputActions] and
+/// [`InputAxes`][crate::InputAxes] resources by name, so the controls can be changed in one place,
+/// or by the player, without changing the systems that use them.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{
+/// #     input::gamepad::{GamepadAxisType, GamepadButtonType},
+/// #     prelude::*,
+/// # };
+/// # use bevy_retrograde_input::*;
+/// fn setup_controls(mut bindings: ResMut<InputBindings>) {
+///     bindings
+///         .bind("jump", InputSource::Key(KeyCode::Space))
+///         .bind("jump", InputSource::GamepadButton(GamepadButtonType::South))
+///         .bind_axis(
+///             "move",
+///             AxisSource::Buttons {
+///                 negative: InputSource::Key(KeyCode::Left),
+///                 positive: InputSource::Key(KeyCode::Right),
+///             },
+///         )
+///         .bind_axis("move", AxisSource::GamepadAxis(GamepadAxisType::LeftStickX));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct InputBindings {
+    actions: HashMap<String, Vec<InputSource>>,
+    axes: HashMap<String, Vec<AxisSource>>,
+}
+
+impl InputBindings {
+    /// Add an input that triggers an action
+    pub fn bind(&mut self, action: &str, source: InputSource) -> &mut Self {
+        let sources = self.actions.entry(action.to_string()).or_default();
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+
+        self
+    }
+
+    /// Add an input that sets the value of an axis
+    pub fn bind_axis(&mut self, axis: &str, source: AxisSource) -> &mut Self {
+        let sources = self.axes.entry(axis.to_string()).or_default();
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+
+        self
+    }
+
+    /// Remove an input from an action
+    pub fn unbind(&mut self, action: &str, source: InputSource) -> &mut Self {
+        if let Some(sources) = self.actions.get_mut(action) {
+            sources.retain(|x| *x != source);
+        }
+
+        self
+    }
+
+    /// Remove an input from an axis
+    pub fn unbind_axis(&mut self, axis: &str, source: AxisSource) -> &mut Self {
+        if let Some(sources) = self.axes.get_mut(axis) {
+            sources.retain(|x| *x != source);
+        }
+
+        self
+    }
+
+    /// Replace an input of an action with a new one, such as when the player rebinds a control
+    ///
+    /// If `old` is [`None`], or isn't bound to the action, the new input is added to the action. If
+    /// the new input is already bound to another action or axis, the binding is left unchanged and
+    /// the conflict is returned, so that the player can be asked whether to unbind it there first.
+    pub fn rebind(
+        &mut self,
+        action: &str,
+        old: Option<InputSource>,
+        new: InputSource,
+    ) -> Result<&mut Self, InputBindingConflict> {
+        if let Some(conflict) = self.find_conflict(&new, Some(action), None) {
+            return Err(conflict);
+        }
+
+        replace_source(
+            self.actions.entry(action.to_string()).or_default(),
+            old,
+            new,
+        );
+
+        Ok(self)
+    }
+
+    /// Replace an input of an axis with a new one, such as when the player rebinds a control
+    ///
+    /// Like [`rebind`][Self::rebind], the new input is added if `old` is [`None`], and the conflict
+    /// is returned if any of its inputs are already bound to another action or axis.
+    pub fn rebind_axis(
+        &mut self,
+        axis: &str,
+        old: Option<AxisSource>,
+        new: AxisSource,
+    ) -> Result<&mut Self, InputBindingConflict> {
+        let conflict = match &new {
+            AxisSource::Buttons { negative, positive } => self
+                .find_conflict(negative, None, Some(axis))
+                .or_else(|| self.find_conflict(positive, None, Some(axis))),
+            AxisSource::GamepadAxis(_) => self
+                .axes
+                .iter()
+                .find(|(name, sources)| *name != axis && sources.contains(&new))
+                .map(|(name, _)| InputBindingConflict::Axis(name.clone())),
+        };
+        if let Some(conflict) = conflict {
+            return Err(conflict);
+        }
+
+        replace_source(self.axes.entry(axis.to_string()).or_default(), old, new);
+
+        Ok(self)
+    }
+
+    /// Get the actions that an input is bound to
+    pub fn actions_bound_to(&self, source: &InputSource) -> impl Iterator<Item = &str> {
+        let source = *source;
+        self.actions
+            .iter()
+            .filter(move |(_, sources)| sources.contains(&source))
+            .map(|(action, _)| action.as_str())
+    }
+
+    /// Get the axes that an input is one of the buttons of
+    pub fn axes_bound_to(&self, source: &InputSource) -> impl Iterator<Item = &str> {
+        let source = *source;
+        self.axes
+            .iter()
+            .filter(move |(_, sources)| {
+                sources.iter().any(|x| match x {
+                    AxisSource::Buttons { negative, positive } => {
+                        *negative == source || *positive == source
+                    }
+                    AxisSource::GamepadAxis(_) => false,
+                })
+            })
+            .map(|(axis, _)| axis.as_str())
+    }
+
+    /// Find an action or axis other than the given ones that an input is bound to
+    fn find_conflict(
+        &self,
+        source: &InputSource,
+        action: Option<&str>,
+        axis: Option<&str>,
+    ) -> Option<InputBindingConflict> {
+        self.actions_bound_to(source)
+            .find(|x| Some(*x) != action)
+            .map(|x| InputBindingConflict::Action(x.to_string()))
+            .or_else(|| {
+                self.axes_bound_to(source)
+                    .find(|x| Some(*x) != axis)
+                    .map(|x| InputBindingConflict::Axis(x.to_string()))
+            })
+    }
+
+    /// Get the inputs that trigger an action
+    pub fn action_sources(&self, action: &str) -> &[InputSource] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the inputs that set the value of an axis
+    pub fn axis_sources(&self, axis: &str) -> &[AxisSource] {
+        self.axes.get(axis).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Iterate over the names of the bound actions
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// Iterate over the names of the bound axes
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+}
+
+/// Replace an input in a list of inputs, or add it if the old input isn't in the list
+fn replace_source<T: PartialEq>(sources: &mut Vec<T>, old: Option<T>, new: T) {
+    match old.and_then(|old| sources.iter().position(|x| *x == old)) {
+        Some(index) if !sources.contains(&new) => sources[index] = new,
+        Some(index) if sources[index] != new => {
+            sources.remove(index);
+        }
+        None if !sources.contains(&new) => sources.push(new),
+        _ => (),
+    }
+}
+
+/// An error returned when rebinding an input that is already bound to another action or axis
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum InputBindingConflict {
+    #[error("The input is already bound to the action `{0}`")]
+    Action(String),
+    #[error("The input is already bound to the axis `{0}`")]
+    Axis(String),
+}
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::CursorWorldPosition;
+
+use crate::{PickedSprites, SpritePickEvent, SpritePickEventKind};
+
+/// Add this component to a [`Pickable`][crate::Pickable] sprite to be able to drag it around the
+/// world with the mouse
+///
+/// While the sprite is dragged it has a [`Dragging`] component, and its [`Transform`] follows the
+/// cursor in world pixels, so it shouldn't have a parent. [`DragEvent`]s are sent when it is picked
+/// up and dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// # use bevy_retrograde_input::*;
+/// struct Slot;
+///
+/// fn spawn_card(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands
+///         .spawn_bundle(SpriteBundle {
+///             image: asset_server.load("card.png"),
+///             ..Default::default()
+///         })
+///         .insert(Pickable::Bounds)
+///         .insert(Draggable {
+///             snap: Some(Vec2::new(8., 8.)),
+///             ..Default::default()
+///         });
+/// }
+///
+/// fn drop_cards(mut events: EventReader<DragEvent>, slots: Query<(), With<Slot>>) {
+///     for event in events.iter() {
+///         if let DragEventKind::Dropped { target: Some(target), .. } = event.kind {
+///             if slots.get(target).is_ok() {
+///                 info!("Put card {:?} into slot {:?}", event.entity, target);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Draggable {
+    /// The mouse button that drags the sprite
+    pub button: MouseButton,
+    /// The size of the grid cells that the sprite's position snaps to while it is dragged, if any
+    pub snap: Option<Vec2>,
+}
+
+impl Default for Draggable {
+    fn default() -> Self {
+        Self {
+            button: MouseButton::Left,
+            snap: None,
+        }
+    }
+}
+
+/// Component added to a [`Draggable`] sprite while it is being dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dragging {
+    /// The position of the sprite when it was picked up
+    pub start: Vec2,
+    /// The offset of the sprite's position from the cursor
+    pub grab_offset: Vec2,
+}
+
+/// Bevy event sent when a [`Draggable`] sprite is picked up or dropped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragEvent {
+    /// The dragged sprite
+    pub entity: Entity,
+    /// What happened to the sprite
+    pub kind: DragEventKind,
+}
+
+/// What happened to the sprite of a [`DragEvent`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragEventKind {
+    /// The sprite was picked up
+    Started,
+    /// The sprite was dropped
+    Dropped {
+        /// The position of the sprite when it was picked up
+        start: Vec2,
+        /// The position that the sprite was dropped at
+        end: Vec2,
+        /// The top [`Pickable`][crate::Pickable] sprite under the cursor when it was dropped, if
+        /// any
+        target: Option<Entity>,
+    },
+}
+
+/// Pick up the draggable sprites that are pressed on, and move the dragged sprites with the cursor
+pub(crate) fn drag_sprites(
+    mut commands: Commands,
+    cursor: Res<CursorWorldPosition>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    picked: Res<PickedSprites>,
+    mut pick_events: EventReader<SpritePickEvent>,
+    mut drag_events: EventWriter<DragEvent>,
+    mut draggables: Query<(Entity, &Draggable, &mut Transform, Option<&Dragging>)>,
+) {
+    // Move or drop the sprites that are being dragged
+    for (entity, draggable, mut transform, dragging) in draggables.iter_mut() {
+        let dragging = match dragging {
+            Some(dragging) => dragging,
+            None => continue,
+        };
+
+        if !mouse_buttons.pressed(draggable.button) {
+            commands.entity(entity).remove::<Dragging>();
+            drag_events.send(DragEvent {
+                entity,
+                kind: DragEventKind::Dropped {
+                    start: dragging.start,
+                    end: transform.translation.truncate(),
+                    target: picked.entities.iter().copied().find(|x| *x != entity),
+                },
+            });
+            continue;
+        }
+
+        if let Some(cursor) = cursor.position {
+            let mut position = cursor + dragging.grab_offset;
+            if let Some(snap) = draggable.snap {
+                position = (position / snap).round() * snap;
+            }
+
+            // Only update the transform if it has changed to avoid triggering change detection
+            if position != transform.translation.truncate() {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+        }
+    }
+
+    // Pick up the sprites that are pressed on
+    for event in pick_events.iter() {
+        let (button, cursor) = match (event.kind, cursor.position) {
+            (SpritePickEventKind::Pressed(button), Some(cursor)) => (button, cursor),
+            _ => continue,
+        };
+        if let Ok((entity, draggable, transform, None)) = draggables.get_mut(event.entity) {
+            if draggable.button != button {
+                continue;
+            }
+
+            let position = transform.translation.truncate();
+            commands.entity(entity).insert(Dragging {
+                start: position,
+                grab_offset: position - cursor,
+            });
+            drag_events.send(DragEvent {
+                entity,
+                kind: DragEventKind::Started,
+            });
+        }
+    }
+}
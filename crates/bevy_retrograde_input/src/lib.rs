@@ -0,0 +1,95 @@
+//! Bevy Retrograde input plugin
+//!
+//! Instead of checking for specific keys and buttons, games bind the names of actions, such as
+//! `"jump"`, and axes, such as `"move"`, to keyboard, mouse, gamepad, and touch inputs in the
+//! [`InputBindings`] resource. Systems then read the [`InputActions`] and [`InputAxes`] resources by
+//! name, so the controls can be changed in one place, or rebound by the player.
+//!
+//! On touch screens, virtual d-pads and buttons can be drawn over the game with the
+//! [`VirtualControls`] resource, which press actions and set axes like any other input.
+//!
+//! Sprites with the [`Pickable`] component can be hovered and clicked with the mouse, which is
+//! reported by the [`PickedSprites`] resource and [`SpritePickEvent`]s. [`Draggable`] sprites can
+//! also be dragged around the world.
+//!
+//! On web, gamepads are read from the browser's Gamepad API, which Bevy doesn't support itself, so
+//! gamepad inputs work the same as on other platforms.
+//!
+//! Players can rebind controls with [`InputBindings::rebind`], which reports an
+//! [`InputBindingConflict`] when the new input is already in use. With the `serialize` feature
+//! enabled, the [`InputBindings`] can be serialized so that they can be saved, and with the `save`
+//! feature enabled, they can be saved automatically with
+//! [`add_saved_input_bindings`][AppBuilderSavedBindingsExt::add_saved_input_bindings].
+
+use bevy::{input::InputSystem, prelude::*};
+
+mod actions;
+pub use actions::*;
+
+mod bindings;
+pub use bindings::*;
+
+mod dragging;
+use dragging::drag_sprites;
+pub use dragging::{DragEvent, DragEventKind, Draggable, Dragging};
+
+mod picking;
+use picking::pick_sprites;
+pub use picking::{Pickable, PickedSprites, SpritePickEvent, SpritePickEventKind};
+
+#[cfg(feature = "save")]
+mod saved_bindings;
+#[cfg(feature = "save")]
+pub use saved_bindings::AppBuilderSavedBindingsExt;
+
+mod virtual_controls;
+use virtual_controls::{update_virtual_controls, VirtualControlState};
+pub use virtual_controls::{
+    VirtualControl, VirtualControlKind, VirtualControls, VirtualControlsVisibility,
+};
+
+#[cfg(wasm)]
+mod web_gamepad;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+struct VirtualControlsSystem;
+
+/// Input plugin for Bevy Retrograde
+pub struct RetroInputPlugin;
+
+impl Plugin for RetroInputPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<InputBindings>()
+            .init_resource::<InputActions>()
+            .init_resource::<InputAxes>()
+            .init_resource::<VirtualControlState>()
+            .init_resource::<PickedSprites>()
+            .add_event::<SpritePickEvent>()
+            .add_event::<DragEvent>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_virtual_controls
+                    .system()
+                    .label(VirtualControlsSystem)
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_input_actions
+                    .system()
+                    .after(InputSystem)
+                    .after(VirtualControlsSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                pick_sprites.system().after(InputSystem),
+            )
+            .add_system(drag_sprites.system());
+
+        #[cfg(wasm)]
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            web_gamepad::poll_web_gamepads.system().before(InputSystem),
+        );
+    }
+}
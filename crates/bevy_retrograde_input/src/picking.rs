@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::{Camera, Image, Sprite, SpriteSheet, Visible};
+
+/// Add this component to a sprite to be able to pick it with the mouse
+///
+/// The sprites under the cursor are listed in the [`PickedSprites`] resource, and
+/// [`SpritePickEvent`]s are sent when the top one is hovered and clicked.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// # use bevy_retrograde_input::*;
+/// fn spawn_chest(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands
+///         .spawn_bundle(SpriteBundle {
+///             image: asset_server.load("chest.png"),
+///             ..Default::default()
+///         })
+///         .insert(Pickable::OpaquePixels);
+/// }
+///
+/// fn open_chests(mut events: EventReader<SpritePickEvent>) {
+///     for event in events.iter() {
+///         if event.kind == SpritePickEventKind::Clicked(MouseButton::Left) {
+///             info!("Opened chest {:?}", event.entity);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pickable {
+    /// The sprite is picked anywhere inside of its rectangle
+    Bounds,
+    /// The sprite is only picked where its pixels aren't transparent
+    OpaquePixels,
+}
+
+impl Default for Pickable {
+    fn default() -> Self {
+        Pickable::Bounds
+    }
+}
+
+/// This resource has the [`Pickable`] sprites under the mouse cursor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PickedSprites {
+    /// The sprites under the cursor, from the top one to the bottom one
+    pub entities: Vec<Entity>,
+}
+
+impl PickedSprites {
+    /// Get the sprite at the top, which is the one that is hovered and clicked
+    pub fn top(&self) -> Option<Entity> {
+        self.entities.first().copied()
+    }
+}
+
+/// Bevy event sent when the top [`Pickable`] sprite under the cursor is hovered or clicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpritePickEvent {
+    /// The sprite entity
+    pub entity: Entity,
+    /// What happened to the sprite
+    pub kind: SpritePickEventKind,
+}
+
+/// What happened to the sprite of a [`SpritePickEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpritePickEventKind {
+    /// The cursor moved onto the sprite
+    HoverStarted,
+    /// The cursor moved off of the sprite
+    HoverEnded,
+    /// A mouse button was pressed on the sprite
+    Pressed(MouseButton),
+    /// A mouse button was released on the sprite
+    Released(MouseButton),
+    /// A mouse button was pressed and released on the sprite
+    Clicked(MouseButton),
+}
+
+/// The sprite that is hovered and the sprites that mouse buttons were pressed on
+#[derive(Default)]
+pub(crate) struct PickingState {
+    hovered: Option<Entity>,
+    pressed: Vec<(MouseButton, Entity)>,
+}
+
+/// Find the pickable sprites under the cursor and send their events
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pick_sprites(
+    windows: Res<Windows>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    image_assets: Res<Assets<Image>>,
+    sprite_sheet_assets: Res<Assets<SpriteSheet>>,
+    cameras: Query<(&Camera, &Transform)>,
+    sprites: Query<(
+        Entity,
+        &Pickable,
+        &Sprite,
+        &Handle<Image>,
+        Option<&Handle<SpriteSheet>>,
+        Option<&Visible>,
+        &GlobalTransform,
+    )>,
+    mut picked: ResMut<PickedSprites>,
+    mut events: EventWriter<SpritePickEvent>,
+    mut state: Local<PickingState>,
+) {
+    let cursor = windows.get_primary().and_then(|window| {
+        let (camera, camera_transform) = cameras.iter().next()?;
+        camera.window_to_world(
+            camera_transform.translation.truncate(),
+            window,
+            window.cursor_position()?,
+        )
+    });
+
+    // Find the sprites under the cursor, where they were last drawn
+    let mut under_cursor = Vec::new();
+    if let Some(cursor) = cursor {
+        for (entity, pickable, sprite, image, sprite_sheet, visible, transform) in sprites.iter() {
+            if !visible.map(|x| **x).unwrap_or(true) {
+                continue;
+            }
+            let image = match image_assets.get(image) {
+                Some(image) => image,
+                None => continue,
+            };
+            let sprite_sheet = sprite_sheet.and_then(|x| sprite_sheet_assets.get(x));
+
+            if hit_test(sprite, image, sprite_sheet, transform, *pickable, cursor) {
+                under_cursor.push((entity, transform.translation.z));
+            }
+        }
+    }
+
+    // Sprites with a higher z are drawn on top
+    under_cursor.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    let new_picked = PickedSprites {
+        entities: under_cursor.into_iter().map(|(entity, _)| entity).collect(),
+    };
+    let top = new_picked.top();
+
+    // Only update the resource if it has changed to avoid triggering change detection
+    if new_picked != *picked {
+        *picked = new_picked;
+    }
+
+    if top != state.hovered {
+        if let Some(entity) = state.hovered {
+            events.send(SpritePickEvent {
+                entity,
+                kind: SpritePickEventKind::HoverEnded,
+            });
+        }
+        if let Some(entity) = top {
+            events.send(SpritePickEvent {
+                entity,
+                kind: SpritePickEventKind::HoverStarted,
+            });
+        }
+        state.hovered = top;
+    }
+
+    for button in mouse_buttons.get_just_pressed() {
+        if let Some(entity) = top {
+            events.send(SpritePickEvent {
+                entity,
+                kind: SpritePickEventKind::Pressed(*button),
+            });
+            state.pressed.push((*button, entity));
+        }
+    }
+
+    for button in mouse_buttons.get_just_released() {
+        let pressed = state
+            .pressed
+            .iter()
+            .position(|(x, _)| x == button)
+            .map(|index| state.pressed.remove(index).1);
+        if let Some(entity) = top {
+            events.send(SpritePickEvent {
+                entity,
+                kind: SpritePickEventKind::Released(*button),
+            });
+            if pressed == Some(entity) {
+                events.send(SpritePickEvent {
+                    entity,
+                    kind: SpritePickEventKind::Clicked(*button),
+                });
+            }
+        }
+    }
+}
+
+/// Get whether or not a position in the world is on a sprite, the same way that the sprite is
+/// drawn
+fn hit_test(
+    sprite: &Sprite,
+    image: &Image,
+    sprite_sheet: Option<&SpriteSheet>,
+    transform: &GlobalTransform,
+    pickable: Pickable,
+    position: Vec2,
+) -> bool {
+    let size = sprite_sheet
+        .map(|x| x.grid_size)
+        .unwrap_or_else(|| UVec2::new(image.width(), image.height()));
+    if size.x == 0 || size.y == 0 {
+        return false;
+    }
+    let size_f = Vec2::new(size.x as f32, size.y as f32);
+
+    let mut sprite_position = transform.translation.truncate();
+    if sprite.pixel_perfect {
+        sprite_position = (sprite_position + Vec2::splat(0.5)).floor();
+    }
+    let mut top_left = sprite_position + sprite.offset;
+    if sprite.centered {
+        top_left -= size_f / 2.;
+    }
+
+    let local = position - top_left;
+    if local.x < 0. || local.y < 0. || local.x >= size_f.x || local.y >= size_f.y {
+        return false;
+    }
+    if pickable == Pickable::Bounds {
+        return true;
+    }
+
+    // Find the pixel of the image under the position
+    let mut x = local.x as u32;
+    let mut y = local.y as u32;
+    if sprite.flip_x {
+        x = size.x - 1 - x;
+    }
+    if sprite.flip_y {
+        y = size.y - 1 - y;
+    }
+    if let Some(sprite_sheet) = sprite_sheet {
+        let columns = (image.width() / size.x).max(1);
+        x += sprite_sheet.tile_index % columns * size.x;
+        y += sprite_sheet.tile_index / columns * size.y;
+    }
+    if x >= image.width() || y >= image.height() {
+        return false;
+    }
+
+    image.get_pixel(x, y)[3] > 0
+}
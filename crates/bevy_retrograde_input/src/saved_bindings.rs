@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use bevy_retrograde_save::AppBuilderSettingsExt;
+
+use crate::InputBindings;
+
+/// Bevy [`AppBuilder`] extension for saving the [`InputBindings`] that the player rebinds
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_input::*;
+/// # use bevy_retrograde_save::RetroSavePlugin;
+/// fn main() {
+///     let mut default_bindings = InputBindings::default();
+///     default_bindings.bind("jump", InputSource::Key(KeyCode::Space));
+///
+///     App::build()
+///         .add_plugin(RetroSavePlugin)
+///         .add_plugin(RetroInputPlugin)
+///         .add_saved_input_bindings("input_bindings", default_bindings);
+/// }
+/// ```
+pub trait AppBuilderSavedBindingsExt {
+    /// Load the [`InputBindings`] from the save slot with the given name, or use the given
+    /// bindings if nothing has been saved yet, and save them whenever they change
+    ///
+    /// The bindings are kept as [`Settings<InputBindings>`][bevy_retrograde_save::Settings], which
+    /// are copied to the [`InputBindings`] resource, so either resource can be changed to rebind
+    /// the inputs.
+    fn add_saved_input_bindings(self, slot: &'static str, defaults: InputBindings) -> Self;
+}
+
+impl AppBuilderSavedBindingsExt for &mut AppBuilder {
+    fn add_saved_input_bindings(self, slot: &'static str, defaults: InputBindings) -> Self {
+        self.add_synced_settings(slot, defaults)
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::{Anchor, Camera, Image, ScreenAnchor, Sprite, SpriteBundle};
+
+/// How far from the center of a virtual d-pad a touch has to be, as a fraction of its size, to
+/// push it in a direction
+const DPAD_DEAD_ZONE: f32 = 0.15;
+
+/// Insert this resource to draw virtual d-pads and buttons over the game for touch screens
+///
+/// The controls are sprites drawn at the retro resolution of the camera, anchored to the screen
+/// like a [`ScreenAnchor`]. While they are touched, virtual buttons press their action and virtual
+/// d-pads set their axes in the [`InputActions`][crate::InputActions] and
+/// [`InputAxes`][crate::InputAxes], the same as any other bound input. Removing the resource
+/// removes the controls.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::Anchor;
+/// # use bevy_retrograde_input::*;
+/// fn setup_touch_controls(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.insert_resource(VirtualControls {
+///         controls: vec![
+///             VirtualControl {
+///                 kind: VirtualControlKind::DPad {
+///                     horizontal: "move".into(),
+///                     vertical: "climb".into(),
+///                 },
+///                 image: asset_server.load("touch/dpad.png"),
+///                 pressed_image: None,
+///                 anchor: Anchor::BottomLeft,
+///                 offset: Vec2::new(4., -4.),
+///                 size: UVec2::new(32, 32),
+///             },
+///             VirtualControl {
+///                 kind: VirtualControlKind::Button("jump".into()),
+///                 image: asset_server.load("touch/a.png"),
+///                 pressed_image: Some(asset_server.load("touch/a-pressed.png")),
+///                 anchor: Anchor::BottomRight,
+///                 offset: Vec2::new(-4., -4.),
+///                 size: UVec2::new(16, 16),
+///             },
+///         ],
+///         ..Default::default()
+///     });
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualControls {
+    /// The controls to draw
+    pub controls: Vec<VirtualControl>,
+    /// When the controls are shown
+    pub visibility: VirtualControlsVisibility,
+    /// The z position of the control sprites, which should be in front of the rest of the game
+    pub z: f32,
+}
+
+impl Default for VirtualControls {
+    fn default() -> Self {
+        Self {
+            controls: Vec::new(),
+            visibility: VirtualControlsVisibility::AfterTouch,
+            z: 100.,
+        }
+    }
+}
+
+/// When [`VirtualControls`] are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirtualControlsVisibility {
+    /// Always show the controls
+    Always,
+    /// Show the controls once the screen has been touched, so that they are only shown on devices
+    /// with a touch screen
+    AfterTouch,
+}
+
+/// A virtual d-pad or button of the [`VirtualControls`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualControl {
+    /// What the control does
+    pub kind: VirtualControlKind,
+    /// The image of the control
+    pub image: Handle<Image>,
+    /// The image of the control while it is touched, or [`None`] to use the same image
+    pub pressed_image: Option<Handle<Image>>,
+    /// The point of the screen that the control is placed at
+    pub anchor: Anchor,
+    /// The offset of the control from its anchor point, in pixels with positive y pointing down
+    pub offset: Vec2,
+    /// The size of the area of the control that can be touched, in pixels, which should be the
+    /// size of its image
+    pub size: UVec2,
+}
+
+/// What a [`VirtualControl`] does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VirtualControlKind {
+    /// A button that presses an action while it is touched
+    Button(String),
+    /// A d-pad that sets a horizontal and a vertical axis to the direction that it is pushed in
+    ///
+    /// Like the directions in the world, the vertical axis is positive when the d-pad is pushed
+    /// down.
+    DPad {
+        horizontal: String,
+        vertical: String,
+    },
+}
+
+/// This resource has the actions and axes set by the [`VirtualControls`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VirtualControlState {
+    pub pressed: HashSet<String>,
+    pub axes: HashMap<String, f32>,
+}
+
+/// Marker component for the sprite of the [`VirtualControl`] at the given index
+struct VirtualControlSprite(usize);
+
+/// Spawn the sprites of the virtual controls, and find the actions and axes that their touches
+/// set
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_virtual_controls(
+    mut commands: Commands,
+    virtual_controls: Option<Res<VirtualControls>>,
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    cameras: Query<(&Camera, &Transform)>,
+    mut sprites: Query<(Entity, &VirtualControlSprite, &mut Handle<Image>)>,
+    mut state: ResMut<VirtualControlState>,
+    mut seen_touch: Local<bool>,
+) {
+    *seen_touch |= touches.iter().next().is_some();
+    let virtual_controls = match virtual_controls {
+        Some(controls)
+            if *seen_touch || controls.visibility == VirtualControlsVisibility::Always =>
+        {
+            controls
+        }
+        _ => {
+            for (entity, ..) in sprites.iter_mut() {
+                commands.entity(entity).despawn();
+            }
+            *state = Default::default();
+            return;
+        }
+    };
+
+    // Spawn the sprites again when the controls change, or when they are first shown
+    if virtual_controls.is_changed() || sprites.iter_mut().next().is_none() {
+        for (entity, ..) in sprites.iter_mut() {
+            commands.entity(entity).despawn();
+        }
+        for (i, control) in virtual_controls.controls.iter().enumerate() {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    image: control.image.clone(),
+                    sprite: Sprite {
+                        centered: false,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0., 0., virtual_controls.z),
+                    ..Default::default()
+                })
+                .insert(ScreenAnchor {
+                    anchor: control.anchor,
+                    offset: control.offset,
+                })
+                .insert(VirtualControlSprite(i));
+        }
+    }
+
+    // Find where the touches are in the camera view
+    let (window, (camera, camera_transform)) = match (windows.get_primary(), cameras.iter().next())
+    {
+        (Some(window), Some(camera)) => (window, camera),
+        _ => return,
+    };
+    let camera_position = camera_transform.translation.truncate();
+    let touch_positions = touches
+        .iter()
+        .filter_map(|touch| camera.window_to_world(camera_position, window, touch.position()))
+        .collect::<Vec<_>>();
+    let target_size = camera.get_target_sizes(window).low;
+    let camera_size = Vec2::new(target_size.x as f32, target_size.y as f32);
+    let camera_top_left = if camera.centered {
+        camera_position - camera_size / 2.
+    } else {
+        camera_position
+    };
+
+    let mut new_state = VirtualControlState::default();
+    let mut pressed_controls = HashSet::new();
+    for (i, control) in virtual_controls.controls.iter().enumerate() {
+        // Get the area of the control in the world, the same way that it is anchored to the screen
+        let size = Vec2::new(control.size.x as f32, control.size.y as f32);
+        let anchor = control.anchor.as_fraction();
+        let top_left = camera_top_left + anchor * camera_size + control.offset - anchor * size;
+        let touch = touch_positions.iter().find(|position| {
+            position.x >= top_left.x
+                && position.x < top_left.x + size.x
+                && position.y >= top_left.y
+                && position.y < top_left.y + size.y
+        });
+        let touch = match touch {
+            Some(touch) => *touch,
+            None => continue,
+        };
+        pressed_controls.insert(i);
+
+        match &control.kind {
+            VirtualControlKind::Button(action) => {
+                new_state.pressed.insert(action.clone());
+            }
+            VirtualControlKind::DPad {
+                horizontal,
+                vertical,
+            } => {
+                // Push the d-pad in the directions that the touch is away from its center
+                let direction = (touch - (top_left + size / 2.)) / size;
+                let axis_value = |x: f32| {
+                    if x.abs() < DPAD_DEAD_ZONE {
+                        0.
+                    } else {
+                        x.signum()
+                    }
+                };
+                new_state
+                    .axes
+                    .insert(horizontal.clone(), axis_value(direction.x));
+                new_state
+                    .axes
+                    .insert(vertical.clone(), axis_value(direction.y));
+            }
+        }
+    }
+    *state = new_state;
+
+    // Show the pressed images of the touched controls
+    for (_, sprite, mut image) in sprites.iter_mut() {
+        let control = match virtual_controls.controls.get(sprite.0) {
+            Some(control) => control,
+            None => continue,
+        };
+        let new_image = match &control.pressed_image {
+            Some(pressed_image) if pressed_controls.contains(&sprite.0) => pressed_image,
+            _ => &control.image,
+        };
+        if *image != *new_image {
+            *image = new_image.clone();
+        }
+    }
+}
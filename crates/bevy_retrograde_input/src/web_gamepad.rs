@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::gamepad::{GamepadAxisType, GamepadButtonType, GamepadEventRaw, GamepadEventType},
+    prelude::*,
+};
+use wasm_bindgen::JsCast;
+
+/// The buttons of the browser's "standard" gamepad mapping, in order
+const STANDARD_BUTTONS: [GamepadButtonType; 17] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::West,
+    GamepadButtonType::North,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::LeftThumb,
+    GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+    GamepadButtonType::Mode,
+];
+
+/// The axes of the browser's "standard" gamepad mapping, in order
+const STANDARD_AXES: [GamepadAxisType; 4] = [
+    GamepadAxisType::LeftStickX,
+    GamepadAxisType::LeftStickY,
+    GamepadAxisType::RightStickX,
+    GamepadAxisType::RightStickY,
+];
+
+/// The button and axis values of a gamepad as of the last poll
+#[derive(Default)]
+pub(crate) struct WebGamepadState {
+    buttons: Vec<f32>,
+    axes: Vec<f32>,
+}
+
+/// Poll the browser's Gamepad API and send Bevy's raw gamepad events for the changes
+///
+/// Bevy's gilrs backend doesn't support web, so this fills in for it. Bevy's input plugin turns the
+/// raw events into the [`Gamepads`], [`Input<GamepadButton>`], and [`Axis<GamepadAxis>`] resources
+/// like on other platforms.
+///
+/// Buttons and axes are mapped by the browser's "standard" layout, which most browsers use for
+/// common controllers. Gamepads with other mappings are read the same way, so their buttons may
+/// not match.
+pub(crate) fn poll_web_gamepads(
+    mut events: EventWriter<GamepadEventRaw>,
+    mut connected: Local<HashMap<u32, WebGamepadState>>,
+) {
+    let gamepads = match web_sys::window().map(|window| window.navigator().get_gamepads()) {
+        Some(Ok(gamepads)) => gamepads,
+        _ => return,
+    };
+
+    // The list has an entry for every slot, which is null for slots without a gamepad
+    let gamepads = gamepads
+        .iter()
+        .filter_map(|gamepad| gamepad.dyn_into::<web_sys::Gamepad>().ok())
+        .filter(|gamepad| gamepad.connected())
+        .collect::<Vec<_>>();
+
+    // Disconnect the gamepads that are gone
+    let disconnected = connected
+        .keys()
+        .filter(|index| !gamepads.iter().any(|gamepad| gamepad.index() == **index))
+        .copied()
+        .collect::<Vec<_>>();
+    for index in disconnected {
+        connected.remove(&index);
+        events.send(GamepadEventRaw(
+            Gamepad(index as usize),
+            GamepadEventType::Disconnected,
+        ));
+    }
+
+    for gamepad in &gamepads {
+        let index = gamepad.index();
+        let bevy_gamepad = Gamepad(index as usize);
+        let state = connected.entry(index).or_insert_with(|| {
+            events.send(GamepadEventRaw(bevy_gamepad, GamepadEventType::Connected));
+            WebGamepadState::default()
+        });
+
+        // Send the buttons that changed
+        let buttons = gamepad
+            .buttons()
+            .iter()
+            .map(|button| {
+                button
+                    .dyn_into::<web_sys::GamepadButton>()
+                    .map(|button| button.value() as f32)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        for (i, (button_type, value)) in STANDARD_BUTTONS.iter().zip(&buttons).enumerate() {
+            if state.buttons.get(i) != Some(value) {
+                events.send(GamepadEventRaw(
+                    bevy_gamepad,
+                    GamepadEventType::ButtonChanged(*button_type, *value),
+                ));
+            }
+        }
+        state.buttons = buttons;
+
+        // Send the axes that changed. The browser's vertical axes are positive when the stick is
+        // pushed down, but Bevy's are positive when it is pushed up.
+        let axes = gamepad
+            .axes()
+            .iter()
+            .map(|axis| axis.as_f64().unwrap_or_default() as f32)
+            .collect::<Vec<_>>();
+        for (i, (axis_type, value)) in STANDARD_AXES.iter().zip(&axes).enumerate() {
+            if state.axes.get(i) != Some(value) {
+                let value = match axis_type {
+                    GamepadAxisType::LeftStickY | GamepadAxisType::RightStickY => -*value,
+                    _ => *value,
+                };
+                events.send(GamepadEventRaw(
+                    bevy_gamepad,
+                    GamepadEventType::AxisChanged(*axis_type, value),
+                ));
+            }
+        }
+        state.axes = axes;
+    }
+}
@@ -4,7 +4,9 @@ use bevy::{
     reflect::TypeUuid,
     utils::{BoxedFuture, HashMap},
 };
-use bevy_retrograde_core::assets::Image;
+use bevy_retrograde_core::{assets::Image, components::Color};
+
+use crate::{LdtkEnum, LdtkFieldError, LdtkFields, LdtkLevelBounds, LevelSelection};
 
 /// An LDtk map asset
 #[derive(TypeUuid)]
@@ -14,6 +16,239 @@ pub struct LdtkMap {
     pub project: ldtk::Project,
     /// A mapping of Tileset identifiers to their texture handles
     pub tile_sets: HashMap<String, Handle<Image>>,
+    /// A mapping of tileset def uid's to the tile animations defined in that tileset's custom data
+    ///
+    /// See [`TileAnimation`] for the custom data format.
+    pub tile_animations: HashMap<i64, HashMap<i64, TileAnimation>>,
+    /// A mapping of tileset def uid's to the enum tags of the tiles in that tileset
+    pub tile_enum_tags: HashMap<i64, TilesetEnumTags>,
+    /// The worlds in the project
+    ///
+    /// Projects that don't use LDtk's multi-world feature have a single world containing all of
+    /// the project's levels.
+    pub worlds: Vec<LdtkWorld>,
+    /// The raw JSON of the project, used to read data that we don't want to depend on the exact
+    /// representation of in [`ldtk::Project`]
+    pub(crate) raw_project: serde_json::Value,
+}
+
+/// A world in an LDtk project
+#[derive(Debug, Clone)]
+pub struct LdtkWorld {
+    /// The identifier of the world
+    pub identifier: String,
+    /// The way that the levels in the world are laid out
+    pub world_layout: WorldLayout,
+    /// The levels in the world
+    pub levels: Vec<ldtk::Level>,
+    /// The world position, in pixels, of the top-left corner of each level in `levels`, in the
+    /// same order
+    pub level_positions: Vec<IVec2>,
+    /// The background of each level in `levels`, in the same order
+    pub level_backgrounds: Vec<LevelBackground>,
+    /// The raw JSON of each level in `levels`, in the same order
+    pub(crate) raw_levels: Vec<serde_json::Value>,
+}
+
+impl LdtkWorld {
+    /// Get the world position, in pixels, of the top-left corner of a level, if the level exists
+    pub fn level_position(&self, level_identifier: &str) -> Option<IVec2> {
+        self.levels
+            .iter()
+            .position(|x| x.identifier == level_identifier)
+            .and_then(|i| self.level_positions.get(i).copied())
+    }
+
+    /// Get the bounds, in pixels, of the selected levels in the world, relative to the map
+    /// position
+    ///
+    /// Returns [`None`] if no levels are selected.
+    pub fn level_bounds(&self, selection: &LevelSelection) -> Option<LdtkLevelBounds> {
+        self.levels
+            .iter()
+            .zip(&self.level_positions)
+            .enumerate()
+            .filter(|(i, _)| {
+                self.raw_levels
+                    .get(*i)
+                    .map(|raw_level| selection.matches(*i, raw_level))
+                    .unwrap_or(false)
+            })
+            .map(|(_, (level, position))| {
+                let min = Vec2::new(position.x as f32, position.y as f32);
+                LdtkLevelBounds {
+                    min,
+                    max: min + Vec2::new(level.px_wid as f32, level.px_hei as f32),
+                }
+            })
+            .reduce(|a, b| LdtkLevelBounds {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
+    }
+
+    /// Get the custom fields of a level, if the level exists
+    pub fn level_fields(&self, level_identifier: &str) -> Option<LdtkFields> {
+        self.raw_levels
+            .iter()
+            .find(|x| x.get("identifier").and_then(|x| x.as_str()) == Some(level_identifier))
+            .map(|level| LdtkFields::from_raw_field_instances(level.get("fieldInstances")))
+    }
+}
+
+/// The way that the levels of an LDtk project are laid out in the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldLayout {
+    /// Levels are placed freely at their world coordinates
+    Free,
+    /// Levels are placed at their world coordinates, which are snapped to the world grid
+    GridVania,
+    /// Levels are placed one after the other from left to right
+    LinearHorizontal,
+    /// Levels are placed one after the other from top to bottom
+    LinearVertical,
+}
+
+impl Default for WorldLayout {
+    fn default() -> Self {
+        WorldLayout::Free
+    }
+}
+
+impl WorldLayout {
+    /// Parse the world layout from the `worldLayout` value of an LDtk project
+    fn from_ldtk_value(value: &str) -> Option<Self> {
+        Some(match value {
+            "Free" => WorldLayout::Free,
+            "GridVania" => WorldLayout::GridVania,
+            "LinearHorizontal" => WorldLayout::LinearHorizontal,
+            "LinearVertical" => WorldLayout::LinearVertical,
+            _ => return None,
+        })
+    }
+}
+
+/// The background of an LDtk level
+#[derive(Debug, Clone)]
+pub struct LevelBackground {
+    /// The background color of the level
+    pub color: Color,
+    /// The background image of the level, if it has one
+    pub image: Option<LevelBackgroundImage>,
+}
+
+/// The background image of an LDtk level
+#[derive(Debug, Clone)]
+pub struct LevelBackgroundImage {
+    /// The handle to the background image
+    pub handle: Handle<Image>,
+    /// The region of the image, in pixels, that is visible in the level: `[x, y, width, height]`
+    pub crop_rect: [f32; 4],
+    /// The scale of the cropped image
+    pub scale: Vec2,
+    /// The position, relative to the level, of the top-left corner of the cropped image
+    pub top_left_px: IVec2,
+}
+
+/// The enum tags of a tileset that uses an enum as its tag source
+#[derive(Debug, Clone, Default)]
+pub struct TilesetEnumTags {
+    /// The identifier of the enum that the tileset is tagged with
+    pub enum_identifier: String,
+    /// A mapping of tile ids to the enum values that the tile is tagged with
+    pub tiles: HashMap<i64, Vec<String>>,
+}
+
+impl LdtkMap {
+    /// Get the enum values that a tile in a tileset has been tagged with
+    ///
+    /// Returns an error if the tileset is tagged with a different enum than `T` or if one of the
+    /// tags is not a valid value for `T`. Tiles without any tags will return an empty list.
+    pub fn tile_enum_tags<T: LdtkEnum>(
+        &self,
+        tileset_uid: i64,
+        tile_id: i64,
+    ) -> Result<Vec<T>, LdtkFieldError> {
+        let tags = if let Some(tags) = self.tile_enum_tags.get(&tileset_uid) {
+            tags
+        } else {
+            return Ok(Vec::new());
+        };
+
+        if tags.enum_identifier != T::IDENTIFIER {
+            return Err(LdtkFieldError::EnumMismatch {
+                field: format!("tileset {} tags", tileset_uid),
+                expected: T::IDENTIFIER,
+                found: tags.enum_identifier.clone(),
+            });
+        }
+
+        tags.tiles
+            .get(&tile_id)
+            .into_iter()
+            .flatten()
+            .map(|value| T::parse_ldtk_value(value))
+            .collect()
+    }
+
+    /// Get a world by its identifier, or the first world in the project if `identifier` is
+    /// [`None`]
+    pub fn world(&self, identifier: Option<&str>) -> Option<&LdtkWorld> {
+        match identifier {
+            Some(identifier) => self.worlds.iter().find(|x| x.identifier == identifier),
+            None => self.worlds.first(),
+        }
+    }
+}
+
+/// An animation for a tile in an LDtk tileset
+///
+/// LDtk doesn't have built-in tile animations, so animations are defined by adding JSON to the
+/// custom data of the first tile of the animation in the LDtk tileset editor:
+///
+/// ```json
+/// { "animation": { "frames": [ 12, 13, 14, 15 ], "fps": 6 } }
+/// ```
+///
+/// `frames` is the list of tile ids in the tileset that make up the animation and `fps` is the
+/// number of frames to display per second, which defaults to `8` if not specified. Any tile in a
+/// layer that uses the animated tile will be animated when the map is spawned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileAnimation {
+    /// The tile ids of each frame of the animation
+    pub frames: Vec<i64>,
+    /// The number of frames per second
+    pub fps: f32,
+}
+
+impl TileAnimation {
+    /// The default number of frames per second for a tile animation
+    pub const DEFAULT_FPS: f32 = 8.0;
+
+    /// Parse a tile animation from the custom data string of a tile, returning [`None`] if the
+    /// custom data doesn't contain an animation.
+    pub fn from_custom_data(data: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(data).ok()?;
+        let animation = value.get("animation")?;
+
+        let frames = animation
+            .get("frames")?
+            .as_array()?
+            .iter()
+            .map(|x| x.as_i64())
+            .collect::<Option<Vec<_>>>()?;
+        let fps = animation
+            .get("fps")
+            .and_then(|x| x.as_f64())
+            .map(|x| x as f32)
+            .unwrap_or(Self::DEFAULT_FPS);
+
+        if frames.is_empty() || fps <= 0.0 {
+            return None;
+        }
+
+        Some(Self { frames, fps })
+    }
 }
 
 /// Add asset types and asset loader to the app builder
@@ -56,11 +291,16 @@ async fn load_ldtk<'a, 'b>(
 ) -> Result<(), LdtkMapLoaderError> {
     // Deserialize the LDTK project file
     let project: ldtk::Project = serde_json::from_slice(bytes)?;
+    let raw_project: serde_json::Value = serde_json::from_slice(bytes)?;
 
     // Create a map asset
     let mut map = LdtkMap {
         project,
         tile_sets: Default::default(),
+        tile_animations: parse_tile_animations(&raw_project),
+        tile_enum_tags: parse_tile_enum_tags(&raw_project),
+        worlds: Vec::new(),
+        raw_project,
     };
 
     // Create our dependency list
@@ -87,8 +327,279 @@ async fn load_ldtk<'a, 'b>(
         map.tile_sets.insert(tileset.identifier.clone(), handle);
     }
 
+    // Get the worlds of the project. Multi-world projects list their worlds in `worlds`, otherwise
+    // the levels are in an implicit world at the root of the project.
+    let raw_worlds = map
+        .raw_project
+        .get("worlds")
+        .and_then(|x| x.as_array())
+        .filter(|x| !x.is_empty());
+    let mut worlds = Vec::new();
+    if let Some(raw_worlds) = raw_worlds {
+        for raw_world in raw_worlds {
+            let raw_levels = raw_world
+                .get("levels")
+                .cloned()
+                .unwrap_or_else(|| serde_json::Value::Array(Vec::new()));
+
+            worlds.push(load_world(
+                raw_world,
+                serde_json::from_value(raw_levels)?,
+                load_context,
+                &mut dependencies,
+            ));
+        }
+    } else {
+        worlds.push(load_world(
+            &map.raw_project,
+            map.project.levels.clone(),
+            load_context,
+            &mut dependencies,
+        ));
+    }
+    map.worlds = worlds;
+
     // Set the loaded map as the default asset for this file
     load_context.set_default_asset(LoadedAsset::new(map).with_dependencies(dependencies));
 
     Ok(())
 }
+
+/// Collect the tile animations from the custom data of all of the tilesets in the project
+///
+/// We read the custom data from the raw JSON so that we don't depend on how the tileset custom data
+/// is represented in the project structure.
+fn parse_tile_animations(project: &serde_json::Value) -> HashMap<i64, HashMap<i64, TileAnimation>> {
+    let mut animations = HashMap::default();
+
+    let tilesets = project
+        .get("defs")
+        .and_then(|x| x.get("tilesets"))
+        .and_then(|x| x.as_array());
+
+    for tileset in tilesets.into_iter().flatten() {
+        let uid = if let Some(uid) = tileset.get("uid").and_then(|x| x.as_i64()) {
+            uid
+        } else {
+            continue;
+        };
+
+        let custom_data = tileset.get("customData").and_then(|x| x.as_array());
+
+        let tileset_animations: HashMap<_, _> = custom_data
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let tile_id = item.get("tileId")?.as_i64()?;
+                let data = item.get("data")?.as_str()?;
+
+                Some((tile_id, TileAnimation::from_custom_data(data)?))
+            })
+            .collect();
+
+        if !tileset_animations.is_empty() {
+            animations.insert(uid, tileset_animations);
+        }
+    }
+
+    animations
+}
+
+/// Collect the enum tags from all of the tilesets in the project that use an enum as a tag source
+fn parse_tile_enum_tags(project: &serde_json::Value) -> HashMap<i64, TilesetEnumTags> {
+    let defs = project.get("defs");
+    let enums = defs.and_then(|x| x.get("enums")).and_then(|x| x.as_array());
+    let tilesets = defs
+        .and_then(|x| x.get("tilesets"))
+        .and_then(|x| x.as_array());
+
+    let mut enum_tags = HashMap::default();
+
+    for tileset in tilesets.into_iter().flatten() {
+        let uid = tileset.get("uid").and_then(|x| x.as_i64());
+        let enum_uid = tileset.get("tagsSourceEnumUid").and_then(|x| x.as_i64());
+        let (uid, enum_uid) = if let (Some(uid), Some(enum_uid)) = (uid, enum_uid) {
+            (uid, enum_uid)
+        } else {
+            continue;
+        };
+
+        // Find the identifier of the enum used as the tag source
+        let enum_identifier = enums
+            .into_iter()
+            .flatten()
+            .find(|x| x.get("uid").and_then(|x| x.as_i64()) == Some(enum_uid))
+            .and_then(|x| x.get("identifier"))
+            .and_then(|x| x.as_str());
+        let enum_identifier = if let Some(identifier) = enum_identifier {
+            identifier.to_owned()
+        } else {
+            continue;
+        };
+
+        // Collect the tags for each tile
+        let mut tiles: HashMap<i64, Vec<String>> = HashMap::default();
+        let tags = tileset.get("enumTags").and_then(|x| x.as_array());
+        for tag in tags.into_iter().flatten() {
+            let value = if let Some(value) = tag.get("enumValueId").and_then(|x| x.as_str()) {
+                value
+            } else {
+                continue;
+            };
+
+            let tile_ids = tag.get("tileIds").and_then(|x| x.as_array());
+            for tile_id in tile_ids.into_iter().flatten().filter_map(|x| x.as_i64()) {
+                tiles.entry(tile_id).or_default().push(value.to_owned());
+            }
+        }
+
+        enum_tags.insert(
+            uid,
+            TilesetEnumTags {
+                enum_identifier,
+                tiles,
+            },
+        );
+    }
+
+    enum_tags
+}
+
+/// Load a world from the raw JSON of an LDtk world or of a project without multiple worlds
+///
+/// Any level background images are added to `dependencies`.
+fn load_world(
+    raw_world: &serde_json::Value,
+    levels: Vec<ldtk::Level>,
+    load_context: &mut LoadContext,
+    dependencies: &mut Vec<AssetPath<'static>>,
+) -> LdtkWorld {
+    let identifier = raw_world
+        .get("identifier")
+        .and_then(|x| x.as_str())
+        .unwrap_or("World")
+        .to_owned();
+    let world_layout = raw_world
+        .get("worldLayout")
+        .and_then(|x| x.as_str())
+        .and_then(WorldLayout::from_ldtk_value)
+        .unwrap_or_default();
+    let raw_levels = raw_world
+        .get("levels")
+        .and_then(|x| x.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let level_positions = parse_level_positions(world_layout, &raw_levels);
+    let level_backgrounds = raw_levels
+        .iter()
+        .map(|level| parse_level_background(level, load_context, dependencies))
+        .collect();
+
+    LdtkWorld {
+        identifier,
+        world_layout,
+        levels,
+        level_positions,
+        level_backgrounds,
+        raw_levels,
+    }
+}
+
+/// Get the world position of every level in a world
+///
+/// LDtk only sets the world coordinates of levels for the `Free` and `GridVania` layouts, so for
+/// the linear layouts we place the levels one after the other.
+fn parse_level_positions(world_layout: WorldLayout, levels: &[serde_json::Value]) -> Vec<IVec2> {
+    let mut next_position = IVec2::ZERO;
+
+    levels
+        .iter()
+        .map(|level| {
+            let get = |key: &str| level.get(key).and_then(|x| x.as_i64()).unwrap_or(0) as i32;
+
+            match world_layout {
+                WorldLayout::Free | WorldLayout::GridVania => {
+                    IVec2::new(get("worldX"), get("worldY"))
+                }
+                WorldLayout::LinearHorizontal => {
+                    let position = next_position;
+                    next_position.x += get("pxWid");
+                    position
+                }
+                WorldLayout::LinearVertical => {
+                    let position = next_position;
+                    next_position.y += get("pxHei");
+                    position
+                }
+            }
+        })
+        .collect()
+}
+
+/// Get the background of a level, adding the background image, if any, to `dependencies`
+fn parse_level_background(
+    level: &serde_json::Value,
+    load_context: &mut LoadContext,
+    dependencies: &mut Vec<AssetPath<'static>>,
+) -> LevelBackground {
+    let color = level
+        .get("__bgColor")
+        .and_then(|x| x.as_str())
+        .and_then(parse_hex_color)
+        .unwrap_or_default();
+
+    let image = if let Some(rel_path) = level.get("bgRelPath").and_then(|x| x.as_str()) {
+        // Get the path to the background image asset and add it to our dependencies
+        let file_path = load_context.path().parent().unwrap().join(rel_path);
+        let asset_path = AssetPath::new(file_path, None);
+        dependencies.push(asset_path.clone());
+
+        let pos = level.get("__bgPos");
+        let get_f32 = |key: &str, i: usize| {
+            pos.and_then(|x| x.get(key))
+                .and_then(|x| x.get(i))
+                .and_then(|x| x.as_f64())
+                .map(|x| x as f32)
+        };
+
+        Some(LevelBackgroundImage {
+            handle: load_context.get_handle(asset_path),
+            crop_rect: [
+                get_f32("cropRect", 0).unwrap_or(0.),
+                get_f32("cropRect", 1).unwrap_or(0.),
+                get_f32("cropRect", 2).unwrap_or(0.),
+                get_f32("cropRect", 3).unwrap_or(0.),
+            ],
+            scale: Vec2::new(
+                get_f32("scale", 0).unwrap_or(1.),
+                get_f32("scale", 1).unwrap_or(1.),
+            ),
+            top_left_px: IVec2::new(
+                get_f32("topLeftPx", 0).unwrap_or(0.) as i32,
+                get_f32("topLeftPx", 1).unwrap_or(0.) as i32,
+            ),
+        })
+    } else {
+        None
+    };
+
+    LevelBackground { color, image }
+}
+
+/// Parse an LDtk hex color such as `#40465B`
+fn parse_hex_color(color: &str) -> Option<Color> {
+    let color = color.strip_prefix('#').unwrap_or(color);
+    if color.len() != 6 {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(color.get(i..i + 2)?, 16).ok();
+
+    Some(Color::from_rgba8(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        255,
+    ))
+}
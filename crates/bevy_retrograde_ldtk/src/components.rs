@@ -1,7 +1,11 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_retrograde_core::prelude::Image;
 use ldtk::LayerInstance;
 
-use crate::asset::LdtkMap;
+use crate::{asset::LdtkMap, LdtkFieldError, LdtkFields, NavGridConfig};
 
 /// A component bundle for spawning an LDtk map
 #[derive(Default, Bundle)]
@@ -12,6 +16,125 @@ pub struct LdtkMapBundle {
     pub transform: Transform,
     /// The world position
     pub global_transform: GlobalTransform,
+    /// Settings for how the map is spawned
+    pub config: LdtkMapConfig,
+    /// Filter for which layers of the map are spawned
+    pub layer_filter: LayerFilter,
+}
+
+/// A filter for which layers of an LDtk map are spawned
+///
+/// A layer is spawned if it matches any of the `include` rules, or if `include` is empty, and it
+/// doesn't match any of the `exclude` rules.
+///
+/// ```
+/// # use bevy_retrograde_ldtk::*;
+/// // Spawn only the visual layers, skipping the collision layer and all entities
+/// let filter = LayerFilter {
+///     exclude: vec![
+///         LayerMatcher::Identifier("Collisions".into()),
+///         LayerMatcher::Type(LdtkLayerType::Entities),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// assert!(filter.matches("Background", LdtkLayerType::Tiles));
+/// assert!(!filter.matches("Collisions", LdtkLayerType::IntGrid));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LayerFilter {
+    /// The layers to spawn. If this is empty all layers will be spawned.
+    pub include: Vec<LayerMatcher>,
+    /// The layers to skip, even if they are matched by `include`
+    pub exclude: Vec<LayerMatcher>,
+}
+
+impl LayerFilter {
+    /// Check whether or not a layer with the given identifier and type should be spawned
+    pub fn matches(&self, identifier: &str, layer_type: LdtkLayerType) -> bool {
+        let is_match = |x: &LayerMatcher| x.matches(identifier, layer_type);
+
+        (self.include.is_empty() || self.include.iter().any(is_match))
+            && !self.exclude.iter().any(is_match)
+    }
+}
+
+/// A rule that matches layers in a [`LayerFilter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerMatcher {
+    /// Match layers with the given identifier
+    Identifier(String),
+    /// Match layers of the given type
+    Type(LdtkLayerType),
+}
+
+impl LayerMatcher {
+    /// Check whether or not this rule matches a layer with the given identifier and type
+    pub fn matches(&self, identifier: &str, layer_type: LdtkLayerType) -> bool {
+        match self {
+            LayerMatcher::Identifier(id) => id == identifier,
+            LayerMatcher::Type(ty) => *ty == layer_type,
+        }
+    }
+}
+
+/// The type of an LDtk layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LdtkLayerType {
+    IntGrid,
+    Entities,
+    Tiles,
+    AutoLayer,
+}
+
+impl LdtkLayerType {
+    /// Get the layer type from the `__type` of an LDtk layer instance
+    pub fn from_ldtk_value(value: &str) -> Option<Self> {
+        Some(match value {
+            "IntGrid" => LdtkLayerType::IntGrid,
+            "Entities" => LdtkLayerType::Entities,
+            "Tiles" => LdtkLayerType::Tiles,
+            "AutoLayer" => LdtkLayerType::AutoLayer,
+            _ => return None,
+        })
+    }
+}
+
+/// Settings for how an LDtk map is spawned
+#[derive(Debug, Clone, Default)]
+pub struct LdtkMapConfig {
+    /// Whether or not to set the background color of the camera to the background color of the
+    /// first level in the map when the map is spawned. Defaults to `false`.
+    pub set_camera_background: bool,
+    /// Whether or not to add [`CameraBounds`][bevy_retrograde_core::prelude::CameraBounds] to the
+    /// camera so that it can't scroll past the edges of the map levels. Defaults to `false`.
+    pub clamp_camera: bool,
+    /// The identifier of the world to spawn for multi-world LDtk projects. If this is [`None`] the
+    /// first world in the project will be spawned.
+    pub world: Option<String>,
+    /// Settings for generating a navigation grid for each level from one of the map's IntGrid
+    /// layers. No navigation grids are generated if this is [`None`].
+    pub nav_grid: Option<NavGridConfig>,
+}
+
+/// Component added to spawned maps containing the world space bounds of the spawned levels, in
+/// pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LdtkLevelBounds {
+    /// The top-left corner of the levels
+    pub min: Vec2,
+    /// The bottom-right corner of the levels
+    pub max: Vec2,
+}
+
+/// Component added to the spawned backgrounds of map levels
+///
+/// Each level has a background entity for its background color and, if the level has one, a
+/// background entity for its background image.
+#[derive(Debug, Clone)]
+pub struct LdtkLevelBackground {
+    pub map: Handle<LdtkMap>,
+    pub level_identifier: String,
 }
 
 /// Component added to spawned map layers
@@ -20,3 +143,210 @@ pub struct LdtkMapLayer {
     pub level_identifier: String,
     pub layer_instance: LayerInstance,
 }
+
+/// Component added to the entities spawned for the entity instances in a map's entity layers
+///
+/// The entity is spawned as a child of the map with its transform set to the position of the
+/// entity in the level. Use [`LdtkFields`] to read the entity's custom fields, including mapping
+/// enum fields to Rust enums.
+#[derive(Debug, Clone)]
+pub struct LdtkEntity {
+    pub map: Handle<LdtkMap>,
+    pub level_identifier: String,
+    pub layer_identifier: String,
+    /// The identifier of the entity definition in LDtk
+    pub identifier: String,
+    /// The custom fields of the entity
+    pub fields: LdtkFields,
+    /// The position, relative to the map, of the top-left corner of the entity's level
+    pub level_position: Vec2,
+    /// The size in pixels of the grid cells of the entity's layer
+    pub grid_size: u32,
+}
+
+impl LdtkEntity {
+    /// Get the position, relative to the map, of the center of a grid cell in the entity's level
+    pub fn cell_position(&self, cell: IVec2) -> Vec2 {
+        let grid_size = self.grid_size as f32;
+        self.level_position
+            + Vec2::new(cell.x as f32, cell.y as f32) * grid_size
+            + Vec2::splat(grid_size / 2.)
+    }
+
+    /// Read a point array field as the positions, relative to the map, of the centers of the grid
+    /// cells that it points to
+    ///
+    /// Entities spawned as children of the map can use the positions as the waypoints of a path.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_retrograde_ldtk::*;
+    /// fn print_patrols(entities: Query<&LdtkEntity, Added<LdtkEntity>>) {
+    ///     for entity in entities.iter() {
+    ///         if let Ok(waypoints) = entity.field_point_positions("patrol") {
+    ///             info!("{} patrols through {:?}", entity.identifier, waypoints);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn field_point_positions(&self, identifier: &str) -> Result<Vec<Vec2>, LdtkFieldError> {
+        Ok(self
+            .fields
+            .field_point_array(identifier)?
+            .into_iter()
+            .map(|cell| self.cell_position(cell))
+            .collect())
+    }
+}
+
+/// Component added to spawned tile layers that can be used to change the tiles of the layer at
+/// runtime
+///
+/// Changes to the tiles are drawn into the layer image at the end of the frame. Changing a cell
+/// that contains an animated tile will stop the animation in that cell.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ldtk::*;
+/// fn break_wall(mut layers: Query<&mut LdtkLayerTiles>) {
+///     for mut tiles in layers.iter_mut() {
+///         // Remove the tiles in cell ( 4, 2 )
+///         tiles.clear(UVec2::new(4, 2));
+///         // Replace the tiles in cell ( 5, 2 ) with tile 12 from the tileset
+///         let tile = tiles.tile_from_id(12);
+///         tiles.set_tile(UVec2::new(5, 2), tile);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LdtkLayerTiles {
+    /// The tileset image that the layer's tiles come from
+    pub tileset: Handle<Image>,
+    /// The size of the tiles in the layer
+    pub grid_size: u32,
+    /// The number of tile columns in the tileset
+    pub tileset_columns: u32,
+    /// The size of the layer in cells
+    pub size: UVec2,
+    /// The stack of tiles in each cell of the layer, from bottom to top
+    pub(crate) cells: HashMap<(u32, u32), Vec<LayerTile>>,
+    /// The cells that have changed since the layer image was last drawn
+    pub(crate) changed_cells: HashSet<(u32, u32)>,
+}
+
+/// A tile in a [`LdtkLayerTiles`] cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerTile {
+    /// The pixel position of the tile in the tileset
+    pub src: UVec2,
+    /// Whether or not the tile is flipped horizontally
+    pub flip_x: bool,
+    /// Whether or not the tile is flipped vertically
+    pub flip_y: bool,
+}
+
+impl LdtkLayerTiles {
+    /// Get the tiles in a cell, from bottom to top
+    pub fn get(&self, cell: UVec2) -> &[LayerTile] {
+        self.cells
+            .get(&(cell.x, cell.y))
+            .map(|x| x.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Replace the stack of tiles in a cell. Cells outside of the layer are ignored.
+    pub fn set(&mut self, cell: UVec2, tiles: Vec<LayerTile>) {
+        if cell.x >= self.size.x || cell.y >= self.size.y {
+            return;
+        }
+
+        self.cells.insert((cell.x, cell.y), tiles);
+        self.changed_cells.insert((cell.x, cell.y));
+    }
+
+    /// Replace the tiles in a cell with a single tile
+    pub fn set_tile(&mut self, cell: UVec2, tile: LayerTile) {
+        self.set(cell, vec![tile]);
+    }
+
+    /// Add a tile on top of the tiles in a cell
+    pub fn push_tile(&mut self, cell: UVec2, tile: LayerTile) {
+        let mut tiles = self.get(cell).to_vec();
+        tiles.push(tile);
+        self.set(cell, tiles);
+    }
+
+    /// Remove all of the tiles in a cell
+    pub fn clear(&mut self, cell: UVec2) {
+        self.set(cell, Vec::new());
+    }
+
+    /// Create an un-flipped tile from the id of a tile in the layer's tileset
+    pub fn tile_from_id(&self, tile_id: u32) -> LayerTile {
+        LayerTile {
+            src: UVec2::new(
+                tile_id % self.tileset_columns * self.grid_size,
+                tile_id / self.tileset_columns * self.grid_size,
+            ),
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+/// Component added to spawned map layers that contain animated tiles
+///
+/// The animated tiles are re-drawn into the layer image whenever their frame changes.
+pub struct LdtkTileAnimations {
+    /// The tileset image that the animation frames come from
+    pub tileset: Handle<Image>,
+    /// The size of the tiles in the layer
+    pub grid_size: u32,
+    /// The layer cells that contain animated tiles
+    pub cells: Vec<AnimatedTileCell>,
+}
+
+/// A cell in a map layer that contains at least one animated tile
+#[derive(Debug, Clone)]
+pub struct AnimatedTileCell {
+    /// The pixel position of the cell in the layer
+    pub px: UVec2,
+    /// All of the tiles stacked in this cell, from bottom to top, including non-animated ones
+    pub tiles: Vec<AnimatedTile>,
+    /// The frame index of each tile that is currently drawn in the layer image
+    pub(crate) current_frames: Vec<usize>,
+}
+
+/// A tile in an [`AnimatedTileCell`]
+#[derive(Debug, Clone)]
+pub struct AnimatedTile {
+    /// The pixel positions in the tileset of every frame of the tile. Non-animated tiles have only
+    /// one frame.
+    pub frames: Vec<UVec2>,
+    /// The number of frames per second
+    pub fps: f32,
+    /// Whether or not the tile is flipped horizontally
+    pub flip_x: bool,
+    /// Whether or not the tile is flipped vertically
+    pub flip_y: bool,
+}
+
+impl AnimatedTileCell {
+    pub(crate) fn new(px: UVec2, tiles: Vec<AnimatedTile>) -> Self {
+        Self {
+            px,
+            // Start with invalid frames so that the cell is re-drawn on the first update
+            current_frames: vec![usize::MAX; tiles.len()],
+            tiles,
+        }
+    }
+}
+
+impl AnimatedTile {
+    /// Get the index of the frame that should be displayed at the given time
+    pub fn frame_at(&self, seconds: f64) -> usize {
+        (seconds * self.fps as f64).floor() as usize % self.frames.len()
+    }
+}
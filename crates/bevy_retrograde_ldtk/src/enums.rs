@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use crate::{LdtkFieldError, LdtkMap};
+
+/// A Rust enum that can be read from the values of an LDtk enum
+///
+/// This is usually implemented with the [`ldtk_enum!`][crate::ldtk_enum] macro, but can be
+/// implemented manually if the Rust enum doesn't have the same name or variants as the LDtk enum.
+pub trait LdtkEnum: Sized {
+    /// The identifier of the enum in LDtk
+    const IDENTIFIER: &'static str;
+    /// The identifiers of all of the values of the enum in LDtk
+    const VALUES: &'static [&'static str];
+
+    /// Get the Rust enum variant for an LDtk enum value, returning [`None`] if it doesn't exist
+    fn from_ldtk_value(value: &str) -> Option<Self>;
+
+    /// Get the Rust enum variant for an LDtk enum value, returning an error if it doesn't exist
+    fn parse_ldtk_value(value: &str) -> Result<Self, LdtkFieldError> {
+        Self::from_ldtk_value(value).ok_or_else(|| LdtkFieldError::UnknownEnumValue {
+            enum_identifier: Self::IDENTIFIER,
+            value: value.to_owned(),
+            expected: Self::VALUES,
+        })
+    }
+}
+
+/// Define a Rust enum that implements [`LdtkEnum`]
+///
+/// The name of the enum and its variants must match the identifiers of the enum and its values in
+/// LDtk.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_retrograde_ldtk::*;
+/// ldtk_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub enum Item {
+///         Sword,
+///         Shield,
+///         Potion,
+///     }
+/// }
+///
+/// assert_eq!(Item::from_ldtk_value("Shield"), Some(Item::Shield));
+/// ```
+#[macro_export]
+macro_rules! ldtk_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+        }
+
+        impl $crate::LdtkEnum for $name {
+            const IDENTIFIER: &'static str = stringify!($name);
+            const VALUES: &'static [&'static str] = &[$(stringify!($variant)),*];
+
+            fn from_ldtk_value(value: &str) -> Option<Self> {
+                $(
+                    if value == stringify!($variant) {
+                        return Some(Self::$variant);
+                    }
+                )*
+
+                None
+            }
+        }
+    };
+}
+
+/// Bevy resource containing the Rust enums that have been registered with
+/// [`register_ldtk_enum`][AppBuilderLdtkEnumExt::register_ldtk_enum]
+///
+/// When a map is loaded, the enums in the map are checked against the registered Rust enums and an
+/// error is logged for any mismatches.
+#[derive(Default, Debug, Clone)]
+pub struct LdtkEnumRegistry {
+    enums: Vec<RegisteredLdtkEnum>,
+}
+
+#[derive(Debug, Clone)]
+struct RegisteredLdtkEnum {
+    identifier: &'static str,
+    values: &'static [&'static str],
+}
+
+impl LdtkEnumRegistry {
+    /// Register a Rust enum
+    pub fn register<T: LdtkEnum>(&mut self) {
+        if !self.enums.iter().any(|x| x.identifier == T::IDENTIFIER) {
+            self.enums.push(RegisteredLdtkEnum {
+                identifier: T::IDENTIFIER,
+                values: T::VALUES,
+            });
+        }
+    }
+
+    /// Check that the registered enums match the enums in the map, returning a list of error
+    /// messages for every mismatch
+    pub fn validate(&self, map: &LdtkMap) -> Vec<String> {
+        let defs = map.raw_project.get("defs");
+        let ldtk_enums = ["enums", "externalEnums"]
+            .iter()
+            .filter_map(|key| defs.and_then(|x| x.get(key)).and_then(|x| x.as_array()))
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut errors = Vec::new();
+        for registered in &self.enums {
+            // Find the LDtk enum definition
+            let ldtk_enum = ldtk_enums.iter().find(|x| {
+                x.get("identifier").and_then(|x| x.as_str()) == Some(registered.identifier)
+            });
+            let ldtk_enum = if let Some(ldtk_enum) = ldtk_enum {
+                ldtk_enum
+            } else {
+                errors.push(format!(
+                    "Rust enum `{}` does not exist in the LDtk project",
+                    registered.identifier
+                ));
+                continue;
+            };
+
+            let ldtk_values = ldtk_enum
+                .get("values")
+                .and_then(|x| x.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|x| x.get("id").and_then(|x| x.as_str()))
+                .collect::<Vec<_>>();
+
+            for value in &ldtk_values {
+                if !registered.values.contains(value) {
+                    errors.push(format!(
+                        "LDtk enum `{}` has value `{}` that is missing from the Rust enum",
+                        registered.identifier, value
+                    ));
+                }
+            }
+            for value in registered.values {
+                if !ldtk_values.contains(value) {
+                    errors.push(format!(
+                        "Rust enum `{}` has variant `{}` that is missing from the LDtk enum",
+                        registered.identifier, value
+                    ));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// LDtk enum extension to the Bevy [`AppBuilder`]
+pub trait AppBuilderLdtkEnumExt {
+    /// Register a Rust enum so that it will be checked against the enums of every LDtk map that is
+    /// loaded
+    fn register_ldtk_enum<T: LdtkEnum>(self) -> Self;
+}
+
+impl AppBuilderLdtkEnumExt for &mut AppBuilder {
+    fn register_ldtk_enum<T: LdtkEnum>(self) -> Self {
+        self.world_mut()
+            .get_resource_or_insert_with(LdtkEnumRegistry::default)
+            .register::<T>();
+
+        self
+    }
+}
+
+/// This system checks every loaded map against the registered Rust enums and logs any mismatches
+pub(crate) fn validate_ldtk_enums(
+    mut events: EventReader<AssetEvent<LdtkMap>>,
+    registry: Res<LdtkEnumRegistry>,
+    map_assets: Res<Assets<LdtkMap>>,
+) {
+    for event in events.iter() {
+        let handle = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+
+        if let Some(map) = map_assets.get(handle) {
+            for error in registry.validate(map) {
+                error!("LDtk enum mismatch: {}", error);
+            }
+        }
+    }
+}
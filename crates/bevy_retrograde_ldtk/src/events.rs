@@ -0,0 +1,166 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_retrograde_core::prelude::Image;
+
+use crate::LdtkMap;
+
+/// Event sent when the entities for a level of an LDtk map have been spawned
+#[derive(Debug, Clone)]
+pub struct LevelSpawned {
+    /// The map entity that the level was spawned for
+    pub map_entity: Entity,
+    /// The map that the level is in
+    pub map: Handle<LdtkMap>,
+    /// The identifier of the level
+    pub level_identifier: String,
+}
+
+/// Event sent when a level of an LDtk map has been despawned, either because the map entity was
+/// despawned or because the map is being reloaded
+#[derive(Debug, Clone)]
+pub struct LevelDespawned {
+    /// The map entity that the level was spawned for
+    pub map_entity: Entity,
+    /// The map that the level is in
+    pub map: Handle<LdtkMap>,
+    /// The identifier of the level
+    pub level_identifier: String,
+}
+
+/// Event sent once all of the layers and entities of a spawned level exist in the world and all of
+/// the level's images have been loaded
+///
+/// This is sent at the earliest on the frame after the [`LevelSpawned`] event, so the commands of
+/// the systems that handled the [`LevelSpawned`] event have been applied as well. This is the event
+/// to use to run setup logic that queries the level's entities.
+#[derive(Debug, Clone)]
+pub struct LevelReady {
+    /// The map entity that the level was spawned for
+    pub map_entity: Entity,
+    /// The map that the level is in
+    pub map: Handle<LdtkMap>,
+    /// The identifier of the level
+    pub level_identifier: String,
+}
+
+/// Keeps track of the levels that have been spawned for each map entity
+#[derive(Default)]
+pub(crate) struct LevelTracker {
+    /// The map handle and the identifiers of the spawned levels of each map entity
+    spawned: HashMap<Entity, (Handle<LdtkMap>, Vec<String>)>,
+    /// The levels that have been spawned but have not been sent a [`LevelReady`] event yet
+    pending_ready: Vec<PendingLevel>,
+}
+
+/// A spawned level that is waiting to be sent a [`LevelReady`] event
+struct PendingLevel {
+    /// The event that was sent when the level was spawned
+    spawned: LevelSpawned,
+    /// The entities spawned for the level
+    entities: Vec<Entity>,
+    /// Whether or not the spawn commands of the level have been applied
+    is_spawned: bool,
+}
+
+impl LevelTracker {
+    /// Record that a level has been spawned along with the given entities, returning the
+    /// [`LevelSpawned`] event for it
+    pub(crate) fn spawn(
+        &mut self,
+        map_entity: Entity,
+        map: &Handle<LdtkMap>,
+        level_identifier: &str,
+        entities: Vec<Entity>,
+    ) -> LevelSpawned {
+        let (_, levels) = self
+            .spawned
+            .entry(map_entity)
+            .or_insert_with(|| (map.clone(), Vec::new()));
+        levels.push(level_identifier.to_owned());
+
+        let event = LevelSpawned {
+            map_entity,
+            map: map.clone(),
+            level_identifier: level_identifier.to_owned(),
+        };
+        self.pending_ready.push(PendingLevel {
+            spawned: event.clone(),
+            entities,
+            is_spawned: false,
+        });
+
+        event
+    }
+
+    /// Record that all of the levels of a map entity have been despawned, returning the
+    /// [`LevelDespawned`] events for them
+    pub(crate) fn despawn_all(&mut self, map_entity: Entity) -> Vec<LevelDespawned> {
+        self.pending_ready
+            .retain(|x| x.spawned.map_entity != map_entity);
+
+        self.spawned
+            .remove(&map_entity)
+            .map(|(map, levels)| {
+                levels
+                    .into_iter()
+                    .map(|level_identifier| LevelDespawned {
+                        map_entity,
+                        map: map.clone(),
+                        level_identifier,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// This system sends [`LevelDespawned`] events for despawned maps and [`LevelReady`] events for
+/// levels that have finished loading
+pub(crate) fn track_level_lifecycle(
+    mut tracker: ResMut<LevelTracker>,
+    removed_maps: RemovedComponents<Handle<LdtkMap>>,
+    level_entities: Query<Option<&Handle<Image>>>,
+    image_assets: Res<Assets<Image>>,
+    mut despawned_events: EventWriter<LevelDespawned>,
+    mut ready_events: EventWriter<LevelReady>,
+) {
+    // Send despawn events for maps that have been despawned or had their map removed
+    for map_entity in removed_maps.iter() {
+        for event in tracker.despawn_all(map_entity) {
+            despawned_events.send(event);
+        }
+    }
+
+    // Send ready events for the levels whose entities have been spawned and whose images have all
+    // loaded
+    let mut ready = Vec::new();
+    tracker.pending_ready.retain(|level| {
+        // The level's entities are spawned with commands, so wait for the frame after the level
+        // was spawned, when they and the commands of any `LevelSpawned` handlers have been applied
+        if !level.is_spawned {
+            return true;
+        }
+
+        // Entities that were despawned since the level was spawned are skipped
+        let is_ready = level
+            .entities
+            .iter()
+            .filter_map(|&entity| level_entities.get(entity).ok().flatten())
+            .all(|image| image_assets.get(image).is_some());
+
+        if is_ready {
+            ready.push(LevelReady {
+                map_entity: level.spawned.map_entity,
+                map: level.spawned.map.clone(),
+                level_identifier: level.spawned.level_identifier.clone(),
+            });
+        }
+
+        !is_ready
+    });
+    for level in &mut tracker.pending_ready {
+        level.is_spawned = true;
+    }
+    for event in ready {
+        ready_events.send(event);
+    }
+}
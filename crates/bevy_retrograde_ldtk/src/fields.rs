@@ -0,0 +1,230 @@
+use bevy::{math::IVec2, utils::HashMap};
+
+use crate::LdtkEnum;
+
+/// An error that occurs when reading the value of an LDtk field
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LdtkFieldError {
+    #[error("Field `{0}` does not exist")]
+    MissingField(String),
+    #[error("Field `{0}` is null")]
+    NullField(String),
+    #[error("Field `{field}` has type `{found}` but was read as `{expected}`")]
+    WrongType {
+        field: String,
+        expected: &'static str,
+        found: String,
+    },
+    #[error("Field `{field}` uses the LDtk enum `{found}` but was read as the enum `{expected}`")]
+    EnumMismatch {
+        field: String,
+        expected: &'static str,
+        found: String,
+    },
+    #[error("Value `{value}` is not a valid value of the enum `{enum_identifier}`, expected one of {expected:?}")]
+    UnknownEnumValue {
+        enum_identifier: &'static str,
+        value: String,
+        expected: &'static [&'static str],
+    },
+}
+
+/// The value of a field on an LDtk entity or level
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkField {
+    /// The LDtk type of the field, such as `Int`, `String`, or `LocalEnum.Item`
+    pub ty: String,
+    /// The raw JSON value of the field
+    pub value: serde_json::Value,
+}
+
+/// The fields of an LDtk entity or level
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LdtkFields(pub HashMap<String, LdtkField>);
+bevy_retrograde_macros::impl_deref!(LdtkFields, HashMap<String, LdtkField>);
+
+impl LdtkFields {
+    /// Collect the fields from the raw JSON `fieldInstances` of an LDtk entity or level
+    pub(crate) fn from_raw_field_instances(field_instances: Option<&serde_json::Value>) -> Self {
+        LdtkFields(
+            field_instances
+                .and_then(|x| x.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|field| {
+                    let identifier = field.get("__identifier")?.as_str()?;
+                    let ty = field.get("__type")?.as_str()?;
+                    let value = field
+                        .get("__value")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null);
+
+                    Some((
+                        identifier.to_owned(),
+                        LdtkField {
+                            ty: ty.to_owned(),
+                            value,
+                        },
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get a field
+    pub fn field(&self, identifier: &str) -> Result<&LdtkField, LdtkFieldError> {
+        self.0
+            .get(identifier)
+            .ok_or_else(|| LdtkFieldError::MissingField(identifier.to_owned()))
+    }
+
+    /// Read an enum field as the Rust enum `T`
+    ///
+    /// Returns an error if the field is null, uses a different enum, or has a value that isn't in
+    /// `T`.
+    pub fn field_enum<T: LdtkEnum>(&self, identifier: &str) -> Result<T, LdtkFieldError> {
+        self.field_enum_option(identifier)?
+            .ok_or_else(|| LdtkFieldError::NullField(identifier.to_owned()))
+    }
+
+    /// Read a nullable enum field as the Rust enum `T`
+    pub fn field_enum_option<T: LdtkEnum>(
+        &self,
+        identifier: &str,
+    ) -> Result<Option<T>, LdtkFieldError> {
+        let field = self.field(identifier)?;
+        check_enum_type::<T>(identifier, &field.ty, false)?;
+
+        match &field.value {
+            serde_json::Value::Null => Ok(None),
+            serde_json::Value::String(value) => T::parse_ldtk_value(value).map(Some),
+            _ => Err(LdtkFieldError::WrongType {
+                field: identifier.to_owned(),
+                expected: "enum",
+                found: field.ty.clone(),
+            }),
+        }
+    }
+
+    /// Read an enum array field as a list of the Rust enum `T`
+    ///
+    /// Null items in the array are skipped.
+    pub fn field_enum_array<T: LdtkEnum>(
+        &self,
+        identifier: &str,
+    ) -> Result<Vec<T>, LdtkFieldError> {
+        let field = self.field(identifier)?;
+        check_enum_type::<T>(identifier, &field.ty, true)?;
+
+        let wrong_type = || LdtkFieldError::WrongType {
+            field: identifier.to_owned(),
+            expected: "enum array",
+            found: field.ty.clone(),
+        };
+
+        field
+            .value
+            .as_array()
+            .ok_or_else(wrong_type)?
+            .iter()
+            .filter(|x| !x.is_null())
+            .map(|x| T::parse_ldtk_value(x.as_str().ok_or_else(wrong_type)?))
+            .collect()
+    }
+
+    /// Read a point field as the grid cell that it points to
+    pub fn field_point(&self, identifier: &str) -> Result<IVec2, LdtkFieldError> {
+        self.field_point_option(identifier)?
+            .ok_or_else(|| LdtkFieldError::NullField(identifier.to_owned()))
+    }
+
+    /// Read a nullable point field as the grid cell that it points to
+    pub fn field_point_option(&self, identifier: &str) -> Result<Option<IVec2>, LdtkFieldError> {
+        let field = self.field(identifier)?;
+        let wrong_type = || LdtkFieldError::WrongType {
+            field: identifier.to_owned(),
+            expected: "point",
+            found: field.ty.clone(),
+        };
+        if field.ty != "Point" {
+            return Err(wrong_type());
+        }
+
+        match &field.value {
+            serde_json::Value::Null => Ok(None),
+            value => parse_point(value).map(Some).ok_or_else(wrong_type),
+        }
+    }
+
+    /// Read a point array field as the grid cells that it points to, such as the waypoints of a
+    /// path
+    ///
+    /// Null items in the array are skipped.
+    pub fn field_point_array(&self, identifier: &str) -> Result<Vec<IVec2>, LdtkFieldError> {
+        let field = self.field(identifier)?;
+        let wrong_type = || LdtkFieldError::WrongType {
+            field: identifier.to_owned(),
+            expected: "point array",
+            found: field.ty.clone(),
+        };
+        if field.ty != "Array<Point>" {
+            return Err(wrong_type());
+        }
+
+        field
+            .value
+            .as_array()
+            .ok_or_else(wrong_type)?
+            .iter()
+            .filter(|x| !x.is_null())
+            .map(|x| parse_point(x).ok_or_else(wrong_type))
+            .collect()
+    }
+}
+
+/// Parse the grid cell of an LDtk point value, which is written as `{ "cx": 1, "cy": 2 }`
+fn parse_point(value: &serde_json::Value) -> Option<IVec2> {
+    Some(IVec2::new(
+        value.get("cx")?.as_i64()? as i32,
+        value.get("cy")?.as_i64()? as i32,
+    ))
+}
+
+/// Make sure that an LDtk field type is an enum with the same identifier as `T`
+fn check_enum_type<T: LdtkEnum>(
+    field: &str,
+    ty: &str,
+    is_array: bool,
+) -> Result<(), LdtkFieldError> {
+    let expected = if is_array { "enum array" } else { "enum" };
+    let wrong_type = || LdtkFieldError::WrongType {
+        field: field.to_owned(),
+        expected,
+        found: ty.to_owned(),
+    };
+
+    // Strip the array wrapper from the type if this is an array
+    let item_ty = if is_array {
+        ty.strip_prefix("Array<")
+            .and_then(|x| x.strip_suffix('>'))
+            .ok_or_else(wrong_type)?
+    } else {
+        ty
+    };
+
+    // Get the name of the enum from the type
+    let enum_identifier = item_ty
+        .strip_prefix("LocalEnum.")
+        .or_else(|| item_ty.strip_prefix("ExternEnum."))
+        .ok_or_else(wrong_type)?;
+
+    if enum_identifier != T::IDENTIFIER {
+        return Err(LdtkFieldError::EnumMismatch {
+            field: field.to_owned(),
+            expected: T::IDENTIFIER,
+            found: enum_identifier.to_owned(),
+        });
+    }
+
+    Ok(())
+}
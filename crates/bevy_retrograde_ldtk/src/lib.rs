@@ -17,6 +17,70 @@
 //! If you run into anything that isn't supported that you want to use in your game open an issue or
 //! PR to help prioritize what gets implemented.
 //!
+//! # Tile Animations
+//!
+//! LDtk doesn't support animated tiles itself, but you can animate tiles by adding an animation to
+//! the custom data of a tile in the LDtk tileset editor. See [`TileAnimation`] for the format.
+//!
+//! # Navigation
+//!
+//! A [`NavGrid`] of walkable cells can be generated for each level from an IntGrid layer by
+//! setting [`LdtkMapConfig::nav_grid`]. The grids are spawned as [`LdtkNavGrid`] entities, which
+//! can find paths through the level with [`LdtkNavGrid::find_path`].
+//!
+//! # Level Events
+//!
+//! The [`LevelSpawned`], [`LevelReady`], and [`LevelDespawned`] events are sent as the levels of a
+//! map are spawned and despawned and can be used to run setup and teardown logic for each level.
+//!
+//! # Level Selection
+//!
+//! All of the levels in a map are spawned by default. The [`LevelSelection`] resource can be used
+//! to only spawn specific levels.
+//!
+//! # Worlds
+//!
+//! Projects that use LDtk's multi-world feature are supported. The world to spawn can be selected
+//! with [`LdtkMapConfig::world`], and the worlds in a map can be listed with [`LdtkMap::worlds`].
+//!
+//! # Modifying Tiles
+//!
+//! The tiles of spawned tile layers can be changed at runtime with the [`LdtkLayerTiles`]
+//! component on the layer entity.
+//!
+//! # Entities and Enums
+//!
+//! The entity instances in a map's entity layers are spawned as children of the map with an
+//! [`LdtkEntity`] component containing the entity's custom fields.
+//!
+//! LDtk enums can be mapped onto Rust enums with the [`ldtk_enum!`] macro and read from entity
+//! fields, level fields, and tileset enum tags:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_ldtk::*;
+//! ldtk_enum! {
+//!     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//!     pub enum Item {
+//!         Sword,
+//!         Shield,
+//!     }
+//! }
+//!
+//! fn read_items(entities: Query<&LdtkEntity, Added<LdtkEntity>>) {
+//!     for entity in entities.iter() {
+//!         match entity.fields.field_enum::<Item>("item") {
+//!             Ok(item) => info!("Found a {:?}", item),
+//!             Err(e) => error!("{}", e),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Registering the enum with
+//! [`register_ldtk_enum`][AppBuilderLdtkEnumExt::register_ldtk_enum] will log an error when a map
+//! is loaded with an enum that doesn't match the Rust enum.
+//!
 //! # License
 //!
 //! Bevy Retrograde LDtk is licensed under the [Katharos License][k_license] which places certain
@@ -29,10 +93,21 @@ use bevy::prelude::*;
 
 mod asset;
 mod components;
+mod enums;
+mod events;
+mod fields;
+mod nav;
+mod pathfinding;
+mod resources;
 mod system;
 
 pub use asset::*;
 pub use components::*;
+pub use enums::*;
+pub use events::{LevelDespawned, LevelReady, LevelSpawned};
+pub use fields::*;
+pub use nav::*;
+pub use resources::*;
 
 use system::add_systems;
 
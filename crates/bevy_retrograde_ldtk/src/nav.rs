@@ -0,0 +1,216 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::LdtkMap;
+
+/// Settings for generating navigation grids from the IntGrid layers of an LDtk map
+///
+/// A [`LdtkNavGrid`] is spawned as a child of the map for every level that has the configured
+/// IntGrid layer.
+#[derive(Debug, Clone)]
+pub struct NavGridConfig {
+    /// The identifier of the IntGrid layer to generate the navigation grid from
+    pub layer_identifier: String,
+    /// The IntGrid values that can't be walked through. Cells with any other value, including
+    /// empty cells, are walkable.
+    pub blocked_values: Vec<i64>,
+    /// The cost of moving into the cells with each IntGrid value, such as mud that is slower to
+    /// walk through. Cells with any other value cost `1.0`.
+    pub cell_costs: HashMap<i64, f32>,
+    /// Whether or not to pre-compute a [`NavGraph`] for the grid
+    pub build_graph: bool,
+    /// Whether or not the graph, and the paths found with [`LdtkNavGrid::find_path`], allow
+    /// diagonal movement. Diagonal movement is never allowed through the corners of blocked
+    /// cells.
+    pub allow_diagonals: bool,
+}
+
+impl Default for NavGridConfig {
+    fn default() -> Self {
+        Self {
+            layer_identifier: "Collisions".into(),
+            blocked_values: vec![1],
+            cell_costs: Default::default(),
+            build_graph: false,
+            allow_diagonals: false,
+        }
+    }
+}
+
+/// Component added to the entities spawned for the navigation grid of each level
+///
+/// The entity's transform is set to the position of the top-left corner of the level.
+#[derive(Debug, Clone)]
+pub struct LdtkNavGrid {
+    pub map: Handle<LdtkMap>,
+    pub level_identifier: String,
+    /// The position, relative to the map, of the top-left corner of the level
+    pub level_position: Vec2,
+    /// Whether or not paths found with [`find_path`][Self::find_path] can move diagonally
+    pub allow_diagonals: bool,
+    /// The walkability grid
+    pub grid: NavGrid,
+    /// The navigation graph for the grid, if [`NavGridConfig::build_graph`] was enabled
+    pub graph: Option<NavGraph>,
+}
+
+/// A grid of walkable and blocked cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavGrid {
+    /// The size of the grid in cells
+    pub size: UVec2,
+    /// The size of each cell in pixels
+    pub cell_size: u32,
+    /// Whether or not each cell is walkable, in rows from top to bottom
+    pub walkable: Vec<bool>,
+    /// The cost of moving into each cell, in the same order as [`walkable`][Self::walkable]
+    pub costs: Vec<f32>,
+}
+
+impl NavGrid {
+    /// Get the index of a cell in [`walkable`][Self::walkable], returning [`None`] if the cell is
+    /// outside of the grid
+    pub fn index(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x as u32 >= self.size.x || cell.y as u32 >= self.size.y
+        {
+            return None;
+        }
+
+        Some(cell.y as usize * self.size.x as usize + cell.x as usize)
+    }
+
+    /// Get the cell at an index in [`walkable`][Self::walkable]
+    pub fn cell(&self, index: usize) -> IVec2 {
+        IVec2::new(
+            (index % self.size.x as usize) as i32,
+            (index / self.size.x as usize) as i32,
+        )
+    }
+
+    /// Check whether or not a cell is walkable. Cells outside of the grid are not walkable.
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).map(|i| self.walkable[i]).unwrap_or(false)
+    }
+
+    /// Get the cell that contains a position, relative to the top-left corner of the grid
+    pub fn cell_at(&self, position: Vec2) -> IVec2 {
+        IVec2::new(
+            (position.x / self.cell_size as f32).floor() as i32,
+            (position.y / self.cell_size as f32).floor() as i32,
+        )
+    }
+
+    /// Get the position of the center of a cell, relative to the top-left corner of the grid
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        let cell_size = self.cell_size as f32;
+        Vec2::new(
+            (cell.x as f32 + 0.5) * cell_size,
+            (cell.y as f32 + 0.5) * cell_size,
+        )
+    }
+
+    /// Get the cost of moving into a cell, which is `1.0` for cells without a cost
+    pub fn cell_cost(&self, cell: IVec2) -> f32 {
+        self.index(cell)
+            .and_then(|i| self.costs.get(i).copied())
+            .unwrap_or(1.0)
+    }
+
+    /// Get the walkable neighbors of a cell and the cost of moving to them
+    ///
+    /// Moves cost the [cost][Self::cell_cost] of the neighbor, times `√2` for diagonal moves.
+    /// Diagonal moves are not allowed if either of the cells next to the corner is blocked.
+    pub fn walkable_neighbors(&self, cell: IVec2, allow_diagonals: bool) -> Vec<(IVec2, f32)> {
+        let mut neighbors = Vec::with_capacity(8);
+
+        for (dx, dy) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let neighbor = cell + IVec2::new(*dx, *dy);
+            if self.is_walkable(neighbor) {
+                neighbors.push((neighbor, self.cell_cost(neighbor)));
+            }
+        }
+
+        if allow_diagonals {
+            for (dx, dy) in &[(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let neighbor = cell + IVec2::new(*dx, *dy);
+                if self.is_walkable(neighbor)
+                    && self.is_walkable(cell + IVec2::new(*dx, 0))
+                    && self.is_walkable(cell + IVec2::new(0, *dy))
+                {
+                    neighbors.push((
+                        neighbor,
+                        std::f32::consts::SQRT_2 * self.cell_cost(neighbor),
+                    ));
+                }
+            }
+        }
+
+        neighbors
+    }
+
+    /// Build a navigation graph for the grid
+    pub fn build_graph(&self, allow_diagonals: bool) -> NavGraph {
+        let edges = (0..self.walkable.len())
+            .map(|index| {
+                if !self.walkable[index] {
+                    return Vec::new();
+                }
+
+                self.walkable_neighbors(self.cell(index), allow_diagonals)
+                    .into_iter()
+                    .filter_map(|(cell, cost)| Some((self.index(cell)?, cost)))
+                    .collect()
+            })
+            .collect();
+
+        NavGraph { edges }
+    }
+}
+
+/// A pre-computed navigation graph for a [`NavGrid`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavGraph {
+    /// The edges from each cell, indexed the same as [`NavGrid::walkable`], to the index of each of
+    /// its walkable neighbors and the cost of moving to it
+    pub edges: Vec<Vec<(usize, f32)>>,
+}
+
+/// Build the navigation grid for an IntGrid layer from its raw JSON
+pub(crate) fn build_nav_grid(layer: &serde_json::Value, config: &NavGridConfig) -> Option<NavGrid> {
+    let width = layer.get("__cWid")?.as_u64()? as u32;
+    let height = layer.get("__cHei")?.as_u64()? as u32;
+    let cell_size = layer.get("__gridSize")?.as_u64()? as u32;
+
+    let mut walkable = vec![true; (width * height) as usize];
+    let mut costs = vec![1.0; (width * height) as usize];
+    let cost_of = |value: i64| config.cell_costs.get(&value).copied().unwrap_or(1.0);
+
+    // Newer LDtk versions store the grid in `intGridCsv`, while older versions store only the
+    // non-empty cells in `intGrid`
+    if let Some(csv) = layer.get("intGridCsv").and_then(|x| x.as_array()) {
+        for ((cell, cost), value) in walkable.iter_mut().zip(costs.iter_mut()).zip(csv) {
+            if let Some(value) = value.as_i64() {
+                *cell = !config.blocked_values.contains(&value);
+                *cost = cost_of(value);
+            }
+        }
+    } else if let Some(cells) = layer.get("intGrid").and_then(|x| x.as_array()) {
+        for cell in cells {
+            let coord = cell.get("coordId").and_then(|x| x.as_u64());
+            // The old format stores the index of the value, which is one less than the value
+            let value = cell.get("v").and_then(|x| x.as_i64()).map(|x| x + 1);
+            if let (Some(coord), Some(value)) = (coord, value) {
+                if let Some(cell) = walkable.get_mut(coord as usize) {
+                    *cell = !config.blocked_values.contains(&value);
+                    costs[coord as usize] = cost_of(value);
+                }
+            }
+        }
+    }
+
+    Some(NavGrid {
+        size: UVec2::new(width, height),
+        cell_size,
+        walkable,
+        costs,
+    })
+}
@@ -0,0 +1,257 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::prelude::*;
+
+use crate::{LdtkNavGrid, NavGraph, NavGrid};
+
+impl NavGrid {
+    /// Find the cheapest path from one cell to another with A*, returning the cells of the path
+    /// from the start to the goal
+    ///
+    /// Returns [`None`] if the goal can't be reached. The start cell doesn't have to be walkable,
+    /// so that entities overlapping a wall can still find their way out.
+    pub fn find_path(
+        &self,
+        start: IVec2,
+        goal: IVec2,
+        allow_diagonals: bool,
+    ) -> Option<Vec<IVec2>> {
+        let start = self.index(start)?;
+        let goal = self.index(goal)?;
+        let path = astar(self, start, goal, allow_diagonals, |index| {
+            self.walkable_neighbors(self.cell(index), allow_diagonals)
+                .into_iter()
+                .filter_map(|(cell, cost)| Some((self.index(cell)?, cost)))
+                .collect()
+        })?;
+
+        Some(path.into_iter().map(|index| self.cell(index)).collect())
+    }
+
+    /// Find the cheapest path between two positions, relative to the top-left corner of the grid,
+    /// returning the waypoints of the path
+    ///
+    /// The waypoints are the centers of the cells where the path turns, ending with the goal
+    /// position, which makes them ready to be followed by an entity.
+    pub fn find_path_positions(
+        &self,
+        start: Vec2,
+        goal: Vec2,
+        allow_diagonals: bool,
+    ) -> Option<Vec<Vec2>> {
+        let cells = self.find_path(self.cell_at(start), self.cell_at(goal), allow_diagonals)?;
+        Some(self.waypoints(&cells, goal))
+    }
+
+    /// Turn the cells of a path into waypoints, leaving out the start cell and the cells in the
+    /// middle of straight lines
+    fn waypoints(&self, cells: &[IVec2], goal: Vec2) -> Vec<Vec2> {
+        let mut waypoints = Vec::new();
+        for (i, cell) in cells.iter().enumerate().skip(1) {
+            let next = match cells.get(i + 1) {
+                Some(next) => *next,
+                None => break,
+            };
+
+            if *cell - cells[i - 1] != next - *cell {
+                waypoints.push(self.cell_center(*cell));
+            }
+        }
+        waypoints.push(goal);
+
+        waypoints
+    }
+}
+
+impl NavGraph {
+    /// Find the cheapest path from one cell of the grid to another with A*, using the
+    /// pre-computed edges of the graph
+    ///
+    /// This is the same as [`NavGrid::find_path`], but faster for grids that are searched often.
+    /// `allow_diagonals` must match the setting that the graph was built with.
+    pub fn find_path(
+        &self,
+        grid: &NavGrid,
+        start: IVec2,
+        goal: IVec2,
+        allow_diagonals: bool,
+    ) -> Option<Vec<IVec2>> {
+        let start = grid.index(start)?;
+        let goal = grid.index(goal)?;
+        let path = astar(grid, start, goal, allow_diagonals, |index| {
+            // Blocked cells don't have edges, so find the neighbors of a blocked start cell
+            if grid.walkable[index] {
+                self.edges.get(index).cloned().unwrap_or_default()
+            } else {
+                grid.walkable_neighbors(grid.cell(index), allow_diagonals)
+                    .into_iter()
+                    .filter_map(|(cell, cost)| Some((grid.index(cell)?, cost)))
+                    .collect()
+            }
+        })?;
+
+        Some(path.into_iter().map(|index| grid.cell(index)).collect())
+    }
+}
+
+impl LdtkNavGrid {
+    /// Find the cheapest path between two positions, relative to the map, returning the waypoints
+    /// of the path
+    ///
+    /// The waypoints are relative to the map too, so entities spawned as children of the map can
+    /// follow them directly. The pre-computed [`NavGraph`] is used if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_retrograde_ldtk::*;
+    /// struct Enemy;
+    /// struct Player;
+    ///
+    /// fn chase_player(
+    ///     nav_grids: Query<&LdtkNavGrid>,
+    ///     players: Query<&Transform, With<Player>>,
+    ///     enemies: Query<&Transform, With<Enemy>>,
+    /// ) {
+    ///     let (nav_grid, player) = match (nav_grids.iter().next(), players.iter().next()) {
+    ///         (Some(nav_grid), Some(player)) => (nav_grid, player),
+    ///         _ => return,
+    ///     };
+    ///
+    ///     for enemy in enemies.iter() {
+    ///         let path = nav_grid.find_path(
+    ///             enemy.translation.truncate(),
+    ///             player.translation.truncate(),
+    ///         );
+    ///         info!("Path to the player: {:?}", path);
+    ///     }
+    /// }
+    /// ```
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let start = start - self.level_position;
+        let goal = goal - self.level_position;
+
+        let (start_cell, goal_cell) = (self.grid.cell_at(start), self.grid.cell_at(goal));
+        let cells = match &self.graph {
+            Some(graph) => graph.find_path(&self.grid, start_cell, goal_cell, self.allow_diagonals),
+            None => self
+                .grid
+                .find_path(start_cell, goal_cell, self.allow_diagonals),
+        }?;
+
+        Some(
+            self.grid
+                .waypoints(&cells, goal)
+                .into_iter()
+                .map(|waypoint| waypoint + self.level_position)
+                .collect(),
+        )
+    }
+}
+
+/// A cell in the open set of A*, ordered so that the cell with the lowest estimated cost is first
+struct OpenCell {
+    index: usize,
+    estimate: f32,
+}
+
+impl PartialEq for OpenCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for OpenCell {}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse the order to make the max-heap a min-heap
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Find the cheapest path between two cells of a grid with A*, returning the indexes of the cells
+/// in the path
+fn astar<F: Fn(usize) -> Vec<(usize, f32)>>(
+    grid: &NavGrid,
+    start: usize,
+    goal: usize,
+    allow_diagonals: bool,
+    neighbors: F,
+) -> Option<Vec<usize>> {
+    if !grid.walkable.get(goal).copied().unwrap_or(false) {
+        return None;
+    }
+
+    // Scale the heuristic by the cheapest cell so that it never overestimates the cost
+    let min_cost = grid
+        .costs
+        .iter()
+        .copied()
+        .fold(f32::INFINITY, f32::min)
+        .max(0.);
+    let goal_cell = grid.cell(goal);
+    let heuristic = |index: usize| {
+        let delta = grid.cell(index) - goal_cell;
+        let (dx, dy) = (delta.x.abs(), delta.y.abs());
+        let (long, short) = (dx.max(dy) as f32, dx.min(dy) as f32);
+        let distance = if allow_diagonals {
+            long + (std::f32::consts::SQRT_2 - 1.) * short
+        } else {
+            long + short
+        };
+        distance * min_cost
+    };
+
+    let mut costs = vec![f32::INFINITY; grid.walkable.len()];
+    let mut came_from = vec![usize::MAX; grid.walkable.len()];
+    let mut open = BinaryHeap::new();
+    costs[start] = 0.;
+    open.push(OpenCell {
+        index: start,
+        estimate: heuristic(start),
+    });
+
+    while let Some(OpenCell { index, estimate }) = open.pop() {
+        if index == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while current != start {
+                current = came_from[current];
+                path.push(current);
+            }
+            path.reverse();
+
+            return Some(path);
+        }
+
+        // Skip cells that were already reached more cheaply
+        if estimate > costs[index] + heuristic(index) {
+            continue;
+        }
+
+        for (neighbor, cost) in neighbors(index) {
+            let cost = costs[index] + cost;
+            if cost < costs[neighbor] {
+                costs[neighbor] = cost;
+                came_from[neighbor] = index;
+                open.push(OpenCell {
+                    index: neighbor,
+                    estimate: cost + heuristic(neighbor),
+                });
+            }
+        }
+    }
+
+    None
+}
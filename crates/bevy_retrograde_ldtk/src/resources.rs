@@ -0,0 +1,48 @@
+/// Bevy resource that controls which levels of LDtk maps are spawned
+///
+/// All levels are spawned by default. When the selection changes, all of the spawned maps are
+/// re-spawned with the new selection.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ldtk::*;
+/// fn go_to_level_2(mut level_selection: ResMut<LevelSelection>) {
+///     *level_selection = LevelSelection::Identifier("Level_2".into());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelSelection {
+    /// Spawn all of the levels
+    All,
+    /// Spawn the level with the given identifier
+    Identifier(String),
+    /// Spawn the level with the given instance id. Only LDtk 1.x projects have level iid's.
+    Iid(String),
+    /// Spawn the level at the given index in its world
+    Index(usize),
+    /// Spawn every level matched by any of the given selections
+    Many(Vec<LevelSelection>),
+}
+
+impl Default for LevelSelection {
+    fn default() -> Self {
+        LevelSelection::All
+    }
+}
+
+impl LevelSelection {
+    /// Check whether or not a level is selected, given its index in its world and its raw JSON
+    pub(crate) fn matches(&self, index: usize, raw_level: &serde_json::Value) -> bool {
+        match self {
+            LevelSelection::All => true,
+            LevelSelection::Identifier(identifier) => {
+                raw_level["identifier"].as_str() == Some(identifier.as_str())
+            }
+            LevelSelection::Iid(iid) => raw_level["iid"].as_str() == Some(iid.as_str()),
+            LevelSelection::Index(i) => *i == index,
+            LevelSelection::Many(selections) => {
+                selections.iter().any(|x| x.matches(index, raw_level))
+            }
+        }
+    }
+}
@@ -1,4 +1,13 @@
-use crate::{asset::LdtkMap, LdtkMapLayer};
+use crate::{
+    asset::LdtkMap,
+    enums::validate_ldtk_enums,
+    events::{track_level_lifecycle, LevelTracker},
+    nav::build_nav_grid,
+    AnimatedTile, AnimatedTileCell, LayerFilter, LayerTile, LdtkEntity, LdtkEnumRegistry,
+    LdtkFields, LdtkLayerTiles, LdtkLayerType, LdtkLevelBackground, LdtkMapConfig, LdtkMapLayer,
+    LdtkNavGrid, LdtkTileAnimations, LevelBackground, LevelDespawned, LevelReady, LevelSelection,
+    LevelSpawned,
+};
 use bevy::{ecs::component::ComponentDescriptor, prelude::*, utils::HashMap};
 
 use bevy_retrograde_core::{
@@ -25,7 +34,17 @@ pub(crate) fn add_systems(app: &mut AppBuilder) {
             SystemSet::new()
                 .with_system(hot_reload_maps.system().label(HotReloadSystem))
                 .with_system(process_ldtk_maps.system().after(HotReloadSystem)),
-        );
+        )
+        .add_event::<LevelSpawned>()
+        .add_event::<LevelDespawned>()
+        .add_event::<LevelReady>()
+        .init_resource::<LevelTracker>()
+        .init_resource::<LevelSelection>()
+        .add_system_to_stage(CoreStage::PostUpdate, track_level_lifecycle.system())
+        .init_resource::<LdtkEnumRegistry>()
+        .add_system(validate_ldtk_enums.system())
+        .add_system(animate_tiles.system())
+        .add_system_to_stage(CoreStage::PostUpdate, redraw_changed_tiles.system());
 }
 
 struct LdtkMapHasLoaded;
@@ -33,12 +52,26 @@ struct LdtkMapHasLoaded;
 /// This system spawns the map layers for every unloaded entity with an LDtk map
 fn process_ldtk_maps(
     mut commands: Commands,
-    mut new_maps: Query<(Entity, &Handle<LdtkMap>), Without<LdtkMapHasLoaded>>,
+    mut new_maps: Query<
+        (
+            Entity,
+            &Handle<LdtkMap>,
+            Option<&LdtkMapConfig>,
+            Option<&LayerFilter>,
+            Option<&Transform>,
+        ),
+        Without<LdtkMapHasLoaded>,
+    >,
+    mut cameras: Query<(Entity, &mut Camera)>,
     map_assets: Res<Assets<LdtkMap>>,
     mut image_assets: ResMut<Assets<Image>>,
+    mut level_tracker: ResMut<LevelTracker>,
+    mut level_spawned_events: EventWriter<LevelSpawned>,
+    level_selection: Res<LevelSelection>,
 ) {
     // Loop through all of the maps
-    'load_map: for (map_ent, map_handle) in new_maps.iter_mut() {
+    'load_map: for (map_ent, map_handle, config, layer_filter, map_transform) in new_maps.iter_mut()
+    {
         // Get the map asset, if available
         if let Some(map) = map_assets.get(map_handle) {
             let project = &map.project;
@@ -64,17 +97,196 @@ fn process_ldtk_maps(
                 }
             }
 
-            // Loop through the levels in the map
-            for level in &map.project.levels {
+            // Get the world to spawn
+            let world_identifier = config.and_then(|x| x.world.as_deref());
+            let world = if let Some(world) = map.world(world_identifier) {
+                world
+            } else {
+                error!(
+                    "Could not spawn LDtk map: world `{}` does not exist",
+                    world_identifier.unwrap_or_default()
+                );
+                commands.entity(map_ent).insert(LdtkMapHasLoaded);
+                continue;
+            };
+
+            // Wait for the level background images to load
+            for background in &world.level_backgrounds {
+                if let Some(image) = &background.image {
+                    if image_assets.get(&image.handle).is_none() {
+                        continue 'load_map;
+                    }
+                }
+            }
+
+            // Set the camera background to the background color of the first level
+            if config.map(|x| x.set_camera_background).unwrap_or(false) {
+                if let Some(background) = world.level_backgrounds.first() {
+                    for (_, mut camera) in cameras.iter_mut() {
+                        camera.background_color = background.color;
+                    }
+                }
+            }
+
+            // Add the bounds of the map levels to the map
+            let map_offset = map_transform
+                .map(|x| x.translation.truncate())
+                .unwrap_or_default();
+            let bounds = world
+                .level_bounds(&level_selection)
+                .map(|bounds| LdtkLevelBounds {
+                    min: bounds.min + map_offset,
+                    max: bounds.max + map_offset,
+                });
+            if let Some(bounds) = bounds {
+                commands.entity(map_ent).insert(bounds);
+
+                // Clamp the camera to the level bounds
+                if config.map(|x| x.clamp_camera).unwrap_or(false) {
+                    for (camera_ent, _) in cameras.iter_mut() {
+                        commands.entity(camera_ent).insert(CameraBounds {
+                            min: bounds.min,
+                            max: bounds.max,
+                        });
+                    }
+                }
+            }
+
+            // Loop through the levels in the world
+            for (level_index, level) in world.levels.iter().enumerate() {
+                // Skip levels that aren't selected
+                if !level_selection.matches(level_index, &world.raw_levels[level_index]) {
+                    continue;
+                }
+
+                let layer_instances = level.layer_instances.as_ref().unwrap();
+                let level_position = world
+                    .level_positions
+                    .get(level_index)
+                    .map(|x| Vec2::new(x.x as f32, x.y as f32))
+                    .unwrap_or_default();
+
+                // The entities spawned for the level
+                let mut level_entities = Vec::new();
+
+                // Spawn the level background
+                if let Some(background) = world.level_backgrounds.get(level_index) {
+                    let background_ents = spawn_level_background(
+                        &mut commands,
+                        &mut image_assets,
+                        LdtkLevelBackground {
+                            map: map_handle.clone(),
+                            level_identifier: level.identifier.clone(),
+                        },
+                        background,
+                        level_position,
+                        UVec2::new(level.px_wid as u32, level.px_hei as u32),
+                    );
+                    commands.entity(map_ent).push_children(&background_ents);
+                    level_entities.extend(background_ents);
+                }
+
+                // Spawn the navigation grid for the level
+                let nav_config = config.and_then(|x| x.nav_grid.as_ref());
+                if let Some(nav_config) = nav_config {
+                    let nav_layer = world.raw_levels[level_index]["layerInstances"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .find(|x| x["__identifier"] == nav_config.layer_identifier.as_str());
+
+                    if let Some(grid) = nav_layer.and_then(|x| build_nav_grid(x, nav_config)) {
+                        let graph = if nav_config.build_graph {
+                            Some(grid.build_graph(nav_config.allow_diagonals))
+                        } else {
+                            None
+                        };
+
+                        let nav_ent = commands
+                            .spawn_bundle((
+                                Transform::from_xyz(level_position.x, level_position.y, 0.),
+                                GlobalTransform::default(),
+                            ))
+                            .insert(LdtkNavGrid {
+                                map: map_handle.clone(),
+                                level_identifier: level.identifier.clone(),
+                                level_position,
+                                allow_diagonals: nav_config.allow_diagonals,
+                                grid,
+                                graph,
+                            })
+                            .id();
+                        commands.entity(map_ent).push_children(&[nav_ent]);
+                        level_entities.push(nav_ent);
+                    }
+                }
+
                 // Loop through the layers in the selected level
-                for (z, layer) in level
-                    .layer_instances
-                    .as_ref()
-                    .unwrap()
+                for (z, layer) in layer_instances
                     .iter()
                     .rev() // Reverse the layer order so that the bottom layer is first
                     .enumerate()
                 {
+                    // Skip layers that are filtered out
+                    if let Some(filter) = layer_filter {
+                        let layer_type = LdtkLayerType::from_ldtk_value(&layer.__type);
+                        if let Some(layer_type) = layer_type {
+                            if !filter.matches(&layer.__identifier, layer_type) {
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Spawn the entities in entity layers
+                    if layer.__type == "Entities" {
+                        // Read the entity instances from the raw JSON, undoing the layer reversal
+                        // to get the index of the layer in the level
+                        let layer_index = layer_instances.len() - 1 - z;
+                        let entity_instances = world.raw_levels[level_index]["layerInstances"]
+                            [layer_index]["entityInstances"]
+                            .as_array()
+                            .into_iter()
+                            .flatten();
+
+                        let mut entities = Vec::new();
+                        for entity in entity_instances {
+                            let identifier = entity["__identifier"].as_str().unwrap_or_default();
+                            let px_x = entity["px"][0].as_i64().unwrap_or_default();
+                            let px_y = entity["px"][1].as_i64().unwrap_or_default();
+
+                            entities.push(
+                                commands
+                                    .spawn_bundle((
+                                        // Place entities just above the layer they are in
+                                        Transform::from_xyz(
+                                            level_position.x + px_x as f32,
+                                            level_position.y + px_y as f32,
+                                            z as f32 * 2. + 1.,
+                                        ),
+                                        GlobalTransform::default(),
+                                    ))
+                                    .insert(LdtkEntity {
+                                        map: map_handle.clone(),
+                                        level_identifier: level.identifier.clone(),
+                                        layer_identifier: layer.__identifier.clone(),
+                                        identifier: identifier.to_owned(),
+                                        fields: LdtkFields::from_raw_field_instances(
+                                            entity.get("fieldInstances"),
+                                        ),
+                                        level_position,
+                                        grid_size: layer.__grid_size as u32,
+                                    })
+                                    .id(),
+                            );
+                        }
+
+                        // Make the entities children of the map entity
+                        commands.entity(map_ent).push_children(&entities);
+                        level_entities.extend(entities);
+
+                        continue;
+                    }
+
                     // Get the information for the tileset associated to this layer
                     let tileset_handle = if let Some(uid) = layer.__tileset_def_uid {
                         *tilesets.get(&uid).expect("Missing tileset")
@@ -101,8 +313,75 @@ fn process_ldtk_maps(
                     let height = (layer.__c_hei * layer.__grid_size) as u32;
                     let mut layer_image = image::RgbaImage::new(width, height);
 
+                    // Get the tile animations for this layer's tileset
+                    let grid_size = layer.__grid_size as u32;
+                    let tileset_columns = (tileset_image.width() / grid_size).max(1);
+                    let tileset_tile_count = tileset_columns * (tileset_image.height() / grid_size);
+                    let tile_animations = layer
+                        .__tileset_def_uid
+                        .and_then(|uid| map.tile_animations.get(&(uid as i64)));
+
+                    // The tiles in each cell of the layer, used to find cells with animated tiles
+                    let mut cell_tiles: HashMap<(u32, u32), (bool, Vec<AnimatedTile>)> =
+                        HashMap::default();
+
+                    // The tiles in each cell of the layer, used for modifying the layer at runtime
+                    let mut layer_tiles: HashMap<(u32, u32), Vec<LayerTile>> = HashMap::default();
+
                     // For every tile in the layer
                     for tile in tiles {
+                        layer_tiles
+                            .entry((tile.px[0] as u32 / grid_size, tile.px[1] as u32 / grid_size))
+                            .or_default()
+                            .push(LayerTile {
+                                src: UVec2::new(tile.src[0] as u32, tile.src[1] as u32),
+                                flip_x: tile.f.x,
+                                flip_y: tile.f.y,
+                            });
+
+                        // Collect the tile's animation frames if it has an animation
+                        if let Some(tile_animations) = tile_animations {
+                            let src_x = tile.src[0] as u32 / grid_size;
+                            let src_y = tile.src[1] as u32 / grid_size;
+                            let tile_id = (src_y * tileset_columns + src_x) as i64;
+                            let animation = tile_animations.get(&tile_id);
+
+                            // Get the position of every frame of the tile in the tileset,
+                            // skipping frames that are outside of the tileset
+                            let frames = animation
+                                .map(|animation| {
+                                    animation
+                                        .frames
+                                        .iter()
+                                        .filter(|&&id| id >= 0 && (id as u32) < tileset_tile_count)
+                                        .map(|&id| {
+                                            let id = id as u32;
+                                            UVec2::new(
+                                                id % tileset_columns * grid_size,
+                                                id / tileset_columns * grid_size,
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                })
+                                .filter(|frames| !frames.is_empty());
+                            let is_animated = frames.is_some();
+
+                            let (has_animation, cell) = cell_tiles
+                                .entry((tile.px[0] as u32, tile.px[1] as u32))
+                                .or_default();
+                            *has_animation |= is_animated;
+                            cell.push(AnimatedTile {
+                                frames: frames.unwrap_or_else(|| {
+                                    vec![UVec2::new(tile.src[0] as u32, tile.src[1] as u32)]
+                                }),
+                                fps: animation
+                                    .map(|x| x.fps)
+                                    .unwrap_or(crate::TileAnimation::DEFAULT_FPS),
+                                flip_x: tile.f.x,
+                                flip_y: tile.f.y,
+                            });
+                        }
+
                         // Get a view of the tilesheet image referenced by the tile
 
                         // TODO: [perf] we only technically need to copy this image if it is flipped,
@@ -156,8 +435,8 @@ fn process_ldtk_maps(
                             // Each layer is 2 units higher than the one before it
                             visible: Visible(layer.visible),
                             transform: Transform::from_xyz(
-                                level.world_x as f32,
-                                level.world_y as f32,
+                                level_position.x,
+                                level_position.y,
                                 z as f32 * 2.,
                             ),
                             ..Default::default()
@@ -167,47 +446,405 @@ fn process_ldtk_maps(
                             level_identifier: level.identifier.clone(),
                             layer_instance: layer.clone(),
                         })
+                        .insert(LdtkLayerTiles {
+                            tileset: tileset_handle.clone(),
+                            grid_size,
+                            tileset_columns,
+                            size: UVec2::new(layer.__c_wid as u32, layer.__c_hei as u32),
+                            cells: layer_tiles,
+                            changed_cells: Default::default(),
+                        })
                         .id();
 
+                    // Add the animated tiles to the layer if there are any
+                    let animated_cells = cell_tiles
+                        .into_iter()
+                        .filter(|(_, (has_animation, _))| *has_animation)
+                        .map(|((x, y), (_, tiles))| AnimatedTileCell::new(UVec2::new(x, y), tiles))
+                        .collect::<Vec<_>>();
+                    if !animated_cells.is_empty() {
+                        commands.entity(layer_ent).insert(LdtkTileAnimations {
+                            tileset: tileset_handle.clone(),
+                            grid_size,
+                            cells: animated_cells,
+                        });
+                    }
+
                     // Make the layer a child of the map entity
                     commands.entity(map_ent).push_children(&[layer_ent]);
+                    level_entities.push(layer_ent);
                 }
 
-                // Mark the map as having been loaded so that we don't process it again
-                commands.entity(map_ent).insert(LdtkMapHasLoaded);
+                level_spawned_events.send(level_tracker.spawn(
+                    map_ent,
+                    map_handle,
+                    &level.identifier,
+                    level_entities,
+                ));
             }
+
+            // Mark the map as having been loaded so that we don't process it again
+            commands.entity(map_ent).insert(LdtkMapHasLoaded);
         }
     }
 }
 
+/// Spawn the background color and background image of a level, returning the spawned entities
+fn spawn_level_background(
+    commands: &mut Commands,
+    image_assets: &mut Assets<Image>,
+    marker: LdtkLevelBackground,
+    background: &LevelBackground,
+    level_position: Vec2,
+    level_size: UVec2,
+) -> Vec<Entity> {
+    let mut entities = Vec::new();
+
+    // Spawn the background color below all of the level layers
+    let color = background.color;
+    let color_image = image::RgbaImage::from_pixel(
+        level_size.x.max(1),
+        level_size.y.max(1),
+        image::Rgba([
+            (color.r * 255.) as u8,
+            (color.g * 255.) as u8,
+            (color.b * 255.) as u8,
+            (color.a * 255.) as u8,
+        ]),
+    );
+    entities.push(
+        commands
+            .spawn_bundle(SpriteBundle {
+                image: image_assets.add(Image::from(color_image)),
+                sprite: Sprite {
+                    centered: false,
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(level_position.x, level_position.y, -2.),
+                ..Default::default()
+            })
+            .insert(marker.clone())
+            .id(),
+    );
+
+    // Spawn the background image above the background color
+    let background_image = if let Some(image) = &background.image {
+        image
+    } else {
+        return entities;
+    };
+    // This unwrap is OK because we wait for the background images to load before spawning the map
+    let source_image = image_assets.get(&background_image.handle).unwrap();
+
+    // Crop the image, clamping the crop rect to the image bounds
+    let [crop_x, crop_y, crop_width, crop_height] = background_image.crop_rect;
+    let crop_x = (crop_x.max(0.) as u32).min(source_image.width());
+    let crop_y = (crop_y.max(0.) as u32).min(source_image.height());
+    let crop_width = (crop_width.max(0.) as u32).min(source_image.width() - crop_x);
+    let crop_height = (crop_height.max(0.) as u32).min(source_image.height() - crop_y);
+    if crop_width == 0 || crop_height == 0 {
+        return entities;
+    }
+    let cropped = source_image
+        .view(crop_x, crop_y, crop_width, crop_height)
+        .to_image();
+
+    // Scale the image
+    let scaled_width = (crop_width as f32 * background_image.scale.x).round() as u32;
+    let scaled_height = (crop_height as f32 * background_image.scale.y).round() as u32;
+    if scaled_width == 0 || scaled_height == 0 {
+        return entities;
+    }
+    let scaled = imageops::resize(
+        &cropped,
+        scaled_width,
+        scaled_height,
+        imageops::FilterType::Nearest,
+    );
+
+    entities.push(
+        commands
+            .spawn_bundle(SpriteBundle {
+                image: image_assets.add(Image::from(scaled)),
+                sprite: Sprite {
+                    centered: false,
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(
+                    level_position.x + background_image.top_left_px.x as f32,
+                    level_position.y + background_image.top_left_px.y as f32,
+                    -1.,
+                ),
+                ..Default::default()
+            })
+            .insert(marker)
+            .id(),
+    );
+
+    entities
+}
+
+/// This system re-draws the animated tiles of map layers whenever their animation frame changes
+///
+/// The animations follow the [`GameTime`], so they stop while the game is paused.
+fn animate_tiles(
+    time: Res<GameTime>,
+    mut layers: Query<(&mut LdtkTileAnimations, &Handle<Image>)>,
+    mut image_assets: ResMut<Assets<Image>>,
+) {
+    let seconds = time.seconds_since_startup();
+
+    for (mut animations, layer_image_handle) in layers.iter_mut() {
+        let LdtkTileAnimations {
+            tileset,
+            grid_size,
+            cells,
+        } = &mut *animations;
+        let grid_size = *grid_size;
+
+        // Find the cells that have changed frames since they were last drawn
+        let changed_cells = cells
+            .iter_mut()
+            .filter_map(|cell| {
+                let frames = cell
+                    .tiles
+                    .iter()
+                    .map(|tile| tile.frame_at(seconds))
+                    .collect::<Vec<_>>();
+
+                if frames != cell.current_frames {
+                    cell.current_frames = frames;
+                    Some(&*cell)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Skip the layer if nothing has changed so that we don't re-upload its image
+        if changed_cells.is_empty() {
+            continue;
+        }
+
+        // Copy the current frame of every tile in the changed cells out of the tileset so that we
+        // can borrow the layer image mutably
+        let cell_images = if let Some(tileset_image) = image_assets.get(&*tileset) {
+            changed_cells
+                .iter()
+                .map(|cell| {
+                    let tiles =
+                        cell.tiles
+                            .iter()
+                            .zip(&cell.current_frames)
+                            .map(|(tile, &frame)| LayerTile {
+                                src: tile.frames[frame],
+                                flip_x: tile.flip_x,
+                                flip_y: tile.flip_y,
+                            });
+                    (cell.px, copy_tiles(tileset_image, grid_size, tiles))
+                })
+                .collect::<Vec<_>>()
+        } else {
+            continue;
+        };
+        let layer_image = if let Some(image) = image_assets.get_mut(layer_image_handle) {
+            image
+        } else {
+            continue;
+        };
+
+        for (px, tile_images) in &cell_images {
+            draw_cell(layer_image, *px, grid_size, tile_images);
+        }
+    }
+}
+
+/// This system re-draws the cells of map layers that have been changed through [`LdtkLayerTiles`]
+fn redraw_changed_tiles(
+    mut layers: Query<
+        (
+            &mut LdtkLayerTiles,
+            &Handle<Image>,
+            Option<&mut LdtkTileAnimations>,
+        ),
+        Changed<LdtkLayerTiles>,
+    >,
+    mut image_assets: ResMut<Assets<Image>>,
+) {
+    for (mut layer_tiles, layer_image_handle, animations) in layers.iter_mut() {
+        if layer_tiles.changed_cells.is_empty() {
+            continue;
+        }
+
+        let grid_size = layer_tiles.grid_size;
+
+        // Copy the tiles of the changed cells out of the tileset so that we can borrow the layer
+        // image mutably
+        let cell_images = if let Some(tileset_image) = image_assets.get(&layer_tiles.tileset) {
+            layer_tiles
+                .changed_cells
+                .iter()
+                .map(|&(x, y)| {
+                    let tiles = layer_tiles.get(UVec2::new(x, y)).iter().copied();
+                    ((x, y), copy_tiles(tileset_image, grid_size, tiles))
+                })
+                .collect::<Vec<_>>()
+        } else {
+            continue;
+        };
+        let layer_image = if let Some(image) = image_assets.get_mut(layer_image_handle) {
+            image
+        } else {
+            continue;
+        };
+
+        let changed_cells = std::mem::take(&mut layer_tiles.changed_cells);
+
+        // Stop animating the changed cells so that the animation doesn't draw over the new tiles
+        if let Some(mut animations) = animations {
+            animations.cells.retain(|cell| {
+                !changed_cells.contains(&(cell.px.x / grid_size, cell.px.y / grid_size))
+            });
+        }
+
+        for ((x, y), tile_images) in &cell_images {
+            draw_cell(
+                layer_image,
+                UVec2::new(x * grid_size, y * grid_size),
+                grid_size,
+                tile_images,
+            );
+        }
+    }
+}
+
+/// Copy a stack of tiles out of the tileset, flipping them as needed and skipping tiles outside of
+/// the tileset
+fn copy_tiles(
+    tileset_image: &image::RgbaImage,
+    grid_size: u32,
+    tiles: impl Iterator<Item = LayerTile>,
+) -> Vec<image::RgbaImage> {
+    tiles
+        .filter(|tile| {
+            tile.src.x + grid_size <= tileset_image.width()
+                && tile.src.y + grid_size <= tileset_image.height()
+        })
+        .map(|tile| {
+            let mut tile_src = tileset_image
+                .view(tile.src.x, tile.src.y, grid_size, grid_size)
+                .to_image();
+
+            if tile.flip_x {
+                flip_horizontal_in_place(&mut tile_src);
+            }
+            if tile.flip_y {
+                flip_vertical_in_place(&mut tile_src);
+            }
+
+            tile_src
+        })
+        .collect()
+}
+
+/// Clear a cell of a layer image and draw a stack of tile images into it
+fn draw_cell(
+    layer_image: &mut image::RgbaImage,
+    px: UVec2,
+    grid_size: u32,
+    tile_images: &[image::RgbaImage],
+) {
+    // Clear the cell
+    let mut tile_target = layer_image.sub_image(px.x, px.y, grid_size, grid_size);
+    for x in 0..grid_size {
+        for y in 0..grid_size {
+            tile_target.put_pixel(x, y, image::Rgba([0; 4]));
+        }
+    }
+
+    // Draw every tile in the cell
+    for tile_src in tile_images {
+        imageops::overlay(&mut tile_target, tile_src, 0, 0);
+    }
+}
+
 type MapEvent = AssetEvent<LdtkMap>;
 
-/// This system watches for changes to map assets and makes sure that the map is reloaded upon
-/// changes.
+/// This system watches for changes to map assets and to the [`LevelSelection`] and makes sure that
+/// the maps are re-spawned upon changes.
 fn hot_reload_maps(
     mut commands: Commands,
     mut events: EventReader<MapEvent>,
     layers: Query<(Entity, &LdtkMapLayer, &Handle<Image>)>,
+    entities: Query<(Entity, &LdtkEntity)>,
+    nav_grids: Query<(Entity, &LdtkNavGrid)>,
+    backgrounds: Query<(Entity, &LdtkLevelBackground, &Handle<Image>)>,
     maps: Query<(Entity, &Handle<LdtkMap>)>,
     mut image_assets: ResMut<Assets<Image>>,
+    mut level_tracker: ResMut<LevelTracker>,
+    mut level_despawned_events: EventWriter<LevelDespawned>,
+    level_selection: Res<LevelSelection>,
 ) {
+    // Collect the maps that need to be re-spawned
+    let mut modified_maps: Vec<Handle<LdtkMap>> = Vec::new();
     for event in events.iter() {
         if let AssetEvent::Modified { handle } = event {
-            // Loop through all the layers in the world, find the ones that are for this map and remove them
-            for (layer_ent, LdtkMapLayer { map, .. }, image_handle) in layers.iter() {
-                if map == handle {
-                    // Despawn the layer
-                    commands.entity(layer_ent).despawn();
-                    // Remove the layer image
-                    image_assets.remove(image_handle);
-                }
+            if !modified_maps.contains(handle) {
+                modified_maps.push(handle.clone());
+            }
+        }
+    }
+
+    // Re-spawn every map if the level selection has changed
+    if level_selection.is_changed() {
+        for (_, handle) in maps.iter() {
+            if !modified_maps.contains(handle) {
+                modified_maps.push(handle.clone());
             }
+        }
+    }
+
+    for handle in &modified_maps {
+        // Loop through all the layers in the world, find the ones that are for this map and remove them
+        for (layer_ent, LdtkMapLayer { map, .. }, image_handle) in layers.iter() {
+            if map == handle {
+                // Despawn the layer
+                commands.entity(layer_ent).despawn();
+                // Remove the layer image
+                image_assets.remove(image_handle);
+            }
+        }
+
+        // Despawn the level backgrounds and remove their images
+        for (ent, LdtkLevelBackground { map, .. }, image_handle) in backgrounds.iter() {
+            if map == handle {
+                commands.entity(ent).despawn();
+                image_assets.remove(image_handle);
+            }
+        }
+
+        // Despawn the navigation grids of this map
+        for (ent, LdtkNavGrid { map, .. }) in nav_grids.iter() {
+            if map == handle {
+                commands.entity(ent).despawn();
+            }
+        }
+
+        // Despawn the entities spawned for this map
+        for (ent, LdtkEntity { map, .. }) in entities.iter() {
+            if map == handle {
+                commands.entity(ent).despawn_recursive();
+            }
+        }
+
+        // Then remove the `LdtkMapHasLoaded` component from the map so that it will be
+        // reloaded by the `process_ldtk_maps` system.
+        for (map_ent, map_handle) in maps.iter() {
+            if map_handle == handle {
+                commands.entity(map_ent).remove::<LdtkMapHasLoaded>();
 
-            // Then remove the `LdtkMapHasLoaded` component from the map so that it will be
-            // reloaded by the `process_ldtk_maps` system.
-            for (map_ent, map_handle) in maps.iter() {
-                if map_handle == handle {
-                    commands.entity(map_ent).remove::<LdtkMapHasLoaded>();
+                for event in level_tracker.despawn_all(map_ent) {
+                    level_despawned_events.send(event);
                 }
             }
         }
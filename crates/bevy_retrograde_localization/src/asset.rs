@@ -0,0 +1,89 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+
+/// The translations of the text of a game to one language, loaded from a `.lang` file
+///
+/// A `.lang` file has one translation on each line, written as the key, an `=`, and the
+/// translated text. Empty lines and lines starting with `#` are ignored, and `\n` in the
+/// translated text is a line break.
+///
+/// ```text
+/// # The main menu
+/// menu.start = Commencer
+/// menu.quit = Quitter
+///
+/// # Arguments are put where their name is in braces
+/// hud.coins = Pièces : {count}
+/// ```
+#[derive(TypeUuid, Debug, Clone, Default)]
+#[uuid = "6c1e7c35-43a8-4d8e-9c57-1b2e0b8f5d94"]
+pub struct Translations(pub HashMap<String, String>);
+bevy_retrograde_macros::impl_deref!(Translations, HashMap<String, String>);
+
+impl Translations {
+    /// Parse translations from the contents of a `.lang` file
+    pub fn parse(source: &str) -> Result<Self, TranslationsLoaderError> {
+        let mut translations = HashMap::default();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.find('=') {
+                Some(split) => (line[..split].trim(), line[split + 1..].trim()),
+                None => return Err(TranslationsLoaderError::InvalidLine(index + 1)),
+            };
+            if key.is_empty() {
+                return Err(TranslationsLoaderError::InvalidLine(index + 1));
+            }
+
+            translations.insert(key.to_string(), value.replace("\\n", "\n"));
+        }
+
+        Ok(Translations(translations))
+    }
+}
+
+/// Add asset types and asset loader to the app builder
+pub(crate) fn add_assets(app: &mut AppBuilder) {
+    app.add_asset::<Translations>()
+        .init_asset_loader::<TranslationsLoader>();
+}
+
+/// An error that occurs when loading translations
+#[derive(thiserror::Error, Debug)]
+pub enum TranslationsLoaderError {
+    #[error("Translations file is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    #[error("Line {0} of translations file is not a `key = translation` pair")]
+    InvalidLine(usize),
+}
+
+/// A translations asset loader
+#[derive(Default)]
+struct TranslationsLoader;
+
+impl AssetLoader for TranslationsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let translations = Translations::parse(std::str::from_utf8(bytes)?)?;
+            load_context.set_default_asset(LoadedAsset::new(translations));
+
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lang"]
+    }
+}
@@ -0,0 +1,245 @@
+//! Bevy Retrograde localization plugin
+//!
+//! The translations of a game's text are loaded from a `.lang` file for each language, which are
+//! added to the [`Locale`] resource along with the language that the game is played in. The
+//! translations of the active language are collected into the [`Localization`] resource, which
+//! translates keys and formats translations with arguments.
+//!
+//! With the `localization` feature of the text crate, text entities can be translated
+//! automatically with a `LocalizedText` component, and with the `localization` feature of the UI
+//! crate, the UI translations are kept up to date with the [`Localization`].
+
+use std::fmt::{self, Write};
+
+use bevy::{prelude::*, utils::HashMap};
+
+mod asset;
+pub use asset::*;
+
+/// Localization plugin for Bevy Retrograde
+pub struct RetroLocalizationPlugin;
+
+impl Plugin for RetroLocalizationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Locale>()
+            .init_resource::<Localization>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_localization.system());
+
+        asset::add_assets(app);
+    }
+}
+
+/// This resource selects the language that the game's text is translated to
+///
+/// The translations of each language are added to the locale, and changing the
+/// [`language`][Locale::language] switches the language of all of the translated text.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_localization::*;
+/// fn load_languages(mut locale: ResMut<Locale>, asset_server: Res<AssetServer>) {
+///     locale.add_language("en", asset_server.load("lang/en.lang"));
+///     locale.add_language("fr", asset_server.load("lang/fr.lang"));
+///     locale.fallback = Some("en".into());
+/// }
+///
+/// fn switch_language(keys: Res<Input<KeyCode>>, mut locale: ResMut<Locale>) {
+///     if keys.just_pressed(KeyCode::L) {
+///         locale.language = if locale.language == "en" { "fr" } else { "en" }.into();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Locale {
+    /// The language that text is translated to, such as `en` or `fr`
+    pub language: String,
+    /// The language used for keys that don't have a translation in the active language
+    pub fallback: Option<String>,
+    languages: HashMap<String, Handle<Translations>>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new("en")
+    }
+}
+
+impl Locale {
+    /// Create a locale without any languages that translates text to the given language
+    pub fn new<L: Into<String>>(language: L) -> Self {
+        Self {
+            language: language.into(),
+            fallback: None,
+            languages: Default::default(),
+        }
+    }
+
+    /// Add the translations of a language, replacing any that it already had
+    pub fn add_language<L: Into<String>>(
+        &mut self,
+        language: L,
+        translations: Handle<Translations>,
+    ) -> &mut Self {
+        self.languages.insert(language.into(), translations);
+        self
+    }
+
+    /// Get the translations of a language
+    pub fn translations(&self, language: &str) -> Option<&Handle<Translations>> {
+        self.languages.get(language)
+    }
+
+    /// Iterate over the languages that have been added
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages.keys().map(|x| x.as_str())
+    }
+}
+
+/// This resource has the translations of the active language of the [`Locale`]
+///
+/// It is updated when the locale changes or its translations are loaded, so systems can check
+/// whether it has changed to update their translated text.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_localization::*;
+/// struct Coins(u32);
+///
+/// fn coin_counter(coins: Res<Coins>, localization: Res<Localization>) {
+///     if coins.is_changed() || localization.is_changed() {
+///         let text = localization.format("hud.coins", &[("count", &coins.0)]);
+///         info!("{}", text);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Localization {
+    language: String,
+    translations: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Get the language that the translations are in
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Get the translation of a key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.translations.get(key).map(|x| x.as_str())
+    }
+
+    /// Translate a key, or get the key itself if it doesn't have a translation, so that missing
+    /// translations are easy to spot
+    pub fn translate<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get(key).unwrap_or(key)
+    }
+
+    /// Translate a key and put the arguments into the translation
+    ///
+    /// See [`format_translation`].
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        format_translation(self.translate(key), args)
+    }
+
+    /// Iterate over the keys and their translations
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.translations
+            .iter()
+            .map(|(key, translation)| (key.as_str(), translation.as_str()))
+    }
+}
+
+/// Put arguments into a translation, where their name is in braces
+///
+/// Arguments that aren't given are left as they are, and `{{` and `}}` are written as single
+/// braces.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_retrograde_localization::*;
+/// let text = format_translation("{name} found {count} coins", &[("name", &"Kim"), ("count", &3)]);
+///
+/// assert_eq!(text, "Kim found 3 coins");
+/// ```
+pub fn format_translation(translation: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut text = String::with_capacity(translation.len());
+
+    let mut rest = translation;
+    while let Some(start) = rest.find(|c: char| c == '{' || c == '}') {
+        text.push_str(&rest[..start]);
+        let brace = &rest[start..start + 1];
+        rest = &rest[start + 1..];
+
+        // Handle escaped braces
+        if let Some(after) = rest.strip_prefix(brace) {
+            text.push_str(brace);
+            rest = after;
+            continue;
+        }
+
+        let arg = if brace == "{" {
+            rest.find('}').and_then(|end| {
+                let name = &rest[..end];
+                args.iter()
+                    .find(|(x, _)| *x == name)
+                    .map(|(_, value)| (end, value))
+            })
+        } else {
+            None
+        };
+        match arg {
+            Some((end, value)) => {
+                write!(text, "{}", value).ok();
+                rest = &rest[end + 1..];
+            }
+            None => text.push_str(brace),
+        }
+    }
+    text.push_str(rest);
+
+    text
+}
+
+/// Collect the translations of the active language when the locale or its translations change
+fn update_localization(
+    locale: Res<Locale>,
+    translations_assets: Res<Assets<Translations>>,
+    mut asset_events: EventReader<AssetEvent<Translations>>,
+    mut localization: ResMut<Localization>,
+) {
+    let languages = std::iter::once(&locale.language)
+        .chain(locale.fallback.iter())
+        .filter_map(|language| locale.translations(language))
+        .collect::<Vec<_>>();
+
+    // Read all of the events, even after one of them changes the translations
+    let translations_changed = asset_events
+        .iter()
+        .fold(false, |changed, event| match event {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => changed || languages.contains(&handle),
+        });
+    if !locale.is_changed() && !translations_changed {
+        return;
+    }
+
+    // Add the fallback translations first so that the active language replaces them
+    let mut new_translations = HashMap::default();
+    for handle in languages.into_iter().rev() {
+        if let Some(translations) = translations_assets.get(handle) {
+            new_translations.extend(translations.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    *localization = Localization {
+        language: locale.language.clone(),
+        translations: new_translations,
+    };
+}
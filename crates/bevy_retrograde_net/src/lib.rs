@@ -0,0 +1,358 @@
+//! Bevy Retrograde networking plugin
+//!
+//! A small networking layer for multiplayer-lite features, such as online leaderboards or simple
+//! co-op, that works the same on desktop and web. Connections are opened and closed with the
+//! [`Network`] resource, messages are sent with [`SendMessage`] events, and everything that
+//! happens to the connections is reported with [`NetEvent`]s.
+//!
+//! WebSockets are supported on every platform, and UDP sockets are supported on desktop. On
+//! desktop, secure `wss://` WebSockets need the `tls` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_net::*;
+//! struct Leaderboard(ConnectionId);
+//!
+//! fn connect(mut commands: Commands, mut network: ResMut<Network>) {
+//!     let connection = network.connect(NetAddress::WebSocket(
+//!         "wss://example.com/leaderboard".into(),
+//!     ));
+//!     commands.insert_resource(Leaderboard(connection));
+//! }
+//!
+//! fn submit_score(leaderboard: Res<Leaderboard>, mut messages: EventWriter<SendMessage>) {
+//!     // Messages sent before the connection is open are sent once it is
+//!     messages.send(SendMessage {
+//!         connection: leaderboard.0,
+//!         message: r#"{"name":"Radish","score":1200}"#.into(),
+//!     });
+//! }
+//!
+//! fn handle_network_events(mut events: EventReader<NetEvent>) {
+//!     for event in events.iter() {
+//!         match event {
+//!             NetEvent::Message { message, .. } => info!("Scores: {:?}", message.as_text()),
+//!             NetEvent::Disconnected {
+//!                 error: Some(error), ..
+//!             } => error!("Lost the leaderboard connection: {}", error),
+//!             _ => (),
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::net::SocketAddr;
+
+use bevy::{prelude::*, utils::HashMap};
+
+mod socket;
+use socket::{Socket, SocketUpdate};
+
+/// Networking plugin for Bevy Retrograde
+pub struct RetroNetPlugin;
+
+impl Plugin for RetroNetPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Network>()
+            .insert_non_send_resource(Sockets::default())
+            .add_event::<SendMessage>()
+            .add_event::<NetEvent>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_network.system());
+    }
+}
+
+/// Identifies a connection opened with [`Network::connect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(u32);
+
+/// The address of a server to connect to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetAddress {
+    /// A WebSocket URL, starting with `ws://` or `wss://`
+    WebSocket(String),
+    /// A UDP socket address
+    ///
+    /// Browsers can't use UDP, so connections to UDP addresses are closed with an error on web.
+    Udp(SocketAddr),
+}
+
+/// A message sent or received over a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl NetMessage {
+    /// Get the bytes of the message, which are UTF-8 for text messages
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            NetMessage::Text(text) => text.as_bytes(),
+            NetMessage::Binary(data) => data,
+        }
+    }
+
+    /// Get the text of the message, or [`None`] if it isn't a text message
+    ///
+    /// UDP doesn't have text messages, so messages received over UDP are always binary.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            NetMessage::Text(text) => Some(text),
+            NetMessage::Binary(_) => None,
+        }
+    }
+}
+
+impl From<String> for NetMessage {
+    fn from(text: String) -> Self {
+        NetMessage::Text(text)
+    }
+}
+
+impl From<&str> for NetMessage {
+    fn from(text: &str) -> Self {
+        NetMessage::Text(text.to_string())
+    }
+}
+
+impl From<Vec<u8>> for NetMessage {
+    fn from(data: Vec<u8>) -> Self {
+        NetMessage::Binary(data)
+    }
+}
+
+/// The state of an open connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The connection is being opened, and messages sent to it are waiting to be sent
+    Connecting,
+    /// The connection is open and messages are being sent and received
+    Connected,
+}
+
+/// Bevy event that sends a message over a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendMessage {
+    pub connection: ConnectionId,
+    pub message: NetMessage,
+}
+
+/// Bevy event sent when something happens to a connection
+#[derive(Debug)]
+pub enum NetEvent {
+    /// The connection was opened
+    Connected(ConnectionId),
+    /// The connection was closed, with the error that closed it if it didn't close normally
+    ///
+    /// This is sent when a connection couldn't be opened too.
+    Disconnected {
+        connection: ConnectionId,
+        error: Option<NetError>,
+    },
+    /// A message was received over the connection
+    Message {
+        connection: ConnectionId,
+        message: NetMessage,
+    },
+}
+
+/// A connection that has been opened with the [`Network`]
+struct Connection {
+    address: NetAddress,
+    state: ConnectionState,
+}
+
+/// A change to the connections that is applied by the network system
+enum ConnectionRequest {
+    Connect(ConnectionId, NetAddress),
+    Disconnect(ConnectionId),
+}
+
+/// This resource opens and closes network connections
+///
+/// Connections are opened and closed in the background, which is reported with [`NetEvent`]s.
+/// Closed connections are forgotten, so the [`state`][Network::state] of a connection is [`None`]
+/// once it has been closed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_net::*;
+/// struct CoopConnection(ConnectionId);
+///
+/// fn reconnect(
+///     mut network: ResMut<Network>,
+///     mut connection: ResMut<CoopConnection>,
+///     mut events: EventReader<NetEvent>,
+/// ) {
+///     for event in events.iter() {
+///         if let NetEvent::Disconnected { connection: id, .. } = event {
+///             if *id == connection.0 {
+///                 connection.0 = network.connect(NetAddress::Udp(([10, 0, 0, 2], 4000).into()));
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Network {
+    connections: HashMap<ConnectionId, Connection>,
+    requests: Vec<ConnectionRequest>,
+    next_id: u32,
+}
+
+impl Network {
+    /// Start opening a connection to an address
+    ///
+    /// A [`NetEvent::Connected`] event is sent once it is open, or a [`NetEvent::Disconnected`]
+    /// event if it can't be opened.
+    pub fn connect(&mut self, address: NetAddress) -> ConnectionId {
+        let id = ConnectionId(self.next_id);
+        self.next_id += 1;
+
+        self.connections.insert(
+            id,
+            Connection {
+                address: address.clone(),
+                state: ConnectionState::Connecting,
+            },
+        );
+        self.requests.push(ConnectionRequest::Connect(id, address));
+
+        id
+    }
+
+    /// Close a connection, after sending the messages that have already been sent to it
+    ///
+    /// A [`NetEvent::Disconnected`] event without an error is sent for it.
+    pub fn disconnect(&mut self, connection: ConnectionId) {
+        if self.connections.remove(&connection).is_some() {
+            self.requests
+                .push(ConnectionRequest::Disconnect(connection));
+        }
+    }
+
+    /// Get the state of a connection, or [`None`] if it is closed
+    pub fn state(&self, connection: ConnectionId) -> Option<ConnectionState> {
+        self.connections
+            .get(&connection)
+            .map(|connection| connection.state)
+    }
+
+    /// Get whether or not a connection is open
+    pub fn is_connected(&self, connection: ConnectionId) -> bool {
+        self.state(connection) == Some(ConnectionState::Connected)
+    }
+
+    /// Get the address of a connection that hasn't been closed
+    pub fn address(&self, connection: ConnectionId) -> Option<&NetAddress> {
+        self.connections
+            .get(&connection)
+            .map(|connection| &connection.address)
+    }
+
+    /// Iterate over the connections that haven't been closed
+    pub fn connections(&self) -> impl Iterator<Item = ConnectionId> + '_ {
+        self.connections.keys().copied()
+    }
+}
+
+/// The sockets of the open connections, which can't be sent between threads on web
+#[derive(Default)]
+struct Sockets(HashMap<ConnectionId, Socket>);
+
+/// Open and close the requested connections, send the messages, and report the updates from the
+/// sockets
+fn update_network(
+    mut network: ResMut<Network>,
+    mut sockets: NonSendMut<Sockets>,
+    mut messages: EventReader<SendMessage>,
+    mut events: EventWriter<NetEvent>,
+) {
+    // Only get the network mutably if it has requests to avoid triggering change detection
+    let requests = if network.requests.is_empty() {
+        Vec::new()
+    } else {
+        std::mem::take(&mut network.requests)
+    };
+
+    // Open connections before sending messages so that messages can be sent to connections opened
+    // in the same frame, and close them after so that the last messages are sent
+    for request in &requests {
+        if let ConnectionRequest::Connect(id, address) = request {
+            sockets.0.insert(*id, Socket::open(address));
+        }
+    }
+    for SendMessage {
+        connection,
+        message,
+    } in messages.iter()
+    {
+        match sockets.0.get_mut(connection) {
+            Some(socket) => socket.send(message.clone()),
+            None => warn!(
+                "Couldn't send message to closed connection {:?}",
+                connection
+            ),
+        }
+    }
+    for request in requests {
+        if let ConnectionRequest::Disconnect(id) = request {
+            if let Some(mut socket) = sockets.0.remove(&id) {
+                socket.close();
+                events.send(NetEvent::Disconnected {
+                    connection: id,
+                    error: None,
+                });
+            }
+        }
+    }
+
+    let mut closed = Vec::new();
+    for (id, socket) in sockets.0.iter_mut() {
+        for update in socket.poll() {
+            match update {
+                SocketUpdate::Connected => {
+                    if let Some(connection) = network.connections.get_mut(id) {
+                        connection.state = ConnectionState::Connected;
+                    }
+                    events.send(NetEvent::Connected(*id));
+                }
+                SocketUpdate::Message(message) => events.send(NetEvent::Message {
+                    connection: *id,
+                    message,
+                }),
+                SocketUpdate::Closed(error) => {
+                    closed.push(*id);
+                    events.send(NetEvent::Disconnected {
+                        connection: *id,
+                        error,
+                    });
+                    break;
+                }
+            }
+        }
+    }
+    for id in closed {
+        sockets.0.remove(&id);
+        network.connections.remove(&id);
+    }
+}
+
+/// An error that closes a connection
+#[derive(thiserror::Error, Debug)]
+pub enum NetError {
+    #[error("Invalid network address: {0}")]
+    InvalidAddress(String),
+    #[error("Network IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("WebSocket error: {0}")]
+    WebSocket(String),
+    #[error("TLS error: {0}")]
+    Tls(String),
+    #[error("{0} isn't supported on this platform")]
+    Unsupported(&'static str),
+}
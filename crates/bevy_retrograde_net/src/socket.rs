@@ -0,0 +1,397 @@
+//! The platform sockets that connections are made with
+
+use crate::{NetAddress, NetError, NetMessage};
+
+/// Something that happened to a socket since it was last polled
+pub(crate) enum SocketUpdate {
+    Connected,
+    Message(NetMessage),
+    /// The socket was closed, with the error that closed it if it didn't close normally
+    Closed(Option<NetError>),
+}
+
+#[cfg(not(wasm))]
+pub(crate) use native::Socket;
+
+#[cfg(wasm)]
+pub(crate) use web::Socket;
+
+#[cfg(not(wasm))]
+mod native {
+    use std::{
+        io::{self, Read, Write},
+        net::{SocketAddr, TcpStream, UdpSocket},
+        thread,
+        time::Duration,
+    };
+
+    use crossbeam_channel::{Receiver, Sender, TryRecvError};
+    use tungstenite::{http::Uri, HandshakeError, Message, WebSocket};
+
+    use super::*;
+
+    /// How long the socket threads wait for incoming messages before checking for messages to send
+    const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+    /// A command sent to a socket thread
+    enum Command {
+        Send(NetMessage),
+        Close,
+    }
+
+    /// A socket that runs on its own thread
+    pub(crate) struct Socket {
+        commands: Sender<Command>,
+        updates: Receiver<SocketUpdate>,
+    }
+
+    impl Socket {
+        pub fn open(address: &NetAddress) -> Self {
+            let (commands, command_receiver) = crossbeam_channel::unbounded();
+            let (update_sender, updates) = crossbeam_channel::unbounded();
+
+            let address = address.clone();
+            thread::spawn(move || {
+                let result = match &address {
+                    NetAddress::WebSocket(url) => {
+                        run_websocket(url, &command_receiver, &update_sender)
+                    }
+                    NetAddress::Udp(address) => {
+                        run_udp(*address, &command_receiver, &update_sender)
+                    }
+                };
+                update_sender.send(SocketUpdate::Closed(result.err())).ok();
+            });
+
+            Self { commands, updates }
+        }
+
+        pub fn send(&mut self, message: NetMessage) {
+            self.commands.send(Command::Send(message)).ok();
+        }
+
+        pub fn close(&mut self) {
+            self.commands.send(Command::Close).ok();
+        }
+
+        pub fn poll(&mut self) -> Vec<SocketUpdate> {
+            self.updates.try_iter().collect()
+        }
+    }
+
+    /// Take the messages waiting to be sent, and whether or not the socket should be closed after
+    /// sending them
+    fn take_commands(commands: &Receiver<Command>) -> (Vec<NetMessage>, bool) {
+        let mut messages = Vec::new();
+        loop {
+            match commands.try_recv() {
+                Ok(Command::Send(message)) => messages.push(message),
+                // The socket is closed when it is dropped too
+                Ok(Command::Close) | Err(TryRecvError::Disconnected) => return (messages, true),
+                Err(TryRecvError::Empty) => return (messages, false),
+            }
+        }
+    }
+
+    /// Get whether or not an error is from a read that timed out without receiving anything
+    fn is_timeout(error: &io::Error) -> bool {
+        matches!(
+            error.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        )
+    }
+
+    /// A stream that a WebSocket can be run over, with or without TLS
+    trait Stream: Read + Write + Send {}
+    impl<T: Read + Write + Send> Stream for T {}
+
+    fn websocket_error(error: tungstenite::Error) -> NetError {
+        match error {
+            tungstenite::Error::Io(error) => NetError::Io(error),
+            error => NetError::WebSocket(error.to_string()),
+        }
+    }
+
+    /// Open a WebSocket, with reads that time out so that messages can be sent while waiting for
+    /// incoming ones
+    fn connect_websocket(url: &str) -> Result<WebSocket<Box<dyn Stream>>, NetError> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|_| NetError::InvalidAddress(url.to_string()))?;
+        let secure = match uri.scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            _ => return Err(NetError::InvalidAddress(url.to_string())),
+        };
+        let host = uri
+            .host()
+            .ok_or_else(|| NetError::InvalidAddress(url.to_string()))?;
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+        let tcp = TcpStream::connect((host, port))?;
+        // Keep a handle to the TCP stream to set the timeout once the handshake is done
+        let tcp_handle = tcp.try_clone()?;
+        let stream: Box<dyn Stream> = if secure {
+            connect_tls(host, tcp)?
+        } else {
+            Box::new(tcp)
+        };
+
+        let (socket, _response) = tungstenite::client(url, stream).map_err(|e| match e {
+            HandshakeError::Failure(error) => websocket_error(error),
+            HandshakeError::Interrupted(_) => {
+                NetError::WebSocket("The WebSocket handshake was interrupted".into())
+            }
+        })?;
+        tcp_handle.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        Ok(socket)
+    }
+
+    #[cfg(feature = "tls")]
+    fn connect_tls(host: &str, tcp: TcpStream) -> Result<Box<dyn Stream>, NetError> {
+        let connector =
+            native_tls::TlsConnector::new().map_err(|e| NetError::Tls(e.to_string()))?;
+        let stream = connector
+            .connect(host, tcp)
+            .map_err(|e| NetError::Tls(e.to_string()))?;
+
+        Ok(Box::new(stream))
+    }
+
+    #[cfg(not(feature = "tls"))]
+    fn connect_tls(_host: &str, _tcp: TcpStream) -> Result<Box<dyn Stream>, NetError> {
+        Err(NetError::Tls(
+            "Secure WebSockets need the `tls` feature on desktop".into(),
+        ))
+    }
+
+    fn run_websocket(
+        url: &str,
+        commands: &Receiver<Command>,
+        updates: &Sender<SocketUpdate>,
+    ) -> Result<(), NetError> {
+        let mut socket = connect_websocket(url)?;
+        updates.send(SocketUpdate::Connected).ok();
+
+        loop {
+            let (messages, closing) = take_commands(commands);
+            for message in messages {
+                let message = match message {
+                    NetMessage::Text(text) => Message::Text(text),
+                    NetMessage::Binary(data) => Message::Binary(data),
+                };
+                socket.write_message(message).map_err(websocket_error)?;
+            }
+            if closing {
+                // Send the close frame, but don't wait for the server to answer it
+                socket.close(None).ok();
+                socket.write_pending().ok();
+                return Ok(());
+            }
+
+            match socket.read_message() {
+                Ok(Message::Text(text)) => {
+                    updates
+                        .send(SocketUpdate::Message(NetMessage::Text(text)))
+                        .ok();
+                }
+                Ok(Message::Binary(data)) => {
+                    updates
+                        .send(SocketUpdate::Message(NetMessage::Binary(data)))
+                        .ok();
+                }
+                // Pings and close frames are answered by the socket
+                Ok(_) => (),
+                Err(tungstenite::Error::Io(error)) if is_timeout(&error) => (),
+                Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+                Err(error) => return Err(websocket_error(error)),
+            }
+        }
+    }
+
+    fn run_udp(
+        address: SocketAddr,
+        commands: &Receiver<Command>,
+        updates: &Sender<SocketUpdate>,
+    ) -> Result<(), NetError> {
+        let local_address: SocketAddr = if address.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local_address)?;
+        socket.connect(address)?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        updates.send(SocketUpdate::Connected).ok();
+
+        // Big enough for the largest UDP datagram
+        let mut buffer = vec![0; 65536];
+        loop {
+            let (messages, closing) = take_commands(commands);
+            for message in messages {
+                socket.send(message.as_bytes())?;
+            }
+            if closing {
+                return Ok(());
+            }
+
+            match socket.recv(&mut buffer) {
+                Ok(length) => {
+                    updates
+                        .send(SocketUpdate::Message(NetMessage::Binary(
+                            buffer[..length].to_vec(),
+                        )))
+                        .ok();
+                }
+                Err(error) if is_timeout(&error) => (),
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+}
+
+#[cfg(wasm)]
+mod web {
+    use bevy::log::warn;
+    use crossbeam_channel::{Receiver, Sender};
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    use web_sys::{BinaryType, CloseEvent, MessageEvent, WebSocket};
+
+    use super::*;
+
+    /// A browser WebSocket
+    pub(crate) struct Socket {
+        socket: Option<WebSocket>,
+        updates: Receiver<SocketUpdate>,
+        /// Messages sent before the socket was open, which the browser doesn't allow
+        pending: Vec<NetMessage>,
+        /// The event handlers of the socket, which must be kept until they are removed from it
+        _callbacks: Vec<Closure<dyn FnMut(JsValue)>>,
+    }
+
+    impl Socket {
+        pub fn open(address: &NetAddress) -> Self {
+            let (sender, updates) = crossbeam_channel::unbounded();
+
+            let socket = match address {
+                NetAddress::WebSocket(url) => {
+                    WebSocket::new(url).map_err(|e| NetError::WebSocket(format!("{:?}", e)))
+                }
+                NetAddress::Udp(_) => Err(NetError::Unsupported("UDP")),
+            };
+            let socket = match socket {
+                Ok(socket) => socket,
+                Err(error) => {
+                    sender.send(SocketUpdate::Closed(Some(error))).ok();
+                    return Self {
+                        socket: None,
+                        updates,
+                        pending: Vec::new(),
+                        _callbacks: Vec::new(),
+                    };
+                }
+            };
+
+            socket.set_binary_type(BinaryType::Arraybuffer);
+            let callbacks = vec![
+                Self::callback(&sender, |sender, _| {
+                    sender.send(SocketUpdate::Connected).ok();
+                }),
+                Self::callback(&sender, |sender, event| {
+                    let data = event.unchecked_into::<MessageEvent>().data();
+                    let message = match data.as_string() {
+                        Some(text) => NetMessage::Text(text),
+                        None => NetMessage::Binary(js_sys::Uint8Array::new(&data).to_vec()),
+                    };
+                    sender.send(SocketUpdate::Message(message)).ok();
+                }),
+                // Errors are always followed by a close event, so they are reported from there
+                Self::callback(&sender, |sender, event| {
+                    let event = event.unchecked_into::<CloseEvent>();
+                    let error = if event.was_clean() {
+                        None
+                    } else {
+                        Some(NetError::WebSocket(format!(
+                            "The connection closed with code {}",
+                            event.code()
+                        )))
+                    };
+                    sender.send(SocketUpdate::Closed(error)).ok();
+                }),
+            ];
+            socket.set_onopen(Some(callbacks[0].as_ref().unchecked_ref()));
+            socket.set_onmessage(Some(callbacks[1].as_ref().unchecked_ref()));
+            socket.set_onclose(Some(callbacks[2].as_ref().unchecked_ref()));
+
+            Self {
+                socket: Some(socket),
+                updates,
+                pending: Vec::new(),
+                _callbacks: callbacks,
+            }
+        }
+
+        /// Create an event handler that reports to the update channel
+        fn callback<F: Fn(&Sender<SocketUpdate>, JsValue) + 'static>(
+            sender: &Sender<SocketUpdate>,
+            f: F,
+        ) -> Closure<dyn FnMut(JsValue)> {
+            let sender = sender.clone();
+            Closure::wrap(Box::new(move |event| f(&sender, event)) as Box<dyn FnMut(JsValue)>)
+        }
+
+        pub fn send(&mut self, message: NetMessage) {
+            let socket = match &self.socket {
+                Some(socket) => socket,
+                None => return,
+            };
+            if socket.ready_state() == WebSocket::CONNECTING {
+                self.pending.push(message);
+                return;
+            }
+
+            let result = match &message {
+                NetMessage::Text(text) => socket.send_with_str(text),
+                NetMessage::Binary(data) => {
+                    socket.send_with_array_buffer_view(&js_sys::Uint8Array::from(data.as_slice()))
+                }
+            };
+            if let Err(e) = result {
+                warn!("Couldn't send network message: {:?}", e);
+            }
+        }
+
+        pub fn close(&mut self) {
+            if let Some(socket) = self.socket.take() {
+                socket.set_onopen(None);
+                socket.set_onmessage(None);
+                socket.set_onclose(None);
+                socket.close().ok();
+            }
+        }
+
+        pub fn poll(&mut self) -> Vec<SocketUpdate> {
+            let updates = self.updates.try_iter().collect::<Vec<_>>();
+
+            // Send the messages that were waiting for the socket to open
+            if updates
+                .iter()
+                .any(|update| matches!(update, SocketUpdate::Connected))
+            {
+                for message in std::mem::take(&mut self.pending) {
+                    self.send(message);
+                }
+            }
+
+            updates
+        }
+    }
+
+    impl Drop for Socket {
+        fn drop(&mut self) {
+            // Remove the event handlers before they are dropped
+            self.close();
+        }
+    }
+}
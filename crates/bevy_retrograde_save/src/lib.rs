@@ -0,0 +1,289 @@
+//! Bevy Retrograde save data plugin
+//!
+//! Games save and load their data, such as progress and high scores, in named [`SaveSlot`]s with
+//! the [`SaveData`] resource. Values are serialized with serde as JSON, and stored in the user's
+//! data directory on desktop, or in the browser's local storage on web.
+//!
+//! On desktop, saves are written in the background to a temporary file that replaces the old save
+//! once it is complete, so that a crash in the middle of saving doesn't lose the old save. The
+//! previous save is always kept as a backup, which is loaded instead if a save can't be read.
+//!
+//! Settings, such as the volume or key bindings, can be added as a [`Settings`] resource, which is
+//! loaded when the game starts and saved whenever it changes.
+//!
+//! The directory or storage key that saves are kept under is named by the [`SaveDataSettings`]
+//! resource.
+
+use std::{borrow::Cow, fmt, marker::PhantomData, sync::Arc};
+
+use bevy::{prelude::*, tasks::IoTaskPool};
+use serde::{de::DeserializeOwned, Serialize};
+
+mod settings;
+pub use settings::*;
+
+mod storage;
+use storage::Storage;
+
+/// Save data plugin for Bevy Retrograde
+pub struct RetroSavePlugin;
+
+impl Plugin for RetroSavePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let settings = app
+            .world_mut()
+            .get_resource_or_insert_with(SaveDataSettings::default)
+            .clone();
+        let task_pool = app.world().get_resource::<IoTaskPool>().cloned();
+
+        app.insert_resource(SaveData {
+            storage: Arc::new(Storage::new(&settings.app_name, task_pool)),
+        });
+    }
+}
+
+/// This resource names the directory or storage key that saves are kept under
+///
+/// It must be inserted before the plugins are added, because the [`SaveData`] is created when they
+/// are.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_save::*;
+/// App::build().insert_resource(SaveDataSettings {
+///     app_name: "super_radish_bros".into(),
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveDataSettings {
+    /// The name of the game, which should be unique to it so that its saves don't mix with other
+    /// games' saves
+    ///
+    /// Defaults to the name of the executable.
+    pub app_name: String,
+}
+
+impl Default for SaveDataSettings {
+    fn default() -> Self {
+        Self {
+            app_name: std::env::current_exe()
+                .ok()
+                .and_then(|path| {
+                    path.file_stem()
+                        .map(|name| name.to_string_lossy().into_owned())
+                })
+                .unwrap_or_else(|| "bevy_retrograde".into()),
+        }
+    }
+}
+
+/// A named slot that a value of type `T` is saved in
+///
+/// # Example
+///
+/// ```
+/// # use bevy_retrograde_save::*;
+/// # #[derive(serde::Serialize, serde::Deserialize)]
+/// # struct Progress;
+/// const PROGRESS: SaveSlot<Progress> = SaveSlot::new("progress");
+///
+/// fn level_slot(level: u32) -> SaveSlot<Progress> {
+///     SaveSlot::owned(format!("level-{}", level))
+/// }
+/// ```
+pub struct SaveSlot<T> {
+    name: Cow<'static, str>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> SaveSlot<T> {
+    /// Create a slot with a fixed name
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a slot with a name built at runtime, such as a numbered save file
+    pub fn owned(name: String) -> Self {
+        Self {
+            name: Cow::Owned(name),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the name of the slot
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> Clone for SaveSlot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SaveSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SaveSlot").field(&self.name).finish()
+    }
+}
+
+/// This resource saves and loads values in [`SaveSlot`]s
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_save::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Default)]
+/// struct Progress {
+///     level: u32,
+///     coins: u32,
+/// }
+///
+/// const PROGRESS: SaveSlot<Progress> = SaveSlot::new("progress");
+///
+/// fn load_progress(mut commands: Commands, save_data: Res<SaveData>) {
+///     commands.insert_resource(save_data.load_or_default(&PROGRESS));
+/// }
+///
+/// fn save_progress(progress: Res<Progress>, save_data: Res<SaveData>) {
+///     if progress.is_changed() {
+///         if let Err(e) = save_data.save(&PROGRESS, &*progress) {
+///             error!("Couldn't save progress: {}", e);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SaveData {
+    storage: Arc<Storage>,
+}
+
+impl SaveData {
+    /// Save a value in a slot
+    ///
+    /// The value is serialized right away, but on desktop it is written in the background, so
+    /// errors writing it are logged instead of returned.
+    pub fn save<T: Serialize>(&self, slot: &SaveSlot<T>, value: &T) -> Result<(), SaveDataError> {
+        let data = serde_json::to_vec_pretty(value).map_err(SaveDataError::Serialize)?;
+        self.storage.write(slot.name(), data, false)
+    }
+
+    /// Save a value in a slot, waiting until it has been written
+    ///
+    /// This is useful for saving right before the game exits, when a save written in the
+    /// background might not finish.
+    pub fn save_now<T: Serialize>(
+        &self,
+        slot: &SaveSlot<T>,
+        value: &T,
+    ) -> Result<(), SaveDataError> {
+        let data = serde_json::to_vec_pretty(value).map_err(SaveDataError::Serialize)?;
+        self.storage.write(slot.name(), data, true)
+    }
+
+    /// Load the value saved in a slot, or [`None`] if nothing has been saved in it
+    ///
+    /// If the save can't be read, the backup of the previous save is loaded instead, and an error
+    /// is only returned if neither of them can be read.
+    pub fn load<T: DeserializeOwned>(
+        &self,
+        slot: &SaveSlot<T>,
+    ) -> Result<Option<T>, SaveDataError> {
+        let data = match self.storage.read(slot.name(), false) {
+            Ok(Some(data)) => data,
+            Ok(None) => return self.load_backup(slot, None),
+            Err(e) => return self.load_backup(slot, Some(e)),
+        };
+
+        match serde_json::from_slice(&data) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => self.load_backup(
+                slot,
+                Some(SaveDataError::Corrupted {
+                    slot: slot.name().to_string(),
+                    error,
+                }),
+            ),
+        }
+    }
+
+    /// Load the value saved in a slot, or the default value if nothing has been saved in it or it
+    /// can't be read
+    pub fn load_or_default<T: DeserializeOwned + Default>(&self, slot: &SaveSlot<T>) -> T {
+        match self.load(slot) {
+            Ok(value) => value.unwrap_or_default(),
+            Err(e) => {
+                error!("Couldn't load save data, using the default value: {}", e);
+                T::default()
+            }
+        }
+    }
+
+    /// Get whether or not anything has been saved in a slot
+    pub fn exists<T>(&self, slot: &SaveSlot<T>) -> bool {
+        matches!(self.storage.read(slot.name(), false), Ok(Some(_)))
+            || matches!(self.storage.read(slot.name(), true), Ok(Some(_)))
+    }
+
+    /// Delete the value saved in a slot, along with its backup
+    pub fn delete<T>(&self, slot: &SaveSlot<T>) -> Result<(), SaveDataError> {
+        self.storage.delete(slot.name())
+    }
+
+    /// Load the backup of a slot after the save couldn't be loaded with the given error
+    fn load_backup<T: DeserializeOwned>(
+        &self,
+        slot: &SaveSlot<T>,
+        save_error: Option<SaveDataError>,
+    ) -> Result<Option<T>, SaveDataError> {
+        if let Some(e) = &save_error {
+            warn!("{}, loading the backup instead", e);
+        }
+
+        let data = match self.storage.read(slot.name(), true)? {
+            Some(data) => data,
+            None => {
+                return match save_error {
+                    Some(e) => Err(e),
+                    None => Ok(None),
+                }
+            }
+        };
+
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|error| SaveDataError::Corrupted {
+                slot: slot.name().to_string(),
+                error,
+            })
+    }
+}
+
+/// An error that occurs when saving or loading data
+#[derive(thiserror::Error, Debug)]
+pub enum SaveDataError {
+    #[error("There is no place to store save data on this platform")]
+    NoStorage,
+    #[error("Error serializing save data: {0}")]
+    Serialize(serde_json::Error),
+    #[error("Save data in slot `{slot}` is corrupted: {error}")]
+    Corrupted {
+        slot: String,
+        error: serde_json::Error,
+    },
+    #[error("Error accessing save data: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Error accessing browser storage: {0}")]
+    Browser(String),
+}
@@ -0,0 +1,175 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::{app::AppExit, prelude::*};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{SaveData, SaveSlot};
+
+/// The time in seconds that settings are saved after they were last changed, so that settings
+/// changed every frame, such as by dragging a volume slider, are only saved once
+const SETTINGS_SAVE_DELAY: f64 = 1.0;
+
+/// Bevy [`AppBuilder`] extension for adding [`Settings`] that are saved automatically
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_save::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Default)]
+/// struct GameSettings {
+///     fullscreen: bool,
+///     screen_shake: bool,
+/// }
+///
+/// fn toggle_fullscreen(keys: Res<Input<KeyCode>>, mut settings: ResMut<Settings<GameSettings>>) {
+///     if keys.just_pressed(KeyCode::F11) {
+///         settings.fullscreen = !settings.fullscreen;
+///     }
+/// }
+///
+/// fn main() {
+///     App::build()
+///         .add_plugin(RetroSavePlugin)
+///         .add_settings::<GameSettings>("settings")
+///         .add_system(toggle_fullscreen.system());
+/// }
+/// ```
+pub trait AppBuilderSettingsExt {
+    /// Add a [`Settings<T>`] resource that is loaded from the save slot with the given name, or
+    /// starts out with the default value if nothing has been saved yet
+    fn add_settings<T: SettingsData + Default>(self, slot: &'static str) -> Self;
+
+    /// Add a [`Settings<T>`] resource that is loaded from the save slot with the given name, or
+    /// starts out with the given value if nothing has been saved yet
+    fn add_settings_with_default<T: SettingsData>(self, slot: &'static str, default: T) -> Self;
+
+    /// Add a [`Settings<T>`] resource like
+    /// [`add_settings_with_default`][Self::add_settings_with_default], along with a plain `T`
+    /// resource that is kept the same as the settings
+    ///
+    /// This is used to save resources that plugins read directly, such as the audio settings or
+    /// the input bindings, so that either resource can be changed to change the settings.
+    fn add_synced_settings<T: SettingsData + Clone + PartialEq>(
+        self,
+        slot: &'static str,
+        default: T,
+    ) -> Self;
+}
+
+impl AppBuilderSettingsExt for &mut AppBuilder {
+    fn add_settings<T: SettingsData + Default>(self, slot: &'static str) -> Self {
+        self.add_settings_with_default(slot, T::default())
+    }
+
+    fn add_settings_with_default<T: SettingsData>(self, slot: &'static str, default: T) -> Self {
+        let slot = SaveSlot::new(slot);
+        let save_data = self
+            .world()
+            .get_resource::<SaveData>()
+            .expect("The save data plugin must be added before settings");
+        let value = match save_data.load(&slot) {
+            Ok(value) => value.unwrap_or(default),
+            Err(e) => {
+                error!("Couldn't load settings, using the defaults: {}", e);
+                default
+            }
+        };
+
+        self.insert_resource(Settings { value, slot })
+            .add_system_to_stage(CoreStage::Last, save_settings::<T>.system())
+    }
+
+    fn add_synced_settings<T: SettingsData + Clone + PartialEq>(
+        self,
+        slot: &'static str,
+        default: T,
+    ) -> Self {
+        let app = self.add_settings_with_default(slot, default);
+        let value = T::clone(app.world().get_resource::<Settings<T>>().unwrap());
+
+        app.insert_resource(value)
+            .add_system_to_stage(CoreStage::PostUpdate, sync_settings::<T>.system())
+    }
+}
+
+/// A type that can be used as [`Settings`]
+pub trait SettingsData: Serialize + DeserializeOwned + Send + Sync + 'static {}
+
+impl<T: Serialize + DeserializeOwned + Send + Sync + 'static> SettingsData for T {}
+
+/// A resource with settings, such as the volume or whether the game is fullscreen, that are loaded
+/// when the game starts and saved whenever they change
+///
+/// Settings are added with [`add_settings`][AppBuilderSettingsExt::add_settings], and the settings
+/// value is accessed through this resource like a reference. Changes are saved a second after the
+/// settings stop changing, or when the app exits.
+pub struct Settings<T> {
+    value: T,
+    slot: SaveSlot<T>,
+}
+
+impl<T> Settings<T> {
+    /// Get the save slot that the settings are saved in
+    pub fn slot(&self) -> &SaveSlot<T> {
+        &self.slot
+    }
+}
+
+impl<T> Deref for Settings<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Settings<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+/// Keep a resource and the settings that it is saved in the same
+fn sync_settings<T: SettingsData + Clone + PartialEq>(
+    mut value: ResMut<T>,
+    mut settings: ResMut<Settings<T>>,
+) {
+    // Only copy the settings when they are different so that copying them doesn't trigger change
+    // detection on the other resource every frame
+    if settings.is_changed() && **settings != *value {
+        *value = (**settings).clone();
+    } else if value.is_changed() && *value != **settings {
+        **settings = value.clone();
+    }
+}
+
+/// Save settings a while after they change, or right away if the app is exiting
+fn save_settings<T: SettingsData>(
+    settings: Res<Settings<T>>,
+    save_data: Res<SaveData>,
+    time: Res<Time>,
+    mut app_exit_events: EventReader<AppExit>,
+    mut save_at: Local<Option<f64>>,
+) {
+    // The settings are added when they are loaded, which doesn't need to be saved
+    if settings.is_changed() && !settings.is_added() {
+        *save_at = Some(time.seconds_since_startup() + SETTINGS_SAVE_DELAY);
+    }
+
+    let exiting = app_exit_events.iter().next().is_some();
+    if let Some(at) = *save_at {
+        if exiting || time.seconds_since_startup() >= at {
+            let result = if exiting {
+                save_data.save_now(&settings.slot, &settings.value)
+            } else {
+                save_data.save(&settings.slot, &settings.value)
+            };
+            if let Err(e) = result {
+                error!("Couldn't save settings: {}", e);
+            }
+            *save_at = None;
+        }
+    }
+}
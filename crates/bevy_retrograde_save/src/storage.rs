@@ -0,0 +1,223 @@
+//! The platform storage that saves are written to
+
+use bevy::tasks::IoTaskPool;
+
+use crate::SaveDataError;
+
+/// Get the name that a slot is stored under, with the characters that can't be in file names
+/// replaced
+fn slot_key(slot: &str) -> String {
+    slot.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(wasm))]
+pub(crate) use native::Storage;
+
+#[cfg(wasm)]
+pub(crate) use web::Storage;
+
+#[cfg(not(wasm))]
+mod native {
+    use std::{
+        collections::HashMap,
+        fs,
+        io::Write,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    use bevy::prelude::*;
+
+    use super::*;
+
+    /// Saves in files in the user's data directory
+    pub(crate) struct Storage {
+        dir: Option<PathBuf>,
+        task_pool: Option<IoTaskPool>,
+        /// The number of writes that have been started for each slot, which makes sure that a
+        /// write that finishes late doesn't replace a newer save
+        generations: Arc<Mutex<HashMap<String, u64>>>,
+        /// Held while a file is written so that writes don't overlap
+        write_lock: Arc<Mutex<()>>,
+    }
+
+    impl Storage {
+        pub fn new(app_name: &str, task_pool: Option<IoTaskPool>) -> Self {
+            Self {
+                dir: dirs::data_dir().map(|dir| dir.join(app_name)),
+                task_pool,
+                generations: Default::default(),
+                write_lock: Default::default(),
+            }
+        }
+
+        fn path(&self, slot: &str, backup: bool) -> Option<PathBuf> {
+            let extension = if backup { "json.bak" } else { "json" };
+            self.dir
+                .as_ref()
+                .map(|dir| dir.join(format!("{}.{}", slot_key(slot), extension)))
+        }
+
+        pub fn read(&self, slot: &str, backup: bool) -> Result<Option<Vec<u8>>, SaveDataError> {
+            let path = self.path(slot, backup).ok_or(SaveDataError::NoStorage)?;
+            match fs::read(path) {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+
+        /// Write a save, in the background unless `blocking` is `true`
+        pub fn write(
+            &self,
+            slot: &str,
+            data: Vec<u8>,
+            blocking: bool,
+        ) -> Result<(), SaveDataError> {
+            let dir = self.dir.clone().ok_or(SaveDataError::NoStorage)?;
+            let path = self.path(slot, false).unwrap();
+            let backup_path = self.path(slot, true).unwrap();
+            let temp_path = path.with_extension("json.tmp");
+
+            let slot = slot.to_string();
+            let generation = {
+                let mut generations = self.generations.lock().unwrap();
+                let generation = generations.entry(slot.clone()).or_default();
+                *generation += 1;
+                *generation
+            };
+            let generations = self.generations.clone();
+            let write_lock = self.write_lock.clone();
+
+            let write = move || -> Result<(), std::io::Error> {
+                let _lock = write_lock.lock().unwrap();
+
+                // Skip the write if a newer one has been started since
+                if generations.lock().unwrap().get(&slot) != Some(&generation) {
+                    return Ok(());
+                }
+
+                // Write the whole save to a temporary file before replacing the old one, and keep
+                // the old one as a backup
+                fs::create_dir_all(&dir)?;
+                let mut file = fs::File::create(&temp_path)?;
+                file.write_all(&data)?;
+                file.sync_all()?;
+                drop(file);
+                if path.exists() {
+                    fs::rename(&path, &backup_path)?;
+                }
+                fs::rename(&temp_path, &path)
+            };
+
+            match &self.task_pool {
+                Some(task_pool) if !blocking => task_pool
+                    .spawn(async move {
+                        if let Err(e) = write() {
+                            error!("Couldn't write save data: {}", e);
+                        }
+                    })
+                    .detach(),
+                _ => write()?,
+            }
+
+            Ok(())
+        }
+
+        pub fn delete(&self, slot: &str) -> Result<(), SaveDataError> {
+            let _lock = self.write_lock.lock().unwrap();
+            for backup in &[false, true] {
+                let path = self.path(slot, *backup).ok_or(SaveDataError::NoStorage)?;
+                match fs::remove_file(path) {
+                    Ok(()) => (),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(wasm)]
+mod web {
+    use super::*;
+
+    /// Saves in the browser's local storage
+    pub(crate) struct Storage {
+        prefix: String,
+    }
+
+    impl Storage {
+        pub fn new(app_name: &str, _task_pool: Option<IoTaskPool>) -> Self {
+            Self {
+                prefix: app_name.to_string(),
+            }
+        }
+
+        fn key(&self, slot: &str, backup: bool) -> String {
+            let suffix = if backup { ".bak" } else { "" };
+            format!("{}/{}{}", self.prefix, slot_key(slot), suffix)
+        }
+
+        fn local_storage() -> Result<web_sys::Storage, SaveDataError> {
+            web_sys::window()
+                .and_then(|window| window.local_storage().ok().flatten())
+                .ok_or(SaveDataError::NoStorage)
+        }
+
+        pub fn read(&self, slot: &str, backup: bool) -> Result<Option<Vec<u8>>, SaveDataError> {
+            Self::local_storage()?
+                .get_item(&self.key(slot, backup))
+                .map(|value| value.map(String::into_bytes))
+                .map_err(|e| SaveDataError::Browser(format!("{:?}", e)))
+        }
+
+        /// Write a save, which is always done right away on web
+        pub fn write(
+            &self,
+            slot: &str,
+            data: Vec<u8>,
+            _blocking: bool,
+        ) -> Result<(), SaveDataError> {
+            let storage = Self::local_storage()?;
+            let browser_error = |e| SaveDataError::Browser(format!("{:?}", e));
+
+            // Keep the old save as a backup
+            if let Some(old) = storage
+                .get_item(&self.key(slot, false))
+                .map_err(browser_error)?
+            {
+                storage
+                    .set_item(&self.key(slot, true), &old)
+                    .map_err(browser_error)?;
+            }
+
+            // The data is JSON, so it is always valid UTF-8
+            let data = String::from_utf8(data).unwrap_or_default();
+            storage
+                .set_item(&self.key(slot, false), &data)
+                .map_err(browser_error)
+        }
+
+        pub fn delete(&self, slot: &str) -> Result<(), SaveDataError> {
+            let storage = Self::local_storage()?;
+            for backup in &[false, true] {
+                storage
+                    .remove_item(&self.key(slot, *backup))
+                    .map_err(|e| SaveDataError::Browser(format!("{:?}", e)))?;
+            }
+
+            Ok(())
+        }
+    }
+}
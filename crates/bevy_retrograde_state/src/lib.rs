@@ -0,0 +1,293 @@
+//! Bevy Retrograde game state plugin
+//!
+//! Games are split into states, such as booting, the main menu, and playing the game, which are
+//! the values of a Bevy [`State`]. Each state can have assets that are loaded before it is
+//! entered, and while they load, a [`LoadingScreen`] with a progress bar is drawn over the game.
+//!
+//! States are added with [`add_game_state`][AppBuilderGameStateExt::add_game_state] and switched
+//! to with the [`GameStates`] resource, which waits for the assets of the next state to load
+//! before setting the [`State`]. Entities spawned with a [`StateEntity`] component are despawned
+//! when their state is left.
+//!
+//! The loading of assets is tracked by the [`AssetPreloader`], which can also load groups of assets
+//! outside of game states, such as [`AssetCollection`]s, and sends an [`AssetsReady`] event when a
+//! group is done loading.
+
+use std::{fmt::Debug, hash::Hash};
+
+use bevy::{ecs::component::Component, prelude::*, utils::HashMap};
+
+mod loading_screen;
+pub use loading_screen::LoadingScreen;
+
+mod progress;
+pub use progress::LoadingProgress;
+
+mod preload;
+pub use preload::{AppBuilderAssetCollectionExt, AssetCollection, AssetPreloader, AssetsReady};
+
+/// The labels of the systems added by the state plugin
+#[derive(SystemLabel, Debug, Clone, Hash, PartialEq, Eq)]
+enum StateSystem {
+    UpdatePreloader,
+}
+
+/// Game state and asset preloading plugin for Bevy Retrograde
+pub struct RetroStatePlugin;
+
+impl Plugin for RetroStatePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<AssetPreloader>()
+            .init_resource::<LoadingScreen>()
+            .add_event::<AssetsReady>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                preload::update_asset_preloader
+                    .system()
+                    .label(StateSystem::UpdatePreloader),
+            );
+    }
+}
+
+/// A type that can be used as a game state
+///
+/// This is implemented for any type that can be used as a Bevy [`State`], which is usually an
+/// enum.
+pub trait GameState: Component + Debug + Clone + Eq + Hash {}
+
+impl<T: Component + Debug + Clone + Eq + Hash> GameState for T {}
+
+/// Bevy [`AppBuilder`] extension for adding game states
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_state::*;
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// enum AppState {
+///     Boot,
+///     Menu,
+///     InGame,
+/// }
+///
+/// fn boot(mut states: ResMut<GameStates<AppState>>) {
+///     states.switch(AppState::Menu);
+/// }
+///
+/// fn spawn_menu(mut commands: Commands) {
+///     // The menu entities are despawned when the menu state is left
+///     commands.spawn().insert(StateEntity(AppState::Menu));
+/// }
+///
+/// fn main() {
+///     App::build()
+///         .add_plugin(RetroStatePlugin)
+///         .add_game_state(AppState::Boot)
+///         .add_state_assets(AppState::Menu, &["menu.png", "menu.ogg"])
+///         .add_state_assets(AppState::InGame, &["level1.ldtk", "player.png"])
+///         .add_state_enter_systems(AppState::Boot, SystemSet::new().with_system(boot.system()))
+///         .add_state_enter_systems(
+///             AppState::Menu,
+///             SystemSet::new().with_system(spawn_menu.system()),
+///         );
+/// }
+/// ```
+pub trait AppBuilderGameStateExt {
+    /// Add a game state type, starting in the given state
+    ///
+    /// The initial state is entered right away, without loading any assets, so it is usually a
+    /// boot state that switches to the first real state of the game. The [`RetroStatePlugin`] must
+    /// be added first.
+    fn add_game_state<S: GameState>(self, initial: S) -> Self;
+
+    /// Add assets, by their path, that are loaded before entering a state and are kept loaded
+    /// until the state is left
+    fn add_state_assets<S: GameState>(self, state: S, paths: &[&str]) -> Self;
+
+    /// Add systems that are run when a state is entered
+    fn add_state_enter_systems<S: GameState>(self, state: S, systems: SystemSet) -> Self;
+
+    /// Add systems that are run when a state is left
+    fn add_state_exit_systems<S: GameState>(self, state: S, systems: SystemSet) -> Self;
+}
+
+impl AppBuilderGameStateExt for &mut AppBuilder {
+    fn add_game_state<S: GameState>(self, initial: S) -> Self {
+        self.add_state(initial)
+            .insert_resource(GameStates::<S>::default())
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                load_game_states::<S>
+                    .system()
+                    .after(StateSystem::UpdatePreloader),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, despawn_state_entities::<S>.system())
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                loading_screen::update_loading_screen::<S>.system(),
+            )
+    }
+
+    fn add_state_assets<S: GameState>(self, state: S, paths: &[&str]) -> Self {
+        self.world_mut()
+            .get_resource_mut::<GameStates<S>>()
+            .expect("The game state must be added before its assets")
+            .assets
+            .entry(state)
+            .or_default()
+            .extend(paths.iter().map(|x| x.to_string()));
+        self
+    }
+
+    fn add_state_enter_systems<S: GameState>(self, state: S, systems: SystemSet) -> Self {
+        self.add_system_set(systems.with_run_criteria(State::on_enter(state)))
+    }
+
+    fn add_state_exit_systems<S: GameState>(self, state: S, systems: SystemSet) -> Self {
+        self.add_system_set(systems.with_run_criteria(State::on_exit(state)))
+    }
+}
+
+/// This resource switches between the game states of type `S`, loading the assets of a state
+/// before entering it
+///
+/// The assets of a state are tracked by the [`AssetPreloader`], which keeps them loaded until
+/// another state is entered.
+pub struct GameStates<S: GameState> {
+    /// The asset paths of each state
+    assets: HashMap<S, Vec<String>>,
+    /// The state that is being loaded
+    next: Option<S>,
+    /// The state that the preloader is loading the assets of
+    loading: Option<S>,
+    /// The state that the preloader is keeping the assets of loaded
+    loaded: Option<S>,
+    progress: LoadingProgress,
+}
+
+impl<S: GameState> Default for GameStates<S> {
+    fn default() -> Self {
+        Self {
+            assets: Default::default(),
+            next: None,
+            loading: None,
+            loaded: None,
+            progress: Default::default(),
+        }
+    }
+}
+
+impl<S: GameState> GameStates<S> {
+    /// Load the assets of a state and switch to it once they are loaded
+    ///
+    /// If another state is already being loaded, it is replaced by this one.
+    pub fn switch(&mut self, state: S) {
+        self.next = Some(state);
+        self.progress = Default::default();
+    }
+
+    /// Get the state that is being loaded, if any
+    pub fn loading(&self) -> Option<&S> {
+        self.next.as_ref()
+    }
+
+    /// Get the progress of loading the assets of the state that is being loaded
+    pub fn progress(&self) -> LoadingProgress {
+        self.progress
+    }
+
+    /// Get the paths of the assets that are loaded before entering a state
+    pub fn assets(&self, state: &S) -> &[String] {
+        self.assets.get(state).map(|x| x.as_slice()).unwrap_or(&[])
+    }
+
+    /// Get the name of the [`AssetPreloader`] group that the assets of a state are tracked in
+    pub fn asset_group(state: &S) -> String {
+        format!("{}::{:?}", std::any::type_name::<S>(), state)
+    }
+}
+
+/// Add this component to an entity to despawn it, along with its children, when the game leaves
+/// the given state
+///
+/// The entity is kept while its state is paused by pushing another state on top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateEntity<S: GameState>(pub S);
+
+/// Load the assets of the next state and switch to it once they are loaded
+fn load_game_states<S: GameState>(
+    mut states: ResMut<GameStates<S>>,
+    mut state: ResMut<State<S>>,
+    mut preloader: ResMut<AssetPreloader>,
+    asset_server: Res<AssetServer>,
+) {
+    let next = match &states.next {
+        Some(next) => next.clone(),
+        None => return,
+    };
+    let states = &mut *states;
+    let group = GameStates::asset_group(&next);
+
+    // Start loading the assets, and stop loading the assets of a state that was switched away from
+    // before it was entered
+    if states.loading.as_ref() != Some(&next) {
+        if let Some(abandoned) = states.loading.take() {
+            if states.loaded.as_ref() != Some(&abandoned) {
+                preloader.remove(&GameStates::asset_group(&abandoned));
+            }
+        }
+
+        let handles = states
+            .assets(&next)
+            .iter()
+            .map(|path| asset_server.load_untyped(path.as_str()))
+            .collect();
+        preloader.track(group.clone(), handles);
+        states.loading = Some(next.clone());
+    }
+
+    states.progress = preloader.group_progress(&group).unwrap_or_default();
+
+    if preloader.is_ready(&group) {
+        // Let the old state's assets be unloaded
+        if let Some(old) = states.loaded.replace(next.clone()) {
+            if old != next {
+                preloader.remove(&GameStates::asset_group(&old));
+            }
+        }
+        states.loading = None;
+        states.next = None;
+
+        if state.current() != &next {
+            state.overwrite_set(next).ok();
+        }
+    }
+}
+
+/// Despawn the state entities of the states that aren't active or paused anymore
+fn despawn_state_entities<S: GameState>(
+    mut commands: Commands,
+    state: Res<State<S>>,
+    entities: Query<(Entity, &StateEntity<S>)>,
+    mut last_stack: Local<Vec<S>>,
+) {
+    // Only despawn entities when the state stack changes, so that entities can be spawned for a
+    // state before it is entered
+    let stack = state
+        .inactives()
+        .iter()
+        .chain(std::iter::once(state.current()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if stack == *last_stack {
+        return;
+    }
+
+    for (entity, StateEntity(entity_state)) in entities.iter() {
+        if last_stack.contains(entity_state) && !stack.contains(entity_state) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    *last_stack = stack;
+}
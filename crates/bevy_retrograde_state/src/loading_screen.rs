@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::{
+    image::{Rgba, RgbaImage},
+    prelude::*,
+};
+
+use crate::{GameState, GameStates};
+
+/// This resource configures the loading screen that is drawn while the assets of a game state are
+/// loading
+///
+/// The loading screen covers the whole camera view with the background color, with a progress bar
+/// in the middle of it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// # use bevy_retrograde_state::*;
+/// App::build().insert_resource(LoadingScreen {
+///     bar_color: Color::from_rgba8(255, 204, 0, 255),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoadingScreen {
+    /// Whether or not the loading screen is drawn
+    pub enabled: bool,
+    /// The color that covers the camera view
+    pub background_color: Color,
+    /// The color of the outline and the filled part of the progress bar
+    pub bar_color: Color,
+    /// The size of the progress bar in pixels, including its outline
+    pub bar_size: UVec2,
+    /// The z position of the loading screen, which should be above all of the other sprites
+    pub z: f32,
+}
+
+impl Default for LoadingScreen {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            background_color: Color::new(0., 0., 0., 1.),
+            bar_color: Color::new(1., 1., 1., 1.),
+            bar_size: UVec2::new(64, 6),
+            z: 1000.,
+        }
+    }
+}
+
+/// The loading screen sprite of a game state type
+#[derive(Default)]
+pub(crate) struct LoadingScreenSprite {
+    entity: Option<Entity>,
+    image: Option<Handle<Image>>,
+    /// The size of the image and the width of the filled part of the bar that were last drawn
+    drawn: Option<(UVec2, u32)>,
+}
+
+/// Show the loading screen while the assets of a state are loading
+pub(crate) fn update_loading_screen<S: GameState>(
+    mut commands: Commands,
+    settings: Res<LoadingScreen>,
+    states: Res<GameStates<S>>,
+    windows: Res<Windows>,
+    cameras: Query<&Camera>,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut sprite: Local<LoadingScreenSprite>,
+) {
+    if !settings.enabled || states.loading().is_none() {
+        if let Some(entity) = sprite.entity.take() {
+            commands.entity(entity).despawn();
+        }
+        sprite.drawn = None;
+        return;
+    }
+
+    let size = match (windows.get_primary(), cameras.iter().next()) {
+        (Some(window), Some(camera)) => camera.get_target_sizes(window).low,
+        _ => return,
+    };
+    let bar_size = settings.bar_size.min(size);
+    let filled = (states.progress().fraction() * bar_size.x.saturating_sub(2) as f32) as u32;
+
+    if sprite.drawn != Some((size, filled)) {
+        let image = draw_loading_screen(&settings, size, bar_size, filled);
+        sprite.image = Some(match sprite.image.take() {
+            Some(handle) => image_assets.set(handle, image),
+            None => image_assets.add(image),
+        });
+        sprite.drawn = Some((size, filled));
+    }
+
+    if sprite.entity.is_none() {
+        let entity = commands
+            .spawn_bundle(SpriteBundle {
+                image: sprite.image.clone().unwrap_or_default(),
+                transform: Transform::from_xyz(0., 0., settings.z),
+                ..Default::default()
+            })
+            .insert(ScreenAnchor {
+                anchor: Anchor::Center,
+                ..Default::default()
+            })
+            .id();
+        sprite.entity = Some(entity);
+    }
+}
+
+/// Draw the loading screen image with a progress bar that has the given number of pixels filled
+fn draw_loading_screen(
+    settings: &LoadingScreen,
+    size: UVec2,
+    bar_size: UVec2,
+    filled: u32,
+) -> Image {
+    let to_rgba = |color: Color| {
+        Rgba([
+            (255. * color.r).round() as u8,
+            (255. * color.g).round() as u8,
+            (255. * color.b).round() as u8,
+            (255. * color.a).round() as u8,
+        ])
+    };
+    let background = to_rgba(settings.background_color);
+    let bar = to_rgba(settings.bar_color);
+
+    let bar_min = (size - bar_size) / 2;
+    let bar_max = bar_min + bar_size;
+    Image(RgbaImage::from_fn(size.x, size.y, |x, y| {
+        if x < bar_min.x || x >= bar_max.x || y < bar_min.y || y >= bar_max.y {
+            return background;
+        }
+
+        let on_outline =
+            x == bar_min.x || x == bar_max.x - 1 || y == bar_min.y || y == bar_max.y - 1;
+        let in_filled = x < bar_min.x + 1 + filled;
+        if on_outline || in_filled {
+            bar
+        } else {
+            background
+        }
+    }))
+}
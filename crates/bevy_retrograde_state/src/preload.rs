@@ -0,0 +1,235 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::LoadingProgress;
+
+/// A group of assets that are loaded together, such as the images, sounds, fonts, and maps of a
+/// level
+///
+/// A collection is added with
+/// [`add_asset_collection`][AppBuilderAssetCollectionExt::add_asset_collection], which starts
+/// loading it and inserts it as a resource, and an [`AssetsReady`] event is sent once all of its
+/// assets have loaded.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::*;
+/// # use bevy_retrograde_state::*;
+/// struct LevelAssets {
+///     player: Handle<Image>,
+///     tiles: Handle<Image>,
+/// }
+///
+/// impl AssetCollection for LevelAssets {
+///     fn load(asset_server: &AssetServer) -> Self {
+///         Self {
+///             player: asset_server.load("player.png"),
+///             tiles: asset_server.load("tiles.png"),
+///         }
+///     }
+///
+///     fn handles(&self) -> Vec<HandleUntyped> {
+///         vec![self.player.clone_untyped(), self.tiles.clone_untyped()]
+///     }
+/// }
+///
+/// fn start_level(mut events: EventReader<AssetsReady>) {
+///     for event in events.iter() {
+///         if event.is_collection::<LevelAssets>() {
+///             info!("Level assets loaded");
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     App::build()
+///         .add_plugin(RetroStatePlugin)
+///         .add_asset_collection::<LevelAssets>()
+///         .add_system(start_level.system());
+/// }
+/// ```
+pub trait AssetCollection: Send + Sync + Sized + 'static {
+    /// Start loading the assets of the collection
+    fn load(asset_server: &AssetServer) -> Self;
+
+    /// Get handles to all of the assets of the collection, which are used to track their progress
+    fn handles(&self) -> Vec<HandleUntyped>;
+}
+
+/// Bevy [`AppBuilder`] extension for adding [`AssetCollection`]s
+pub trait AppBuilderAssetCollectionExt {
+    /// Start loading an asset collection and insert it as a resource
+    fn add_asset_collection<T: AssetCollection>(self) -> Self;
+}
+
+impl AppBuilderAssetCollectionExt for &mut AppBuilder {
+    fn add_asset_collection<T: AssetCollection>(self) -> Self {
+        let asset_server = self
+            .world()
+            .get_resource::<AssetServer>()
+            .expect("The asset plugin must be added before asset collections")
+            .clone();
+        let collection = self
+            .world_mut()
+            .get_resource_mut::<AssetPreloader>()
+            .expect("The state plugin must be added before asset collections")
+            .load_collection::<T>(&asset_server);
+
+        self.insert_resource(collection)
+    }
+}
+
+/// This resource tracks the progress of loading groups of assets
+///
+/// Each group has a name, which is the type name for [`AssetCollection`]s, and the preloader keeps
+/// the assets of a group loaded until the group is removed. An [`AssetsReady`] event is sent when
+/// all of the assets of a group are done loading.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_state::*;
+/// fn load_music(asset_server: Res<AssetServer>, mut preloader: ResMut<AssetPreloader>) {
+///     let handles = asset_server.load_folder("music").unwrap();
+///     preloader.track("music", handles);
+/// }
+///
+/// fn print_progress(preloader: Res<AssetPreloader>) {
+///     let progress = preloader.progress();
+///     info!("Loaded {} of {} assets", progress.loaded, progress.total);
+/// }
+/// ```
+#[derive(Default)]
+pub struct AssetPreloader {
+    groups: Vec<PreloadGroup>,
+}
+
+/// A named group of assets tracked by the [`AssetPreloader`]
+struct PreloadGroup {
+    name: String,
+    handles: Vec<HandleUntyped>,
+    progress: LoadingProgress,
+    ready: bool,
+}
+
+impl AssetPreloader {
+    /// Start tracking the loading of a group of assets, replacing the group with the same name if
+    /// there is one
+    pub fn track<N: Into<String>>(&mut self, name: N, handles: Vec<HandleUntyped>) {
+        let name = name.into();
+        self.remove(&name);
+        self.groups.push(PreloadGroup {
+            name,
+            progress: LoadingProgress {
+                total: handles.len(),
+                ..Default::default()
+            },
+            handles,
+            ready: false,
+        });
+    }
+
+    /// Start loading an [`AssetCollection`] and track it under its type name
+    pub fn load_collection<T: AssetCollection>(&mut self, asset_server: &AssetServer) -> T {
+        let collection = T::load(asset_server);
+        self.track(std::any::type_name::<T>(), collection.handles());
+        collection
+    }
+
+    /// Stop tracking a group of assets, which lets them be unloaded once nothing else uses them
+    pub fn remove(&mut self, name: &str) {
+        self.groups.retain(|group| group.name != name);
+    }
+
+    /// Get the progress of loading all of the tracked assets
+    pub fn progress(&self) -> LoadingProgress {
+        self.groups
+            .iter()
+            .fold(LoadingProgress::default(), |total, group| LoadingProgress {
+                loaded: total.loaded + group.progress.loaded,
+                failed: total.failed + group.progress.failed,
+                total: total.total + group.progress.total,
+            })
+    }
+
+    /// Get the progress of loading a group of assets, if it is tracked
+    pub fn group_progress(&self, name: &str) -> Option<LoadingProgress> {
+        self.groups
+            .iter()
+            .find(|group| group.name == name)
+            .map(|group| group.progress)
+    }
+
+    /// Get whether or not all of the assets of a group are done loading
+    pub fn is_ready(&self, name: &str) -> bool {
+        self.groups
+            .iter()
+            .any(|group| group.name == name && group.ready)
+    }
+
+    /// Get whether or not all of the assets of an [`AssetCollection`] are done loading
+    pub fn is_collection_ready<T: AssetCollection>(&self) -> bool {
+        self.is_ready(std::any::type_name::<T>())
+    }
+}
+
+/// Bevy event sent when all of the assets of a group tracked by the [`AssetPreloader`] are done
+/// loading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetsReady {
+    /// The name of the group
+    pub group: String,
+    /// The final progress of the group, which has the number of assets that failed to load
+    pub progress: LoadingProgress,
+}
+
+impl AssetsReady {
+    /// Get whether or not the ready group is an [`AssetCollection`] of type `T`
+    pub fn is_collection<T: AssetCollection>(&self) -> bool {
+        self.group == std::any::type_name::<T>()
+    }
+}
+
+/// Update the progress of the asset groups and send the events of the ones that are ready
+pub(crate) fn update_asset_preloader(
+    asset_server: Res<AssetServer>,
+    mut preloader: ResMut<AssetPreloader>,
+    mut events: EventWriter<AssetsReady>,
+) {
+    // Only get the preloader mutably if a group is loading to avoid triggering change detection
+    if preloader.groups.iter().all(|group| group.ready) {
+        return;
+    }
+
+    for group in preloader.groups.iter_mut().filter(|group| !group.ready) {
+        let mut progress = LoadingProgress {
+            total: group.handles.len(),
+            ..Default::default()
+        };
+        for handle in &group.handles {
+            match asset_server.get_load_state(handle) {
+                LoadState::Loaded => progress.loaded += 1,
+                LoadState::Failed => progress.failed += 1,
+                _ => (),
+            }
+        }
+        group.progress = progress;
+
+        if progress.is_done() {
+            if progress.failed > 0 {
+                warn!(
+                    "{} assets of {} failed to load",
+                    progress.failed, group.name
+                );
+            }
+
+            group.ready = true;
+            events.send(AssetsReady {
+                group: group.name.clone(),
+                progress,
+            });
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/// The progress of loading a group of assets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadingProgress {
+    /// The number of assets that have loaded
+    pub loaded: usize,
+    /// The number of assets that failed to load
+    pub failed: usize,
+    /// The total number of assets
+    pub total: usize,
+}
+
+impl LoadingProgress {
+    /// Get whether or not all of the assets have either loaded or failed to load
+    pub fn is_done(&self) -> bool {
+        self.loaded + self.failed >= self.total
+    }
+
+    /// Get the fraction of the assets that are done loading, from `0.0` to `1.0`
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+}
@@ -5,22 +5,47 @@ use bevy::{
     utils::BoxedFuture,
 };
 
+use crate::TtfFontSettings;
+
 /// A font asset
 #[derive(TypeUuid, Clone, Debug)]
 #[uuid = "8dd853b0-f6b7-406a-b1c0-d81abd4137fc"]
 pub struct Font(crate::bdf::Font);
 bevy_retrograde_macros::impl_deref!(Font, crate::bdf::Font);
 
+impl Font {
+    /// Rasterize a TTF/OTF font with the given settings
+    ///
+    /// The TTF/OTF font loader always uses the [`TtfFontSettings`] resource, so this can be used to
+    /// create fonts at other sizes, which can then be added to the font assets manually.
+    pub fn from_ttf(bytes: &[u8], settings: &TtfFontSettings) -> Result<Self, FontLoaderError> {
+        Ok(Font(crate::ttf::rasterize(bytes, settings)?))
+    }
+}
+
 /// An error that occurs when loading an image file
 #[derive(thiserror::Error, Debug)]
 pub enum FontLoaderError {
     #[error("Error loading font: {0}")]
     FontError(#[from] crate::bdf::Error),
+    #[error("Error loading TTF/OTF font: {0}")]
+    TtfError(#[from] ab_glyph::InvalidFont),
+    #[error("Error loading BMFont: {0}")]
+    BmFontError(#[from] crate::bmfont::Error),
+    #[error("BMFont descriptor is not valid UTF-8: {0}")]
+    Utf8Error(#[from] std::str::Utf8Error),
+    #[error("Error loading BMFont page: {0}")]
+    AssetIoError(#[from] bevy::asset::AssetIoError),
+    #[error("Error parsing BMFont page image: {0}")]
+    ImageError(#[from] bevy_retrograde_core::image::ImageError),
 }
 
 /// An image asset loader
 #[derive(Default)]
-pub(crate) struct FontLoader;
+pub(crate) struct FontLoader {
+    /// The settings used to rasterize TTF/OTF fonts
+    pub ttf_settings: TtfFontSettings,
+}
 
 impl AssetLoader for FontLoader {
     fn load<'a>(
@@ -29,22 +54,53 @@ impl AssetLoader for FontLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
         // Create a future for the load function
-        Box::pin(async move { Ok(load_image(bytes, load_context).await?) })
+        Box::pin(async move { Ok(load_font(bytes, load_context, &self.ttf_settings).await?) })
     }
 
     fn extensions(&self) -> &[&str] {
-        &["bdf"]
+        &["bdf", "ttf", "otf", "fnt"]
     }
 }
 
-async fn load_image<'a, 'b>(
+async fn load_font<'a, 'b>(
     bytes: &'a [u8],
     load_context: &'a mut LoadContext<'b>,
+    ttf_settings: &'a TtfFontSettings,
 ) -> Result<(), FontLoaderError> {
+    // Check which kind of font this is
+    let extension = load_context
+        .path()
+        .extension()
+        .and_then(|x| x.to_str())
+        .map(|x| x.to_lowercase())
+        .unwrap_or_default();
+
     // Load the font
-    let font = crate::bdf::parse(bytes)?;
+    let font = match extension.as_str() {
+        "bdf" => crate::bdf::parse(bytes)?,
+        "fnt" => load_bmfont(bytes, load_context).await?,
+        _ => crate::ttf::rasterize(bytes, ttf_settings)?,
+    };
 
     load_context.set_default_asset(LoadedAsset::new(Font(font)));
 
     Ok(())
 }
+
+async fn load_bmfont<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<crate::bdf::Font, FontLoaderError> {
+    // Parse the font descriptor
+    let bmfont = crate::bmfont::parse(std::str::from_utf8(bytes)?)?;
+
+    // Load the page textures, which are relative to the descriptor
+    let mut pages = Vec::with_capacity(bmfont.pages.len());
+    for page in &bmfont.pages {
+        let page_path = load_context.path().parent().unwrap().join(page);
+        let page_bytes = load_context.read_asset_bytes(page_path).await?;
+        pages.push(bevy_retrograde_core::image::load_from_memory(&page_bytes)?.to_rgba8());
+    }
+
+    Ok(bmfont.into_font(&pages))
+}
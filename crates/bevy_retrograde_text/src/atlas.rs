@@ -0,0 +1,237 @@
+//! Glyph atlas used to draw text on the GPU
+
+use bevy::{asset::HandleId, utils::HashMap};
+use bevy_retrograde_core::{
+    graphics::{Surface, Texture},
+    image::{imageops, Rgba, RgbaImage},
+    luminance::{
+        context::GraphicsContext,
+        pixel::NormRGBA8UI,
+        texture::{Dim2, GenMipmaps, MagFilter, MinFilter, Sampler, Wrap},
+    },
+};
+
+use crate::prelude::*;
+
+/// The space in pixels left between the glyphs in the atlas
+const GLYPH_PADDING: u32 = 1;
+
+/// The sampler of atlas textures, which keeps the glyphs pixelated
+const PIXELATED_SAMPLER: Sampler = Sampler {
+    wrap_r: Wrap::ClampToEdge,
+    wrap_s: Wrap::ClampToEdge,
+    wrap_t: Wrap::ClampToEdge,
+    min_filter: MinFilter::Nearest,
+    mag_filter: MagFilter::Nearest,
+    depth_comparison: None,
+};
+
+/// The position and size of a glyph in a [`GlyphAtlas`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An image that the glyphs of fonts are packed into so that text can be drawn on the GPU as one
+/// quad per glyph
+///
+/// Render hooks keep the atlas uploaded to a texture, so that text that changes doesn't have to be
+/// rasterized and uploaded to the GPU again unless it uses glyphs that are not in the atlas yet.
+///
+/// Glyphs are drawn in white and are packed into rows in the order that they are added. When the
+/// atlas is full it is cleared, which increments its [`generation`][Self::generation], and the
+/// glyphs that are still needed are added again.
+pub struct GlyphAtlas {
+    image: RgbaImage,
+    glyphs: HashMap<(HandleId, char), AtlasRect>,
+    /// The position of the next glyph in the current row
+    row_x: u32,
+    /// The top of the current row
+    row_y: u32,
+    /// The height of the tallest glyph in the current row
+    row_height: u32,
+    /// The region of the image that has changed since it was last uploaded
+    dirty_region: Option<AtlasRect>,
+    generation: u32,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new(512)
+    }
+}
+
+impl GlyphAtlas {
+    /// Create an empty atlas that is `size` pixels wide and tall
+    pub fn new(size: u32) -> Self {
+        Self {
+            image: RgbaImage::new(size, size),
+            glyphs: Default::default(),
+            row_x: 0,
+            row_y: 0,
+            row_height: 0,
+            dirty_region: None,
+            generation: 0,
+        }
+    }
+
+    /// Get the width and height of the atlas in pixels
+    pub fn size(&self) -> u32 {
+        self.image.width()
+    }
+
+    /// Get the atlas image
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Get the number of times that the atlas has been cleared
+    ///
+    /// Any [`AtlasRect`]s or [`GlyphQuad`]s from an earlier generation are no longer valid.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Get the position of a glyph in the atlas
+    pub fn get(&self, font: HandleId, codepoint: char) -> Option<AtlasRect> {
+        self.glyphs.get(&(font, codepoint)).copied()
+    }
+
+    /// Add glyphs to the atlas
+    ///
+    /// If the glyphs don't all fit in the atlas, the atlas is cleared before adding them again.
+    /// Glyphs that don't fit even in an empty atlas are skipped.
+    pub fn insert_glyphs(&mut self, glyphs: &[(HandleId, &Font, char)]) {
+        if glyphs
+            .iter()
+            .all(|&(id, font, codepoint)| self.insert(id, font, codepoint))
+        {
+            return;
+        }
+
+        self.clear();
+        let mut skipped = 0;
+        for &(id, font, codepoint) in glyphs {
+            if !self.insert(id, font, codepoint) {
+                skipped += 1;
+            }
+        }
+
+        if skipped > 0 {
+            bevy::log::warn!(
+                "Glyph atlas is too small to fit all of the glyphs in view, skipped {} glyphs",
+                skipped
+            );
+        }
+    }
+
+    /// Add a glyph to the atlas, returning `false` if there isn't room for it
+    fn insert(&mut self, id: HandleId, font: &Font, codepoint: char) -> bool {
+        if self.glyphs.contains_key(&(id, codepoint)) {
+            return true;
+        }
+        let glyph = if let Some(glyph) = font.glyphs.get(&codepoint) {
+            glyph
+        } else {
+            return true;
+        };
+
+        let (width, height) = (glyph.bounds.width, glyph.bounds.height);
+        let size = self.size();
+
+        // Start a new row if the glyph doesn't fit in the current one
+        if self.row_x + width > size {
+            self.row_y += self.row_height + GLYPH_PADDING;
+            self.row_x = 0;
+            self.row_height = 0;
+        }
+        if width > size || self.row_y + height > size {
+            return false;
+        }
+
+        let rect = AtlasRect {
+            x: self.row_x,
+            y: self.row_y,
+            width,
+            height,
+        };
+        self.row_x += width + GLYPH_PADDING;
+        self.row_height = self.row_height.max(height);
+
+        // Draw the glyph, overwriting anything left in its place from before the atlas was cleared
+        for x in 0..width {
+            for y in 0..height {
+                let alpha = if glyph.bitmap.get(x, y) { 255 } else { 0 };
+                self.image
+                    .put_pixel(rect.x + x, rect.y + y, Rgba([255, 255, 255, alpha]));
+            }
+        }
+
+        // Grow the dirty region to include the glyph
+        self.dirty_region = Some(match self.dirty_region {
+            Some(dirty) => {
+                let x = dirty.x.min(rect.x);
+                let y = dirty.y.min(rect.y);
+                AtlasRect {
+                    x,
+                    y,
+                    width: (dirty.x + dirty.width).max(rect.x + rect.width) - x,
+                    height: (dirty.y + dirty.height).max(rect.y + rect.height) - y,
+                }
+            }
+            None => rect,
+        });
+        self.glyphs.insert((id, codepoint), rect);
+
+        true
+    }
+
+    /// Remove all of the glyphs from the atlas
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+        self.row_x = 0;
+        self.row_y = 0;
+        self.row_height = 0;
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Get the region of the atlas image that has changed since the last time this was called, and
+    /// that needs to be uploaded to the atlas texture
+    pub fn take_dirty_region(&mut self) -> Option<AtlasRect> {
+        self.dirty_region.take()
+    }
+
+    /// Create a texture for the atlas and upload the atlas image to it
+    pub fn create_texture(&self, surface: &mut Surface) -> Texture<Dim2, NormRGBA8UI> {
+        let mut texture = surface
+            .new_texture::<Dim2, NormRGBA8UI>([self.size(), self.size()], 0, PIXELATED_SAMPLER)
+            .expect("Could not create glyph atlas texture");
+        texture
+            .upload_raw(GenMipmaps::No, self.image.as_raw())
+            .expect("Could not upload glyph atlas");
+
+        texture
+    }
+
+    /// Upload the region of the atlas image that has changed since it was last uploaded to a
+    /// texture created with [`create_texture`][Self::create_texture]
+    pub fn upload_dirty_region(&mut self, texture: &mut Texture<Dim2, NormRGBA8UI>) {
+        if let Some(region) = self.take_dirty_region() {
+            let pixels =
+                imageops::crop_imm(&self.image, region.x, region.y, region.width, region.height)
+                    .to_image();
+
+            texture
+                .upload_part_raw(
+                    GenMipmaps::No,
+                    [region.x, region.y],
+                    [region.width, region.height],
+                    pixels.as_raw(),
+                )
+                .expect("Could not upload glyph atlas");
+        }
+    }
+}
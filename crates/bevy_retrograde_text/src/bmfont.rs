@@ -0,0 +1,271 @@
+//! AngelCode BMFont parser
+//!
+//! Supports both the text and the XML descriptor formats. Page textures are converted to
+//! monochrome glyph bitmaps so that BMFonts can be used just like BDF fonts.
+
+use bevy::utils::HashMap;
+use bevy_retrograde_core::image::RgbaImage;
+
+use crate::bdf::{Bitmap, BoundingBox, Font, Glyph};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Missing `{0}` tag")]
+    MissingTag(&'static str),
+    #[error("Missing `{attribute}` attribute in `{tag}` tag")]
+    MissingAttribute {
+        tag: &'static str,
+        attribute: &'static str,
+    },
+    #[error("Invalid value for `{attribute}` attribute in `{tag}` tag: {value}")]
+    InvalidAttribute {
+        tag: &'static str,
+        attribute: &'static str,
+        value: String,
+    },
+}
+
+/// A parsed BMFont descriptor
+#[derive(Debug, Clone)]
+pub struct BmFont {
+    pub face: String,
+    pub size: i32,
+    pub line_height: u32,
+    pub base: u32,
+    /// The page texture file names, relative to the descriptor, indexed by page id
+    pub pages: Vec<String>,
+    pub chars: Vec<BmChar>,
+}
+
+/// A character in a BMFont
+#[derive(Debug, Clone)]
+pub struct BmChar {
+    pub id: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+    pub x_advance: i32,
+    pub page: usize,
+}
+
+/// A tag in a BMFont descriptor, such as `char id=32 x=0 ...` or `<char id="32" x="0" ... />`
+struct Tag<'a> {
+    name: &'a str,
+    attributes: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Tag<'a> {
+    fn parse(source: &'a str) -> Option<Self> {
+        let source = source
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .trim_end_matches('/')
+            .trim();
+
+        // Skip empty lines, closing tags, and XML declarations or comments
+        if source.is_empty() || source.starts_with(|c: char| c == '/' || c == '?' || c == '!') {
+            return None;
+        }
+
+        let (name, mut rest) = source.split_at(
+            source
+                .find(char::is_whitespace)
+                .unwrap_or_else(|| source.len()),
+        );
+
+        let mut attributes = HashMap::default();
+        loop {
+            rest = rest.trim_start();
+            let eq = if let Some(eq) = rest.find('=') {
+                eq
+            } else {
+                break;
+            };
+            let key = rest[..eq].trim();
+            rest = rest[eq + 1..].trim_start();
+
+            // Values may be quoted, in which case they may contain whitespace
+            let value = if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or_else(|| quoted.len());
+                rest = quoted.get(end + 1..).unwrap_or("");
+                &quoted[..end]
+            } else {
+                let end = rest.find(char::is_whitespace).unwrap_or_else(|| rest.len());
+                let value = &rest[..end];
+                rest = &rest[end..];
+                value
+            };
+
+            attributes.insert(key, value);
+        }
+
+        Some(Tag { name, attributes })
+    }
+
+    fn get<T: std::str::FromStr>(
+        &self,
+        tag: &'static str,
+        attribute: &'static str,
+    ) -> Result<T, Error> {
+        let value = self
+            .attributes
+            .get(attribute)
+            .ok_or(Error::MissingAttribute { tag, attribute })?;
+
+        value.parse().map_err(|_| Error::InvalidAttribute {
+            tag,
+            attribute,
+            value: value.to_string(),
+        })
+    }
+
+    fn get_or_default<T: std::str::FromStr + Default>(
+        &self,
+        tag: &'static str,
+        attribute: &'static str,
+    ) -> Result<T, Error> {
+        if self.attributes.contains_key(attribute) {
+            self.get(tag, attribute)
+        } else {
+            Ok(T::default())
+        }
+    }
+}
+
+/// Parse a BMFont descriptor in either the text or XML format
+pub fn parse(source: &str) -> Result<BmFont, Error> {
+    // XML descriptors may put several tags on one line, so we split them on the tag start instead
+    let tags = if source.trim_start().starts_with('<') {
+        source.split('<').filter_map(Tag::parse).collect::<Vec<_>>()
+    } else {
+        source.lines().filter_map(Tag::parse).collect::<Vec<_>>()
+    };
+
+    let info = tags
+        .iter()
+        .find(|x| x.name == "info")
+        .ok_or(Error::MissingTag("info"))?;
+    let common = tags
+        .iter()
+        .find(|x| x.name == "common")
+        .ok_or(Error::MissingTag("common"))?;
+
+    let mut pages = Vec::new();
+    for page in tags.iter().filter(|x| x.name == "page") {
+        let id: usize = page.get("page", "id")?;
+        let file: String = page.get("page", "file")?;
+
+        if pages.len() <= id {
+            pages.resize(id + 1, String::new());
+        }
+        pages[id] = file;
+    }
+
+    let chars = tags
+        .iter()
+        .filter(|x| x.name == "char")
+        .map(|tag| {
+            Ok(BmChar {
+                id: tag.get("char", "id")?,
+                x: tag.get("char", "x")?,
+                y: tag.get("char", "y")?,
+                width: tag.get("char", "width")?,
+                height: tag.get("char", "height")?,
+                x_offset: tag.get_or_default("char", "xoffset")?,
+                y_offset: tag.get_or_default("char", "yoffset")?,
+                x_advance: tag.get("char", "xadvance")?,
+                page: tag.get_or_default("char", "page")?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(BmFont {
+        face: info.get_or_default("info", "face")?,
+        size: info.get_or_default("info", "size")?,
+        line_height: common.get("common", "lineHeight")?,
+        base: common.get("common", "base")?,
+        pages,
+        chars,
+    })
+}
+
+impl BmFont {
+    /// Convert the BMFont to a glyph font using the loaded page textures
+    ///
+    /// Any pixel in a page texture with an alpha of at least 50% is considered part of the glyph.
+    pub fn into_font(self, pages: &[RgbaImage]) -> Font {
+        let base = self.base as i32;
+
+        let mut glyphs = HashMap::default();
+        let mut max_width = 0;
+        for bm_char in &self.chars {
+            let codepoint = if let Some(codepoint) = std::char::from_u32(bm_char.id) {
+                codepoint
+            } else {
+                continue;
+            };
+            let page = if let Some(page) = pages.get(bm_char.page) {
+                page
+            } else {
+                continue;
+            };
+
+            // Glyphs are drawn starting at the left edge of the glyph cell, so we bake the x offset
+            // into the bitmap
+            let x_offset = bm_char.x_offset.max(0) as u32;
+            let width = bm_char.width + x_offset;
+            let height = bm_char.height;
+
+            let mut bitmap = Bitmap::new(width, height);
+            for x in 0..bm_char.width {
+                for y in 0..bm_char.height {
+                    let (src_x, src_y) = (bm_char.x + x, bm_char.y + y);
+                    if src_x < page.width()
+                        && src_y < page.height()
+                        && page.get_pixel(src_x, src_y)[3] >= 128
+                    {
+                        bitmap.set(x + x_offset, y, true);
+                    }
+                }
+            }
+
+            let advance = bm_char.x_advance.max(0) as u32;
+            max_width = max_width.max(advance).max(width);
+            glyphs.insert(
+                codepoint,
+                Glyph {
+                    codepoint,
+                    device_width: (advance, 0),
+                    scalable_width: (advance, 0),
+                    bounds: BoundingBox {
+                        width,
+                        height,
+                        x: 0,
+                        // BMFont offsets are measured down from the top of the line, but BDF glyph
+                        // offsets are measured up from the baseline
+                        y: base - bm_char.y_offset - height as i32,
+                    },
+                    bitmap,
+                },
+            );
+        }
+
+        Font {
+            font_spec: self.face,
+            font_size: (self.size.abs() as u32, 72, 72),
+            glyphs,
+            bounds: BoundingBox {
+                width: max_width,
+                height: self.line_height,
+                x: 0,
+                y: base - self.line_height as i32,
+            },
+            comments: Vec::new(),
+            properties: HashMap::default(),
+        }
+    }
+}
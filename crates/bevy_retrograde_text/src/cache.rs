@@ -0,0 +1,162 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use bevy::{asset::HandleId, prelude::*, utils::HashMap};
+use bevy_retrograde_core::prelude::*;
+
+use crate::prelude::*;
+
+/// A key identifying the result of rasterizing a text block
+///
+/// Two text blocks with the same key will rasterize to the same image. The key is made from the
+/// text, its spans and effects, the font, and the text block settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextRasterKey(u64);
+
+impl TextRasterKey {
+    /// Get the key for a text block
+    pub fn new<F: Into<HandleId>>(text: &Text, font: F, text_block: Option<&TextBlock>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        let hash_color = |color: &Color, hasher: &mut DefaultHasher| {
+            for channel in &[color.r, color.g, color.b, color.a] {
+                channel.to_bits().hash(hasher);
+            }
+        };
+
+        font.into().hash(&mut hasher);
+
+        text.text.hash(&mut hasher);
+        hash_color(&text.color, &mut hasher);
+        for span in &text.spans {
+            span.range.hash(&mut hasher);
+            span.color.is_some().hash(&mut hasher);
+            if let Some(color) = &span.color {
+                hash_color(color, &mut hasher);
+            }
+            span.effect.is_some().hash(&mut hasher);
+            match &span.effect {
+                Some(TextEffect::Wave {
+                    amplitude,
+                    frequency,
+                    wavelength,
+                }) => {
+                    0u8.hash(&mut hasher);
+                    for value in &[amplitude, frequency, wavelength] {
+                        value.to_bits().hash(&mut hasher);
+                    }
+                }
+                Some(TextEffect::Shake { amplitude, rate }) => {
+                    1u8.hash(&mut hasher);
+                    amplitude.to_bits().hash(&mut hasher);
+                    rate.to_bits().hash(&mut hasher);
+                }
+                Some(TextEffect::Rainbow { speed, spread }) => {
+                    2u8.hash(&mut hasher);
+                    speed.to_bits().hash(&mut hasher);
+                    spread.to_bits().hash(&mut hasher);
+                }
+                Some(TextEffect::Gradient { from, to }) => {
+                    3u8.hash(&mut hasher);
+                    hash_color(from, &mut hasher);
+                    hash_color(to, &mut hasher);
+                }
+                Some(TextEffect::FadeIn { chars_per_second }) => {
+                    4u8.hash(&mut hasher);
+                    chars_per_second.to_bits().hash(&mut hasher);
+                }
+                None => (),
+            }
+        }
+        text.outline.is_some().hash(&mut hasher);
+        if let Some(outline) = &text.outline {
+            hash_color(&outline.color, &mut hasher);
+            outline.thickness.hash(&mut hasher);
+        }
+        text.shadow.is_some().hash(&mut hasher);
+        if let Some(shadow) = &text.shadow {
+            hash_color(&shadow.color, &mut hasher);
+            shadow.offset.x.hash(&mut hasher);
+            shadow.offset.y.hash(&mut hasher);
+        }
+
+        text_block.is_some().hash(&mut hasher);
+        if let Some(block) = text_block {
+            block.width.hash(&mut hasher);
+            block.height.hash(&mut hasher);
+            block.horizontal_align.hash(&mut hasher);
+            block.vertical_align.hash(&mut hasher);
+            block.letter_spacing.hash(&mut hasher);
+            block.line_height.hash(&mut hasher);
+            block.overflow.hash(&mut hasher);
+            block.max_lines.hash(&mut hasher);
+        }
+
+        TextRasterKey(hasher.finish())
+    }
+
+    /// Get the key for the same text block rendered with fallback fonts
+    pub fn with_fallbacks<'a, I: IntoIterator<Item = &'a Handle<Font>>>(
+        self,
+        fallbacks: I,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        for fallback in fallbacks {
+            fallback.id.hash(&mut hasher);
+        }
+
+        TextRasterKey(hasher.finish())
+    }
+}
+
+/// Component containing the key of the text that is currently rasterized to a text entity's image
+pub(crate) struct RasterizedText(pub TextRasterKey);
+
+/// Resource that caches the rasterized images of text entities
+///
+/// Text entities with the same text, font, and text block settings share the same image, and
+/// updating a text entity without actually changing it will not cause it to be re-rasterized.
+///
+/// The cache does not keep the images alive, so images are unloaded as usual when no text entity
+/// uses them anymore.
+#[derive(Default, Debug)]
+pub struct TextImageCache {
+    images: HashMap<TextRasterKey, Handle<Image>>,
+}
+
+impl TextImageCache {
+    /// Get the cached image for the given key, if it is still loaded
+    pub fn get(&self, key: &TextRasterKey, image_assets: &Assets<Image>) -> Option<Handle<Image>> {
+        let handle = self.images.get(key)?;
+
+        if image_assets.get(handle).is_some() {
+            // Upgrade the weak handle to a strong one for the caller
+            Some(image_assets.get_handle(handle))
+        } else {
+            None
+        }
+    }
+
+    /// Add an image to the cache
+    pub fn insert(
+        &mut self,
+        key: TextRasterKey,
+        handle: &Handle<Image>,
+        image_assets: &Assets<Image>,
+    ) {
+        // Clean out images that have been unloaded whenever the cache grows large
+        if self.images.len() >= 256 {
+            self.images
+                .retain(|_, handle| image_assets.get(&*handle).is_some());
+        }
+
+        self.images.insert(key, handle.clone_weak());
+    }
+
+    /// Remove all of the images from the cache
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bevy::prelude::*;
 use bevy_retrograde_core::prelude::*;
 
@@ -7,6 +9,10 @@ use crate::prelude::*;
 /// assets are not loaded.
 pub(crate) struct TextNeedsUpdate;
 
+/// Marker component indicating that a text entity has its own image, that isn't shared with any
+/// other text entities, because it is animated by text effects
+pub(crate) struct AnimatedTextImage;
+
 #[derive(Bundle, Default, Debug, Clone)]
 pub struct TextBundle {
     pub font: Handle<Font>,
@@ -17,11 +23,31 @@ pub struct TextBundle {
     pub global_transform: GlobalTransform,
 }
 
+/// Fonts used, in order, for any characters that are missing from the font of a text entity
+///
+/// The text will not be rendered until all of the fallback fonts have loaded.
+#[derive(Debug, Clone, Default)]
+pub struct FontFallbacks(pub Vec<Handle<Font>>);
+bevy_retrograde_macros::impl_deref!(FontFallbacks, Vec<Handle<Font>>);
+
 /// The text inside a text entity or text block
+///
+/// The style of parts of the text can be changed with [`TextSpan`]s. Spans can be added manually,
+/// created from a list of strings with [`Text::from_spans`], or parsed from markup with
+/// [`Text::from_markup`].
 #[derive(Debug, Clone)]
 pub struct Text {
     pub text: String,
+    /// The color of any text that doesn't have a color set by a span
     pub color: Color,
+    /// Styles applied to ranges of the text
+    ///
+    /// When spans overlap, the last span in the list wins.
+    pub spans: Vec<TextSpan>,
+    /// An outline drawn around the glyphs
+    pub outline: Option<TextOutline>,
+    /// A shadow drawn behind the glyphs, and their outline if they have one
+    pub shadow: Option<TextShadow>,
 }
 
 impl Default for Text {
@@ -29,10 +55,393 @@ impl Default for Text {
         Self {
             text: String::new(),
             color: Color::new(1., 1., 1., 1.),
+            spans: Vec::new(),
+            outline: None,
+            shadow: None,
+        }
+    }
+}
+
+/// An outline around text
+///
+/// Text with an outline is padded by the outline thickness on every side.
+#[derive(Debug, Clone)]
+pub struct TextOutline {
+    pub color: Color,
+    /// The thickness of the outline in pixels
+    pub thickness: u32,
+}
+
+impl Default for TextOutline {
+    fn default() -> Self {
+        Self {
+            color: Color::default(),
+            thickness: 1,
+        }
+    }
+}
+
+/// A drop shadow behind text
+///
+/// Text with a shadow is padded by the largest component of the shadow offset on every side.
+#[derive(Debug, Clone)]
+pub struct TextShadow {
+    pub color: Color,
+    /// The offset of the shadow in pixels, with positive y pointing down
+    pub offset: IVec2,
+}
+
+impl Default for TextShadow {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0., 0., 0., 0.5),
+            offset: IVec2::new(1, 1),
+        }
+    }
+}
+
+/// The style of a range of a [`Text`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    /// The byte range of the text that this span applies to
+    pub range: Range<usize>,
+    /// The color of the text in the span, or [`None`] to use the color of the text
+    pub color: Option<Color>,
+    /// An effect applied to each character in the span
+    pub effect: Option<TextEffect>,
+}
+
+/// A per-character effect applied to a [`TextSpan`]
+///
+/// Animated effects are advanced by the [`TextEffectClock`] of the text entity, which is added
+/// automatically to any text entity with effects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEffect {
+    /// Move the characters up and down in a wave
+    Wave {
+        /// The height of the wave in pixels
+        amplitude: f32,
+        /// The number of times per second that each character moves up and down
+        frequency: f32,
+        /// The number of characters in one wave
+        wavelength: f32,
+    },
+    /// Shake the characters randomly
+    Shake {
+        /// The maximum distance in pixels that the characters move in each direction
+        amplitude: f32,
+        /// The number of times per second that the characters move
+        rate: f32,
+    },
+    /// Cycle the characters through the colors of the rainbow
+    ///
+    /// The alpha of the text color is kept.
+    Rainbow {
+        /// The number of times per second that each character cycles through the rainbow
+        speed: f32,
+        /// The change in hue, from `0.0` to `1.0`, between one character and the next
+        spread: f32,
+    },
+    /// Blend the color of the characters from one color at the start of the span to another at the
+    /// end of the span
+    Gradient { from: Color, to: Color },
+    /// Reveal the characters one after the other, starting when the text changes
+    FadeIn {
+        /// The number of characters revealed each second
+        chars_per_second: f32,
+    },
+}
+
+impl TextEffect {
+    /// A wave that is 2 pixels high
+    pub fn wave() -> Self {
+        TextEffect::Wave {
+            amplitude: 2.,
+            frequency: 1.,
+            wavelength: 8.,
+        }
+    }
+
+    /// A shake that moves the characters by up to 1 pixel
+    pub fn shake() -> Self {
+        TextEffect::Shake {
+            amplitude: 1.,
+            rate: 15.,
+        }
+    }
+
+    /// A rainbow that cycles once per second
+    pub fn rainbow() -> Self {
+        TextEffect::Rainbow {
+            speed: 1.,
+            spread: 0.1,
+        }
+    }
+
+    /// A fade in that reveals 30 characters per second
+    pub fn fade_in() -> Self {
+        TextEffect::FadeIn {
+            chars_per_second: 30.,
+        }
+    }
+
+    /// Whether or not the effect changes over time
+    pub fn is_animated(&self) -> bool {
+        !matches!(self, TextEffect::Gradient { .. })
+    }
+
+    /// The furthest distance in pixels that the effect can move a character
+    pub(crate) fn max_offset(&self) -> u32 {
+        match self {
+            TextEffect::Wave { amplitude, .. } | TextEffect::Shake { amplitude, .. } => {
+                amplitude.abs().ceil() as u32
+            }
+            _ => 0,
         }
     }
 }
 
+/// Component that tracks the time since the text effects of a text entity started
+///
+/// The clock is added to any text entity that has [`TextEffect`]s and is reset whenever the
+/// [`Text`] changes. Set [`elapsed`][Self::elapsed] to restart or skip ahead in the effects, for
+/// instance to skip a [`TextEffect::FadeIn`] when the player presses a button.
+#[derive(Debug, Clone, Default)]
+pub struct TextEffectClock {
+    /// The number of seconds since the effects started
+    pub elapsed: f32,
+}
+
+/// An error that occurs when parsing text markup
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum TextMarkupError {
+    #[error("Unknown markup tag `[{0}]`")]
+    UnknownTag(String),
+    #[error("Invalid color `{0}`, expected a hex color such as `#ff0000`")]
+    InvalidColor(String),
+    #[error("Closing tag `[/{0}]` does not have a matching opening tag")]
+    UnmatchedClosingTag(String),
+    #[error("Markup tag is missing a closing `]`")]
+    UnclosedTag,
+    #[error("Invalid value `{value}` for `[{tag}]` tag")]
+    InvalidValue { tag: String, value: String },
+}
+
+impl Text {
+    /// Create text from a list of strings, each with an optional color
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_retrograde_text::prelude::*;
+    /// # use bevy_retrograde_core::prelude::*;
+    /// let text = Text::from_spans(vec![
+    ///     ("You found the ", None),
+    ///     ("Golden Key", Some(Color::new(1., 0.8, 0., 1.))),
+    ///     ("!", None),
+    /// ]);
+    ///
+    /// assert_eq!(text.text, "You found the Golden Key!");
+    /// ```
+    pub fn from_spans<'a, I: IntoIterator<Item = (&'a str, Option<Color>)>>(spans: I) -> Self {
+        let mut text = Text::default();
+
+        for (span_text, color) in spans {
+            let start = text.text.len();
+            text.text.push_str(span_text);
+            text.spans.push(TextSpan {
+                range: start..text.text.len(),
+                color,
+                effect: None,
+            });
+        }
+
+        text
+    }
+
+    /// Parse text with inline markup
+    ///
+    /// Colors are set with `[color=#rrggbb]` or `[color=#rrggbbaa]` and reset with `[/color]`.
+    /// Tags may be nested. A literal `[` is written as `[[`.
+    ///
+    /// The following [`TextEffect`]s are also supported, with an optional value:
+    ///
+    /// | Tag                             | Effect                    | Value                      |
+    /// |---------------------------------|---------------------------|----------------------------|
+    /// | `[wave]`, `[wave=2]`            | [`TextEffect::Wave`]      | The amplitude in pixels    |
+    /// | `[shake]`, `[shake=1]`          | [`TextEffect::Shake`]     | The amplitude in pixels    |
+    /// | `[rainbow]`, `[rainbow=1]`      | [`TextEffect::Rainbow`]   | The cycles per second      |
+    /// | `[gradient=#ff0000,#0000ff]`    | [`TextEffect::Gradient`]  | The start and end colors   |
+    /// | `[fade]`, `[fade=30]`           | [`TextEffect::FadeIn`]    | The characters per second  |
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_retrograde_text::prelude::*;
+    /// let text = Text::from_markup("You found the [color=#ffcc00]Golden Key[/color]!").unwrap();
+    ///
+    /// assert_eq!(text.text, "You found the Golden Key!");
+    /// assert_eq!(text.spans[0].range, 14..24);
+    ///
+    /// let text = Text::from_markup("This is [wave][rainbow]AMAZING[/rainbow][/wave]!").unwrap();
+    ///
+    /// assert_eq!(text.spans[0].effect, Some(TextEffect::wave()));
+    /// assert_eq!(text.spans[1].range, 8..15);
+    /// ```
+    pub fn from_markup(markup: &str) -> Result<Self, TextMarkupError> {
+        let mut text = Text::default();
+        // The stack of open tags, with the index of their span
+        let mut open_tags: Vec<(&str, usize)> = Vec::new();
+
+        let mut rest = markup;
+        while let Some(tag_start) = rest.find('[') {
+            text.text.push_str(&rest[..tag_start]);
+            rest = &rest[tag_start + 1..];
+
+            // Handle escaped brackets
+            if let Some(after) = rest.strip_prefix('[') {
+                text.text.push('[');
+                rest = after;
+                continue;
+            }
+
+            let tag_end = rest.find(']').ok_or(TextMarkupError::UnclosedTag)?;
+            let tag = &rest[..tag_end];
+            rest = &rest[tag_end + 1..];
+
+            // Closing tag
+            if let Some(name) = tag.strip_prefix('/') {
+                match open_tags.pop() {
+                    Some((open_name, span_i)) if open_name == name => {
+                        text.spans[span_i].range.end = text.text.len();
+                    }
+                    _ => return Err(TextMarkupError::UnmatchedClosingTag(name.into())),
+                }
+
+            // Opening tag
+            } else {
+                let (name, value) = tag.split_at(tag.find('=').unwrap_or_else(|| tag.len()));
+                let value = value.trim_start_matches('=');
+
+                // Parse the optional number value of an effect tag
+                let number = |default: f32| {
+                    if value.is_empty() {
+                        Ok(default)
+                    } else {
+                        value
+                            .parse::<f32>()
+                            .map_err(|_| TextMarkupError::InvalidValue {
+                                tag: name.into(),
+                                value: value.into(),
+                            })
+                    }
+                };
+
+                let (color, effect) = match name {
+                    "color" => (Some(parse_hex_color(value)?), None),
+                    "wave" => (
+                        None,
+                        Some(TextEffect::Wave {
+                            amplitude: number(2.)?,
+                            frequency: 1.,
+                            wavelength: 8.,
+                        }),
+                    ),
+                    "shake" => (
+                        None,
+                        Some(TextEffect::Shake {
+                            amplitude: number(1.)?,
+                            rate: 15.,
+                        }),
+                    ),
+                    "rainbow" => (
+                        None,
+                        Some(TextEffect::Rainbow {
+                            speed: number(1.)?,
+                            spread: 0.1,
+                        }),
+                    ),
+                    "gradient" => {
+                        let (from, to) = value.split_at(value.find(',').ok_or_else(|| {
+                            TextMarkupError::InvalidValue {
+                                tag: name.into(),
+                                value: value.into(),
+                            }
+                        })?);
+                        (
+                            None,
+                            Some(TextEffect::Gradient {
+                                from: parse_hex_color(from.trim())?,
+                                to: parse_hex_color(to[1..].trim())?,
+                            }),
+                        )
+                    }
+                    "fade" => (
+                        None,
+                        Some(TextEffect::FadeIn {
+                            chars_per_second: number(30.)?,
+                        }),
+                    ),
+                    _ => return Err(TextMarkupError::UnknownTag(tag.into())),
+                };
+                let span = TextSpan {
+                    range: text.text.len()..text.text.len(),
+                    color,
+                    effect,
+                };
+
+                open_tags.push((name, text.spans.len()));
+                text.spans.push(span);
+            }
+        }
+        text.text.push_str(rest);
+
+        // Close any tags that were left open at the end of the text
+        for (_, span_i) in open_tags {
+            text.spans[span_i].range.end = text.text.len();
+        }
+
+        Ok(text)
+    }
+
+    /// Get the color of the character at the given byte index, taking the spans into account
+    pub fn color_at(&self, index: usize) -> Color {
+        self.spans
+            .iter()
+            .rev()
+            .filter(|span| span.range.contains(&index))
+            .find_map(|span| span.color)
+            .unwrap_or(self.color)
+    }
+
+    /// Whether or not any of the spans of the text have an effect
+    pub fn has_effects(&self) -> bool {
+        self.spans.iter().any(|span| span.effect.is_some())
+    }
+}
+
+/// Parse a `#rrggbb` or `#rrggbbaa` color
+fn parse_hex_color(color: &str) -> Result<Color, TextMarkupError> {
+    let invalid = || TextMarkupError::InvalidColor(color.into());
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .map(|x| u8::from_str_radix(x, 16).map_err(|_| invalid()))
+            .unwrap_or(Ok(255))
+    };
+
+    Ok(Color::from_rgba8(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        channel(6)?,
+    ))
+}
+
 /// The configuration for a text block
 #[derive(Debug, Clone)]
 pub struct TextBlock {
@@ -40,6 +449,18 @@ pub struct TextBlock {
     pub horizontal_align: TextHorizontalAlign,
     pub height: Option<u32>,
     pub vertical_align: TextVerticalAlign,
+    /// Extra space in pixels added between each glyph, which may be negative to tighten the text
+    pub letter_spacing: i32,
+    /// The distance in pixels from the top of one line to the top of the next, or [`None`] to use
+    /// the height of the font
+    pub line_height: Option<u32>,
+    /// What to do with text that doesn't fit in the text block
+    pub overflow: TextOverflow,
+    /// The maximum number of lines to show
+    ///
+    /// Lines after the maximum are handled according to the [`overflow`][Self::overflow] mode,
+    /// except that with [`TextOverflow::Grow`] and [`TextOverflow::ScaleToFit`] they are clipped.
+    pub max_lines: Option<u32>,
 }
 
 impl Default for TextBlock {
@@ -49,12 +470,16 @@ impl Default for TextBlock {
             horizontal_align: TextHorizontalAlign::Left,
             height: None,
             vertical_align: TextVerticalAlign::Top,
+            letter_spacing: 0,
+            line_height: None,
+            overflow: TextOverflow::default(),
+            max_lines: None,
         }
     }
 }
 
 /// The alignment of text horizontally
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextHorizontalAlign {
     Left,
     Center,
@@ -62,9 +487,30 @@ pub enum TextHorizontalAlign {
 }
 
 /// The alignment of text vertically
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TextVerticalAlign {
     Top,
     Middle,
     Bottom,
 }
+
+/// The behavior of text that doesn't fit in its text block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextOverflow {
+    /// Make the text block image larger to fit the text
+    Grow,
+    /// Cut off text that doesn't fit
+    Clip,
+    /// Cut off text that doesn't fit and end the last visible line with an ellipsis (`…`)
+    ///
+    /// If the font doesn't have a `…` glyph, three periods are used instead.
+    Ellipsis,
+    /// Scale the text down until it fits, using nearest neighbor filtering
+    ScaleToFit,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        TextOverflow::Grow
+    }
+}
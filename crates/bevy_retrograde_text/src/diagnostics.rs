@@ -0,0 +1,189 @@
+//! In-game diagnostics overlay
+
+use bevy::prelude::*;
+use bevy_retrograde_core::{diagnostics::RenderTimings, graphics::RenderStats, prelude::*};
+
+use crate::prelude::*;
+
+/// Plugin that adds an in-game overlay showing performance diagnostics
+///
+/// The overlay shows the frame rate, frame time, entity count, renderer statistics, and texture
+/// cache statistics in the top-left corner of the camera. The render time is shown too if the
+/// [`RetroRenderDiagnosticsPlugin`][bevy_retrograde_core::diagnostics::RetroRenderDiagnosticsPlugin]
+/// has been added. It is drawn with the font set in the [`DiagnosticsOverlay`] resource and will
+/// not be shown until a font has been set.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_text::{diagnostics::*, prelude::*};
+/// fn setup(mut overlay: ResMut<DiagnosticsOverlay>, asset_server: Res<AssetServer>) {
+///     overlay.font = asset_server.load("cozette.bdf");
+/// }
+/// ```
+pub struct RetroDiagnosticsOverlayPlugin;
+
+impl Plugin for RetroDiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<DiagnosticsOverlay>()
+            .add_system(toggle_diagnostics_overlay.system())
+            .add_system_to_stage(CoreStage::PostUpdate, update_diagnostics_overlay.system());
+    }
+}
+
+/// Resource used to configure the diagnostics overlay
+#[derive(Debug, Clone)]
+pub struct DiagnosticsOverlay {
+    /// The font used to draw the overlay
+    pub font: Handle<Font>,
+    /// The key that shows and hides the overlay
+    pub toggle_key: KeyCode,
+    /// Whether or not the overlay is visible
+    pub visible: bool,
+    /// The number of seconds between updates of the overlay text
+    pub update_interval: f32,
+    /// The color of the overlay text
+    pub color: Color,
+}
+
+impl Default for DiagnosticsOverlay {
+    fn default() -> Self {
+        Self {
+            font: Default::default(),
+            toggle_key: KeyCode::F3,
+            visible: false,
+            update_interval: 0.5,
+            color: Color::new(1., 1., 1., 1.),
+        }
+    }
+}
+
+/// Marker component for the diagnostics overlay text entity
+struct DiagnosticsOverlayText;
+
+/// The frame timings collected since the last overlay update
+#[derive(Default)]
+struct FrameTimings {
+    frames: u32,
+    elapsed: f32,
+    max_frame_time: f32,
+    render_cpu_time: f64,
+    render_gpu_time: Option<f64>,
+}
+
+fn toggle_diagnostics_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DiagnosticsOverlay>,
+) {
+    if keyboard_input.just_pressed(overlay.toggle_key) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+fn update_diagnostics_overlay(
+    mut commands: Commands,
+    overlay: Res<DiagnosticsOverlay>,
+    render_stats: Res<RenderStats>,
+    render_timings: Option<Res<RenderTimings>>,
+    time: Res<Time>,
+    mut timings: Local<FrameTimings>,
+    entities: Query<Entity>,
+    mut overlay_texts: Query<(Entity, &mut Text, &mut Visible), With<DiagnosticsOverlayText>>,
+) {
+    // Collect the frame timings
+    let delta = time.delta_seconds();
+    timings.frames += 1;
+    timings.elapsed += delta;
+    timings.max_frame_time = timings.max_frame_time.max(delta);
+    if let Some(render_timings) = &render_timings {
+        timings.render_cpu_time += render_timings.cpu_time;
+        if let Some(gpu_time) = render_timings.gpu_time {
+            *timings.render_gpu_time.get_or_insert(0.) += gpu_time;
+        }
+    }
+
+    // Spawn the overlay text once a font has been set
+    let (overlay_ent, mut text, mut visible) =
+        if let Some(components) = overlay_texts.iter_mut().next() {
+            components
+        } else {
+            if overlay.font != Handle::default() {
+                commands
+                    .spawn_bundle(TextBundle {
+                        font: overlay.font.clone(),
+                        sprite: Sprite {
+                            centered: false,
+                            ..Default::default()
+                        },
+                        // Draw on top of everything else
+                        transform: Transform::from_xyz(0., 0., 1000.),
+                        ..Default::default()
+                    })
+                    // Keep the overlay in the top-left corner of the camera
+                    .insert(ScreenAnchor {
+                        anchor: Anchor::TopLeft,
+                        offset: Vec2::ZERO,
+                    })
+                    .insert(DiagnosticsOverlayText);
+            }
+            return;
+        };
+
+    // Update the overlay font if it has changed
+    if overlay.is_changed() {
+        commands.entity(overlay_ent).insert(overlay.font.clone());
+    }
+
+    visible.0 = overlay.visible;
+    if !overlay.visible {
+        return;
+    }
+
+    // Update the text at the update interval
+    if timings.elapsed < overlay.update_interval && !overlay.is_changed() {
+        return;
+    }
+
+    let frame_time = timings.elapsed / timings.frames.max(1) as f32;
+    let fps = if frame_time > 0. { 1. / frame_time } else { 0. };
+
+    // Show the average render time if the render timings are being collected
+    let frames = timings.frames.max(1) as f64;
+    let render_time = match (&render_timings, timings.render_gpu_time) {
+        (Some(_), Some(gpu_time)) => format!(
+            "\nRender time: {:.2} ms (GPU {:.2} ms)",
+            timings.render_cpu_time / frames * 1000.,
+            gpu_time / frames * 1000.
+        ),
+        (Some(_), None) => format!(
+            "\nRender time: {:.2} ms",
+            timings.render_cpu_time / frames * 1000.
+        ),
+        (None, _) => String::new(),
+    };
+
+    *text = Text {
+        text: format!(
+            "FPS: {:.0}\n\
+            Frame time: {:.2} ms (max {:.2} ms)\n\
+            Entities: {}\n\
+            Renderables: {}\n\
+            Render batches: {}\n\
+            Textures: {} ({:.1} MiB){}",
+            fps,
+            frame_time * 1000.,
+            timings.max_frame_time * 1000.,
+            entities.iter().count(),
+            render_stats.renderables,
+            render_stats.render_batches,
+            render_stats.cached_textures,
+            render_stats.cached_texture_bytes as f32 / 1024. / 1024.,
+            render_time,
+        ),
+        color: overlay.color,
+        ..Default::default()
+    };
+
+    *timings = FrameTimings::default();
+}
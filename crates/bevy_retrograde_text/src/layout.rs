@@ -0,0 +1,597 @@
+//! Text layout
+//!
+//! Text is laid out once into a list of positioned glyphs, which can then either be rasterized to
+//! an image on the CPU or drawn as quads from a [`GlyphAtlas`] on the GPU.
+
+use std::f32::consts::TAU;
+
+use bevy::{asset::HandleId, prelude::*};
+use bevy_retrograde_core::prelude::*;
+use unicode_linebreak::BreakOpportunity;
+
+use crate::{
+    bdf::{Bitmap, BoundingBox, Glyph},
+    prelude::*,
+};
+
+/// The glyphs of a text block, positioned in the text block
+///
+/// Layouts are created with [`layout_text_block`]. Text entities that are drawn from the glyph atlas
+/// have their layout kept up to date in a [`TextLayout`] component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    /// The size of the text block in pixels
+    pub size: UVec2,
+    /// The scale of the glyphs, which is less than `1.0` if the text has been scaled down with
+    /// [`TextOverflow::ScaleToFit`]
+    pub scale: f32,
+    /// The glyphs in the text block, not including whitespace
+    pub glyphs: Vec<LaidOutGlyph>,
+}
+
+/// A glyph positioned in a [`TextLayout`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaidOutGlyph {
+    /// The font that the glyph is from, where `0` is the primary font and `1` and up are the
+    /// fallback fonts, in order
+    pub font: usize,
+    /// The character of the glyph in its font
+    pub codepoint: char,
+    /// The position of the top-left corner of the glyph in pixels
+    ///
+    /// Glyphs may stick out of the text block, in which case they are clipped when drawn.
+    pub position: Vec2,
+    /// The size of the glyph in pixels, before it is scaled
+    pub size: UVec2,
+    /// The color of the glyph, including the color of any effects
+    pub color: Color,
+}
+
+/// A quad that draws a glyph from a [`GlyphAtlas`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphQuad {
+    /// The position of the top-left corner of the quad in the text block in pixels
+    pub min: Vec2,
+    /// The position of the bottom-right corner of the quad in the text block in pixels
+    pub max: Vec2,
+    /// The texture coordinate of the top-left corner of the quad in the atlas
+    pub uv_min: Vec2,
+    /// The texture coordinate of the bottom-right corner of the quad in the atlas
+    pub uv_max: Vec2,
+    pub color: Color,
+}
+
+impl TextLayout {
+    /// Get the glyphs that have to be in the atlas to draw this layout
+    ///
+    /// `fonts` are the handle ids of the primary font and the fallback fonts that the text was laid
+    /// out with, along with the fonts themselves.
+    pub fn atlas_glyphs<'a>(
+        &self,
+        fonts: &[(HandleId, &'a Font)],
+    ) -> Vec<(HandleId, &'a Font, char)> {
+        self.glyphs
+            .iter()
+            .filter_map(|glyph| {
+                fonts
+                    .get(glyph.font)
+                    .map(|(id, font)| (*id, *font, glyph.codepoint))
+            })
+            .collect()
+    }
+
+    /// Get the quads that draw the glyphs of this layout from the atlas, clipped to the text block
+    ///
+    /// `fonts` are the handle ids of the primary font and the fallback fonts that the text was laid
+    /// out with. Glyphs that are not in the atlas are skipped.
+    pub fn glyph_quads(&self, atlas: &GlyphAtlas, fonts: &[HandleId]) -> Vec<GlyphQuad> {
+        let block_size = Vec2::new(self.size.x as f32, self.size.y as f32);
+        let atlas_size = atlas.size() as f32;
+
+        self.glyphs
+            .iter()
+            .filter_map(|glyph| {
+                let rect = atlas.get(*fonts.get(glyph.font)?, glyph.codepoint)?;
+
+                // Get the corners of the glyph and clip them to the text block
+                let min = glyph.position;
+                let max = min + Vec2::new(rect.width as f32, rect.height as f32) * self.scale;
+                let clipped_min = min.max(Vec2::ZERO);
+                let clipped_max = max.min(block_size);
+                if clipped_min.x >= clipped_max.x || clipped_min.y >= clipped_max.y {
+                    return None;
+                }
+
+                // Get the part of the glyph in the atlas that is left after clipping
+                let rect_min = Vec2::new(rect.x as f32, rect.y as f32);
+                let uv = |point: Vec2| (rect_min + (point - min) / self.scale) / atlas_size;
+
+                Some(GlyphQuad {
+                    min: clipped_min,
+                    max: clipped_max,
+                    uv_min: uv(clipped_min),
+                    uv_max: uv(clipped_max),
+                    color: glyph.color,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Build the vertices and indices of glyph quads, with two triangles per quad
+///
+/// `vertex` creates a vertex from a position, a texture coordinate, and a color, so that render
+/// hooks can build the quads with their own vertex types.
+pub fn glyph_quad_mesh<V>(
+    quads: &[GlyphQuad],
+    mut vertex: impl FnMut([f32; 2], [f32; 2], [f32; 4]) -> V,
+) -> (Vec<V>, Vec<u32>) {
+    let mut vertices = Vec::with_capacity(quads.len() * 4);
+    let mut indices = Vec::with_capacity(quads.len() * 6);
+    for quad in quads {
+        let color = [quad.color.r, quad.color.g, quad.color.b, quad.color.a];
+        let first_index = vertices.len() as u32;
+
+        for &(pos, uv) in &[
+            ([quad.min.x, quad.min.y], [quad.uv_min.x, quad.uv_min.y]),
+            ([quad.max.x, quad.min.y], [quad.uv_max.x, quad.uv_min.y]),
+            ([quad.max.x, quad.max.y], [quad.uv_max.x, quad.uv_max.y]),
+            ([quad.min.x, quad.max.y], [quad.uv_min.x, quad.uv_max.y]),
+        ] {
+            vertices.push(vertex(pos, uv, color));
+        }
+
+        indices.extend([0, 1, 2, 0, 2, 3].iter().map(|offset| first_index + offset));
+    }
+
+    (vertices, indices)
+}
+
+/// A glyph in a line of text that is being laid out
+struct LineGlyph<'a> {
+    font: usize,
+    glyph: &'a Glyph,
+    color: Color,
+    offset: IVec2,
+}
+
+/// Lay out the glyphs of a text block
+///
+/// Glyphs for characters that are missing from the font are taken from the fallback fonts, in
+/// order, and are aligned to the baseline of the primary font. Characters that are not in any of
+/// the fonts are laid out as a space, or skipped if the font doesn't have a space.
+///
+/// If `effect_time` is set, the [`TextEffect`]s of the text are applied as they are the given number
+/// of seconds after the effects started, and the text block is padded on every side by the
+/// furthest distance that the effects can move a character. Otherwise the effects are ignored.
+pub fn layout_text_block(
+    text: &Text,
+    font: &Font,
+    fallbacks: &[&Font],
+    text_block: Option<&TextBlock>,
+    effect_time: Option<f32>,
+) -> TextLayout {
+    let empty_glyph = Glyph {
+        codepoint: ' ',
+        device_width: (0, 0),
+        scalable_width: (0, 0),
+        bounds: BoundingBox {
+            width: 0,
+            height: 0,
+            x: 0,
+            y: 0,
+        },
+        bitmap: Bitmap::new(0, 0),
+    };
+    let default_glyph = font.glyphs.get(&' ');
+    let font_bounds = &font.bounds;
+
+    // Get a glyph from the font, or from the first fallback font that has it
+    let find_glyph = |char: char| {
+        font.glyphs.get(&char).map(|x| (0, x)).or_else(|| {
+            fallbacks
+                .iter()
+                .enumerate()
+                .find_map(|(i, x)| x.glyphs.get(&char).map(|x| (i + 1, x)))
+        })
+    };
+
+    // Calculate line breaks for the text. The break positions are byte offsets, so convert them to
+    // the index of the character after the break, which is also the index of its glyph.
+    let mut last_break = (0, 0);
+    let mut line_breaks = unicode_linebreak::linebreaks(&text.text)
+        .map(|(byte_i, op)| {
+            let (last_byte_i, last_char_i) = last_break;
+            let char_i = last_char_i + text.text[last_byte_i..byte_i].chars().count();
+            last_break = (byte_i, char_i);
+            (char_i, op)
+        })
+        .collect::<Vec<_>>();
+    line_breaks.reverse();
+    let line_breaks = line_breaks; // Make immutable
+
+    // Create a vector that holds all of the lines of the text and the glyphs in each line
+    let mut lines: Vec<Vec<LineGlyph>> = Default::default();
+
+    // The height of a line
+    let line_height = text_block
+        .and_then(|x| x.line_height)
+        .unwrap_or(font.bounds.height);
+
+    // Get the horizontal distance from the start of a glyph to the start of the next one
+    let letter_spacing = text_block.map(|x| x.letter_spacing).unwrap_or(0);
+    let advance = |glyph: &Glyph| (glyph.device_width.0 as i32 + letter_spacing).max(0) as u32;
+    let line_width = |line: &[LineGlyph]| line.iter().fold(0, |width, x| width + advance(x.glyph));
+
+    // Start glyph layout
+    let mut current_line = Vec::new();
+    let mut line_x = 0; // The x position in the line we are currently at
+    let mut line_start = 0; // The index of the first glyph in the current line
+    let glyph_count = text.text.chars().count();
+    for (glyph_i, (char_i, char)) in text.text.char_indices().enumerate() {
+        // Get the glyph for this character, falling back to the fallback fonts and then to a blank
+        // glyph if the character isn't in any of the fonts
+        let (font_i, glyph) = find_glyph(char)
+            .or_else(|| default_glyph.map(|x| (0, x)))
+            .unwrap_or((0, &empty_glyph));
+
+        // Add the next glyph to the current line
+        let (color, offset) = match effect_time {
+            Some(time) => apply_span_effects(text, char_i, time),
+            None => (text.color_at(char_i), IVec2::ZERO),
+        };
+        current_line.push(LineGlyph {
+            font: font_i,
+            glyph,
+            color,
+            offset,
+        });
+
+        // Calculate the new x position of the line after adding this glyph
+        line_x += advance(glyph);
+
+        // If this character must break the line
+        if line_breaks
+            .iter()
+            .any(|(i, op)| i == &(glyph_i + 1) && op == &BreakOpportunity::Mandatory)
+            // The last character always breaks, but we want to ignore that one
+            && glyph_i != glyph_count - 1
+        {
+            // Add this line to the lines list
+            lines.push(current_line);
+            // Start a new line
+            current_line = Vec::new();
+            line_start = glyph_i + 1;
+            // Reset the line x position
+            line_x = 0;
+
+        // If the new line x goes over our max width, we need to find the last position we
+        // can break the line
+        } else if text_block.map(|x| line_x > x.width).unwrap_or(false) {
+            for (break_i, line_break) in &line_breaks {
+                match (break_i, line_break) {
+                    // We found a spot in the current line that we can break the line
+                    (&split_i, BreakOpportunity::Allowed)
+                        if split_i < glyph_i && split_i > line_start =>
+                    {
+                        // Get the point in the line at which to break it
+                        let split_at = split_i - line_start;
+                        // Split the broken off characters into a new line
+                        let next_line = current_line.split_off(split_at);
+                        // Add the current line to the lines list
+                        lines.push(current_line);
+                        // Set the new current line to the next line
+                        current_line = next_line;
+                        line_start = split_i;
+                        // Reset our current line x counter to the length of the new current
+                        // line
+                        line_x = line_width(&current_line);
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    lines.push(current_line);
+
+    // Get the overflow behavior of the text block
+    let overflow = text_block.map(|x| x.overflow).unwrap_or_default();
+    let clips = matches!(overflow, TextOverflow::Clip | TextOverflow::Ellipsis);
+
+    // Get the maximum number of lines that fit in the text block
+    let max_lines = text_block.and_then(|block| {
+        let height_lines = block.height.filter(|_| clips).map(|height| {
+            (height.saturating_sub(font.bounds.height) / line_height.max(1) + 1) as usize
+        });
+
+        match (block.max_lines.map(|x| x as usize), height_lines) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    });
+
+    // Remove the lines that don't fit in the text block
+    let mut truncated = false;
+    if let Some(max_lines) = max_lines {
+        if lines.len() > max_lines.max(1) {
+            lines.truncate(max_lines.max(1));
+            truncated = true;
+        }
+    }
+
+    // Add an ellipsis to the lines that have been cut off
+    if let (TextOverflow::Ellipsis, Some(block)) = (overflow, text_block) {
+        let ellipsis = find_glyph('…')
+            .map(|x| vec![x])
+            .or_else(|| font.glyphs.get(&'.').map(|x| vec![(0, x); 3]))
+            .unwrap_or_default();
+        let ellipsis_width = ellipsis.iter().fold(0, |width, (_, g)| width + advance(g));
+
+        let line_count = lines.len();
+        for (line_i, line) in lines.iter_mut().enumerate() {
+            let mut width = line_width(line);
+            let is_cut_off = truncated && line_i == line_count - 1;
+
+            if !is_cut_off && width <= block.width {
+                continue;
+            }
+
+            // Remove glyphs until the ellipsis fits
+            while width + ellipsis_width > block.width
+                || line.last().map(|x| x.glyph.codepoint.is_whitespace()) == Some(true)
+            {
+                if let Some(removed) = line.pop() {
+                    width -= advance(removed.glyph);
+                } else {
+                    break;
+                }
+            }
+
+            let color = line.last().map(|x| x.color).unwrap_or(text.color);
+            line.extend(ellipsis.iter().map(|&(font, glyph)| LineGlyph {
+                font,
+                glyph,
+                color,
+                offset: IVec2::ZERO,
+            }));
+        }
+    }
+
+    // Get the height of the lines of the text block, making sure that the last line has room for
+    // the full height of the font
+    let lines_height = line_height * (lines.len() as u32 - 1) + line_height.max(font.bounds.height);
+
+    // Calculate the height and width of the text block
+    let block_height = match text_block.and_then(|x| x.height) {
+        Some(height) if clips => height,
+        height => lines_height.max(height.unwrap_or(0)),
+    };
+    let block_width = lines.iter().map(|line| line_width(line)).max().unwrap_or(0);
+    // Make sure the block is at least as wide as the specified text block width, or exactly as
+    // wide if the text is clipped
+    let block_width = text_block
+        .map(|x| {
+            if clips {
+                x.width
+            } else {
+                x.width.max(block_width)
+            }
+        })
+        .unwrap_or(block_width);
+
+    // Get the padding needed to keep characters moved by effects inside of the text block
+    let effect_padding: u32 = if effect_time.is_some() {
+        text.spans
+            .iter()
+            .filter_map(|span| span.effect.map(|x| x.max_offset()))
+            .max()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    // Calculate the y offset to account for vertical alignment
+    let y_offset = text_block
+        .map(|block| match (block.height, &block.vertical_align) {
+            (None, _) => 0,
+            (_, TextVerticalAlign::Top) => 0,
+            (Some(_), TextVerticalAlign::Middle) => block_height.saturating_sub(lines_height) / 2,
+            (Some(_), TextVerticalAlign::Bottom) => block_height.saturating_sub(lines_height),
+        })
+        .unwrap_or(0);
+
+    // Position the glyphs in all of the lines
+    let mut glyphs = Vec::new();
+    for (line_i, line) in lines.iter().enumerate() {
+        let line_y = line_i as u32 * line_height;
+        let mut line_x = 0u32;
+
+        // Calculate the x offset to account for text alignment
+        let x_offset = text_block
+            .map(|block| {
+                let free_width = block_width - line_width(line).min(block_width);
+                match &block.horizontal_align {
+                    TextHorizontalAlign::Left => 0,
+                    TextHorizontalAlign::Center => free_width / 2,
+                    TextHorizontalAlign::Right => free_width,
+                }
+            })
+            .unwrap_or(0);
+
+        for line_glyph in line {
+            let glyph = line_glyph.glyph;
+            let bounds = &glyph.bounds;
+
+            // Skip whitespace and empty glyphs
+            if !glyph.codepoint.is_whitespace() && bounds.width > 0 && bounds.height > 0 {
+                // Get the position of the top-left corner of the glyph, aligning the bottom of the
+                // glyph bounds to the baseline of the font
+                let glyph_x = (line_x + x_offset + effect_padding) as i32 + line_glyph.offset.x;
+                let glyph_y = (line_y + y_offset + effect_padding) as i32
+                    + font_bounds.height as i32
+                    + font_bounds.y
+                    - bounds.height as i32
+                    - bounds.y
+                    + line_glyph.offset.y;
+
+                glyphs.push(LaidOutGlyph {
+                    font: line_glyph.font,
+                    codepoint: glyph.codepoint,
+                    position: Vec2::new(glyph_x as f32, glyph_y as f32),
+                    size: UVec2::new(bounds.width, bounds.height),
+                    color: line_glyph.color,
+                });
+            }
+
+            // Increment line position
+            line_x += advance(glyph);
+        }
+    }
+
+    let layout = TextLayout {
+        size: UVec2::new(
+            block_width + effect_padding * 2,
+            block_height + effect_padding * 2,
+        ),
+        scale: 1.,
+        glyphs,
+    };
+
+    // Scale the text down to fit in the text block if necessary
+    match (overflow, text_block) {
+        (TextOverflow::ScaleToFit, Some(block)) => scale_to_fit(layout, block),
+        _ => layout,
+    }
+}
+
+/// Scale a layout down, keeping its aspect ratio, so that it fits in the text block
+fn scale_to_fit(mut layout: TextLayout, block: &TextBlock) -> TextLayout {
+    let (width, height) = (layout.size.x, layout.size.y);
+    let block_height = block.height.unwrap_or(height);
+    if width <= block.width && height <= block_height {
+        return layout;
+    }
+
+    // Get the largest size that fits in the text block
+    let scale = (block.width as f32 / width as f32).min(block_height as f32 / height as f32);
+    let scaled_width = ((width as f32 * scale).floor() as u32).max(1);
+    let scaled_height = ((height as f32 * scale).floor() as u32).max(1);
+
+    // Place the scaled text in the text block according to the alignment
+    let x = match block.horizontal_align {
+        TextHorizontalAlign::Left => 0,
+        TextHorizontalAlign::Center => block.width.saturating_sub(scaled_width) / 2,
+        TextHorizontalAlign::Right => block.width.saturating_sub(scaled_width),
+    };
+    let y = match block.vertical_align {
+        TextVerticalAlign::Top => 0,
+        TextVerticalAlign::Middle => block_height.saturating_sub(scaled_height) / 2,
+        TextVerticalAlign::Bottom => block_height.saturating_sub(scaled_height),
+    };
+    let offset = Vec2::new(x as f32, y as f32);
+
+    for glyph in &mut layout.glyphs {
+        glyph.position = glyph.position * scale + offset;
+    }
+    layout.size = UVec2::new(block.width, block_height);
+    layout.scale = scale;
+
+    layout
+}
+
+/// Get the color and the offset of the character at the given byte index after applying the
+/// effects of the spans that contain it
+fn apply_span_effects(text: &Text, char_i: usize, time: f32) -> (Color, IVec2) {
+    let mut color = text.color_at(char_i);
+    let mut offset = Vec2::ZERO;
+
+    for span in &text.spans {
+        let effect = match &span.effect {
+            Some(effect) if span.range.contains(&char_i) => effect,
+            _ => continue,
+        };
+
+        // Get the index of the character in the span
+        let index = text
+            .text
+            .get(span.range.start..char_i)
+            .map(|x| x.chars().count())
+            .unwrap_or(0) as f32;
+
+        match *effect {
+            TextEffect::Wave {
+                amplitude,
+                frequency,
+                wavelength,
+            } => {
+                let phase = frequency * time - index / wavelength.max(1.);
+                offset.y += amplitude * (phase * TAU).sin();
+            }
+            TextEffect::Shake { amplitude, rate } => {
+                // Pick a new random offset for each character every tick
+                let tick = (time * rate).max(0.) as u32;
+                let seed = (char_i as u32).wrapping_mul(0x27d4_eb2d) ^ tick;
+                offset.x += amplitude * noise(seed);
+                offset.y += amplitude * noise(seed ^ 0x1656_67b1);
+            }
+            TextEffect::Rainbow { speed, spread } => {
+                let hue = (time * speed + index * spread).rem_euclid(1.);
+                let (r, g, b) = hue_to_rgb(hue);
+                color = Color::new(r, g, b, color.a);
+            }
+            TextEffect::Gradient { from, to } => {
+                let span_chars = text
+                    .text
+                    .get(span.range.clone())
+                    .map(|x| x.chars().count())
+                    .unwrap_or(0);
+                let t = if span_chars > 1 {
+                    index / (span_chars - 1) as f32
+                } else {
+                    0.
+                };
+                let lerp = |a: f32, b: f32| a + (b - a) * t;
+                color = Color::new(
+                    lerp(from.r, to.r),
+                    lerp(from.g, to.g),
+                    lerp(from.b, to.b),
+                    lerp(from.a, to.a),
+                );
+            }
+            TextEffect::FadeIn { chars_per_second } => {
+                color.a *= (time * chars_per_second - index).max(0.).min(1.);
+            }
+        }
+    }
+
+    (
+        color,
+        IVec2::new(offset.x.round() as i32, offset.y.round() as i32),
+    )
+}
+
+/// Get a pseudo-random number from `-1.0` to `1.0` for the given seed
+fn noise(seed: u32) -> f32 {
+    let mut x = seed.wrapping_mul(0x9e37_79b9);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x85eb_ca6b);
+    x ^= x >> 13;
+
+    x as f32 / u32::MAX as f32 * 2. - 1.
+}
+
+/// Get the fully saturated color for a hue from `0.0` to `1.0`
+fn hue_to_rgb(hue: f32) -> (f32, f32, f32) {
+    let h = hue * 6.;
+    let x = 1. - (h % 2. - 1.).abs();
+
+    match h as u32 {
+        0 => (1., x, 0.),
+        1 => (x, 1., 0.),
+        2 => (0., 1., x),
+        3 => (0., x, 1.),
+        4 => (x, 0., 1.),
+        _ => (1., 0., x),
+    }
+}
@@ -1,13 +1,42 @@
 //! Bevy Retrograde text rendering plugin
+//!
+//! BDF, TTF/OTF, and AngelCode BMFont (`.fnt`) fonts are supported. BMFont descriptors may use
+//! either the text or the XML format, and their page textures are loaded from paths relative to the
+//! descriptor. Fonts are always drawn in the color of the text, so any colors in BMFont page
+//! textures are ignored.
+//!
+//! TTF/OTF fonts are rasterized at a fixed pixel size, without antialiasing, using the
+//! [`TtfFontSettings`] resource. To change the settings, insert the resource before adding the
+//! [`RetroTextPlugin`].
+//!
+//! Text is drawn on the GPU as one quad per glyph from a [`GlyphAtlas`] that the glyphs of all
+//! visible text are packed into, so changing text doesn't require rasterizing and uploading a new
+//! image. Text with an outline or a shadow is still rasterized to an image and drawn like a sprite.
+//!
+//! Parts of the text can be animated with per-character [`TextEffect`]s, such as a wave or a
+//! fade-in, which are set on [`TextSpan`]s or with markup in [`Text::from_markup`].
+//!
+//! With the `localization` feature enabled, the text of a text entity can be set to the
+//! translation of a localization key with a [`LocalizedText`] component.
+//!
+//! Text is positioned in world space like sprites, but HUD text can be kept in place on the screen
+//! by adding a [`ScreenAnchor`][bevy_retrograde_core::prelude::ScreenAnchor] component.
 
 #![allow(clippy::type_complexity)]
 
 use bevy::{asset::AssetStage, ecs::component::ComponentDescriptor, prelude::*};
+use bevy_retrograde_core::prelude::AppBuilderRenderHookExt;
 
 #[doc(hidden)]
 pub mod prelude {
     pub use crate::assets::*;
+    pub use crate::atlas::{AtlasRect, GlyphAtlas};
+    pub use crate::cache::{TextImageCache, TextRasterKey};
     pub use crate::components::*;
+    pub use crate::layout::{GlyphQuad, LaidOutGlyph, TextLayout};
+    #[cfg(feature = "localization")]
+    pub use crate::localized::LocalizedText;
+    pub use crate::ttf::TtfFontSettings;
     pub use crate::RetroTextPlugin;
 }
 
@@ -15,11 +44,34 @@ mod assets;
 
 mod components;
 
+mod cache;
+pub(crate) use cache::RasterizedText;
+
+mod atlas;
+
+mod layout;
+pub use layout::{glyph_quad_mesh, layout_text_block};
+
+mod render_hook;
+
 pub(crate) mod bdf;
 
+mod bmfont;
+
+mod ttf;
+pub use ttf::TtfFontSettings;
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+
+#[cfg(feature = "localization")]
+mod localized;
+
 mod systems;
-pub use systems::rasterize_text_block;
 use systems::*;
+pub use systems::{
+    rasterize_text_block, rasterize_text_block_with_effects, rasterize_text_block_with_fallbacks,
+};
 
 use prelude::*;
 
@@ -27,26 +79,55 @@ use prelude::*;
 #[derive(StageLabel, Debug, Clone, Hash, PartialEq, Eq)]
 pub struct RetroTextStage;
 
+/// The labels of the systems in the [`RetroTextStage`]
+#[derive(SystemLabel, Debug, Clone, Hash, PartialEq, Eq)]
+enum TextSystem {
+    AdvanceEffects,
+}
+
 /// Text rendering plugin for Bevy Retrograde
 pub struct RetroTextPlugin;
 
 impl Plugin for RetroTextPlugin {
     fn build(&self, app: &mut AppBuilder) {
+        // Get the settings for rasterizing TTF/OTF fonts
+        let ttf_settings = app
+            .world_mut()
+            .get_resource_or_insert_with(TtfFontSettings::default)
+            .clone();
+
         app
             // use sparce storage for marker components
             .register_component(ComponentDescriptor::new::<TextNeedsUpdate>(
                 bevy::ecs::component::StorageType::SparseSet,
             ))
+            .register_component(ComponentDescriptor::new::<AnimatedTextImage>(
+                bevy::ecs::component::StorageType::SparseSet,
+            ))
+            // Add the text image cache
+            .init_resource::<TextImageCache>()
             // Add our font asset
             .add_asset::<Font>()
             // Add our font asset loader
-            .add_asset_loader(FontLoader)
+            .add_asset_loader(FontLoader { ttf_settings })
+            // Add the glyph atlas text render hook
+            .add_render_hook::<render_hook::TextRenderHook>()
             // Add our font rendering system
             .add_stage_before(
                 // We have to run before assets are uploaded to prevent frame delays on text updates
                 AssetStage::LoadAssets,
                 RetroTextStage,
-                SystemStage::single(font_rendering.system()),
+                SystemStage::parallel()
+                    .with_system(
+                        advance_text_effects
+                            .system()
+                            .label(TextSystem::AdvanceEffects),
+                    )
+                    .with_system(font_rendering.system().after(TextSystem::AdvanceEffects)),
             );
+
+        // Translate localized text once the localization has been updated for the frame
+        #[cfg(feature = "localization")]
+        app.add_system_to_stage(CoreStage::PostUpdate, localized::translate_text.system());
     }
 }
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy_retrograde_localization::Localization;
+
+use crate::prelude::*;
+
+/// Add this component to a text entity to set its [`Text`] to the translation of a localization
+/// key
+///
+/// The text is translated again whenever the [`Localization`] or this component changes. The
+/// translation may use markup, which sets the spans of the text like [`Text::from_markup`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_text::prelude::*;
+/// fn spawn_coin_counter(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands
+///         .spawn_bundle(TextBundle {
+///             font: asset_server.load("cozette.bdf"),
+///             ..Default::default()
+///         })
+///         .insert(LocalizedText::new("hud.coins").with_arg("count", 0));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalizedText {
+    /// The localization key of the text
+    pub key: String,
+    /// The names of the arguments put into the translation and their values
+    pub args: Vec<(String, String)>,
+}
+
+impl LocalizedText {
+    /// Create localized text without any arguments
+    pub fn new<K: Into<String>>(key: K) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add an argument to put into the translation
+    pub fn with_arg<N: Into<String>, V: ToString>(mut self, name: N, value: V) -> Self {
+        self.set_arg(name, value);
+        self
+    }
+
+    /// Set the value of an argument, adding it if it isn't set yet
+    pub fn set_arg<N: Into<String>, V: ToString>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.to_string();
+        match self.args.iter_mut().find(|(x, _)| *x == name) {
+            Some((_, old)) => *old = value,
+            None => self.args.push((name, value)),
+        }
+    }
+}
+
+/// Translate the text of the entities with [`LocalizedText`]
+pub(crate) fn translate_text(
+    localization: Res<Localization>,
+    mut texts: Query<(&LocalizedText, ChangeTrackers<LocalizedText>, &mut Text)>,
+) {
+    for (localized, localized_tracker, mut text) in texts.iter_mut() {
+        if !localization.is_changed() && !localized_tracker.is_changed() {
+            continue;
+        }
+
+        let args = localized
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_str(), value as &dyn std::fmt::Display))
+            .collect::<Vec<_>>();
+        let translation = localization.format(&localized.key, &args);
+        let translated = Text::from_markup(&translation).unwrap_or_else(|e| {
+            warn!(
+                "Invalid markup in translation of `{}`: {}",
+                localized.key, e
+            );
+            Text {
+                text: translation,
+                ..Default::default()
+            }
+        });
+
+        // Only update the text if it has changed to avoid triggering change detection
+        if text.text != translated.text || text.spans != translated.spans {
+            text.text = translated.text;
+            text.spans = translated.spans;
+        }
+    }
+}
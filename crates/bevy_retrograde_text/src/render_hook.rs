@@ -0,0 +1,349 @@
+use std::iter;
+
+use bevy::{
+    app::{Events, ManualEventReader},
+    prelude::*,
+    utils::HashMap,
+};
+use bevy_retrograde_core::{
+    graphics::{
+        FrameContext, Program, RenderHook, RenderHookRenderableHandle, SceneFramebuffer, Surface,
+        Tess, Texture, TextureCache,
+    },
+    luminance::{
+        self,
+        blending::{Blending, Equation, Factor},
+        context::GraphicsContext,
+        depth_test::DepthComparison,
+        pipeline::{PipelineState, TextureBinding},
+        pixel::{NormRGBA8UI, NormUnsigned},
+        render_state::RenderState,
+        shader::Uniform,
+        texture::Dim2,
+        Semantics, UniformInterface, Vertex,
+    },
+    prelude::{Sprite, Visible},
+};
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Semantics)]
+pub enum VertexSemantics {
+    #[sem(name = "v_pos", repr = "[f32; 2]", wrapper = "VertexPosition")]
+    Position,
+    #[sem(name = "v_uv", repr = "[f32; 2]", wrapper = "VertexUv")]
+    Uv,
+    #[sem(name = "v_color", repr = "[f32; 4]", wrapper = "VertexColor")]
+    Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Vertex)]
+#[vertex(sem = "VertexSemantics")]
+struct GlyphVert {
+    pos: VertexPosition,
+    uv: VertexUv,
+    color: VertexColor,
+}
+
+#[derive(UniformInterface)]
+struct TextUniformInterface {
+    camera_position: Uniform<[f32; 2]>,
+    camera_size: Uniform<[i32; 2]>,
+    camera_centered: Uniform<i32>,
+
+    pixel_perfect: Uniform<i32>,
+
+    atlas: Uniform<TextureBinding<Dim2, NormUnsigned>>,
+
+    text_size: Uniform<[i32; 2]>,
+    text_centered: Uniform<i32>,
+    text_position: Uniform<[f32; 3]>,
+    text_offset: Uniform<[f32; 2]>,
+}
+
+/// The glyph quads of a text entity
+struct GlyphTess {
+    /// The layout that the quads were built from
+    layout: TextLayout,
+    /// The generation of the atlas that the quads were built from
+    atlas_generation: u32,
+    /// The quads, or [`None`] if the text doesn't have any visible glyphs
+    tess: Option<Tess<GlyphVert, u32>>,
+}
+
+/// The render hook that draws text entities as quads from a glyph atlas
+pub(crate) struct TextRenderHook {
+    program: Program<(), (), TextUniformInterface>,
+    atlas: GlyphAtlas,
+    atlas_texture: Texture<Dim2, NormRGBA8UI>,
+    /// The glyph quads of the text entities drawn in the last frame
+    ///
+    /// The quads are only re-built when the layout of the text changes or the atlas is cleared.
+    glyph_tesses: HashMap<Entity, GlyphTess>,
+    /// The text entities to render this frame
+    current_text_batch: Vec<Entity>,
+    font_events: ManualEventReader<AssetEvent<Font>>,
+}
+
+impl RenderHook for TextRenderHook {
+    fn init(_window_id: bevy::window::WindowId, surface: &mut Surface) -> Box<dyn RenderHook> {
+        let atlas = GlyphAtlas::default();
+
+        // Create the atlas texture, starting out empty
+        let atlas_texture = atlas.create_texture(surface);
+
+        Box::new(Self {
+            program: surface
+                .new_shader_program::<(), (), TextUniformInterface>()
+                .from_strings(
+                    include_str!("render_hook/text.vert"),
+                    None,
+                    None,
+                    include_str!("render_hook/text.frag"),
+                )
+                .unwrap()
+                .program,
+            atlas,
+            atlas_texture,
+            glyph_tesses: Default::default(),
+            current_text_batch: Default::default(),
+            font_events: Default::default(),
+        })
+    }
+
+    fn prepare(
+        &mut self,
+        world: &mut World,
+        surface: &mut Surface,
+        _texture_cache: &mut TextureCache,
+        _frame_context: &FrameContext,
+    ) -> Vec<RenderHookRenderableHandle> {
+        self.current_text_batch.clear();
+
+        let mut texts = world.query::<(
+            Entity,
+            &TextLayout,
+            &Handle<Font>,
+            Option<&FontFallbacks>,
+            &Visible,
+            &GlobalTransform,
+        )>();
+        let font_assets = world.get_resource::<Assets<Font>>().unwrap();
+
+        // Clear the atlas if any fonts have been reloaded
+        let font_events = world.get_resource::<Events<AssetEvent<Font>>>().unwrap();
+        if self
+            .font_events
+            .iter(font_events)
+            .any(|event| matches!(event, AssetEvent::Modified { .. }))
+        {
+            self.atlas.clear();
+        }
+
+        // Collect the visible text entities and the glyphs that they need
+        let mut renderables = Vec::new();
+        let mut text_font_ids = Vec::new();
+        let mut atlas_glyphs = Vec::new();
+        for (ent, layout, font, fallbacks, visible, transform) in texts.iter(world) {
+            // Skip invisible text
+            if !visible.0 {
+                continue;
+            }
+
+            // Get the font and the fallback fonts of the text
+            let font_ids = iter::once(font.id)
+                .chain(fallbacks.iter().flat_map(|x| x.iter()).map(|x| x.id))
+                .collect::<Vec<_>>();
+            let fonts = if let Some(fonts) = font_ids
+                .iter()
+                .map(|&id| font_assets.get(id).map(|font| (id, font)))
+                .collect::<Option<Vec<_>>>()
+            {
+                fonts
+            } else {
+                continue;
+            };
+            atlas_glyphs.extend(layout.atlas_glyphs(&fonts));
+
+            self.current_text_batch.push(ent);
+            text_font_ids.push(font_ids);
+            renderables.push(RenderHookRenderableHandle {
+                // Set the identifier to the index of the text entity in the text batch
+                identifier: self.current_text_batch.len() - 1,
+                depth: transform.translation.z,
+                is_transparent: true,
+                entity: Some(ent),
+            });
+        }
+
+        // Add the glyphs to the atlas and upload any new glyphs to the atlas texture
+        self.atlas.insert_glyphs(&atlas_glyphs);
+        self.atlas.upload_dirty_region(&mut self.atlas_texture);
+
+        // Re-use the glyph quads of text that hasn't changed since the last frame. Any quads of text
+        // that isn't drawn this frame are dropped.
+        let mut previous_glyph_tesses = std::mem::take(&mut self.glyph_tesses);
+        for (ent, font_ids) in self.current_text_batch.iter().zip(&text_font_ids) {
+            let (_, layout, ..) = texts.get(world, *ent).unwrap();
+
+            let glyph_tess = match previous_glyph_tesses.remove(ent) {
+                Some(glyph_tess)
+                    if &glyph_tess.layout == layout
+                        && glyph_tess.atlas_generation == self.atlas.generation() =>
+                {
+                    glyph_tess
+                }
+                _ => GlyphTess {
+                    layout: layout.clone(),
+                    atlas_generation: self.atlas.generation(),
+                    tess: build_glyph_tess(surface, &layout.glyph_quads(&self.atlas, font_ids)),
+                },
+            };
+
+            self.glyph_tesses.insert(*ent, glyph_tess);
+        }
+
+        renderables
+    }
+
+    fn render(
+        &mut self,
+        world: &mut World,
+        surface: &mut Surface,
+        _texture_cache: &mut TextureCache,
+        frame_context: &FrameContext,
+        target_framebuffer: &SceneFramebuffer,
+        renderables: &[RenderHookRenderableHandle],
+    ) {
+        let Self {
+            program,
+            atlas_texture,
+            glyph_tesses,
+            current_text_batch,
+            ..
+        } = self;
+
+        // Create the text query
+        let mut texts = world.query::<(&TextLayout, &Sprite, &GlobalTransform)>();
+
+        // Create the render state
+        let render_state = &RenderState::default()
+            .set_blending_separate(
+                Blending {
+                    equation: Equation::Additive,
+                    src: Factor::SrcAlpha,
+                    dst: Factor::SrcAlphaComplement,
+                },
+                Blending {
+                    equation: Equation::Additive,
+                    src: Factor::SrcAlpha,
+                    dst: Factor::SrcAlphaComplement,
+                },
+            )
+            .set_depth_test(Some(DepthComparison::LessOrEqual));
+
+        // Do the render
+        surface
+            .new_pipeline_gate()
+            .pipeline(
+                // Render to the scene framebuffer
+                target_framebuffer,
+                &PipelineState::default()
+                    .enable_clear_color(false)
+                    .enable_clear_depth(false),
+                |pipeline, mut shading_gate| {
+                    // All of the text is drawn from the same atlas
+                    let bound_atlas = pipeline.bind_texture(atlas_texture).unwrap();
+
+                    shading_gate.shade(program, |mut interface, uniforms, mut render_gate| {
+                        // Set the camera and atlas uniforms
+                        interface.set(
+                            &uniforms.camera_position,
+                            [frame_context.camera_pos.x, frame_context.camera_pos.y],
+                        );
+                        interface.set(
+                            &uniforms.camera_size,
+                            [
+                                frame_context.target_sizes.low.x as i32,
+                                frame_context.target_sizes.low.y as i32,
+                            ],
+                        );
+                        interface.set(
+                            &uniforms.camera_centered,
+                            if frame_context.camera.centered { 1 } else { 0 },
+                        );
+                        interface.set(&uniforms.atlas, bound_atlas.binding());
+
+                        for renderable in renderables {
+                            let text_entity = current_text_batch
+                                .get(renderable.identifier)
+                                .expect("Tried to render non-existent renderable");
+
+                            // Get the glyph quads of the text, skipping text without any glyphs
+                            let tess = if let Some(tess) =
+                                glyph_tesses.get(text_entity).and_then(|x| x.tess.as_ref())
+                            {
+                                tess
+                            } else {
+                                continue;
+                            };
+
+                            let (layout, sprite, world_transform) =
+                                texts.get(world, *text_entity).unwrap();
+
+                            // Set the text uniforms
+                            interface.set(
+                                &uniforms.pixel_perfect,
+                                if sprite.pixel_perfect { 1 } else { 0 },
+                            );
+                            interface.set(
+                                &uniforms.text_size,
+                                [layout.size.x as i32, layout.size.y as i32],
+                            );
+                            interface
+                                .set(&uniforms.text_centered, if sprite.centered { 1 } else { 0 });
+                            let pos = world_transform.translation;
+                            interface.set(&uniforms.text_position, [pos.x, pos.y, pos.z]);
+                            interface
+                                .set(&uniforms.text_offset, [sprite.offset.x, sprite.offset.y]);
+
+                            // Render the glyphs
+                            render_gate
+                                .render(render_state, |mut tess_gate| tess_gate.render(tess))?;
+                        }
+
+                        Ok(())
+                    })
+                },
+            )
+            .assume()
+            .into_result()
+            .expect("Could not render");
+    }
+}
+
+/// Upload glyph quads to the GPU
+fn build_glyph_tess(surface: &mut Surface, quads: &[GlyphQuad]) -> Option<Tess<GlyphVert, u32>> {
+    if quads.is_empty() {
+        return None;
+    }
+
+    let (vertices, indices) = glyph_quad_mesh(quads, |pos, uv, color| {
+        GlyphVert::new(
+            VertexPosition::new(pos),
+            VertexUv::new(uv),
+            VertexColor::new(color),
+        )
+    });
+
+    Some(
+        surface
+            .new_tess()
+            .set_mode(luminance::tess::Mode::Triangle)
+            .set_vertices(vertices)
+            .set_indices(indices)
+            .build()
+            .expect("Could not create glyph tesselation"),
+    )
+}
@@ -0,0 +1,9 @@
+varying vec2 uv;
+varying vec4 color;
+
+uniform sampler2D atlas;
+
+void main() {
+  // The glyphs in the atlas are white, so we tint them with the glyph color
+  gl_FragColor = color * texture2D(atlas, uv);
+}
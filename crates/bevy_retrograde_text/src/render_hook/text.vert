@@ -0,0 +1,70 @@
+attribute vec2 v_pos;
+attribute vec2 v_uv;
+attribute vec4 v_color;
+
+varying vec2 uv;
+varying vec4 color;
+
+uniform ivec2 camera_size;
+uniform vec2 camera_position;
+uniform bool camera_centered;
+
+uniform bool pixel_perfect;
+
+uniform ivec2 text_size;
+uniform bool text_centered;
+uniform vec3 text_position;
+uniform vec2 text_offset;
+
+// Define our own round function because WebGL1 doesn't come with
+float round_f(float num) {
+  float fractional = num - floor(num);
+  if (fractional >= 0.5) {
+    return ceil(num);
+  } else {
+    return floor(num);
+  }
+}
+
+void main() {
+  uv = v_uv;
+  color = v_color;
+
+  // Get the camera position, possibly adjusted to center the view
+  vec2 adjusted_camera_pos = camera_position;
+  if (camera_centered) {
+    adjusted_camera_pos -= vec2(camera_size) / 2.0;
+  }
+
+  // Round the text position if it is in pixel-perfect mode
+  vec3 text_pos_adjusted = text_position;
+  if (pixel_perfect) {
+    text_pos_adjusted = vec3(
+      round_f(text_position.x),
+      round_f(text_position.y),
+      text_position.z
+    );
+  }
+
+  // Get the pixel screen position of the text
+  vec2 screen_pos = text_pos_adjusted.xy - adjusted_camera_pos + text_offset;
+
+  // The vertex position is already in pixels, relative to the top-left corner of the text
+  vec2 vertex_pos = v_pos;
+
+  // Center the text if necessary
+  if (text_centered) {
+    vertex_pos -= vec2(text_size) / 2.0;
+  }
+
+  // Calculate the normalized coordinate of this vertice
+  vec2 norm_pos = ((vertex_pos + screen_pos) / vec2(camera_size) - 0.5) * 2.0;
+
+  // Normalize the text Z component, allocating 2048 layers -1023 to 1024
+  float norm_z = (-text_position.z + 1024.0) / 2048.0;
+
+  // Invert the y component
+  vec2 pos = norm_pos * vec2(1.0, -1.0);
+
+  gl_Position = vec4(pos, norm_z, 1.);
+}
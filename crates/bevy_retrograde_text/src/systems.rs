@@ -1,14 +1,47 @@
-use bdf::Glyph;
 use bevy_retrograde_core::{
-    image::{GenericImage, Rgba, RgbaImage},
+    image::{Rgba, RgbaImage},
     prelude::*,
 };
-use unicode_linebreak::BreakOpportunity;
 
 use crate::*;
 
-trait GlyphExt {
-    fn real_width(&self) -> u32;
+/// Advance the effect clocks of text entities with effects, adding clocks to new text entities with
+/// effects and removing them from text entities that no longer have any
+pub(crate) fn advance_text_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut texts: Query<(
+        Entity,
+        &Text,
+        ChangeTrackers<Text>,
+        Option<&mut TextEffectClock>,
+    )>,
+) {
+    for (ent, text, text_tracker, clock) in texts.iter_mut() {
+        let clock: Option<Mut<TextEffectClock>> = clock;
+
+        match (text.has_effects(), clock) {
+            (true, None) => {
+                commands.entity(ent).insert(TextEffectClock::default());
+            }
+            (true, Some(mut clock)) => {
+                if text_tracker.is_changed() {
+                    clock.elapsed = 0.;
+                // Only animated effects need to be re-rasterized as time passes
+                } else if text
+                    .spans
+                    .iter()
+                    .any(|span| span.effect.map(|x| x.is_animated()) == Some(true))
+                {
+                    clock.elapsed += time.delta_seconds();
+                }
+            }
+            (false, Some(_)) => {
+                commands.entity(ent).remove::<TextEffectClock>();
+            }
+            (false, None) => (),
+        }
+    }
 }
 
 pub(crate) fn font_rendering(
@@ -19,51 +52,207 @@ pub(crate) fn font_rendering(
             &Handle<Font>,
             Option<&TextBlock>,
             Option<&mut Handle<Image>>,
+            Option<&RasterizedText>,
+            Option<&FontFallbacks>,
+            Option<&TextEffectClock>,
+            Option<&AnimatedTextImage>,
+            Option<&mut TextLayout>,
+            Option<&TextNeedsUpdate>,
         ),
         Or<(
             Added<Text>,
             Added<Handle<Font>>,
             Added<TextBlock>,
+            Added<FontFallbacks>,
             Changed<Text>,
             Changed<Handle<Font>>,
             Changed<TextBlock>,
+            Changed<FontFallbacks>,
+            Changed<TextEffectClock>,
             With<TextNeedsUpdate>,
         )>,
     >,
+    font_users: Query<(Entity, &Handle<Font>, Option<&FontFallbacks>), With<Text>>,
     mut commands: Commands,
     font_assets: Res<Assets<Font>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
     mut image_assets: ResMut<Assets<Image>>,
+    mut cache: ResMut<TextImageCache>,
 ) {
+    // Clear the cache and mark the text using the fonts for an update if any fonts have been
+    // reloaded
+    let modified_fonts = font_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut reloaded_texts = Vec::new();
+    if !modified_fonts.is_empty() {
+        cache.clear();
+
+        for (ent, font_handle, fallbacks) in font_users.iter() {
+            let uses_modified_font = std::iter::once(font_handle)
+                .chain(fallbacks.iter().flat_map(|x| x.iter()))
+                .any(|handle| modified_fonts.contains(&handle));
+            if uses_modified_font {
+                commands.entity(ent).insert(TextNeedsUpdate);
+                reloaded_texts.push(ent);
+            }
+        }
+    }
+
     // For all update text entities
-    for (ent, text, font_handle, text_block, image_handle) in texts.iter_mut() {
+    for (
+        ent,
+        text,
+        font_handle,
+        text_block,
+        image_handle,
+        rasterized_text,
+        fallbacks,
+        effect_clock,
+        animated_image,
+        text_layout,
+        needs_update,
+    ) in texts.iter_mut()
+    {
         // The block below fixes inferrence in Rust Analyzer 🤷‍♂️. It shouldn't be necessary once that's fixed
         let text: &Text = text;
         let text_block: Option<&TextBlock> = text_block;
         let image_handle: Option<Mut<Handle<Image>>> = image_handle;
+        let rasterized_text: Option<&RasterizedText> = rasterized_text;
+        let fallbacks: Option<&FontFallbacks> = fallbacks;
+        let effect_clock: Option<&TextEffectClock> = effect_clock;
+        let animated_image: Option<&AnimatedTextImage> = animated_image;
+        let text_layout: Option<Mut<TextLayout>> = text_layout;
+        let needs_update: Option<&TextNeedsUpdate> = needs_update;
 
-        // Try to load the font
-        let font = if let Some(font) = font_assets.get(font_handle) {
-            font
-        } else {
-            // Mark this text as needing an update if the font has not been loading yet so we can
-            // come back to it later
-            commands.entity(ent).insert(TextNeedsUpdate);
-            continue;
-        };
+        // Try to load the font and its fallbacks
+        let font = font_assets.get(font_handle);
+        let fallback_fonts = fallbacks
+            .iter()
+            .flat_map(|x| x.iter())
+            .map(|x| font_assets.get(x))
+            .collect::<Option<Vec<_>>>();
+        let (font, fallback_fonts) =
+            if let (Some(font), Some(fallback_fonts)) = (font, fallback_fonts) {
+                (font, fallback_fonts)
+            } else {
+                // Mark this text as needing an update if the fonts have not been loaded yet so we can
+                // come back to it later
+                commands.entity(ent).insert(TextNeedsUpdate);
+                continue;
+            };
 
         // Remove text update flag now that we are updating it
         commands.entity(ent).remove::<TextNeedsUpdate>();
 
-        let image = rasterize_text_block(text, font, text_block);
+        let effect_time = if text.has_effects() {
+            Some(effect_clock.map(|x| x.elapsed).unwrap_or(0.))
+        } else {
+            None
+        };
+
+        // Text without an outline or a shadow is drawn from the glyph atlas by the text render
+        // hook, so it only needs to be laid out
+        if text.outline.is_none() && text.shadow.is_none() {
+            let layout = layout_text_block(text, font, &fallback_fonts, text_block, effect_time);
 
-        // Update or add the new image handle to the entity
-        let new_image_handle = image_assets.add(Image(image));
+            commands
+                .entity(ent)
+                .insert(SpriteSize(layout.size))
+                .remove::<Handle<Image>>()
+                .remove::<RasterizedText>()
+                .remove::<AnimatedTextImage>();
+            match text_layout {
+                // Only update the layout if it has actually changed, so that the render hook
+                // doesn't have to re-build the glyph quads
+                Some(mut text_layout) => {
+                    if *text_layout != layout {
+                        *text_layout = layout;
+                    }
+                }
+                None => {
+                    commands.entity(ent).insert(layout);
+                }
+            }
+            continue;
+        }
+        commands
+            .entity(ent)
+            .remove::<TextLayout>()
+            .remove::<SpriteSize>();
+
+        // Text with effects is re-rasterized every time its effect clock advances, so instead of
+        // going through the cache it gets its own image that is updated in place
+        if let Some(elapsed) = effect_time {
+            let image = Image(rasterize_text_block_with_effects(
+                text,
+                font,
+                &fallback_fonts,
+                text_block,
+                elapsed,
+            ));
+
+            if let (Some(handle), Some(_)) = (&image_handle, animated_image) {
+                if let Some(existing_image) = image_assets.get_mut(&**handle) {
+                    *existing_image = image;
+                    continue;
+                }
+            }
+
+            let new_image_handle = image_assets.add(image);
+            if let Some(mut handle) = image_handle {
+                *handle = new_image_handle;
+            } else {
+                commands.entity(ent).insert(new_image_handle);
+            }
+            commands
+                .entity(ent)
+                .insert(AnimatedTextImage)
+                .remove::<RasterizedText>();
+            continue;
+        }
+
+        // Skip the update if the text has been touched without actually changing, unless it has been
+        // marked for an update because its fonts have been reloaded
+        let mut key = TextRasterKey::new(text, font_handle, text_block);
+        if let Some(fallbacks) = fallbacks {
+            key = key.with_fallbacks(fallbacks.iter());
+        }
+        if image_handle.is_some()
+            && needs_update.is_none()
+            && !reloaded_texts.contains(&ent)
+            && rasterized_text.map(|x| x.0) == Some(key)
+        {
+            continue;
+        }
+
+        // Get the image from the cache, or rasterize it if it isn't cached
+        let new_image_handle = if let Some(handle) = cache.get(&key, &image_assets) {
+            handle
+        } else {
+            let image =
+                rasterize_text_block_with_fallbacks(text, font, &fallback_fonts, text_block);
+            let handle = image_assets.add(Image(image));
+            cache.insert(key, &handle, &image_assets);
+
+            handle
+        };
+
+        // Update or add the new image handle to the entity. The old image will be unloaded once no
+        // other text entities are using it.
         if let Some(mut handle) = image_handle {
-            image_assets.remove(&*handle);
             *handle = new_image_handle;
         } else {
             commands.entity(ent).insert(new_image_handle);
         }
+        commands
+            .entity(ent)
+            .insert(RasterizedText(key))
+            .remove::<AnimatedTextImage>();
     }
 }
 
@@ -76,189 +265,192 @@ pub fn rasterize_text_block(
     font: &Font,
     text_block: Option<&TextBlock>,
 ) -> bevy_retrograde_core::image::ImageBuffer<Rgba<u8>, Vec<u8>> {
-    let default_glyph = font.glyphs.get(&' ');
-    let font_bounds = &font.bounds;
-
-    // Calculate line breaks for the text
-    let mut line_breaks = unicode_linebreak::linebreaks(&text.text).collect::<Vec<_>>();
-    line_breaks.reverse();
-    let line_breaks = line_breaks; // Make immutable
-
-    // Create a vector that holds all of the lines of the text and the glyphs in each line
-    let mut lines: Vec<Vec<Glyph>> = Default::default();
-
-    // The height of a line
-    let line_height = font.bounds.height;
-
-    // Start glyph layout
-    let mut current_line = Vec::new();
-    let mut line_x = 0; // The x position in the line we are currently at
-    for (char_i, char) in text.text.char_indices() {
-        // Get the glyph for this character
-        let glyph = font
-            .glyphs
-            .get(&char)
-            .or(default_glyph)
-            .unwrap_or_else(|| panic!("Font does not contain glyph for character: {:?}", char));
-
-        // Add the next glyph to the current line
-        current_line.push(glyph.clone());
-
-        // Wrap the line if necessary
-        if let Some(max_width) = text_block.map(|x| x.width) {
-            // Calculate the new x position of the line after adding this glyph
-            line_x += glyph.device_width.0;
-
-            // If this character must break the line
-            if line_breaks
-                .iter()
-                .any(|(i, op)| i == &(char_i + 1) && op == &BreakOpportunity::Mandatory)
-                // The last character always breaks, but we want to ignore that one
-                && char_i != text.text.len() - 1
-            {
-                // Add this line to the lines list
-                lines.push(current_line);
-                // Start a new line
-                current_line = Vec::new();
-                // Reset the line x position
-                line_x = 0;
-
-            // If the new line x goes over our max width, we need to find the last position we
-            // can break the line
-            } else if line_x > max_width {
-                for (break_i, line_break) in &line_breaks {
-                    match (break_i, line_break) {
-                        // We found a spot that we can break the line
-                        (split_i, unicode_linebreak::BreakOpportunity::Allowed)
-                            if split_i < &char_i =>
-                        {
-                            // Figure out how many character will be broken off
-                            let broken_chars = char_i - split_i;
-                            // Get the point in the line at which to break it
-                            let split_at = current_line.len() - 1 - broken_chars;
-                            // Split the broken off characters into a new line
-                            let next_line = current_line.split_off(split_at);
-                            // Add the current line to the lines list
-                            lines.push(current_line);
-                            // Set the new current line to the next line
-                            current_line = next_line;
-                            // Reset our current line x counter to the length of the new current
-                            // line
-                            line_x = current_line
-                                .iter()
-                                .fold(0, |width, g| width + g.device_width.0);
-                            break;
-                        }
-                        _ => (),
-                    }
+    rasterize_text_block_with_fallbacks(text, font, &[], text_block)
+}
+
+/// Get the image for a text block, using glyphs from the fallback fonts, in order, for any
+/// characters that are missing from the font
+///
+/// Glyphs from fallback fonts are aligned to the baseline of the primary font. Characters that
+/// are not in any of the fonts are rendered as a space, or skipped if the font doesn't have a
+/// space.
+///
+/// The [`TextEffect`]s of the text are ignored. Use [`rasterize_text_block_with_effects`] to draw
+/// them.
+pub fn rasterize_text_block_with_fallbacks(
+    text: &Text,
+    font: &Font,
+    fallbacks: &[&Font],
+    text_block: Option<&TextBlock>,
+) -> bevy_retrograde_core::image::ImageBuffer<Rgba<u8>, Vec<u8>> {
+    rasterize(text, font, fallbacks, text_block, None)
+}
+
+/// Get the image for a text block with its [`TextEffect`]s drawn as they are the given number of
+/// seconds after the effects started
+///
+/// The image is padded on every side by the furthest distance that the effects can move a
+/// character.
+pub fn rasterize_text_block_with_effects(
+    text: &Text,
+    font: &Font,
+    fallbacks: &[&Font],
+    text_block: Option<&TextBlock>,
+    elapsed: f32,
+) -> bevy_retrograde_core::image::ImageBuffer<Rgba<u8>, Vec<u8>> {
+    rasterize(text, font, fallbacks, text_block, Some(elapsed))
+}
+
+fn rasterize(
+    text: &Text,
+    font: &Font,
+    fallbacks: &[&Font],
+    text_block: Option<&TextBlock>,
+    effect_time: Option<f32>,
+) -> RgbaImage {
+    let layout = layout_text_block(text, font, fallbacks, text_block, effect_time);
+    let fonts = std::iter::once(font)
+        .chain(fallbacks.iter().copied())
+        .collect::<Vec<_>>();
+
+    // Create a new image the size of the text block
+    let mut image = RgbaImage::new(layout.size.x, layout.size.y);
+    let (image_width, image_height) = image.dimensions();
+
+    for laid_out_glyph in &layout.glyphs {
+        let glyph = if let Some(glyph) = fonts
+            .get(laid_out_glyph.font)
+            .and_then(|x| x.glyphs.get(&laid_out_glyph.codepoint))
+        {
+            glyph
+        } else {
+            continue;
+        };
+        let color = &laid_out_glyph.color;
+        let pixel_color = Rgba([
+            (255. * color.r).round() as u8,
+            (255. * color.g).round() as u8,
+            (255. * color.b).round() as u8,
+            (255. * color.a).round() as u8,
+        ]);
+
+        // Get the pixels covered by the glyph after it has been scaled
+        let position = laid_out_glyph.position;
+        let scaled_width = glyph.bounds.width as f32 * layout.scale;
+        let scaled_height = glyph.bounds.height as f32 * layout.scale;
+        let min_x = position.x.floor().max(0.) as u32;
+        let min_y = position.y.floor().max(0.) as u32;
+        let max_x = ((position.x + scaled_width).ceil().max(0.) as u32).min(image_width);
+        let max_y = ((position.y + scaled_height).ceil().max(0.) as u32).min(image_height);
+
+        for pixel_x in min_x..max_x {
+            for pixel_y in min_y..max_y {
+                // Sample the glyph bitmap with nearest neighbor filtering
+                let x = ((pixel_x as f32 + 0.5 - position.x) / layout.scale).floor();
+                let y = ((pixel_y as f32 + 0.5 - position.y) / layout.scale).floor();
+                if x < 0.
+                    || y < 0.
+                    || x as u32 >= glyph.bounds.width
+                    || y as u32 >= glyph.bounds.height
+                {
+                    continue;
+                }
+
+                if glyph.bitmap.get(x as u32, y as u32) {
+                    image.put_pixel(pixel_x, pixel_y, pixel_color);
                 }
             }
         }
     }
-    lines.push(current_line);
 
-    // Get the height of the lines of the text block
-    let lines_height = line_height * lines.len() as u32;
+    apply_text_effects(image, text)
+}
+
+/// Apply the outline and shadow of the text to the rasterized text image
+///
+/// The image is padded on every side so that there is room for the effects.
+fn apply_text_effects(image: RgbaImage, text: &Text) -> RgbaImage {
+    if text.outline.is_none() && text.shadow.is_none() {
+        return image;
+    }
+
+    let thickness = text
+        .outline
+        .as_ref()
+        .map(|x| x.thickness as i32)
+        .unwrap_or(0);
+    let shadow_offset = text.shadow.as_ref().map(|x| x.offset).unwrap_or_default();
+    let padding = thickness + shadow_offset.x.abs().max(shadow_offset.y.abs());
 
-    // Calculate the height and width of the text block image
-    let image_height = lines_height.max(text_block.map(|x| x.height).flatten().unwrap_or(0));
-    let image_width = lines.iter().fold(0, |width, line| {
-        let line_width = line
-            .iter()
-            .fold(0, |width, glyph| width + glyph.device_width.0);
+    let (width, height) = image.dimensions();
+    let padded_width = width + padding as u32 * 2;
+    let padded_height = height + padding as u32 * 2;
 
-        if line_width > width {
-            line_width
-        } else {
-            width
+    // Get the mask of the pixels covered by the outlined glyphs
+    let mut mask = vec![false; (padded_width * padded_height) as usize];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] == 0 {
+            continue;
         }
-    }) as u32;
-    // Make sure image is at least as wide as the specified text block width
-    let image_width = text_block
-        .map(|x| x.width.max(image_width))
-        .unwrap_or(image_width);
-
-    // Create a new image the size of the text box
-    let mut image: RgbaImage = RgbaImage::new(image_width, image_height);
-
-    // Calculate the y offset to account for vertical alignment
-    let y_offset = text_block
-        .map(|block| match (block.height, &block.vertical_align) {
-            (None, _) => 0,
-            (_, TextVerticalAlign::Top) => 0,
-            (Some(_), TextVerticalAlign::Middle) => (image_height - lines_height) / 2,
-            (Some(_), TextVerticalAlign::Bottom) => image_height - lines_height,
-        })
-        .unwrap_or(0);
 
-    // Loop through all the lines
-    for (line_i, line) in lines.iter().enumerate() {
-        let line_y = line_i as u32 * line_height;
-        let mut line_x = 0u32;
-
-        // Calculate the x offset to account for text alignment
-        let x_offset = text_block
-            .map(|block| match &block.horizontal_align {
-                TextHorizontalAlign::Left => 0,
-                other => {
-                    // Get the full width of the characters in this line
-                    let chars_width = line
-                        .iter()
-                        .fold(0, |width, glyph| width + glyph.device_width.0);
-
-                    match other {
-                        TextHorizontalAlign::Center => {
-                            (image_width - chars_width.min(image_width)) / 2
-                        }
-                        TextHorizontalAlign::Right => image_width - chars_width.min(image_width),
-                        _ => 0, // unreachable, but this works, too
-                    }
+        for dx in -thickness..=thickness {
+            for dy in -thickness..=thickness {
+                let mask_x = (x as i32 + padding + dx) as u32;
+                let mask_y = (y as i32 + padding + dy) as u32;
+                mask[(mask_y * padded_width + mask_x) as usize] = true;
+            }
+        }
+    }
+
+    let to_rgba = |color: &Color| {
+        Rgba([
+            (255. * color.r).round() as u8,
+            (255. * color.g).round() as u8,
+            (255. * color.b).round() as u8,
+            (255. * color.a).round() as u8,
+        ])
+    };
+
+    let mut padded_image = RgbaImage::new(padded_width, padded_height);
+
+    // Draw the shadow of the outlined glyphs
+    if let Some(shadow) = &text.shadow {
+        let shadow_color = to_rgba(&shadow.color);
+        for x in 0..padded_width {
+            for y in 0..padded_height {
+                let shadow_x = x as i32 + shadow.offset.x;
+                let shadow_y = y as i32 + shadow.offset.y;
+
+                if mask[(y * padded_width + x) as usize]
+                    && shadow_x >= 0
+                    && shadow_y >= 0
+                    && (shadow_x as u32) < padded_width
+                    && (shadow_y as u32) < padded_height
+                {
+                    padded_image.put_pixel(shadow_x as u32, shadow_y as u32, shadow_color);
                 }
-            })
-            .unwrap_or(0);
-
-        // Loop through all the glyphs in each line
-        for glyph in line {
-            // Get bounds
-            let bounds = &glyph.bounds;
-
-            // Skip rasterizing whitespace chars
-            if !glyph.codepoint.is_whitespace() {
-                // Create a sub-image of the text block for the area occupied by the glyph
-                let mut sub_img = image.sub_image(
-                    line_x + x_offset,
-                    line_y + y_offset,
-                    bounds.width,
-                    bounds.height,
-                );
-
-                for x in 0..bounds.width {
-                    for y in 0..bounds.height {
-                        let pixel = sub_img.get_pixel_mut(
-                            x,
-                            (y as i32 + font_bounds.height as i32 + font_bounds.y
-                                - bounds.height as i32
-                                - bounds.y) as u32,
-                        );
-
-                        *pixel = Rgba([
-                            (255. * text.color.r).round() as u8,
-                            (255. * text.color.g).round() as u8,
-                            (255. * text.color.b).round() as u8,
-                            if glyph.bitmap.get(x, y) {
-                                (255. * text.color.a).round() as u8
-                            } else {
-                                0
-                            },
-                        ]);
-                    }
+            }
+        }
+    }
+
+    // Draw the outline
+    if let Some(outline) = &text.outline {
+        let outline_color = to_rgba(&outline.color);
+        for x in 0..padded_width {
+            for y in 0..padded_height {
+                if mask[(y * padded_width + x) as usize] {
+                    padded_image.put_pixel(x, y, outline_color);
                 }
             }
+        }
+    }
 
-            // Increment line position
-            line_x += glyph.device_width.0;
+    // Draw the glyphs on top
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] != 0 {
+            padded_image.put_pixel(x + padding as u32, y + padding as u32, *pixel);
         }
     }
 
-    image
+    padded_image
 }
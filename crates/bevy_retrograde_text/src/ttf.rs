@@ -0,0 +1,117 @@
+//! TTF/OTF font rasterizer
+//!
+//! Rasterizes the glyphs of a TTF/OTF font at a fixed pixel size into the same glyph
+//! representation that is used for BDF fonts.
+
+use ab_glyph::{point, Font as _, FontRef, PxScale, ScaleFont};
+use bevy::utils::HashMap;
+
+use crate::bdf::{Bitmap, BoundingBox, Font, Glyph};
+
+/// The settings used when rasterizing TTF/OTF fonts
+#[derive(Debug, Clone)]
+pub struct TtfFontSettings {
+    /// The height of the font in pixels
+    ///
+    /// Pixel fonts will usually only look sharp at the size, or multiples of the size, that they
+    /// were designed for.
+    pub size: u32,
+    /// The coverage, from `0.0` to `1.0`, that a pixel must have to be drawn
+    ///
+    /// Glyphs are never antialiased, every pixel is either fully drawn or not drawn at all.
+    pub threshold: f32,
+}
+
+impl Default for TtfFontSettings {
+    fn default() -> Self {
+        Self {
+            size: 16,
+            threshold: 0.5,
+        }
+    }
+}
+
+pub fn rasterize(bytes: &[u8], settings: &TtfFontSettings) -> Result<Font, ab_glyph::InvalidFont> {
+    let font = FontRef::try_from_slice(bytes)?;
+    let scale = PxScale::from(settings.size as f32);
+    let scaled_font = font.as_scaled(scale);
+
+    // Round the font metrics to whole pixels. These are grown below if any glyph extends past them
+    // so that no glyph gets cut off.
+    let mut ascent = scaled_font.ascent().ceil() as i32;
+    let mut descent = scaled_font.descent().floor() as i32;
+
+    let mut glyphs = HashMap::default();
+    let mut max_width = 0;
+    for (glyph_id, codepoint) in font.codepoint_ids() {
+        // Round the advance so that every glyph starts on a pixel boundary
+        let advance = scaled_font.h_advance(glyph_id).round().max(0.) as u32;
+
+        let glyph = glyph_id.with_scale_and_position(scale, point(0., 0.));
+        let (bitmap, bounds) = if let Some(outline) = font.outline_glyph(glyph) {
+            let px_bounds = outline.px_bounds();
+
+            // Glyphs are drawn starting at the left edge of the glyph cell, so we bake the left
+            // side bearing into the bitmap
+            let x_offset = px_bounds.min.x.max(0.) as u32;
+            let width = px_bounds.width() as u32 + x_offset;
+            let height = px_bounds.height() as u32;
+
+            let mut bitmap = Bitmap::new(width, height);
+            outline.draw(|x, y, coverage| {
+                if coverage >= settings.threshold {
+                    bitmap.set(x + x_offset, y, true);
+                }
+            });
+
+            (
+                bitmap,
+                BoundingBox {
+                    width,
+                    height,
+                    x: 0,
+                    // BDF glyph offsets are measured upwards from the baseline
+                    y: -px_bounds.max.y as i32,
+                },
+            )
+        } else {
+            (
+                Bitmap::new(0, 0),
+                BoundingBox {
+                    width: 0,
+                    height: 0,
+                    x: 0,
+                    y: 0,
+                },
+            )
+        };
+
+        max_width = max_width.max(advance).max(bounds.width);
+        ascent = ascent.max(bounds.y + bounds.height as i32);
+        descent = descent.min(bounds.y);
+        glyphs.insert(
+            codepoint,
+            Glyph {
+                codepoint,
+                device_width: (advance, 0),
+                scalable_width: (advance, 0),
+                bounds,
+                bitmap,
+            },
+        );
+    }
+
+    Ok(Font {
+        font_spec: String::new(),
+        font_size: (settings.size, 72, 72),
+        glyphs,
+        bounds: BoundingBox {
+            width: max_width,
+            height: (ascent - descent) as u32,
+            x: 0,
+            y: descent,
+        },
+        comments: Vec::new(),
+        properties: HashMap::default(),
+    })
+}
@@ -0,0 +1,101 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+use bevy_retrograde_core::assets::Image;
+
+/// A Tiled map asset
+#[derive(TypeUuid)]
+#[uuid = "2d5f4a5c-8a41-4c0b-9b7e-5f1f3f9f0c2e"]
+pub struct TiledMap {
+    /// The full structure of the Tiled map
+    pub map: tiled::Map,
+    /// A mapping of tileset first gid's to their texture handles
+    pub tilesets: HashMap<u32, Handle<Image>>,
+}
+
+impl TiledMap {
+    /// Get the tileset that a global tile id belongs to
+    pub fn tileset_for_gid(&self, gid: u32) -> Option<&tiled::Tileset> {
+        self.map
+            .tilesets
+            .iter()
+            .filter(|x| x.first_gid <= gid)
+            .max_by_key(|x| x.first_gid)
+    }
+}
+
+/// Add asset types and asset loader to the app builder
+pub(crate) fn add_assets(app: &mut AppBuilder) {
+    app.add_asset::<TiledMap>()
+        .init_asset_loader::<TiledMapLoader>();
+}
+
+/// An error that occurs when loading a Tiled map
+#[derive(thiserror::Error, Debug)]
+pub enum TiledMapLoaderError {
+    #[error("Could not parse Tiled map file: {0}")]
+    ParsingError(#[from] tiled::TiledError),
+    #[error("Tileset `{0}` does not have an image")]
+    MissingTilesetImage(String),
+}
+
+/// A Tiled map asset loader
+#[derive(Default)]
+struct TiledMapLoader;
+
+impl AssetLoader for TiledMapLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        // Create a future for the load function
+        Box::pin(async move { Ok(load_tiled(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+}
+
+async fn load_tiled<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), TiledMapLoaderError> {
+    // Parse the Tiled map file
+    let map = tiled::parse(bytes)?;
+
+    // Create our dependency list
+    let mut dependencies = Vec::new();
+    let mut tilesets = HashMap::default();
+
+    // Loop through the tilesets
+    for tileset in &map.tilesets {
+        let image = tileset
+            .images
+            .first()
+            .ok_or_else(|| TiledMapLoaderError::MissingTilesetImage(tileset.name.clone()))?;
+
+        // Get the path to the tileset image asset
+        let file_path = load_context.path().parent().unwrap().join(&image.source);
+        let asset_path = AssetPath::new(file_path, None);
+
+        // Add asset to our dependencies list to make sure it is loaded by the asset
+        // server when our map is.
+        dependencies.push(asset_path.clone());
+
+        // Obtain a handle to the tileset image asset
+        let handle: Handle<Image> = load_context.get_handle(asset_path);
+        tilesets.insert(tileset.first_gid, handle);
+    }
+
+    // Set the loaded map as the default asset for this file
+    load_context.set_default_asset(
+        LoadedAsset::new(TiledMap { map, tilesets }).with_dependencies(dependencies),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use crate::asset::TiledMap;
+
+/// A component bundle for spawning a Tiled map
+#[derive(Default, Bundle)]
+pub struct TiledMapBundle {
+    /// The handle to a map asset
+    pub map: Handle<TiledMap>,
+    /// The transform of the map
+    pub transform: Transform,
+    /// The world position
+    pub global_transform: GlobalTransform,
+}
+
+/// Component added to spawned map tile layers
+pub struct TiledMapLayer {
+    pub map: Handle<TiledMap>,
+    /// The name of the layer in Tiled
+    pub name: String,
+    /// The custom properties of the layer
+    pub properties: tiled::Properties,
+    /// The collision shapes of all of the tiles in the layer that have collision shapes defined in
+    /// their tileset
+    pub colliders: Vec<TiledTileCollider>,
+}
+
+/// A collision shape of a tile in a [`TiledMapLayer`]
+#[derive(Debug, Clone)]
+pub struct TiledTileCollider {
+    /// The position of the shape relative to the top-left corner of the layer
+    pub position: Vec2,
+    /// The shape of the collider, relative to its position
+    pub shape: tiled::ObjectShape,
+}
+
+/// Component added to the entities spawned for the objects in a map's object layers
+///
+/// The entity is spawned as a child of the map with its transform set to the position of the
+/// object.
+#[derive(Debug, Clone)]
+pub struct TiledObject {
+    pub map: Handle<TiledMap>,
+    /// The name of the object layer that the object is in
+    pub layer_name: String,
+    /// The full object data
+    pub object: tiled::Object,
+}
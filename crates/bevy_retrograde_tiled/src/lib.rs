@@ -0,0 +1,65 @@
+//! A [Bevy Retrograde][br] plugin for loading [Tiled] tile maps.
+//!
+//! [tiled]: https://www.mapeditor.org/
+//!
+//! [br]: https://github.com/katharostech/bevy_retrograde
+//!
+//! Tiled maps are spawned the same way as LDtk maps: each tile layer is rendered into a single
+//! sprite that is spawned as a child of the map entity with a [`TiledMapLayer`] component, and
+//! each object in an object layer is spawned as a child of the map with a [`TiledObject`]
+//! component.
+//!
+//! The collision shapes that are defined for tiles in the Tiled tileset editor are collected on the
+//! [`TiledMapLayer`] component so that they can be used to create colliders for the layer.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_tiled::*;
+//! fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     commands.spawn_bundle(TiledMapBundle {
+//!         map: asset_server.load("maps/level1.tmx"),
+//!         ..Default::default()
+//!     });
+//! }
+//! ```
+//!
+//! # Caveats
+//!
+//! - Only orthogonal maps are supported.
+//! - Tilesets must be embedded in the map file. External `.tsx` tilesets are not supported yet.
+//! - Infinite maps and image layers are not supported yet.
+//! - Tile objects are spawned as [`TiledObject`]s, but their tile image is not rendered.
+//!
+//! # License
+//!
+//! Bevy Retrograde Tiled is licensed under the [Katharos License][k_license] which places certain
+//! restrictions on what you are allowed to use it for. Please read and understand the terms before
+//! using Bevy Retrograde Tiled for your project.
+//!
+//! [k_license]: https://github.com/katharostech/katharos-license
+
+use bevy::prelude::*;
+
+mod asset;
+mod components;
+mod system;
+
+pub use asset::*;
+pub use components::*;
+
+/// Re-export of the [`tiled`] crate
+pub use tiled;
+
+use system::add_systems;
+
+/// Bevy plugin that adds support for loading Tiled tile maps
+#[derive(Default)]
+pub struct TiledPlugin;
+
+impl Plugin for TiledPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // Add asssets and systems
+        add_assets(app);
+        add_systems(app);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{asset::TiledMap, TiledMapLayer, TiledObject, TiledTileCollider};
+use bevy::{ecs::component::ComponentDescriptor, prelude::*};
+
+use bevy_retrograde_core::{
+    image::{
+        self,
+        imageops::{self, flip_horizontal_in_place, flip_vertical_in_place},
+        GenericImage, GenericImageView,
+    },
+    prelude::*,
+};
+
+#[derive(Debug, Clone, Copy, StageLabel, Hash, PartialEq, Eq, SystemLabel)]
+struct HotReloadSystem;
+
+/// Add the Tiled map systems to the app builder
+pub(crate) fn add_systems(app: &mut AppBuilder) {
+    app
+        // Register our marker component with sparse storage
+        .register_component(ComponentDescriptor::new::<TiledMapHasLoaded>(
+            bevy::ecs::component::StorageType::SparseSet,
+        ))
+        .add_system_set_to_stage(
+            CoreStage::PreUpdate,
+            SystemSet::new()
+                .with_system(hot_reload_maps.system().label(HotReloadSystem))
+                .with_system(process_tiled_maps.system().after(HotReloadSystem)),
+        );
+}
+
+struct TiledMapHasLoaded;
+
+/// This system spawns the map layers and objects for every unloaded entity with a Tiled map
+fn process_tiled_maps(
+    mut commands: Commands,
+    new_maps: Query<(Entity, &Handle<TiledMap>), Without<TiledMapHasLoaded>>,
+    map_assets: Res<Assets<TiledMap>>,
+    mut image_assets: ResMut<Assets<Image>>,
+) {
+    // Loop through all of the maps
+    'load_map: for (map_ent, map_handle) in new_maps.iter() {
+        // Get the map asset, if available
+        let tiled_map = if let Some(map) = map_assets.get(map_handle) {
+            map
+        } else {
+            continue;
+        };
+        let map = &tiled_map.map;
+
+        // Wait for all of the tilesets to load
+        for image_handle in tiled_map.tilesets.values() {
+            if image_assets.get(image_handle).is_none() {
+                continue 'load_map;
+            }
+        }
+
+        if map.orientation != tiled::Orientation::Orthogonal {
+            warn!("Only orthogonal Tiled maps are supported, the map may not display correctly");
+        }
+
+        // Spawn the tile layers
+        for layer in &map.layers {
+            let rows = match &layer.tiles {
+                tiled::LayerData::Finite(rows) => rows,
+                tiled::LayerData::Infinite(_) => {
+                    warn!(
+                        "Skipping layer `{}`: infinite Tiled maps are not supported",
+                        layer.name
+                    );
+                    continue;
+                }
+            };
+
+            // Create the layer image
+            let width = map.width * map.tile_width;
+            let height = map.height * map.tile_height;
+            let mut layer_image = image::RgbaImage::new(width, height);
+            let mut colliders = Vec::new();
+
+            // For every tile in the layer
+            for (y, row) in rows.iter().enumerate() {
+                for (x, tile) in row.iter().enumerate() {
+                    // Skip empty tiles
+                    if tile.gid == 0 {
+                        continue;
+                    }
+
+                    // Get the tileset and its image
+                    let tileset = if let Some(tileset) = tiled_map.tileset_for_gid(tile.gid) {
+                        tileset
+                    } else {
+                        continue;
+                    };
+                    let tileset_image = if let Some(image) = tiled_map
+                        .tilesets
+                        .get(&tileset.first_gid)
+                        .and_then(|x| image_assets.get(x))
+                    {
+                        image
+                    } else {
+                        continue;
+                    };
+
+                    // Find the tile in the tileset image, taking the margin and spacing into
+                    // account
+                    let tile_id = tile.gid - tileset.first_gid;
+                    let stride_x = tileset.tile_width + tileset.spacing;
+                    let stride_y = tileset.tile_height + tileset.spacing;
+                    let columns = ((tileset_image.width() + tileset.spacing)
+                        .saturating_sub(tileset.margin * 2)
+                        / stride_x)
+                        .max(1);
+                    let src_x = tileset.margin + tile_id % columns * stride_x;
+                    let src_y = tileset.margin + tile_id / columns * stride_y;
+                    if src_x + tileset.tile_width > tileset_image.width()
+                        || src_y + tileset.tile_height > tileset_image.height()
+                    {
+                        continue;
+                    }
+
+                    let mut tile_src = tileset_image
+                        .view(src_x, src_y, tileset.tile_width, tileset.tile_height)
+                        .to_image();
+
+                    // Apply the tile flips. Tiled applies the diagonal flip first.
+                    if tile.flip_d {
+                        tile_src = imageops::flip_horizontal(&imageops::rotate90(&tile_src));
+                    }
+                    if tile.flip_h {
+                        flip_horizontal_in_place(&mut tile_src);
+                    }
+                    if tile.flip_v {
+                        flip_vertical_in_place(&mut tile_src);
+                    }
+
+                    // Tiles are aligned to the bottom-left corner of their cell, which matters for
+                    // tilesets with a different tile size than the map
+                    let px_x = x as u32 * map.tile_width;
+                    let px_y = (y as u32 + 1) * map.tile_height;
+                    let px_y = px_y.saturating_sub(tile_src.height());
+
+                    // Overlay the tile on top of the layer
+                    let target_width = tile_src.width().min(width.saturating_sub(px_x));
+                    let target_height = tile_src.height().min(height.saturating_sub(px_y));
+                    let mut tile_target =
+                        layer_image.sub_image(px_x, px_y, target_width, target_height);
+                    imageops::overlay(&mut tile_target, &tile_src, 0, 0);
+
+                    // Collect the collision shapes of the tile
+                    let tile_collisions = tileset
+                        .tiles
+                        .iter()
+                        .find(|x| x.id == tile_id)
+                        .and_then(|x| x.objectgroup.as_ref());
+                    for object in tile_collisions.iter().flat_map(|x| &x.objects) {
+                        colliders.push(TiledTileCollider {
+                            position: Vec2::new(px_x as f32 + object.x, px_y as f32 + object.y),
+                            shape: object.shape.clone(),
+                        });
+                    }
+                }
+            }
+
+            // If the layer opacity is not 100%, adjust the transparency accordingly
+            if layer.opacity < 1.0 {
+                for pixel in layer_image.pixels_mut() {
+                    pixel[3] = (layer.opacity * pixel[3] as f32) as u8;
+                }
+            }
+
+            // Spawn the layer
+            let layer_ent = commands
+                .spawn_bundle(SpriteBundle {
+                    image: image_assets.add(Image::from(layer_image)),
+                    sprite: Sprite {
+                        centered: false,
+                        ..Default::default()
+                    },
+                    visible: Visible(layer.visible),
+                    // Each layer is 2 units higher than the one before it
+                    transform: Transform::from_xyz(0., 0., layer.layer_index as f32 * 2.),
+                    ..Default::default()
+                })
+                .insert(TiledMapLayer {
+                    map: map_handle.clone(),
+                    name: layer.name.clone(),
+                    properties: layer.properties.clone(),
+                    colliders,
+                })
+                .id();
+
+            // Make the layer a child of the map entity
+            commands.entity(map_ent).push_children(&[layer_ent]);
+        }
+
+        // Spawn the objects in the object layers
+        for object_group in &map.object_groups {
+            let z = object_group.layer_index.unwrap_or(0) as f32 * 2.;
+
+            let objects = object_group
+                .objects
+                .iter()
+                .map(|object| {
+                    commands
+                        .spawn_bundle((
+                            Transform::from_xyz(object.x, object.y, z),
+                            GlobalTransform::default(),
+                        ))
+                        .insert(TiledObject {
+                            map: map_handle.clone(),
+                            layer_name: object_group.name.clone(),
+                            object: object.clone(),
+                        })
+                        .id()
+                })
+                .collect::<Vec<_>>();
+
+            // Make the objects children of the map entity
+            commands.entity(map_ent).push_children(&objects);
+        }
+
+        // Mark the map as having been loaded so that we don't process it again
+        commands.entity(map_ent).insert(TiledMapHasLoaded);
+    }
+}
+
+type MapEvent = AssetEvent<TiledMap>;
+
+/// This system watches for changes to map assets and makes sure that the map is reloaded upon
+/// changes.
+fn hot_reload_maps(
+    mut commands: Commands,
+    mut events: EventReader<MapEvent>,
+    layers: Query<(Entity, &TiledMapLayer, &Handle<Image>)>,
+    objects: Query<(Entity, &TiledObject)>,
+    maps: Query<(Entity, &Handle<TiledMap>)>,
+    mut image_assets: ResMut<Assets<Image>>,
+) {
+    for event in events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            // Despawn the layers of this map and remove their images
+            for (layer_ent, TiledMapLayer { map, .. }, image_handle) in layers.iter() {
+                if map == handle {
+                    commands.entity(layer_ent).despawn();
+                    image_assets.remove(image_handle);
+                }
+            }
+
+            // Despawn the objects of this map
+            for (object_ent, TiledObject { map, .. }) in objects.iter() {
+                if map == handle {
+                    commands.entity(object_ent).despawn_recursive();
+                }
+            }
+
+            // Then remove the `TiledMapHasLoaded` component from the map so that it will be
+            // reloaded by the `process_tiled_maps` system.
+            for (map_ent, map_handle) in maps.iter() {
+                if map_handle == handle {
+                    commands.entity(map_ent).remove::<TiledMapHasLoaded>();
+                }
+            }
+        }
+    }
+}
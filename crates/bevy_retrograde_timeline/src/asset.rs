@@ -0,0 +1,126 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_retrograde_core::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+/// A sequence of actions that are played one after the other, such as a cutscene
+///
+/// Timelines can be created in code or loaded from `.timeline` files, which are YAML lists of
+/// [`TimelineAction`]s, and are played with the [`TimelinePlayer`][crate::TimelinePlayer].
+///
+/// ```yaml
+/// - fade: { color: { r: 0, g: 0, b: 0, a: 0 }, duration: 1 }
+/// - parallel:
+///     - move: { entity: hero, points: [[32, 0], [64, 16]], speed: 24 }
+///     - animation: { entity: hero, state: walk }
+/// - animation: { entity: hero, state: idle }
+/// - sound: sounds/door.ogg
+/// - wait: 0.5
+/// - dialog: { speaker: Old Man, text: "It's dangerous to go alone!" }
+/// - event: give_sword
+/// ```
+#[derive(TypeUuid, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[uuid = "96effeab-a6d9-4787-bdf5-989eb4d24f6f"]
+#[serde(transparent)]
+pub struct Timeline {
+    pub actions: Vec<TimelineAction>,
+}
+
+/// An action of a [`Timeline`]
+///
+/// Entities are found by their Bevy [`Name`] component.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineAction {
+    /// Wait for the given number of seconds
+    Wait(f32),
+    /// Move an entity through the points, in world space, one after the other
+    ///
+    /// The entity is moved in whole pixels, starting from where it is when the action starts.
+    Move {
+        entity: String,
+        points: Vec<Vec2>,
+        /// The speed in pixels per second
+        speed: f32,
+    },
+    /// Switch the [`AnimationController`][bevy_retrograde_animation::AnimationController] of an
+    /// entity to a state
+    Animation {
+        entity: String,
+        state: String,
+        /// Whether or not to wait for the animation of the state to finish, which only works for
+        /// animations that don't loop
+        #[serde(default)]
+        wait: bool,
+    },
+    /// Show a dialog, waiting until [`skip_action`][crate::TimelinePlayer::skip_action] is called
+    ///
+    /// Timelines don't draw dialogs themselves, but send a [`TimelineEvent::Dialog`] event for the
+    /// game to show the dialog, and a [`TimelineEvent::DialogEnded`] event when it should be
+    /// hidden.
+    ///
+    /// [`TimelineEvent::Dialog`]: crate::TimelineEvent::Dialog
+    /// [`TimelineEvent::DialogEnded`]: crate::TimelineEvent::DialogEnded
+    Dialog {
+        #[serde(default)]
+        speaker: Option<String>,
+        text: String,
+    },
+    /// Play the sound at the given asset path, which requires the `audio` feature
+    Sound(String),
+    /// Fade the [`ScreenFade`][crate::ScreenFade] to a color over the given number of seconds
+    ///
+    /// Fading to a color with an alpha of `0.0` shows the game again.
+    Fade { color: Color, duration: f32 },
+    /// Send a [`TimelineEvent::Custom`][crate::TimelineEvent::Custom] event with the given name,
+    /// for anything else that the game needs to do
+    Event(String),
+    /// Play the actions at the same time, waiting until all of them are finished
+    Parallel(Vec<TimelineAction>),
+}
+
+/// Add asset types and asset loader to the app builder
+pub(crate) fn add_assets(app: &mut AppBuilder) {
+    app.add_asset::<Timeline>()
+        .init_asset_loader::<TimelineLoader>();
+}
+
+/// An error that occurs when loading a timeline
+#[derive(thiserror::Error, Debug)]
+pub enum TimelineLoaderError {
+    #[error("Could not parse timeline file: {0}")]
+    Parse(#[from] serde_yaml::Error),
+}
+
+/// A timeline file asset loader
+#[derive(Default)]
+struct TimelineLoader;
+
+impl AssetLoader for TimelineLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move { Ok(load_timeline(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["timeline"]
+    }
+}
+
+async fn load_timeline<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), TimelineLoaderError> {
+    let timeline: Timeline = serde_yaml::from_slice(bytes)?;
+
+    load_context.set_default_asset(LoadedAsset::new(timeline));
+
+    Ok(())
+}
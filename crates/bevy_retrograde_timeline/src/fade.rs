@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::{
+    image::{Rgba, RgbaImage},
+    prelude::*,
+};
+
+/// This resource has the color that covers the camera view, which is used to fade the screen in
+/// and out
+///
+/// The screen is faded by the [`Fade`][crate::TimelineAction::Fade] action of timelines, and can
+/// also be set directly.
+#[derive(Debug, Clone)]
+pub struct ScreenFade {
+    /// The color that covers the camera view, which isn't drawn when its alpha is `0.0`
+    pub color: Color,
+    /// The z position of the fade, which should be above all of the other sprites
+    pub z: f32,
+}
+
+impl Default for ScreenFade {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0., 0., 0., 0.),
+            z: 900.,
+        }
+    }
+}
+
+/// The sprite that the screen fade is drawn with
+#[derive(Default)]
+pub(crate) struct ScreenFadeSprite {
+    entity: Option<Entity>,
+    image: Option<Handle<Image>>,
+    /// The size and color of the image that was last drawn
+    drawn: Option<(UVec2, Rgba<u8>)>,
+}
+
+/// Draw the screen fade over the camera view
+pub(crate) fn draw_screen_fade(
+    mut commands: Commands,
+    fade: Res<ScreenFade>,
+    windows: Res<Windows>,
+    cameras: Query<&Camera>,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut sprite: Local<ScreenFadeSprite>,
+) {
+    let color = Rgba([
+        (255. * fade.color.r).round() as u8,
+        (255. * fade.color.g).round() as u8,
+        (255. * fade.color.b).round() as u8,
+        (255. * fade.color.a).round() as u8,
+    ]);
+    if color[3] == 0 {
+        if let Some(entity) = sprite.entity.take() {
+            commands.entity(entity).despawn();
+        }
+        sprite.drawn = None;
+        return;
+    }
+
+    let size = match (windows.get_primary(), cameras.iter().next()) {
+        (Some(window), Some(camera)) => camera.get_target_sizes(window).low,
+        _ => return,
+    };
+
+    if sprite.drawn != Some((size, color)) {
+        let image = Image(RgbaImage::from_pixel(size.x, size.y, color));
+        sprite.image = Some(match sprite.image.take() {
+            Some(handle) => image_assets.set(handle, image),
+            None => image_assets.add(image),
+        });
+        sprite.drawn = Some((size, color));
+    }
+
+    if sprite.entity.is_none() {
+        let entity = commands
+            .spawn_bundle(SpriteBundle {
+                image: sprite.image.clone().unwrap_or_default(),
+                transform: Transform::from_xyz(0., 0., fade.z),
+                ..Default::default()
+            })
+            .insert(ScreenAnchor {
+                anchor: Anchor::Center,
+                ..Default::default()
+            })
+            .id();
+        sprite.entity = Some(entity);
+    }
+}
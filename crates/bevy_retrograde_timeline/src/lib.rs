@@ -0,0 +1,102 @@
+//! Bevy Retrograde timeline plugin
+//!
+//! A [`Timeline`] is a list of actions that are played one after the other, such as moving
+//! entities, playing animations and sounds, showing dialogs, and fading the screen, which makes it
+//! easy to put together simple cutscenes. Timelines can be written in code or loaded from
+//! `.timeline` files, and are played with the [`TimelinePlayer`], which can pause and skip them.
+//!
+//! The [`TimelineEvent`]s sent while a timeline plays tell the game when to show dialogs, when to
+//! do anything else that the timeline asks for, and when the timeline is finished.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_timeline::*;
+//! fn start_cutscene(mut player: ResMut<TimelinePlayer>, mut timelines: ResMut<Assets<Timeline>>) {
+//!     player.play(timelines.add(Timeline {
+//!         actions: vec![
+//!             TimelineAction::Move {
+//!                 entity: "hero".into(),
+//!                 points: vec![Vec2::new(64., 0.)],
+//!                 speed: 32.,
+//!             },
+//!             TimelineAction::Dialog {
+//!                 speaker: Some("Hero".into()),
+//!                 text: "What was that?".into(),
+//!             },
+//!         ],
+//!     }));
+//! }
+//!
+//! fn show_dialogs(mut events: EventReader<TimelineEvent>) {
+//!     for event in events.iter() {
+//!         match event {
+//!             TimelineEvent::Dialog { text, .. } => info!("{}", text),
+//!             TimelineEvent::Finished => info!("The cutscene is over"),
+//!             _ => (),
+//!         }
+//!     }
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+mod asset;
+pub use asset::*;
+
+mod fade;
+pub use fade::ScreenFade;
+
+mod player;
+pub use player::{TimelineEvent, TimelinePlayer};
+
+/// Timeline plugin for Bevy Retrograde
+pub struct RetroTimelinePlugin;
+
+impl Plugin for RetroTimelinePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        asset::add_assets(app);
+
+        app.init_resource::<TimelinePlayer>()
+            .init_resource::<ScreenFade>()
+            .add_event::<TimelineEvent>()
+            .add_system(player::play_timeline.system())
+            .add_system_to_stage(CoreStage::PostUpdate, fade::draw_screen_fade.system());
+
+        #[cfg(feature = "audio")]
+        app.add_system_to_stage(CoreStage::PostUpdate, play_timeline_sounds.system());
+    }
+}
+
+/// Marker component for the entities that play the sounds of timelines
+#[cfg(feature = "audio")]
+struct TimelineSound;
+
+/// Play the sounds of the timeline and despawn the ones that are finished
+#[cfg(feature = "audio")]
+fn play_timeline_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut player: ResMut<TimelinePlayer>,
+    sounds: Query<(Entity, &bevy_retrograde_audio::AudioEmitter), With<TimelineSound>>,
+) {
+    for (entity, emitter) in sounds.iter() {
+        if !emitter.playing {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    // Only get the player mutably if there are sounds to play to avoid triggering change detection
+    if player.sounds.is_empty() {
+        return;
+    }
+    for path in player.sounds.drain(..) {
+        commands
+            .spawn()
+            .insert(bevy_retrograde_audio::AudioEmitter::new(
+                asset_server.load(path.as_str()),
+            ))
+            .insert(TimelineSound);
+    }
+}
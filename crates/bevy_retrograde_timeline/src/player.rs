@@ -0,0 +1,391 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_retrograde_animation::AnimationController;
+use bevy_retrograde_core::prelude::Color;
+
+use crate::{ScreenFade, Timeline, TimelineAction};
+
+/// This resource plays a [`Timeline`]
+///
+/// One timeline is played at a time, and it can be paused, or skipped to the end of the current
+/// action or the whole timeline, such as when the player presses a button during a cutscene.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_timeline::*;
+/// fn start_cutscene(asset_server: Res<AssetServer>, mut player: ResMut<TimelinePlayer>) {
+///     player.play(asset_server.load("cutscenes/intro.timeline"));
+/// }
+///
+/// fn skip_cutscene(keyboard: Res<Input<KeyCode>>, mut player: ResMut<TimelinePlayer>) {
+///     if keyboard.just_pressed(KeyCode::Space) {
+///         // Continue past the current dialog
+///         player.skip_action();
+///     } else if keyboard.just_pressed(KeyCode::Escape) {
+///         player.skip();
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct TimelinePlayer {
+    timeline: Option<Handle<Timeline>>,
+    /// The index of the current action
+    action: usize,
+    progress: ActionProgress,
+    skip: TimelineSkip,
+    /// The asset paths of the sounds to play
+    pub(crate) sounds: Vec<String>,
+    /// Whether or not the timeline is paused
+    pub paused: bool,
+}
+
+impl TimelinePlayer {
+    /// Start playing a timeline from the beginning, stopping the timeline that is playing if there
+    /// is one
+    ///
+    /// The timeline starts once its asset is loaded.
+    pub fn play(&mut self, timeline: Handle<Timeline>) {
+        self.timeline = Some(timeline);
+        self.action = 0;
+        self.progress = Default::default();
+        self.skip = TimelineSkip::None;
+    }
+
+    /// Stop the timeline where it is, without sending a
+    /// [`Finished`][crate::TimelineEvent::Finished] event
+    pub fn stop(&mut self) {
+        self.timeline = None;
+    }
+
+    /// Finish the current action right away, which also continues past a dialog
+    pub fn skip_action(&mut self) {
+        if self.skip == TimelineSkip::None {
+            self.skip = TimelineSkip::Action;
+        }
+    }
+
+    /// Finish all of the actions of the timeline right away
+    ///
+    /// Entities are moved to the end of their paths, and animations and fades are set to their
+    /// last state, but dialogs aren't shown and sounds aren't played.
+    pub fn skip(&mut self) {
+        self.skip = TimelineSkip::All;
+    }
+
+    /// Get the timeline that is playing
+    pub fn timeline(&self) -> Option<&Handle<Timeline>> {
+        self.timeline.as_ref()
+    }
+
+    /// Get whether or not a timeline is playing, which is still `true` when it is paused
+    pub fn is_playing(&self) -> bool {
+        self.timeline.is_some()
+    }
+
+    /// Get the index of the action that is playing in the timeline
+    pub fn current_action(&self) -> Option<usize> {
+        self.timeline.as_ref().map(|_| self.action)
+    }
+}
+
+/// Bevy event sent by a playing [`Timeline`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineEvent {
+    /// A [`Dialog`][TimelineAction::Dialog] action started and the game should show the dialog
+    Dialog {
+        speaker: Option<String>,
+        text: String,
+    },
+    /// The dialog action ended and the game should hide the dialog
+    DialogEnded,
+    /// An [`Event`][TimelineAction::Event] action was played
+    Custom(String),
+    /// The timeline played or skipped all of its actions
+    Finished,
+}
+
+/// How much of the timeline to skip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimelineSkip {
+    None,
+    Action,
+    All,
+}
+
+impl Default for TimelineSkip {
+    fn default() -> Self {
+        TimelineSkip::None
+    }
+}
+
+/// The progress of playing an action
+#[derive(Default)]
+struct ActionProgress {
+    started: bool,
+    /// The time in seconds since the action started
+    elapsed: f32,
+    /// The entity that the action is for
+    entity: Option<Entity>,
+    /// The position of the entity when a move started
+    start_position: Vec2,
+    /// The color of the screen fade when a fade started
+    start_color: Option<Color>,
+    /// Whether or not a dialog was shown
+    shown: bool,
+    /// The progress of each action played in parallel, which is [`None`] once it is finished
+    children: Vec<Option<ActionProgress>>,
+}
+
+/// The parts of the world that timeline actions change
+#[derive(SystemParam)]
+pub(crate) struct TimelineWorld<'a> {
+    names: Query<'a, (Entity, &'static Name)>,
+    transforms: Query<'a, &'static mut Transform>,
+    animations: Query<'a, &'static mut AnimationController>,
+    fade: ResMut<'a, ScreenFade>,
+    events: EventWriter<'a, TimelineEvent>,
+}
+
+impl<'a> TimelineWorld<'a> {
+    /// Find the entity with a name
+    fn find_entity(&self, name: &str) -> Option<Entity> {
+        let entity = self
+            .names
+            .iter()
+            .find(|(_, entity_name)| entity_name.as_str() == name)
+            .map(|(entity, _)| entity);
+        if entity.is_none() {
+            warn!("Timeline could not find an entity named {:?}", name);
+        }
+
+        entity
+    }
+}
+
+/// Play the actions of the timeline
+pub(crate) fn play_timeline(
+    time: Res<Time>,
+    timeline_assets: Res<Assets<Timeline>>,
+    mut player: ResMut<TimelinePlayer>,
+    mut world: TimelineWorld,
+) {
+    let timeline = match &player.timeline {
+        Some(handle) => handle,
+        None => return,
+    };
+    // Only get the player mutably if the timeline is playing to avoid triggering change detection
+    if player.paused && player.skip == TimelineSkip::None {
+        return;
+    }
+    let timeline = match timeline_assets.get(timeline) {
+        Some(timeline) => timeline,
+        None => return,
+    };
+
+    let player = &mut *player;
+    let mut skip = std::mem::take(&mut player.skip);
+    let mut delta = if player.paused {
+        0.
+    } else {
+        time.delta_seconds()
+    };
+    loop {
+        let action = match timeline.actions.get(player.action) {
+            Some(action) => action,
+            None => {
+                player.timeline = None;
+                world.events.send(TimelineEvent::Finished);
+                return;
+            }
+        };
+
+        if !update_action(
+            action,
+            &mut player.progress,
+            &mut world,
+            &mut player.sounds,
+            delta,
+            skip,
+        ) {
+            return;
+        }
+
+        // Start the next action right away, so that actions without a duration are played in the
+        // same frame
+        player.action += 1;
+        player.progress = Default::default();
+        delta = 0.;
+        if skip == TimelineSkip::Action {
+            skip = TimelineSkip::None;
+        }
+    }
+}
+
+/// Advance an action by the given time in seconds, returning whether or not it is finished
+fn update_action(
+    action: &TimelineAction,
+    progress: &mut ActionProgress,
+    world: &mut TimelineWorld,
+    sounds: &mut Vec<String>,
+    delta: f32,
+    skip: TimelineSkip,
+) -> bool {
+    let starting = !progress.started;
+    progress.started = true;
+    progress.elapsed += delta;
+    let skipping = skip != TimelineSkip::None;
+
+    match action {
+        TimelineAction::Wait(duration) => skipping || progress.elapsed >= *duration,
+
+        TimelineAction::Move {
+            entity,
+            points,
+            speed,
+        } => {
+            if starting {
+                progress.entity = world.find_entity(entity);
+            }
+            let entity = match progress.entity {
+                Some(entity) => entity,
+                None => return true,
+            };
+            let mut transform = match world.transforms.get_mut(entity) {
+                Ok(transform) => transform,
+                Err(_) => return true,
+            };
+            if starting {
+                progress.start_position = transform.translation.truncate();
+            }
+
+            let distance = if skipping || *speed <= 0. {
+                f32::INFINITY
+            } else {
+                progress.elapsed * speed
+            };
+            let (position, finished) = point_along_path(progress.start_position, points, distance);
+
+            // Move in whole pixels the same way that the sprite renderer rounds positions
+            let position = (position + Vec2::splat(0.5)).floor();
+            if transform.translation.truncate() != position {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+
+            finished
+        }
+
+        TimelineAction::Animation {
+            entity,
+            state,
+            wait,
+        } => {
+            if starting {
+                progress.entity = world.find_entity(entity);
+            }
+            let entity = match progress.entity {
+                Some(entity) => entity,
+                None => return true,
+            };
+            let mut controller = match world.animations.get_mut(entity) {
+                Ok(controller) => controller,
+                Err(_) => return true,
+            };
+            if starting {
+                controller.play(state.as_str());
+            }
+
+            !wait
+                || skipping
+                || controller.current_state() != state.as_str()
+                || controller.is_finished()
+        }
+
+        TimelineAction::Dialog { speaker, text } => {
+            if starting && skip != TimelineSkip::All {
+                world.events.send(TimelineEvent::Dialog {
+                    speaker: speaker.clone(),
+                    text: text.clone(),
+                });
+                progress.shown = true;
+            }
+
+            if skipping && progress.shown {
+                world.events.send(TimelineEvent::DialogEnded);
+            }
+            skipping
+        }
+
+        TimelineAction::Sound(path) => {
+            if starting && skip != TimelineSkip::All {
+                if cfg!(feature = "audio") {
+                    sounds.push(path.clone());
+                } else {
+                    warn!("Timeline sounds can't be played without the `audio` feature");
+                }
+            }
+            true
+        }
+
+        TimelineAction::Fade { color, duration } => {
+            let start = *progress.start_color.get_or_insert(world.fade.color);
+            let ratio = if skipping || *duration <= 0. {
+                1.
+            } else {
+                (progress.elapsed / duration).min(1.)
+            };
+
+            world.fade.color = Color::new(
+                start.r + (color.r - start.r) * ratio,
+                start.g + (color.g - start.g) * ratio,
+                start.b + (color.b - start.b) * ratio,
+                start.a + (color.a - start.a) * ratio,
+            );
+            ratio >= 1.
+        }
+
+        TimelineAction::Event(name) => {
+            if starting {
+                world.events.send(TimelineEvent::Custom(name.clone()));
+            }
+            true
+        }
+
+        TimelineAction::Parallel(actions) => {
+            if starting {
+                progress.children = actions.iter().map(|_| Some(Default::default())).collect();
+            }
+
+            let mut finished = true;
+            for (action, child) in actions.iter().zip(progress.children.iter_mut()) {
+                if let Some(child_progress) = child {
+                    if update_action(action, child_progress, world, sounds, delta, skip) {
+                        *child = None;
+                    } else {
+                        finished = false;
+                    }
+                }
+            }
+            finished
+        }
+    }
+}
+
+/// Get the point that is the given distance along a path that goes from the start through the
+/// points, and whether or not it is the end of the path
+fn point_along_path(start: Vec2, points: &[Vec2], distance: f32) -> (Vec2, bool) {
+    let mut from = start;
+    let mut remaining = distance;
+    for &to in points {
+        let length = from.distance(to);
+        if remaining < length {
+            return (from + (to - from) * (remaining / length), false);
+        }
+
+        remaining -= length;
+        from = to;
+    }
+
+    (from, true)
+}
@@ -0,0 +1,111 @@
+use std::f32::consts::PI;
+
+/// An easing function that shapes how a tween moves from its start to its end
+///
+/// See [easings.net](https://easings.net) for what each of the easing functions looks like.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ease {
+    /// Move at a constant speed
+    Linear,
+    /// Jump from the start to the end in the given number of equal steps, which looks like the
+    /// choppy animation of old games
+    Steps(u32),
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    /// Pull back a little before moving to the end
+    BackIn,
+    /// Overshoot the end a little before settling on it
+    BackOut,
+    /// Bounce on the end like a dropped ball
+    BounceOut,
+    /// Spring past the end a few times before settling on it
+    ElasticOut,
+}
+
+impl Default for Ease {
+    fn default() -> Self {
+        Ease::Linear
+    }
+}
+
+impl Ease {
+    /// Get the eased ratio for a ratio of time from `0.0` at the start to `1.0` at the end
+    ///
+    /// Easing functions always start at `0.0` and end at `1.0`, but some of them go past those
+    /// values in between.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_retrograde_tween::*;
+    /// assert_eq!(Ease::Linear.apply(0.25), 0.25);
+    /// assert_eq!(Ease::QuadIn.apply(0.5), 0.25);
+    /// assert_eq!(Ease::Steps(4).apply(0.3), 0.25);
+    /// ```
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.max(0.).min(1.);
+        // The constants of the back easing functions, which overshoot by 10%
+        const BACK: f32 = 1.70158;
+
+        match *self {
+            Ease::Linear => t,
+            Ease::Steps(steps) => {
+                let steps = steps.max(1) as f32;
+                (t * steps).floor() / steps
+            }
+            Ease::QuadIn => t * t,
+            Ease::QuadOut => 1. - (1. - t) * (1. - t),
+            Ease::QuadInOut => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(2) / 2.
+                }
+            }
+            Ease::CubicIn => t * t * t,
+            Ease::CubicOut => 1. - (1. - t).powi(3),
+            Ease::CubicInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+            Ease::SineIn => 1. - (t * PI / 2.).cos(),
+            Ease::SineOut => (t * PI / 2.).sin(),
+            Ease::SineInOut => -((t * PI).cos() - 1.) / 2.,
+            Ease::BackIn => (BACK + 1.) * t * t * t - BACK * t * t,
+            Ease::BackOut => 1. + (BACK + 1.) * (t - 1.).powi(3) + BACK * (t - 1.).powi(2),
+            Ease::BounceOut => {
+                const N: f32 = 7.5625;
+                const D: f32 = 2.75;
+                if t < 1. / D {
+                    N * t * t
+                } else if t < 2. / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+            Ease::ElasticOut => {
+                if t == 0. || t == 1. {
+                    t
+                } else {
+                    2f32.powf(-10. * t) * ((t * 10. - 0.75) * (2. * PI / 3.)).sin() + 1.
+                }
+            }
+        }
+    }
+}
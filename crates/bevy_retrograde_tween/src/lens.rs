@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::{Camera, CameraSize, Color};
+
+use crate::TweenLens;
+
+/// Tweens the translation of a [`Transform`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformPositionLens {
+    pub start: Vec3,
+    pub end: Vec3,
+    /// Whether or not to snap the x and y position to whole pixels
+    ///
+    /// Pixel-perfect sprites are always drawn at whole pixels, but snapping the position makes it
+    /// move one pixel at a time instead of moving in between pixels, so that anything that reads
+    /// the position, such as collision detection, agrees with where the sprite is drawn.
+    pub snap: bool,
+}
+
+impl TweenLens<Transform> for TransformPositionLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        let mut position = self.start + (self.end - self.start) * ratio;
+        if self.snap {
+            // Round the same way as the sprite renderer, which rounds halves up even when they are
+            // negative
+            position.x = (position.x + 0.5).floor();
+            position.y = (position.y + 0.5).floor();
+        }
+
+        target.translation = position;
+    }
+}
+
+/// Tweens the background color of a [`Camera`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraBackgroundLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl TweenLens<Camera> for CameraBackgroundLens {
+    fn lerp(&mut self, target: &mut Camera, ratio: f32) {
+        target.background_color = lerp_color(self.start, self.end, ratio);
+    }
+}
+
+/// Tweens the size of a [`Camera`] along its fixed axis, which zooms it in or out
+///
+/// The size is rounded to whole pixels. Cameras with a
+/// [`LetterBoxed`][CameraSize::LetterBoxed] size aren't changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CameraSizeLens {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl TweenLens<Camera> for CameraSizeLens {
+    fn lerp(&mut self, target: &mut Camera, ratio: f32) {
+        let size = (self.start as f32 + (self.end as f32 - self.start as f32) * ratio)
+            .round()
+            .max(1.) as u32;
+
+        match &mut target.size {
+            CameraSize::FixedHeight(x) | CameraSize::FixedWidth(x) if *x != size => *x = size,
+            _ => (),
+        }
+    }
+}
+
+/// Tweens the volume of a bus of the [`AudioMixer`][bevy_retrograde_audio::AudioMixer]
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_audio::*;
+/// # use bevy_retrograde_tween::*;
+/// fn fade_out_music(mut tweens: ResMut<ResourceTweens<AudioMixer>>) {
+///     tweens.add(Tween::new(
+///         2.,
+///         Ease::SineOut,
+///         MixerBusVolumeLens {
+///             bus: MUSIC_BUS.into(),
+///             start: 1.,
+///             end: 0.,
+///         },
+///     ));
+/// }
+/// ```
+#[cfg(feature = "audio")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixerBusVolumeLens {
+    /// The name of the bus
+    pub bus: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[cfg(feature = "audio")]
+impl TweenLens<bevy_retrograde_audio::AudioMixer> for MixerBusVolumeLens {
+    fn lerp(&mut self, target: &mut bevy_retrograde_audio::AudioMixer, ratio: f32) {
+        target.set_bus_volume(
+            self.bus.as_str(),
+            self.start + (self.end - self.start) * ratio as f64,
+        );
+    }
+}
+
+/// Tweens the master volume of the [`AudioMixer`][bevy_retrograde_audio::AudioMixer]
+#[cfg(feature = "audio")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixerMasterVolumeLens {
+    pub start: f64,
+    pub end: f64,
+}
+
+#[cfg(feature = "audio")]
+impl TweenLens<bevy_retrograde_audio::AudioMixer> for MixerMasterVolumeLens {
+    fn lerp(&mut self, target: &mut bevy_retrograde_audio::AudioMixer, ratio: f32) {
+        target.set_master_volume(self.start + (self.end - self.start) * ratio as f64);
+    }
+}
+
+fn lerp_color(start: Color, end: Color, ratio: f32) -> Color {
+    Color::new(
+        start.r + (end.r - start.r) * ratio,
+        start.g + (end.g - start.g) * ratio,
+        start.b + (end.b - start.b) * ratio,
+        start.a + (end.a - start.a) * ratio,
+    )
+}
@@ -0,0 +1,212 @@
+//! Bevy Retrograde tweening plugin
+//!
+//! A [`Tween`] animates a component or a resource over time with [`Ease`]ing functions, through
+//! [`TweenLens`]es that set the values being animated. Tweens can be a sequence of steps, can be
+//! repeated, and send a [`TweenCompleted`] event when they finish.
+//!
+//! Lenses are included for the position of a [`Transform`], which can be snapped to whole pixels,
+//! and the background color and size of a [`Camera`][bevy_retrograde_core::prelude::Camera]. With
+//! the `audio` feature, the volumes of the audio mixer can be tweened too. Other components and
+//! resources can be tweened by implementing [`TweenLens`] for them and adding them with the
+//! [`AppBuilderTweenExt`].
+//!
+//! Entities can also be moved through a list of waypoints at a constant speed with the
+//! [`PathFollow`] component.
+//!
+//! Tweens and paths are played with the
+//! [`GameTime`][bevy_retrograde_core::prelude::GameTime], so they stop while the game is paused
+//! and follow its time scale.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_core::prelude::*;
+//! # use bevy_retrograde_tween::*;
+//! fn spawn_coin(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     commands
+//!         .spawn_bundle(SpriteBundle {
+//!             image: asset_server.load("coin.png"),
+//!             ..Default::default()
+//!         })
+//!         .insert(
+//!             Tween::new(
+//!                 0.4,
+//!                 Ease::BounceOut,
+//!                 TransformPositionLens {
+//!                     start: Vec3::new(0., -32., 0.),
+//!                     end: Vec3::new(0., 0., 0.),
+//!                     snap: true,
+//!                 },
+//!             )
+//!             .with_id(1),
+//!         );
+//! }
+//!
+//! fn coin_landed(mut events: EventReader<TweenCompleted>) {
+//!     for event in events.iter() {
+//!         if event.id == 1 {
+//!             info!("The coin landed");
+//!         }
+//!     }
+//! }
+//! ```
+
+use bevy::{ecs::component::Component, prelude::*};
+use bevy_retrograde_core::prelude::{Camera, GameTime};
+
+mod ease;
+pub use ease::Ease;
+
+mod tween;
+pub use tween::{Tween, TweenLens, TweenRepeat};
+
+mod lens;
+pub use lens::*;
+
+mod path;
+pub use path::{PathFollow, PathMode, WaypointReached};
+
+/// Tweening plugin for Bevy Retrograde
+pub struct RetroTweenPlugin;
+
+impl Plugin for RetroTweenPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<TweenCompleted>()
+            .add_event::<WaypointReached>()
+            .add_system(path::follow_paths.system())
+            .add_tween_component::<Transform>()
+            .add_tween_component::<Camera>();
+
+        #[cfg(feature = "audio")]
+        app.add_tween_resource::<bevy_retrograde_audio::AudioMixer>();
+    }
+}
+
+/// Bevy [`AppBuilder`] extension for tweening other components and resources
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_tween::*;
+/// struct Health(f32);
+///
+/// fn main() {
+///     App::build()
+///         .add_plugin(RetroTweenPlugin)
+///         .add_tween_component::<Health>();
+/// }
+/// ```
+pub trait AppBuilderTweenExt {
+    /// Play the [`Tween<T>`] components of entities on their `T` component
+    fn add_tween_component<T: Component>(self) -> Self;
+
+    /// Play the tweens in the [`ResourceTweens<T>`] resource on the `T` resource
+    fn add_tween_resource<T: Component>(self) -> Self;
+}
+
+impl AppBuilderTweenExt for &mut AppBuilder {
+    fn add_tween_component<T: Component>(self) -> Self {
+        self.add_system(tween_components::<T>.system())
+    }
+
+    fn add_tween_resource<T: Component>(self) -> Self {
+        self.init_resource::<ResourceTweens<T>>()
+            .add_system(tween_resources::<T>.system())
+    }
+}
+
+/// Bevy event sent when a [`Tween`] finishes playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TweenCompleted {
+    /// The entity that the tween was on, or [`None`] if it was tweening a resource
+    pub entity: Option<Entity>,
+    /// The [`id`][Tween::id] of the tween
+    pub id: u64,
+}
+
+/// This resource has the tweens that are playing on the resource of type `T`
+///
+/// Resources are tweened once they are added with
+/// [`add_tween_resource`][AppBuilderTweenExt::add_tween_resource].
+pub struct ResourceTweens<T> {
+    tweens: Vec<Tween<T>>,
+}
+
+impl<T> Default for ResourceTweens<T> {
+    fn default() -> Self {
+        Self { tweens: Vec::new() }
+    }
+}
+
+impl<T> ResourceTweens<T> {
+    /// Start playing a tween
+    pub fn add(&mut self, tween: Tween<T>) {
+        self.tweens.push(tween);
+    }
+
+    /// Stop all of the tweens
+    pub fn clear(&mut self) {
+        self.tweens.clear();
+    }
+
+    /// Get whether or not there are no tweens playing
+    pub fn is_empty(&self) -> bool {
+        self.tweens.is_empty()
+    }
+}
+
+/// Play the tweens of components, removing them when they finish
+fn tween_components<T: Component>(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut tweens: Query<(Entity, &mut Tween<T>, &mut T)>,
+    mut events: EventWriter<TweenCompleted>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut tween, mut target) in tweens.iter_mut() {
+        // Skip paused tweens so that they don't trigger change detection
+        if tween.paused || tween.is_finished() {
+            continue;
+        }
+
+        if tween.tick(delta, &mut *target) {
+            commands.entity(entity).remove::<Tween<T>>();
+            events.send(TweenCompleted {
+                entity: Some(entity),
+                id: tween.id,
+            });
+        }
+    }
+}
+
+/// Play the tweens of a resource, removing them when they finish
+fn tween_resources<T: Component>(
+    time: Res<GameTime>,
+    mut tweens: ResMut<ResourceTweens<T>>,
+    target: Option<ResMut<T>>,
+    mut events: EventWriter<TweenCompleted>,
+) {
+    let mut target = match target {
+        Some(target) if !tweens.is_empty() => target,
+        _ => return,
+    };
+    if tweens.tweens.iter().all(|tween| tween.paused) {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    let mut i = 0;
+    while i < tweens.tweens.len() {
+        if tweens.tweens[i].tick(delta, &mut *target) {
+            let tween = tweens.tweens.remove(i);
+            events.send(TweenCompleted {
+                entity: None,
+                id: tween.id,
+            });
+        } else {
+            i += 1;
+        }
+    }
+}
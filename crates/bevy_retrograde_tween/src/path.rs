@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::GameTime;
+
+/// What a [`PathFollow`] does after it reaches the last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    /// Stop at the last waypoint
+    Once,
+    /// Go back to the first waypoint and keep going around the path
+    Loop,
+    /// Go back through the waypoints in reverse, turning around at each end of the path
+    PingPong,
+}
+
+impl Default for PathMode {
+    fn default() -> Self {
+        PathMode::Once
+    }
+}
+
+/// Component that moves an entity through a list of waypoints at a constant speed
+///
+/// The entity is moved in whole pixels, starting from where it is when the component is added, and
+/// a [`WaypointReached`] event is sent every time it reaches a waypoint. This works well for
+/// patrolling enemies and moving platforms.
+///
+/// The position of the entity along the path is kept by the component, so that paths can be
+/// followed at less than a pixel per frame, which means that the path overrides anything else that
+/// moves the entity's [`Transform`] on x and y while it is being followed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_tween::*;
+/// fn spawn_platform(mut commands: Commands) {
+///     commands
+///         .spawn()
+///         .insert(Transform::from_xyz(0., 0., 0.))
+///         .insert(
+///             PathFollow::new(vec![Vec2::new(64., 0.), Vec2::new(0., 0.)], 16.)
+///                 .with_mode(PathMode::Loop),
+///         );
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathFollow {
+    /// The positions that the entity moves through, in the same space as its [`Transform`]
+    pub waypoints: Vec<Vec2>,
+    /// The speed in pixels per second
+    pub speed: f32,
+    pub mode: PathMode,
+    /// Whether or not the entity is stopped where it is
+    pub paused: bool,
+    /// The index of the waypoint that the entity is moving towards
+    target: usize,
+    /// Whether or not the entity is moving towards the end of a [`PingPong`][PathMode::PingPong]
+    /// path
+    forward: bool,
+    /// The position of the entity on the path before it is rounded to whole pixels
+    position: Option<Vec2>,
+    finished: bool,
+}
+
+impl PathFollow {
+    /// Create a path that is followed once at the given speed in pixels per second
+    pub fn new(waypoints: Vec<Vec2>, speed: f32) -> Self {
+        Self {
+            waypoints,
+            speed,
+            mode: PathMode::Once,
+            paused: false,
+            target: 0,
+            forward: true,
+            position: None,
+            finished: false,
+        }
+    }
+
+    /// Set what happens after the last waypoint is reached
+    pub fn with_mode(mut self, mode: PathMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the index of the waypoint that the entity is moving towards, or [`None`] if it is
+    /// finished
+    pub fn target_waypoint(&self) -> Option<usize> {
+        if self.finished {
+            None
+        } else {
+            Some(self.target)
+        }
+    }
+
+    /// Get whether or not the entity has reached the end of a path that is followed
+    /// [`Once`][PathMode::Once]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Start following the path from the first waypoint again, from where the entity is now
+    pub fn restart(&mut self) {
+        self.target = 0;
+        self.forward = true;
+        self.position = None;
+        self.finished = false;
+    }
+
+    /// Move towards the next waypoint, returning `false` if the path is finished
+    fn next_waypoint(&mut self) -> bool {
+        let last = self.waypoints.len().saturating_sub(1);
+        match self.mode {
+            PathMode::Once if self.target >= last => {
+                self.finished = true;
+                return false;
+            }
+            PathMode::Once => self.target += 1,
+            PathMode::Loop => self.target = (self.target + 1) % self.waypoints.len().max(1),
+            PathMode::PingPong => {
+                if self.forward && self.target >= last {
+                    self.forward = false;
+                } else if !self.forward && self.target == 0 {
+                    self.forward = true;
+                }
+
+                self.target = if self.forward {
+                    (self.target + 1).min(last)
+                } else {
+                    self.target.saturating_sub(1)
+                };
+            }
+        }
+
+        true
+    }
+}
+
+/// Bevy event sent when an entity with a [`PathFollow`] reaches a waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaypointReached {
+    pub entity: Entity,
+    /// The index of the waypoint
+    pub waypoint: usize,
+    /// Whether or not this is the end of a path that is followed [`Once`][PathMode::Once]
+    pub finished: bool,
+}
+
+/// Move the entities with a [`PathFollow`] along their paths
+pub(crate) fn follow_paths(
+    time: Res<GameTime>,
+    mut paths: Query<(Entity, &mut PathFollow, &mut Transform)>,
+    mut events: EventWriter<WaypointReached>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut path, mut transform) in paths.iter_mut() {
+        // Skip paths that aren't moving so that they don't trigger change detection
+        if path.paused || path.finished || path.speed <= 0. {
+            continue;
+        }
+        if path.waypoints.is_empty() {
+            path.finished = true;
+            continue;
+        }
+
+        let path = &mut *path;
+        let mut position = *path
+            .position
+            .get_or_insert_with(|| transform.translation.truncate());
+        let mut distance = path.speed * delta;
+        // The number of waypoints reached without moving, to stop paths where all of the
+        // waypoints are in the same place from looping forever
+        let mut reached_in_place = 0;
+        while distance > 0. && reached_in_place <= path.waypoints.len() {
+            let target = path.waypoints[path.target];
+            let to_target = position.distance(target);
+            if to_target > distance {
+                position += (target - position) * (distance / to_target);
+                break;
+            }
+
+            position = target;
+            distance -= to_target;
+            if to_target > 0. {
+                reached_in_place = 0;
+            } else {
+                reached_in_place += 1;
+            }
+
+            let waypoint = path.target;
+            let keep_going = path.next_waypoint();
+            events.send(WaypointReached {
+                entity,
+                waypoint,
+                finished: !keep_going,
+            });
+            if !keep_going {
+                break;
+            }
+        }
+        path.position = Some(position);
+
+        // Move in whole pixels the same way that the sprite renderer rounds positions
+        let position = (position + Vec2::splat(0.5)).floor();
+        if transform.translation.truncate() != position {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
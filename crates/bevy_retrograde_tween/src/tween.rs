@@ -0,0 +1,225 @@
+use crate::Ease;
+
+/// Sets a value of a target of type `T` for a point in a [`Tween`]
+///
+/// Lenses are implemented for the values that Bevy Retrograde can tween, and can be implemented
+/// to tween anything else.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_retrograde_tween::*;
+/// struct Health(f32);
+///
+/// struct HealthLens {
+///     start: f32,
+///     end: f32,
+/// }
+///
+/// impl TweenLens<Health> for HealthLens {
+///     fn lerp(&mut self, target: &mut Health, ratio: f32) {
+///         target.0 = self.start + (self.end - self.start) * ratio;
+///     }
+/// }
+/// ```
+pub trait TweenLens<T>: Send + Sync + 'static {
+    /// Set the value of the target for an eased ratio that is `0.0` at the start of the tween and
+    /// `1.0` at the end of it
+    fn lerp(&mut self, target: &mut T, ratio: f32);
+}
+
+/// How many times a [`Tween`] is played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Play the tween once
+    Once,
+    /// Play the tween the given number of times
+    Times(u32),
+    /// Play the tween until it is removed
+    Forever,
+}
+
+impl Default for TweenRepeat {
+    fn default() -> Self {
+        TweenRepeat::Once
+    }
+}
+
+/// One step of a [`Tween`], which is either a lens moved over time, or a delay
+struct TweenStep<T> {
+    duration: f32,
+    ease: Ease,
+    lens: Option<Box<dyn TweenLens<T>>>,
+}
+
+/// A sequence of steps that animate values of a target of type `T` over time
+///
+/// A tween can be added as a component to animate another component of the same entity, such as
+/// its [`Transform`][bevy::prelude::Transform], or added to a
+/// [`ResourceTweens`][crate::ResourceTweens] resource to animate a resource. A
+/// [`TweenCompleted`][crate::TweenCompleted] event is sent when it is finished.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_tween::*;
+/// // Move right, wait, and then come back, three times
+/// let tween = Tween::new(
+///     0.5,
+///     Ease::QuadInOut,
+///     TransformPositionLens {
+///         start: Vec3::new(0., 0., 0.),
+///         end: Vec3::new(32., 0., 0.),
+///         snap: true,
+///     },
+/// )
+/// .then_delay(0.25)
+/// .then(
+///     0.5,
+///     Ease::QuadInOut,
+///     TransformPositionLens {
+///         start: Vec3::new(32., 0., 0.),
+///         end: Vec3::new(0., 0., 0.),
+///         snap: true,
+///     },
+/// )
+/// .with_repeat(TweenRepeat::Times(3));
+/// ```
+pub struct Tween<T> {
+    steps: Vec<TweenStep<T>>,
+    /// The index of the current step
+    step: usize,
+    /// The time in seconds since the current step started
+    elapsed: f32,
+    repeat: TweenRepeat,
+    /// The number of times that the tween has been played to the end
+    played: u32,
+    finished: bool,
+    /// Whether or not the tween is paused
+    pub paused: bool,
+    /// An id that is sent in the [`TweenCompleted`][crate::TweenCompleted] event, to tell which
+    /// tween completed
+    pub id: u64,
+}
+
+impl<T> Tween<T> {
+    /// Create a tween with one step that moves a lens over the given duration in seconds
+    pub fn new<L: TweenLens<T>>(duration: f32, ease: Ease, lens: L) -> Self {
+        Self::from_step(TweenStep {
+            duration,
+            ease,
+            lens: Some(Box::new(lens)),
+        })
+    }
+
+    /// Create a tween that starts with a delay of the given duration in seconds
+    pub fn delay(duration: f32) -> Self {
+        Self::from_step(TweenStep {
+            duration,
+            ease: Ease::Linear,
+            lens: None,
+        })
+    }
+
+    fn from_step(step: TweenStep<T>) -> Self {
+        Self {
+            steps: vec![step],
+            step: 0,
+            elapsed: 0.,
+            repeat: TweenRepeat::Once,
+            played: 0,
+            finished: false,
+            paused: false,
+            id: 0,
+        }
+    }
+
+    /// Add a step that moves a lens after the previous steps
+    pub fn then<L: TweenLens<T>>(mut self, duration: f32, ease: Ease, lens: L) -> Self {
+        self.steps.push(TweenStep {
+            duration,
+            ease,
+            lens: Some(Box::new(lens)),
+        });
+        self
+    }
+
+    /// Add a delay after the previous steps
+    pub fn then_delay(mut self, duration: f32) -> Self {
+        self.steps.push(TweenStep {
+            duration,
+            ease: Ease::Linear,
+            lens: None,
+        });
+        self
+    }
+
+    /// Set how many times the tween is played
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Set the id that is sent in the [`TweenCompleted`][crate::TweenCompleted] event
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Get the duration in seconds of playing the tween once
+    pub fn duration(&self) -> f32 {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+
+    /// Get whether or not the tween has finished playing
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advance the tween by the given time in seconds and update the target, returning whether or
+    /// not the tween finished
+    pub fn tick(&mut self, delta: f32, target: &mut T) -> bool {
+        if self.paused || self.finished {
+            return false;
+        }
+
+        self.elapsed += delta;
+        loop {
+            let step = &mut self.steps[self.step];
+            if self.elapsed < step.duration {
+                if let Some(lens) = &mut step.lens {
+                    lens.lerp(target, step.ease.apply(self.elapsed / step.duration));
+                }
+                return false;
+            }
+
+            // Finish the step exactly at its end before moving on to the next one
+            if let Some(lens) = &mut step.lens {
+                lens.lerp(target, 1.);
+            }
+            self.elapsed -= step.duration;
+            self.step += 1;
+
+            if self.step == self.steps.len() {
+                self.played += 1;
+                let play_again = match self.repeat {
+                    TweenRepeat::Once => false,
+                    TweenRepeat::Times(times) => self.played < times,
+                    TweenRepeat::Forever => true,
+                };
+                if !play_again {
+                    self.finished = true;
+                    return true;
+                }
+
+                self.step = 0;
+                // A tween without a duration can't be played more than once per frame
+                if self.duration() <= 0. {
+                    self.elapsed = 0.;
+                    return false;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::f32::consts::PI;
+
+use raui::prelude::{
+    AnimatedValue, Animation, Animator, Deserialize, Scalar, Serialize, Vec2, WidgetContext,
+};
+
+/// An easing curve that shapes the progress of an animation
+///
+/// See <https://easings.net> for what each curve looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+    /// Overshoots the end a little before settling, which suits things that pop into place
+    BackOut,
+    /// Bounces against the end like a dropped ball
+    BounceOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::CubicOut
+    }
+}
+
+impl Easing {
+    /// Ease the linear progress `t` of an animation, from `0.0` to `1.0`
+    pub fn ease(self, t: Scalar) -> Scalar {
+        let t = t.max(0.).min(1.);
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1. - (1. - t) * (1. - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(2) / 2.
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1. - (1. - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+            Easing::SineInOut => -((PI * t).cos() - 1.) / 2.,
+            Easing::BackOut => {
+                const C1: Scalar = 1.70158;
+                const C3: Scalar = C1 + 1.;
+                1. + C3 * (t - 1.).powi(3) + C1 * (t - 1.).powi(2)
+            }
+            Easing::BounceOut => {
+                const N1: Scalar = 7.5625;
+                const D1: Scalar = 2.75;
+                if t < 1. / D1 {
+                    N1 * t * t
+                } else if t < 2. / D1 {
+                    let t = t - 1.5 / D1;
+                    N1 * t * t + 0.75
+                } else if t < 2.5 / D1 {
+                    let t = t - 2.25 / D1;
+                    N1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D1;
+                    N1 * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// Interpolate between two values
+pub fn lerp(from: Scalar, to: Scalar, t: Scalar) -> Scalar {
+    from + (to - from) * t
+}
+
+/// Interpolate between two vectors, such as position offsets or scales
+pub fn lerp_vec2(from: Vec2, to: Vec2, t: Scalar) -> Vec2 {
+    Vec2 {
+        x: lerp(from.x, to.x, t),
+        y: lerp(from.y, to.y, t),
+    }
+}
+
+/// The name of the animated value used by [`start_ui_animation`] and [`ui_animation_progress`]
+const PROGRESS_VALUE: &str = "progress";
+
+/// Start, or restart, an animation of a widget that lasts `duration` seconds
+///
+/// This is meant to be called from a widget's life cycle hooks, and the progress of the animation
+/// is read with [`ui_animation_progress`]. Animations are advanced by the UI app's
+/// `animations_delta_time`, which follows Bevy's [`Time`][bevy::core::Time].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, *};
+/// fn flashing_text(mut ctx: WidgetContext) -> WidgetNode {
+///     ctx.life_cycle.mount(|context| {
+///         start_ui_animation(&context.animator, "flash", 0.5);
+///     });
+///
+///     let alpha = ui_animation_progress(&ctx, "flash", Easing::QuadOut);
+///
+///     make_widget!(text_box)
+///         .with_props(TextBoxProps {
+///             text: "Level Up!".into(),
+///             color: Color {
+///                 r: 1.,
+///                 g: 1.,
+///                 b: 1.,
+///                 a: alpha,
+///             },
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+pub fn start_ui_animation(animator: &Animator, name: &str, duration: Scalar) {
+    let _ = animator.change(
+        name,
+        Some(Animation::Value(AnimatedValue {
+            name: PROGRESS_VALUE.to_owned(),
+            duration,
+        })),
+    );
+}
+
+/// Get the eased progress, from `0.0` to `1.0`, of an animation started with
+/// [`start_ui_animation`]
+///
+/// Animations that have finished, or that were never started, have a progress of `1.0`.
+pub fn ui_animation_progress(ctx: &WidgetContext, name: &str, easing: Easing) -> Scalar {
+    easing.ease(
+        ctx.animator
+            .value_progress_factor(name, PROGRESS_VALUE)
+            .unwrap_or(1.),
+    )
+}
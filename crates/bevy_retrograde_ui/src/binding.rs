@@ -0,0 +1,316 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use bevy::{
+    ecs::component::Component,
+    prelude::{AppBuilder, Entity, World},
+    utils::HashMap,
+};
+use raui::prelude::{MessageData, Scalar, WidgetContext};
+
+/// Bevy [`AppBuilder`] extension for binding Bevy resources and components to the UI
+///
+/// Bound resources and components can be read by widgets with [`ui_resource`] and
+/// [`ui_component`], and changed by widgets by sending a [`UiResourceMutation`] or
+/// [`UiComponentMutation`] signal, which is applied to the Bevy world right after the UI is
+/// updated.
+///
+/// In the default [`Forced`][crate::UiProcessMode::Forced] process mode, the UI is processed every
+/// frame, so widgets that read bound data always show its latest value. In the
+/// [`Retained`][crate::UiProcessMode::Retained] mode, the UI is processed again after the widgets
+/// change bound data, but changes made by Bevy systems need a call to
+/// [`UiInvalidation::invalidate`][crate::UiInvalidation::invalidate].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::{raui::prelude::*, *};
+/// #[derive(Clone, Default)]
+/// struct Volume(f32);
+///
+/// fn use_volume_buttons(ctx: &mut WidgetContext) {
+///     ctx.life_cycle.change(|context| {
+///         for msg in context.messenger.messages {
+///             if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+///                 if msg.trigger_stop() {
+///                     context.signals.write(UiResourceMutation::new(|volume: &mut Volume| {
+///                         volume.0 = (volume.0 + 0.1).min(1.);
+///                     }));
+///                 }
+///             }
+///         }
+///     });
+/// }
+///
+/// #[pre_hooks(use_volume_buttons)]
+/// fn volume_label(mut ctx: WidgetContext) -> WidgetNode {
+///     let volume = ui_resource::<Volume>(&mut ctx).unwrap_or_default();
+///
+///     make_widget!(text_box)
+///         .with_props(TextBoxProps {
+///             text: format!("Volume: {:.0}%", volume.0 * 100.),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+///
+/// fn main() {
+///     App::build()
+///         .init_resource::<Volume>()
+///         .add_ui_resource_binding::<Volume>();
+/// }
+/// ```
+pub trait AppBuilderUiBindingExt {
+    /// Let widgets change a resource with [`UiResourceMutation`] signals
+    fn add_ui_resource_binding<T: Component>(self) -> Self;
+    /// Let widgets change a component with [`UiComponentMutation`] signals
+    fn add_ui_component_binding<T: Component>(self) -> Self;
+    /// Let widgets read a number from a resource by name, with [`ui_bound_value`]
+    ///
+    /// Widgets like the [`retro_progress_bar`][crate::widgets::retro_progress_bar] take the name of
+    /// a bound value in their props, so that they can show the value without custom code.
+    fn add_ui_resource_value<T, F>(self, name: &str, read: F) -> Self
+    where
+        T: Component,
+        F: Fn(&T) -> Scalar + Send + Sync + 'static;
+    /// Let widgets read a number from a component by name, with [`ui_bound_value`]
+    ///
+    /// The value is read from the component of the entity given to [`ui_bound_value`], or from the
+    /// first entity with the component if no entity is given, which suits components that only one
+    /// entity has, such as the health of the player.
+    fn add_ui_component_value<T, F>(self, name: &str, read: F) -> Self
+    where
+        T: Component,
+        F: Fn(&T) -> Scalar + Send + Sync + 'static;
+}
+
+impl AppBuilderUiBindingExt for &mut AppBuilder {
+    fn add_ui_resource_binding<T: Component>(self) -> Self {
+        self.world_mut()
+            .get_resource_or_insert_with(UiBindings::default)
+            .appliers
+            .push(apply_resource_mutation::<T>);
+
+        self
+    }
+
+    fn add_ui_component_binding<T: Component>(self) -> Self {
+        self.world_mut()
+            .get_resource_or_insert_with(UiBindings::default)
+            .appliers
+            .push(apply_component_mutation::<T>);
+
+        self
+    }
+
+    fn add_ui_resource_value<T, F>(self, name: &str, read: F) -> Self
+    where
+        T: Component,
+        F: Fn(&T) -> Scalar + Send + Sync + 'static,
+    {
+        self.world_mut()
+            .get_resource_or_insert_with(UiValueBindings::default)
+            .readers
+            .insert(
+                name.to_string(),
+                Arc::new(move |world: &mut World, _: Option<Entity>| {
+                    world.get_resource::<T>().map(&read)
+                }),
+            );
+
+        self
+    }
+
+    fn add_ui_component_value<T, F>(self, name: &str, read: F) -> Self
+    where
+        T: Component,
+        F: Fn(&T) -> Scalar + Send + Sync + 'static,
+    {
+        self.world_mut()
+            .get_resource_or_insert_with(UiValueBindings::default)
+            .readers
+            .insert(
+                name.to_string(),
+                Arc::new(
+                    move |world: &mut World, entity: Option<Entity>| match entity {
+                        Some(entity) => world.get::<T>(entity).map(&read),
+                        None => world.query::<&T>().iter(world).next().map(&read),
+                    },
+                ),
+            );
+
+        self
+    }
+}
+
+/// Read a resource from the Bevy world in a widget
+///
+/// Returns [`None`] if the resource doesn't exist, or if the widget isn't processed by the Bevy
+/// Retrograde UI.
+pub fn ui_resource<T: Component + Clone>(ctx: &mut WidgetContext) -> Option<T> {
+    ctx.process_context
+        .get_mut::<World>()
+        .and_then(|world| world.get_resource::<T>())
+        .cloned()
+}
+
+/// Read a component of an entity from the Bevy world in a widget
+///
+/// Returns [`None`] if the entity doesn't have the component, or if the widget isn't processed by
+/// the Bevy Retrograde UI.
+pub fn ui_component<T: Component + Clone>(ctx: &mut WidgetContext, entity: Entity) -> Option<T> {
+    ctx.process_context
+        .get_mut::<World>()
+        .and_then(|world| world.get::<T>(entity))
+        .cloned()
+}
+
+/// Read a number bound with
+/// [`add_ui_resource_value`][AppBuilderUiBindingExt::add_ui_resource_value] or
+/// [`add_ui_component_value`][AppBuilderUiBindingExt::add_ui_component_value] in a widget
+///
+/// The entity is only used by component values. Returns [`None`] if there is no value with the
+/// given name, if the resource or component doesn't exist, or if the widget isn't processed by the
+/// Bevy Retrograde UI.
+pub fn ui_bound_value(
+    ctx: &mut WidgetContext,
+    name: &str,
+    entity: Option<Entity>,
+) -> Option<Scalar> {
+    let world = ctx.process_context.get_mut::<World>()?;
+    let read = world
+        .get_resource::<UiValueBindings>()?
+        .readers
+        .get(name)?
+        .clone();
+
+    read(world, entity)
+}
+
+/// A signal that changes a resource bound with
+/// [`add_ui_resource_binding`][AppBuilderUiBindingExt::add_ui_resource_binding]
+pub struct UiResourceMutation<T>(Arc<dyn Fn(&mut T) + Send + Sync>);
+
+impl<T> UiResourceMutation<T> {
+    /// Create a signal that changes the resource with the given function
+    pub fn new<F: Fn(&mut T) + Send + Sync + 'static>(mutate: F) -> Self {
+        Self(Arc::new(mutate))
+    }
+}
+
+/// A signal that changes a component bound with
+/// [`add_ui_component_binding`][AppBuilderUiBindingExt::add_ui_component_binding]
+pub struct UiComponentMutation<T> {
+    entity: Entity,
+    mutate: Arc<dyn Fn(&mut T) + Send + Sync>,
+}
+
+impl<T> UiComponentMutation<T> {
+    /// Create a signal that changes the component of the entity with the given function
+    pub fn new<F: Fn(&mut T) + Send + Sync + 'static>(entity: Entity, mutate: F) -> Self {
+        Self {
+            entity,
+            mutate: Arc::new(mutate),
+        }
+    }
+}
+
+impl<T> Clone for UiResourceMutation<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Clone for UiComponentMutation<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entity: self.entity,
+            mutate: self.mutate.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for UiResourceMutation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UiResourceMutation")
+            .field(&std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for UiComponentMutation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UiComponentMutation")
+            .field("entity", &self.entity)
+            .field("component", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T: 'static> MessageData for UiResourceMutation<T> {
+    fn clone_message(&self) -> Box<dyn MessageData> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl<T: 'static> MessageData for UiComponentMutation<T> {
+    fn clone_message(&self) -> Box<dyn MessageData> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// This resource contains the functions that apply the mutation signals of the bound resources and
+/// components to the Bevy world
+#[derive(Default, Clone)]
+pub(crate) struct UiBindings {
+    appliers: Vec<fn(&mut World, &dyn MessageData) -> bool>,
+}
+
+impl UiBindings {
+    /// Apply a signal to the world if it is a mutation of a bound resource or component, returning
+    /// whether or not it was
+    pub fn apply(&self, world: &mut World, message: &dyn MessageData) -> bool {
+        self.appliers.iter().any(|apply| apply(world, message))
+    }
+}
+
+/// A function that reads a bound value from the Bevy world
+type UiValueReader = Arc<dyn Fn(&mut World, Option<Entity>) -> Option<Scalar> + Send + Sync>;
+
+/// This resource contains the functions that read the bound values from the Bevy world, by name
+#[derive(Default, Clone)]
+pub(crate) struct UiValueBindings {
+    readers: HashMap<String, UiValueReader>,
+}
+
+fn apply_resource_mutation<T: Component>(world: &mut World, message: &dyn MessageData) -> bool {
+    match message.as_any().downcast_ref::<UiResourceMutation<T>>() {
+        Some(mutation) => {
+            if let Some(mut resource) = world.get_resource_mut::<T>() {
+                (mutation.0)(&mut resource);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+fn apply_component_mutation<T: Component>(world: &mut World, message: &dyn MessageData) -> bool {
+    match message.as_any().downcast_ref::<UiComponentMutation<T>>() {
+        Some(mutation) => {
+            if let Some(mut component) = world.get_mut::<T>(mutation.entity) {
+                (mutation.mutate)(&mut component);
+            }
+            true
+        }
+        None => false,
+    }
+}
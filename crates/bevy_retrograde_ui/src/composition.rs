@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+
+use bevy::{prelude::*, window::WindowId};
+use raui::prelude::MessageData;
+
+pub(crate) fn add_composition_systems(app: &mut AppBuilder) {
+    app.add_event::<UiTextComposition>()
+        .init_resource::<UiTextFocus>();
+
+    #[cfg(wasm)]
+    web::add_web_text_input(app);
+}
+
+/// Bevy event for text composed with an input method editor (IME), which is how languages such as
+/// Chinese, Japanese, and Korean are typed
+///
+/// While text is being composed, the focused [`retro_text_input`][crate::widgets::retro_text_input]
+/// shows it at the cursor, and once it is committed it is typed into the input.
+///
+/// On web, these events are sent for the browser's composition and input events. On desktop,
+/// windowing doesn't report the text being composed yet, but the committed text is typed like any
+/// other characters, so these events only need to be sent by platform-specific code that wants to
+/// show the composition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiTextComposition {
+    /// The text being composed changed, or the composition was cancelled if the text is empty
+    Update(String),
+    /// The composed text was committed
+    Commit(String),
+}
+
+/// A message sent to the focused text input with the text being composed with an input method
+///
+/// The text is empty once the composition is committed or cancelled. The
+/// [`retro_text_input`][crate::widgets::retro_text_input] widget handles these messages, and custom
+/// text input widgets can handle them too.
+#[derive(MessageData, Debug, Clone, PartialEq, Eq)]
+pub struct TextCompositionChange(pub String);
+
+/// This resource has the windows whose UI has a focused text input
+#[derive(Default)]
+pub(crate) struct UiTextFocus(pub HashSet<WindowId>);
+
+/// Get whether or not the keyboard focus is in the element that composes text on web, which
+/// counts as the window having keyboard focus
+#[cfg(wasm)]
+pub(crate) fn composing_has_focus() -> bool {
+    web::input_has_focus()
+}
+
+/// Get whether or not the keyboard focus is in the element that composes text on web, which
+/// counts as the window having keyboard focus
+#[cfg(not(wasm))]
+pub(crate) fn composing_has_focus() -> bool {
+    false
+}
+
+#[cfg(wasm)]
+mod web {
+    use std::{cell::RefCell, rc::Rc};
+
+    use bevy::{ecs::system::NonSendMut, prelude::*};
+    use wasm_bindgen::{prelude::*, JsCast};
+    use web_sys::{
+        CompositionEvent, Document, HtmlElement, HtmlInputElement, InputEvent, KeyboardEvent,
+        KeyboardEventInit,
+    };
+
+    use super::{UiTextComposition, UiTextFocus};
+
+    /// The ID of the hidden input element
+    const INPUT_ID: &str = "bevy-retrograde-text-input";
+
+    pub(super) fn add_web_text_input(app: &mut AppBuilder) {
+        app.insert_non_send_resource(WebTextInput::default())
+            .add_system_to_stage(CoreStage::PreUpdate, update_web_text_input.system());
+    }
+
+    /// The hidden input element that text is typed into while a text input is focused
+    ///
+    /// Browsers only compose text in editable elements, so the keyboard focus is moved from the
+    /// canvas to this element, and the text typed into it is sent as [`UiTextComposition`]s.
+    #[derive(Default)]
+    struct WebTextInput {
+        input: Option<HtmlInputElement>,
+        events: Rc<RefCell<Vec<UiTextComposition>>>,
+    }
+
+    pub(super) fn input_has_focus() -> bool {
+        web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.active_element())
+            .map(|element| element.id() == INPUT_ID)
+            .unwrap_or(false)
+    }
+
+    /// Focus the hidden input while a text input is focused, and send the text typed into it
+    fn update_web_text_input(
+        mut web_input: NonSendMut<WebTextInput>,
+        focus: Res<UiTextFocus>,
+        mut events: EventWriter<UiTextComposition>,
+    ) {
+        let document = match web_sys::window().and_then(|window| window.document()) {
+            Some(document) => document,
+            None => return,
+        };
+
+        let focused = !focus.0.is_empty();
+        if focused && web_input.input.is_none() {
+            web_input.input = create_input(&document, web_input.events.clone());
+        }
+
+        if let Some(input) = &web_input.input {
+            let has_focus = input_has_focus();
+            if focused && !has_focus {
+                let _ = input.focus();
+            } else if !focused && has_focus {
+                let _ = input.blur();
+                if let Some(canvas) = canvas(&document) {
+                    let _ = canvas.focus();
+                }
+            }
+        }
+
+        for event in web_input.events.borrow_mut().drain(..) {
+            events.send(event);
+        }
+    }
+
+    /// Get the canvas that the game is drawn to
+    fn canvas(document: &Document) -> Option<HtmlElement> {
+        document
+            .query_selector("canvas")
+            .ok()
+            .flatten()
+            .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+    }
+
+    /// Create the hidden input element and listen to its events
+    fn create_input(
+        document: &Document,
+        events: Rc<RefCell<Vec<UiTextComposition>>>,
+    ) -> Option<HtmlInputElement> {
+        let input = document
+            .create_element("input")
+            .ok()?
+            .dyn_into::<HtmlInputElement>()
+            .ok()?;
+        input.set_id(INPUT_ID);
+        input.set_attribute("autocomplete", "off").ok()?;
+        input.set_attribute("autocapitalize", "off").ok()?;
+        // Keep the input out of sight in the top left corner, where the input method shows the
+        // text being composed
+        input
+            .set_attribute(
+                "style",
+                "position: fixed; left: 0; top: 0; width: 1px; height: 1px; opacity: 0; \
+                 border: none; padding: 0;",
+            )
+            .ok()?;
+        document.body()?.append_child(&input).ok()?;
+
+        // Send the text being composed
+        let update_events = events.clone();
+        let on_composition_update = Closure::wrap(Box::new(move |event: CompositionEvent| {
+            update_events
+                .borrow_mut()
+                .push(UiTextComposition::Update(event.data().unwrap_or_default()));
+        }) as Box<dyn FnMut(CompositionEvent)>);
+        input
+            .add_event_listener_with_callback(
+                "compositionupdate",
+                on_composition_update.as_ref().unchecked_ref(),
+            )
+            .ok()?;
+        on_composition_update.forget();
+
+        // Send the text that is typed or committed, and clear the input for the next text.
+        // Browsers differ in whether the input event comes before or after the composition ends,
+        // so the text is committed by whichever one finds it in the input.
+        let commit_events = events;
+        let commit_input = input.clone();
+        let commit = move |composing: bool| {
+            if composing {
+                return;
+            }
+            let text = commit_input.value();
+            commit_input.set_value("");
+            commit_events.borrow_mut().push(if text.is_empty() {
+                UiTextComposition::Update(text)
+            } else {
+                UiTextComposition::Commit(text)
+            });
+        };
+        let commit_on_input = commit.clone();
+        let on_input =
+            Closure::wrap(
+                Box::new(move |event: InputEvent| commit_on_input(event.is_composing()))
+                    as Box<dyn FnMut(InputEvent)>,
+            );
+        input
+            .add_event_listener_with_callback("input", on_input.as_ref().unchecked_ref())
+            .ok()?;
+        on_input.forget();
+        let on_composition_end =
+            Closure::wrap(Box::new(move |_: CompositionEvent| commit(false))
+                as Box<dyn FnMut(CompositionEvent)>);
+        input
+            .add_event_listener_with_callback(
+                "compositionend",
+                on_composition_end.as_ref().unchecked_ref(),
+            )
+            .ok()?;
+        on_composition_end.forget();
+
+        // The canvas doesn't get keyboard events while the input has focus, so pass them on to it
+        // for the keys that edit the text, such as backspace and the arrow keys. Characters are
+        // only typed for the canvas' key press events, so they aren't typed twice.
+        let on_key = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            // The keys pressed while composing text edit the composition instead
+            if event.is_composing() || event.key() == "Process" {
+                return;
+            }
+            let canvas = match web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| canvas(&document))
+            {
+                Some(canvas) => canvas,
+                None => return,
+            };
+
+            let mut init = KeyboardEventInit::new();
+            init.key(&event.key())
+                .code(&event.code())
+                .repeat(event.repeat())
+                .shift_key(event.shift_key())
+                .ctrl_key(event.ctrl_key())
+                .alt_key(event.alt_key())
+                .meta_key(event.meta_key());
+            if let Ok(copy) =
+                KeyboardEvent::new_with_keyboard_event_init_dict(&event.type_(), &init)
+            {
+                let _ = canvas.dispatch_event(&copy);
+            }
+        }) as Box<dyn FnMut(KeyboardEvent)>);
+        for event_type in &["keydown", "keyup"] {
+            input
+                .add_event_listener_with_callback(event_type, on_key.as_ref().unchecked_ref())
+                .ok()?;
+        }
+        on_key.forget();
+
+        Some(input)
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::{math::UVec2, prelude::*};
+use raui::prelude::{
+    make_widget, ContentBoxItemLayout, ImageBoxImage, ImageBoxMaterial, ImageBoxProps, Rect,
+    WidgetContext, WidgetNode,
+};
+
+pub(crate) fn add_cursor_systems(app: &mut AppBuilder) {
+    app.add_system_to_stage(CoreStage::PostUpdate, update_os_cursor_visibility.system());
+}
+
+/// Insert this resource to hide the OS cursor and draw a pixel-art cursor over the UI instead
+///
+/// The cursor is drawn in the primary window, above the UI, at the position of the mouse in the UI.
+/// It changes image as the player hovers over buttons, presses the mouse, and drags widgets, and
+/// states without an image of their own use the `default` image. Removing the resource shows the OS
+/// cursor again.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut commands: Commands) {
+///     commands.insert_resource(CustomCursor {
+///         hover: Some(CustomCursorImage::new("ui/hand.png", UVec2::new(3, 0))),
+///         ..CustomCursor::new(CustomCursorImage::new("ui/arrow.png", UVec2::ZERO))
+///     });
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomCursor {
+    /// The image of the cursor when it isn't in any other state
+    pub default: CustomCursorImage,
+    /// The image of the cursor while it is over a button
+    pub hover: Option<CustomCursorImage>,
+    /// The image of the cursor while the left mouse button is pressed
+    pub pressed: Option<CustomCursorImage>,
+    /// The image of the cursor while it is dragging a widget
+    pub dragging: Option<CustomCursorImage>,
+}
+
+impl CustomCursor {
+    /// Create a cursor that has the same image in every state
+    pub fn new(default: CustomCursorImage) -> Self {
+        Self {
+            default,
+            hover: None,
+            pressed: None,
+            dragging: None,
+        }
+    }
+
+    /// Get the image of the cursor in the given state
+    pub(crate) fn image(&self, state: CursorState) -> &CustomCursorImage {
+        match state {
+            CursorState::Default => None,
+            CursorState::Hover => self.hover.as_ref(),
+            CursorState::Pressed => self.pressed.as_ref(),
+            CursorState::Dragging => self.dragging.as_ref(),
+        }
+        .unwrap_or(&self.default)
+    }
+}
+
+/// An image of a [`CustomCursor`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomCursorImage {
+    /// The asset path of the image
+    pub path: String,
+    /// The pixel of the image that points at the mouse position, from the top left corner
+    pub hotspot: UVec2,
+}
+
+impl CustomCursorImage {
+    /// Create a cursor image from its asset path and hotspot
+    pub fn new<P: Into<String>>(path: P, hotspot: UVec2) -> Self {
+        Self {
+            path: path.into(),
+            hotspot,
+        }
+    }
+}
+
+/// The states of the mouse cursor that a [`CustomCursor`] can have images for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CursorState {
+    Default,
+    Hover,
+    Pressed,
+    Dragging,
+}
+
+/// Where to draw the custom cursor this frame, passed to the cursor widget in the process context
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CursorDraw {
+    /// The image of the cursor, or [`None`] if no cursor is drawn
+    pub image: Option<String>,
+    /// The position of the top left corner of the image in the UI
+    pub position: raui::prelude::Vec2,
+    /// The size of the image in the UI
+    pub size: raui::prelude::Vec2,
+}
+
+/// Widget that draws the custom cursor on top of the rest of the UI
+pub(crate) fn cursor_widget(mut ctx: WidgetContext) -> WidgetNode {
+    let draw = match ctx.process_context.get_mut::<CursorDraw>() {
+        Some(draw) => draw.clone(),
+        None => return WidgetNode::None,
+    };
+    let image = match draw.image {
+        Some(image) => image,
+        None => return WidgetNode::None,
+    };
+    let raui::prelude::Vec2 { x, y } = draw.position;
+
+    make_widget!(raui::prelude::content_box)
+        .key(ctx.key)
+        .listed_slot(
+            make_widget!(raui::prelude::image_box)
+                .with_props(ImageBoxProps {
+                    material: ImageBoxMaterial::Image(ImageBoxImage {
+                        id: image,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .with_props(ContentBoxItemLayout {
+                    anchors: Rect::default(),
+                    margin: Rect {
+                        left: x,
+                        top: y,
+                        right: -(x + draw.size.x),
+                        bottom: -(y + draw.size.y),
+                    },
+                    ..Default::default()
+                }),
+        )
+        .into()
+}
+
+/// Hide the OS cursor of the primary window while there is a custom cursor
+fn update_os_cursor_visibility(
+    custom_cursor: Option<Res<CustomCursor>>,
+    mut windows: ResMut<Windows>,
+    mut os_cursor_hidden: Local<bool>,
+) {
+    let hide = custom_cursor.is_some();
+    if hide == *os_cursor_hidden {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_cursor_visibility(!hide);
+        *os_cursor_hidden = hide;
+    }
+}
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+
+pub(crate) fn add_debug_systems(app: &mut AppBuilder) {
+    app.init_resource::<UiDebugOverlay>()
+        .add_system(toggle_ui_debug_overlay.system());
+}
+
+/// This resource controls the UI debug overlay
+///
+/// While the overlay is enabled, the UI is drawn with outlines around the layout rect of every
+/// widget in green, around the clipping regions in red, around the selected button in yellow, and
+/// around the focused text input in cyan. The ID of the widget under the mouse is also logged
+/// whenever it changes, which helps with finding which widget is taking up space in a layout.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut debug_overlay: ResMut<UiDebugOverlay>) {
+///     debug_overlay.toggle_key = Some(KeyCode::F12);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UiDebugOverlay {
+    /// Whether or not the overlay is shown
+    pub enabled: bool,
+    /// A key that shows and hides the overlay, if any
+    pub toggle_key: Option<KeyCode>,
+    /// Whether or not to log the ID of the widget under the mouse while the overlay is shown
+    pub log_widget_under_cursor: bool,
+}
+
+impl Default for UiDebugOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: None,
+            log_widget_under_cursor: true,
+        }
+    }
+}
+
+/// Show or hide the debug overlay when its toggle key is pressed
+fn toggle_ui_debug_overlay(
+    keyboard: Res<Input<KeyCode>>,
+    mut debug_overlay: ResMut<UiDebugOverlay>,
+) {
+    if let Some(key) = debug_overlay.toggle_key {
+        if keyboard.just_pressed(key) {
+            debug_overlay.enabled = !debug_overlay.enabled;
+        }
+    }
+}
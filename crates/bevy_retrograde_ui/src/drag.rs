@@ -0,0 +1,150 @@
+use raui::prelude::{
+    ButtonNotifyMessage, Deserialize, MessageData, NavSignal, PropsData, Serialize, Signal, Vec2,
+    WidgetContext, WidgetId,
+};
+
+/// The props of a widget that can be dragged with the [`use_retro_draggable`] hook
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroDraggableProps {
+    /// The data that is dropped on the drop target, such as the ID of an inventory item or a card
+    pub payload: String,
+}
+
+/// The state of a widget with the [`use_retro_draggable`] hook
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroDraggableState {
+    /// Whether or not the widget is being dragged
+    pub dragging: bool,
+    /// How far the pointer has been dragged from where the drag started
+    pub offset: Vec2,
+}
+
+/// A message sent by the interactions engine to the widgets taking part in a drag-and-drop
+#[derive(MessageData, Debug, Clone)]
+pub enum UiDragMessage {
+    /// Sent to the dragged widget every time the pointer moves while it is dragged, with how far
+    /// the pointer has moved since the drag started
+    Moved(Vec2),
+    /// Sent to the dragged widget when it is dropped
+    Ended,
+    /// Sent to the button under the pointer when a widget is dropped on it
+    Dropped {
+        /// The widget that was dropped
+        source: WidgetId,
+        /// The payload of the widget that was dropped
+        payload: String,
+    },
+}
+
+/// Bevy event sent when a widget is dropped on a drop target
+///
+/// Drop targets are widgets with the [`use_retro_drop_target`] hook, such as the
+/// [`retro_drop_target`][crate::widgets::retro_drop_target] widget.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn equip_items(mut drops: EventReader<UiDropped>) {
+///     for drop in drops.iter() {
+///         if drop.target.key() == "weapon-slot" {
+///             info!("Equipped {}", drop.payload);
+///         }
+///     }
+/// }
+/// ```
+#[derive(MessageData, Debug, Clone)]
+pub struct UiDropped {
+    /// The widget that was dropped
+    pub source: WidgetId,
+    /// The drop target that it was dropped on
+    pub target: WidgetId,
+    /// The payload of the widget that was dropped
+    pub payload: String,
+}
+
+/// Signal sent by a draggable widget when the pointer is pressed on it, which the interactions
+/// engine starts the drag from once the pointer has moved far enough
+#[derive(MessageData, Debug, Clone)]
+pub(crate) struct UiDragStart {
+    pub payload: String,
+}
+
+/// Hook that lets a button be dragged with the mouse and dropped on widgets with the
+/// [`use_retro_drop_target`] hook
+///
+/// The drag starts once the pointer has moved the
+/// [`drag_threshold`][crate::UiNavigationSettings::drag_threshold] away from where it was pressed.
+/// While it is dragged, the [`RetroDraggableState`] of the widget has the offset of the pointer, so
+/// that the widget can follow it.
+pub fn use_retro_draggable(ctx: &mut WidgetContext) {
+    ctx.life_cycle.change(|context| {
+        let mut state = context
+            .state
+            .read_cloned_or_default::<RetroDraggableState>();
+        let mut changed = false;
+
+        for msg in context.messenger.messages {
+            let pressed = if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                msg.trigger_start()
+            } else {
+                matches!(
+                    msg.as_any().downcast_ref::<NavSignal>(),
+                    Some(NavSignal::Accept(true))
+                )
+            };
+
+            if pressed {
+                let props = context
+                    .props
+                    .read_cloned_or_default::<RetroDraggableProps>();
+                context.signals.write(UiDragStart {
+                    payload: props.payload,
+                });
+            } else if let Some(msg) = msg.as_any().downcast_ref::<UiDragMessage>() {
+                match msg {
+                    UiDragMessage::Moved(offset) => {
+                        state.dragging = true;
+                        state.offset = *offset;
+                    }
+                    UiDragMessage::Ended => state = Default::default(),
+                    UiDragMessage::Dropped { .. } => continue,
+                }
+                changed = true;
+            }
+        }
+
+        if changed {
+            let _ = context.state.write(state);
+        }
+    });
+}
+
+/// Hook that makes a button send [`UiDropped`] events when a widget with the
+/// [`use_retro_draggable`] hook is dropped on it
+pub fn use_retro_drop_target(ctx: &mut WidgetContext) {
+    ctx.life_cycle.change(|context| {
+        for msg in context.messenger.messages {
+            if let Some(UiDragMessage::Dropped { source, payload }) =
+                msg.as_any().downcast_ref::<UiDragMessage>()
+            {
+                context.signals.write(UiDropped {
+                    source: source.clone(),
+                    target: context.id.to_owned(),
+                    payload: payload.clone(),
+                });
+            }
+        }
+    });
+}
+
+/// Get the drag that the signals of the UI start, if any
+pub(crate) fn find_drag_start(signals: &[Signal]) -> Option<(WidgetId, String)> {
+    signals.iter().find_map(|(sender, message)| {
+        message
+            .as_any()
+            .downcast_ref::<UiDragStart>()
+            .map(|start| (sender.clone(), start.payload.clone()))
+    })
+}
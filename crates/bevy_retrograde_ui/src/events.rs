@@ -0,0 +1,215 @@
+use bevy::{app::Events, ecs::component::Component, prelude::*};
+use raui::prelude::{
+    ButtonNotifyMessage, ButtonProps, MessageData, NavSignal, Signal, WidgetContext, WidgetId,
+};
+
+use crate::{binding::UiBindings, UiDropped};
+
+pub(crate) fn add_events(app: &mut AppBuilder) {
+    app.add_event::<UiSignal>()
+        .add_ui_signal_event::<UiButtonClicked>()
+        .add_ui_signal_event::<UiValueChanged>()
+        .add_ui_signal_event::<UiDropped>();
+}
+
+/// Bevy [`AppBuilder`] extension for getting the signals of UI widgets as Bevy events
+///
+/// Every signal is sent as a [`UiSignal`] event, but signals of the types added with
+/// [`add_ui_signal_event`][Self::add_ui_signal_event] are also sent as events of their own type,
+/// so that game systems can read them with an `EventReader` without downcasting. The signals of
+/// the bundled widgets, such as [`UiButtonClicked`] and [`UiValueChanged`], are added by the UI
+/// plugin.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::{raui::prelude::*, *};
+/// #[derive(MessageData, Debug, Clone)]
+/// struct BuyItem(String);
+///
+/// fn shop_item(mut ctx: WidgetContext) -> WidgetNode {
+///     ctx.life_cycle.change(|context| {
+///         for msg in context.messenger.messages {
+///             if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+///                 if msg.trigger_stop() {
+///                     context.signals.write(BuyItem("potion".into()));
+///                 }
+///             }
+///         }
+///     });
+///
+///     Default::default()
+/// }
+///
+/// fn buy_items(mut purchases: EventReader<BuyItem>) {
+///     for purchase in purchases.iter() {
+///         info!("Bought {}", purchase.0);
+///     }
+/// }
+///
+/// fn main() {
+///     App::build()
+///         .add_ui_signal_event::<BuyItem>()
+///         .add_system(buy_items.system());
+/// }
+/// ```
+pub trait AppBuilderUiSignalExt {
+    /// Send the signals of type `T` that UI widgets write as Bevy events of type `T`
+    fn add_ui_signal_event<T: MessageData + Clone + Component>(self) -> Self;
+}
+
+impl AppBuilderUiSignalExt for &mut AppBuilder {
+    fn add_ui_signal_event<T: MessageData + Clone + Component>(self) -> Self {
+        self.add_event::<T>()
+            .world_mut()
+            .get_resource_or_insert_with(UiSignalEvents::default)
+            .senders
+            .push(send_signal_event::<T>);
+
+        self
+    }
+}
+
+/// This resource contains the functions that send the signals of the types added with
+/// [`add_ui_signal_event`][AppBuilderUiSignalExt::add_ui_signal_event] as Bevy events
+#[derive(Default, Clone)]
+pub(crate) struct UiSignalEvents {
+    senders: Vec<fn(&mut World, &dyn MessageData)>,
+}
+
+fn send_signal_event<T: MessageData + Clone + Component>(
+    world: &mut World,
+    message: &dyn MessageData,
+) {
+    if let Some(message) = message.as_any().downcast_ref::<T>() {
+        world
+            .get_resource_mut::<Events<T>>()
+            .unwrap()
+            .send(message.clone());
+    }
+}
+
+/// Bevy event sent when a UI button is clicked
+///
+/// Buttons send this event when they have the [`use_ui_button_events`] hook. The
+/// [`widgets`][crate::widgets] that are buttons always send it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn start_game(mut clicks: EventReader<UiButtonClicked>) {
+///     for click in clicks.iter() {
+///         if click.0.key() == "start-button" {
+///             // Start the game
+///         }
+///     }
+/// }
+/// ```
+#[derive(MessageData, Debug, Clone)]
+pub struct UiButtonClicked(pub WidgetId);
+
+/// A value edited in the UI
+#[derive(Debug, Clone, PartialEq)]
+pub enum UiValue {
+    Text(String),
+    Bool(bool),
+    Number(f32),
+}
+
+/// Bevy event sent when a value is edited in the UI, such as the text of a text input
+#[derive(MessageData, Debug, Clone)]
+pub struct UiValueChanged {
+    /// The widget that the value was edited in
+    pub widget: WidgetId,
+    /// The new value
+    pub value: UiValue,
+}
+
+/// Bevy event sent for every signal that a UI widget sends
+///
+/// Widgets send signals with `context.signals.write()`, so this can be used to get any message out
+/// of the UI, including the [`UiButtonClicked`] and [`UiValueChanged`] events, which are also sent
+/// as their own events. Use [`add_ui_signal_event`][AppBuilderUiSignalExt::add_ui_signal_event] to
+/// send signals of your own types as their own events too.
+#[derive(Debug)]
+pub struct UiSignal {
+    /// The widget that sent the signal
+    pub sender: WidgetId,
+    /// The signal message
+    pub message: Box<dyn MessageData>,
+}
+
+impl UiSignal {
+    /// Get the message if it is of type `T`
+    pub fn downcast<T: 'static>(&self) -> Option<&T> {
+        self.message.as_any().downcast_ref::<T>()
+    }
+}
+
+/// Hook that makes a button send [`UiButtonClicked`] events when it is clicked
+///
+/// This works both for widgets that are buttons themselves, with the `use_button` hook, and for
+/// widgets that are notified by a button with `ButtonNotifyProps`. In either case the event has the
+/// ID of the widget with this hook.
+pub fn use_ui_button_events(ctx: &mut WidgetContext) {
+    ctx.life_cycle.change(|context| {
+        for msg in context.messenger.messages {
+            let clicked = if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                msg.trigger_stop()
+            } else if let Some(NavSignal::Accept(false)) = msg.as_any().downcast_ref::<NavSignal>()
+            {
+                // The state still has the button props from before this change
+                context
+                    .state
+                    .read_cloned_or_default::<ButtonProps>()
+                    .trigger
+            } else {
+                false
+            };
+
+            if clicked {
+                context
+                    .signals
+                    .write(UiButtonClicked(context.id.to_owned()));
+            }
+        }
+    });
+}
+
+/// Apply the UI binding mutations and send the other signals consumed from the UI app as Bevy
+/// events, returning whether or not any bound resources or components were changed
+pub(crate) fn send_ui_signals(world: &mut World, signals: Vec<Signal>) -> bool {
+    let bindings = world
+        .get_resource::<UiBindings>()
+        .cloned()
+        .unwrap_or_default();
+    let signal_events = world
+        .get_resource::<UiSignalEvents>()
+        .cloned()
+        .unwrap_or_default();
+
+    let mut bindings_changed = false;
+
+    for (sender, message) in signals {
+        // Apply the changes of widgets to bound resources and components
+        if bindings.apply(world, &*message) {
+            bindings_changed = true;
+            continue;
+        }
+
+        // Send the signal as an event of its own type if it has one
+        for send in &signal_events.senders {
+            send(world, &*message);
+        }
+
+        world
+            .get_resource_mut::<Events<UiSignal>>()
+            .unwrap()
+            .send(UiSignal { sender, message });
+    }
+
+    bindings_changed
+}
@@ -0,0 +1,96 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use bevy::{
+    asset::{Asset, AssetPath, HandleId, LoadState},
+    prelude::{AssetServer, Handle},
+};
+use bevy_retrograde_core::prelude::Image;
+use dashmap::DashMap;
+
+/// The prefix of the IDs that RAUI props use to refer to asset handles
+const HANDLE_ID_PREFIX: &str = "bevy-handle:";
+
+lazy_static::lazy_static! {
+    /// The asset handles that have been given IDs with [`UiHandleExt::ui_id`]
+    static ref UI_HANDLE_IDS: DashMap<String, HandleId> = DashMap::new();
+}
+
+/// Extension trait for using Bevy asset handles in RAUI props
+///
+/// RAUI refers to images and fonts by name, which Bevy Retrograde normally loads as asset paths.
+/// The ID of a handle can be used instead of an asset path, which allows the UI to use assets that
+/// have already been loaded, or that were created at runtime and don't have a path.
+///
+/// The UI keeps the assets that it uses loaded, so the handles don't need to be kept elsewhere.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::Image;
+/// # use bevy_retrograde_ui::{raui::prelude::*, UiHandleExt};
+/// fn portrait(image: &Handle<Image>) -> WidgetNode {
+///     make_widget!(image_box)
+///         .with_props(ImageBoxProps {
+///             material: ImageBoxMaterial::Image(ImageBoxImage {
+///                 id: image.ui_id(),
+///                 ..Default::default()
+///             }),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+pub trait UiHandleExt {
+    /// Get the ID that refers to this handle in RAUI props, such as the `id` of an `ImageBoxImage`
+    /// or the `name` of a `TextBoxFont`
+    fn ui_id(&self) -> String;
+}
+
+impl<T: Asset> UiHandleExt for Handle<T> {
+    fn ui_id(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.id.hash(&mut hasher);
+        let id = format!("{}{:016x}", HANDLE_ID_PREFIX, hasher.finish());
+
+        UI_HANDLE_IDS.entry(id.clone()).or_insert(self.id);
+
+        id
+    }
+}
+
+/// Create a handle for a texture that a render hook uploads to the GPU itself
+///
+/// Images in the UI are drawn from the textures in the
+/// [`TextureCache`][bevy_retrograde_core::graphics::TextureCache], which normally has the textures
+/// of [`Image`] assets. A render hook can also insert a texture of its own, such as one that it
+/// renders a minimap or character preview to, under a handle from this function, and the texture
+/// can then be shown in the UI with the handle's [`ui_id`][UiHandleExt::ui_id].
+pub fn new_ui_texture_handle() -> Handle<Image> {
+    Handle::weak(HandleId::random::<Image>())
+}
+
+/// Get the handle of an image or font used by the UI, from either its asset path or its handle ID
+pub(crate) fn get_ui_handle<T: Asset>(asset_server: &AssetServer, name: &str) -> Handle<T> {
+    match UI_HANDLE_IDS.get(name) {
+        Some(handle_id) => asset_server.get_handle(*handle_id),
+        None => asset_server.get_handle(HandleId::from(AssetPath::from(name))),
+    }
+}
+
+/// Get the handle of an image or font used by the UI, loading it if it is an asset path that hasn't
+/// started loading yet
+pub(crate) fn load_ui_handle<T: Asset>(asset_server: &AssetServer, name: &str) -> Handle<T> {
+    let handle = get_ui_handle(asset_server, name);
+
+    if !name.starts_with(HANDLE_ID_PREFIX) {
+        if let LoadState::NotLoaded = asset_server.get_load_state(&handle) {
+            asset_server.load::<T, _>(name);
+        }
+    }
+
+    handle
+}
@@ -1,18 +1,79 @@
 use bevy::{
     app::{Events, ManualEventReader},
+    core::Time,
     input::{
+        gamepad::{
+            Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads,
+        },
         keyboard::KeyboardInput,
         mouse::{MouseButtonInput, MouseWheel},
-        Input,
+        touch::{TouchInput, TouchPhase},
+        Axis, Input,
     },
     prelude::{KeyCode, World},
-    window::{CursorMoved, ReceivedCharacter},
+    window::{CursorLeft, CursorMoved, ReceivedCharacter, Window, WindowFocused, WindowId},
 };
 
 use raui::prelude::{
-    DefaultInteractionsEngine, DefaultInteractionsEngineResult, InteractionsEngine,
+    DefaultInteractionsEngine, DefaultInteractionsEngineResult, Interaction, InteractionsEngine,
+    MessageData, NavDirection, NavJump, NavSignal, NavTextChange, Signal, WidgetId,
 };
 
+use crate::{
+    composition::{composing_has_focus, TextCompositionChange, UiTextComposition, UiTextFocus},
+    cursor::CursorState,
+    drag::{find_drag_start, UiDragMessage},
+    modal::ModalStack,
+    UiNavigationSettings, UiScaleMode,
+};
+
+/// A message sent to the focused text input when text is selected with the keyboard
+///
+/// Text is selected by holding shift while moving the cursor, or with ctrl + A. The
+/// [`retro_text_input`][crate::widgets::retro_text_input] widget handles these messages, and custom
+/// text input widgets can handle them too, alongside the usual [`NavTextChange`]s.
+#[derive(MessageData, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextSelectionChange {
+    /// Move the cursor left, extending the selection
+    SelectLeft,
+    /// Move the cursor right, extending the selection
+    SelectRight,
+    /// Move the cursor to the start of the text, extending the selection
+    SelectStart,
+    /// Move the cursor to the end of the text, extending the selection
+    SelectEnd,
+    /// Select all of the text
+    SelectAll,
+}
+
+/// A text editing event for the focused text input
+enum TextInputEvent {
+    Change(NavTextChange),
+    Select(TextSelectionChange),
+    Compose(String),
+}
+
+/// A touch that taps buttons and drags scroll views
+struct Touch {
+    id: u64,
+    /// The position where the touch started
+    start: raui::prelude::Vec2,
+    /// The position of the touch as of its last event
+    last: raui::prelude::Vec2,
+    /// Whether or not the touch has moved far enough to scroll instead of tap
+    scrolling: bool,
+}
+
+/// A widget that the mouse is pressed on, which is dragged once the mouse moves far enough
+struct Drag {
+    source: WidgetId,
+    payload: String,
+    /// The position of the mouse when the drag started
+    start: raui::prelude::Vec2,
+    /// Whether or not the mouse has moved far enough to start dragging
+    dragging: bool,
+}
+
 pub(crate) struct BevyInteractionsEngine {
     engine: DefaultInteractionsEngine,
     mouse_position: raui::prelude::Vec2,
@@ -21,10 +82,43 @@ pub(crate) struct BevyInteractionsEngine {
     mouse_button_event_reader: ManualEventReader<MouseButtonInput>,
     mouse_scroll_event_reader: ManualEventReader<MouseWheel>,
     character_input_event_reader: ManualEventReader<ReceivedCharacter>,
+    composition_event_reader: ManualEventReader<UiTextComposition>,
+    touch_event_reader: ManualEventReader<TouchInput>,
+    /// The touch that is tapping or scrolling the UI, if any
+    touch: Option<Touch>,
+    /// The direction that the gamepads are held in, if any
+    gamepad_direction: Option<NavDirection>,
+    /// The time in seconds until the held gamepad direction navigates again
+    gamepad_repeat_timer: f32,
+    /// Text editing events that haven't been sent to the focused text input yet
+    text_input_events: Vec<TextInputEvent>,
+    /// The window whose UI this engine interacts with
+    window_id: WindowId,
+    /// Whether or not the window has keyboard focus, which keyboard and gamepad input is only
+    /// used when it does
+    window_focused: bool,
+    /// Whether or not the cursor is in the window, which mouse input is only used when it is
+    cursor_in_window: bool,
+    window_focused_event_reader: ManualEventReader<WindowFocused>,
+    cursor_left_event_reader: ManualEventReader<CursorLeft>,
+    /// Whether or not the left mouse button is held down
+    trigger_held: bool,
+    /// The widget being dragged, if any
+    drag: Option<Drag>,
+    /// The drag that ended with the last mouse release, which is dropped once the interactions
+    /// engine has found the button under the mouse
+    finished_drag: Option<Drag>,
+    /// Drag messages that haven't been sent to the dragged widget yet
+    drag_messages: Vec<(WidgetId, UiDragMessage)>,
+    /// The modals that are open
+    modals: ModalStack,
+    /// The button that was selected before the interactions engine last ran
+    selected_before_interactions: Option<WidgetId>,
 }
 
-impl Default for BevyInteractionsEngine {
-    fn default() -> Self {
+impl BevyInteractionsEngine {
+    /// Create an interactions engine for the UI of a window
+    pub fn new(window_id: WindowId) -> Self {
         BevyInteractionsEngine {
             engine: {
                 let mut e = DefaultInteractionsEngine::default();
@@ -38,68 +132,193 @@ impl Default for BevyInteractionsEngine {
             mouse_button_event_reader: Default::default(),
             mouse_scroll_event_reader: Default::default(),
             character_input_event_reader: Default::default(),
+            composition_event_reader: Default::default(),
+            touch_event_reader: Default::default(),
+            touch: None,
+            gamepad_direction: None,
+            gamepad_repeat_timer: 0.,
+            text_input_events: Default::default(),
+            window_id,
+            // The primary window starts out with keyboard focus
+            window_focused: window_id.is_primary(),
+            cursor_in_window: false,
+            window_focused_event_reader: Default::default(),
+            cursor_left_event_reader: Default::default(),
+            trigger_held: false,
+            drag: None,
+            finished_drag: None,
+            drag_messages: Default::default(),
+            modals: Default::default(),
+            selected_before_interactions: None,
+        }
+    }
+
+    /// Get the position of the mouse in the UI and the state of the cursor, or [`None`] if the
+    /// mouse isn't in the window
+    pub fn cursor(&self) -> Option<(raui::prelude::Vec2, CursorState)> {
+        if !self.cursor_in_window {
+            return None;
+        }
+
+        let state = if self.drag.as_ref().map(|x| x.dragging).unwrap_or(false) {
+            CursorState::Dragging
+        } else if self.trigger_held {
+            CursorState::Pressed
+        } else if self.engine.selected_button().is_some() {
+            CursorState::Hover
+        } else {
+            CursorState::Default
+        };
+
+        Some((self.mouse_position, state))
+    }
+
+    /// Get the button that is selected, if any
+    pub fn selected_button(&self) -> Option<&WidgetId> {
+        self.engine.selected_button()
+    }
+
+    /// Get the text input that is focused, if any
+    pub fn focused_text_input(&self) -> Option<&WidgetId> {
+        self.engine.focused_text_input()
+    }
+
+    /// Open and close modals, and start dragging a widget if the UI signals that the mouse was
+    /// pressed on a draggable widget
+    pub fn handle_signals(&mut self, signals: &[Signal]) {
+        for signal in self.modals.handle_signals(
+            signals,
+            self.selected_before_interactions.as_ref(),
+            self.engine.selected_button(),
+        ) {
+            self.engine.interact(Interaction::Navigate(signal));
+        }
+
+        // The signal is sent the frame after the mouse is pressed, so it may have been released
+        // already
+        if !self.trigger_held || self.drag.is_some() {
+            return;
+        }
+
+        if let Some((source, payload)) = find_drag_start(signals) {
+            self.drag = Some(Drag {
+                source,
+                payload,
+                start: self.mouse_position,
+                dragging: false,
+            });
         }
     }
-}
 
-impl BevyInteractionsEngine {
     pub fn update(&mut self, world: &mut World, target_size: bevy::math::UVec2) {
         use crate::raui::prelude::*;
 
         let windows = world.get_resource::<bevy::window::Windows>().unwrap();
         let keyboard_state = world.get_resource::<Input<KeyCode>>().unwrap();
+        let settings = world
+            .get_resource::<UiNavigationSettings>()
+            .cloned()
+            .unwrap_or_default();
+        let ui_coords_mapping = world
+            .get_resource::<UiScaleMode>()
+            .cloned()
+            .unwrap_or_default()
+            .coords_mapping(target_size);
+
+        // Track whether or not the window has keyboard focus
+        let window_focused_events = world.get_resource::<Events<WindowFocused>>().unwrap();
+        for event in self.window_focused_event_reader.iter(window_focused_events) {
+            if event.id == self.window_id {
+                // On web, the keyboard focus moves out of the canvas while text is typed, but the
+                // keyboard events are still passed on to the window
+                self.window_focused = event.focused || composing_has_focus();
+            }
+        }
+
+        // Track whether or not the cursor has left the window
+        let cursor_left_events = world.get_resource::<Events<CursorLeft>>().unwrap();
+        for event in self.cursor_left_event_reader.iter(cursor_left_events) {
+            if event.id == self.window_id {
+                self.cursor_in_window = false;
+            }
+        }
 
         // Process cursor move events
         let cursor_moved_events = world.get_resource::<Events<CursorMoved>>().unwrap();
         for event in self.cursor_moved_event_reader.iter(cursor_moved_events) {
+            if event.id != self.window_id {
+                continue;
+            }
+            self.cursor_in_window = true;
+
             let window = windows.get(event.id).unwrap();
-            let coords_mapping = CoordsMapping::new_scaling(
-                Rect {
-                    left: 0.,
-                    right: window.width(),
-                    top: 0.,
-                    bottom: window.height(),
-                },
-                CoordsMappingScaling::Stretch(Vec2 {
-                    x: target_size.x as f32,
-                    y: target_size.y as f32,
-                }),
-            );
-
-            self.mouse_position = coords_mapping.real_to_virtual_vec2(
-                Vec2 {
-                    x: event.position.x,
-                    y: window.height() - event.position.y,
-                },
-                false,
-            );
+            self.mouse_position =
+                window_to_ui_position(window, event.position, target_size, &ui_coords_mapping);
 
-            self.engine
-                .interact(Interaction::PointerMove(self.mouse_position));
+            if let Some(interaction) = self
+                .modals
+                .filter_pointer(Interaction::PointerMove(self.mouse_position))
+            {
+                self.engine.interact(interaction);
+            }
+
+            // Move the dragged widget, starting the drag once the mouse has moved far enough
+            if let Some(drag) = &mut self.drag {
+                let offset = Vec2 {
+                    x: self.mouse_position.x - drag.start.x,
+                    y: self.mouse_position.y - drag.start.y,
+                };
+                if offset.x.hypot(offset.y) >= settings.drag_threshold {
+                    drag.dragging = true;
+                }
+                if drag.dragging {
+                    self.drag_messages
+                        .push((drag.source.clone(), UiDragMessage::Moved(offset)));
+                }
+            }
         }
 
         // Process mouse button events
         let mouse_button_events = world.get_resource::<Events<MouseButtonInput>>().unwrap();
         for event in self.mouse_button_event_reader.iter(mouse_button_events) {
+            if !self.cursor_in_window {
+                continue;
+            }
+
             let button = match event.button {
                 bevy::prelude::MouseButton::Left => raui::prelude::PointerButton::Trigger,
                 bevy::prelude::MouseButton::Right => raui::prelude::PointerButton::Context,
                 _ => continue,
             };
 
-            self.engine.interact(match event.state {
+            let is_trigger = matches!(button, PointerButton::Trigger);
+            if let Some(interaction) = self.modals.filter_pointer(match event.state {
                 bevy::input::ElementState::Pressed => {
                     Interaction::PointerDown(button, self.mouse_position)
                 }
                 bevy::input::ElementState::Released => {
                     Interaction::PointerUp(button, self.mouse_position)
                 }
-            });
+            }) {
+                self.engine.interact(interaction);
+            }
+
+            // Drop the dragged widget when the left mouse button is released
+            if is_trigger {
+                self.trigger_held = event.state == bevy::input::ElementState::Pressed;
+                if !self.trigger_held {
+                    self.finished_drag = self.drag.take().filter(|drag| drag.dragging);
+                }
+            }
         }
 
         // Process mouse scroll events
         let mouse_scroll_events = world.get_resource::<Events<MouseWheel>>().unwrap();
         for event in self.mouse_scroll_event_reader.iter(mouse_scroll_events) {
+            if !self.cursor_in_window {
+                continue;
+            }
+
             let multiplier = match event.unit {
                 bevy::input::mouse::MouseScrollUnit::Line => 10.,
                 bevy::input::mouse::MouseScrollUnit::Pixel => 1.,
@@ -116,98 +335,214 @@ impl BevyInteractionsEngine {
                 ))));
         }
 
+        // Process touch events. Touches that are lifted without moving click the button that they
+        // tapped, and touches that move scroll the scroll view under them.
+        let touch_events = world.get_resource::<Events<TouchInput>>().unwrap();
+        for event in self.touch_event_reader.iter(touch_events) {
+            // Touches don't say which window they are in, so they only go to the primary window
+            if !self.window_id.is_primary() {
+                continue;
+            }
+            let window = match windows.get(self.window_id) {
+                Some(window) => window,
+                None => continue,
+            };
+            let position =
+                window_to_ui_position(window, event.position, target_size, &ui_coords_mapping);
+
+            match event.phase {
+                TouchPhase::Started => {
+                    if self.touch.is_none() {
+                        // Move the pointer to the touch so that the button or scroll view under it
+                        // is selected
+                        self.mouse_position = position;
+                        if let Some(interaction) = self
+                            .modals
+                            .filter_pointer(Interaction::PointerMove(position))
+                        {
+                            self.engine.interact(interaction);
+                        }
+                        self.touch = Some(Touch {
+                            id: event.id,
+                            start: position,
+                            last: position,
+                            scrolling: false,
+                        });
+                    }
+                }
+                TouchPhase::Moved => {
+                    if let Some(touch) = &mut self.touch {
+                        if touch.id == event.id {
+                            let moved = Vec2 {
+                                x: position.x - touch.start.x,
+                                y: position.y - touch.start.y,
+                            };
+                            if moved.x.hypot(moved.y) >= settings.drag_threshold {
+                                touch.scrolling = true;
+                            }
+
+                            if touch.scrolling {
+                                let value = Vec2 {
+                                    x: position.x - touch.last.x,
+                                    y: position.y - touch.last.y,
+                                };
+                                self.engine.interact(Interaction::Navigate(NavSignal::Jump(
+                                    NavJump::Scroll(NavScroll::Units(value, true)),
+                                )));
+                            }
+                            touch.last = position;
+                        }
+                    }
+                }
+                TouchPhase::Ended => {
+                    if let Some(touch) = self.touch.take() {
+                        if touch.id != event.id {
+                            self.touch = Some(touch);
+                        } else if !touch.scrolling {
+                            // Click where the touch started, which is where the pointer is
+                            for interaction in vec![
+                                Interaction::PointerDown(PointerButton::Trigger, touch.start),
+                                Interaction::PointerUp(PointerButton::Trigger, touch.start),
+                            ] {
+                                if let Some(interaction) = self.modals.filter_pointer(interaction) {
+                                    self.engine.interact(interaction);
+                                }
+                            }
+                        }
+                    }
+                }
+                TouchPhase::Cancelled => {
+                    if matches!(&self.touch, Some(touch) if touch.id == event.id) {
+                        self.touch = None;
+                    }
+                }
+            }
+        }
+
         // Process character input events
         let character_input_events = world.get_resource::<Events<ReceivedCharacter>>().unwrap();
         for event in self
             .character_input_event_reader
             .iter(character_input_events)
         {
-            if self.engine.focused_text_input().is_some() {
-                self.engine
-                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                        NavTextChange::InsertCharacter(event.char),
-                    )));
+            if !self.window_focused {
+                continue;
+            }
+
+            // Control characters such as backspace and enter are handled as keyboard events below
+            if self.engine.focused_text_input().is_some() && !event.char.is_control() {
+                self.text_input_events.push(TextInputEvent::Change(
+                    NavTextChange::InsertCharacter(event.char),
+                ));
+            }
+        }
+
+        // Process text composed with an input method
+        let composition_events = world.get_resource::<Events<UiTextComposition>>().unwrap();
+        for event in self.composition_event_reader.iter(composition_events) {
+            if !self.window_focused || self.engine.focused_text_input().is_none() {
+                continue;
+            }
+
+            match event {
+                UiTextComposition::Update(text) => {
+                    self.text_input_events
+                        .push(TextInputEvent::Compose(text.clone()));
+                }
+                UiTextComposition::Commit(text) => {
+                    self.text_input_events
+                        .push(TextInputEvent::Compose(String::new()));
+                    self.text_input_events.extend(
+                        text.chars()
+                            .filter(|c| !c.is_control())
+                            .map(|c| TextInputEvent::Change(NavTextChange::InsertCharacter(c))),
+                    );
+                }
             }
         }
 
         // Process keyboard events
         let keyboard_events = world.get_resource::<Events<KeyboardInput>>().unwrap();
         for event in self.keyboard_event_reader.iter(keyboard_events) {
+            if !self.window_focused {
+                continue;
+            }
+
             match event.state {
                 bevy::input::ElementState::Pressed => {
                     if self.engine.focused_text_input().is_some() {
-                        match event.key_code {
-                            Some(KeyCode::Left) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::MoveCursorLeft,
-                                    )))
+                        let shift_pressed = keyboard_state.pressed(KeyCode::LShift)
+                            | keyboard_state.pressed(KeyCode::RShift);
+                        let ctrl_pressed = keyboard_state.pressed(KeyCode::LControl)
+                            | keyboard_state.pressed(KeyCode::RControl);
+                        let event = match (event.key_code, shift_pressed) {
+                            (Some(KeyCode::Left), false) => {
+                                TextInputEvent::Change(NavTextChange::MoveCursorLeft)
                             }
-                            Some(KeyCode::Right) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::MoveCursorRight,
-                                    )))
+                            (Some(KeyCode::Left), true) => {
+                                TextInputEvent::Select(TextSelectionChange::SelectLeft)
                             }
-                            Some(KeyCode::Home) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::MoveCursorStart,
-                                    )))
+                            (Some(KeyCode::Right), false) => {
+                                TextInputEvent::Change(NavTextChange::MoveCursorRight)
                             }
-                            Some(KeyCode::End) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::MoveCursorEnd,
-                                    )))
+                            (Some(KeyCode::Right), true) => {
+                                TextInputEvent::Select(TextSelectionChange::SelectRight)
                             }
-                            Some(KeyCode::Back) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::DeleteLeft,
-                                    )))
+                            (Some(KeyCode::Home), false) => {
+                                TextInputEvent::Change(NavTextChange::MoveCursorStart)
                             }
-                            Some(KeyCode::Delete) => {
-                                self.engine
-                                    .interact(Interaction::Navigate(NavSignal::TextChange(
-                                        NavTextChange::DeleteRight,
-                                    )))
+                            (Some(KeyCode::Home), true) => {
+                                TextInputEvent::Select(TextSelectionChange::SelectStart)
                             }
-                            Some(KeyCode::Return) | Some(KeyCode::NumpadEnter) => self
-                                .engine
-                                .interact(Interaction::Navigate(NavSignal::TextChange(
-                                    NavTextChange::NewLine,
-                                ))),
-                            Some(KeyCode::Escape) => {
+                            (Some(KeyCode::End), false) => {
+                                TextInputEvent::Change(NavTextChange::MoveCursorEnd)
+                            }
+                            (Some(KeyCode::End), true) => {
+                                TextInputEvent::Select(TextSelectionChange::SelectEnd)
+                            }
+                            (Some(KeyCode::A), _) if ctrl_pressed => {
+                                TextInputEvent::Select(TextSelectionChange::SelectAll)
+                            }
+                            (Some(KeyCode::Back), _) => {
+                                TextInputEvent::Change(NavTextChange::DeleteLeft)
+                            }
+                            (Some(KeyCode::Delete), _) => {
+                                TextInputEvent::Change(NavTextChange::DeleteRight)
+                            }
+                            (Some(KeyCode::Return), _) | (Some(KeyCode::NumpadEnter), _) => {
+                                TextInputEvent::Change(NavTextChange::NewLine)
+                            }
+                            (Some(KeyCode::Escape), _) => {
                                 self.engine.interact(Interaction::Navigate(
                                     NavSignal::FocusTextInput(().into()),
                                 ));
+                                continue;
                             }
-                            _ => {}
-                        }
+                            _ => continue,
+                        };
+                        self.text_input_events.push(event);
                     } else {
                         let shift_pressed = keyboard_state.pressed(KeyCode::LShift)
                             | keyboard_state.pressed(KeyCode::RShift);
                         match event.key_code {
                             Some(KeyCode::Up) | Some(KeyCode::W) => {
-                                self.engine.interact(Interaction::Navigate(NavSignal::Up))
+                                self.navigate(NavDirection::Up, &settings)
                             }
                             Some(KeyCode::Down) | Some(KeyCode::S) => {
-                                self.engine.interact(Interaction::Navigate(NavSignal::Down))
+                                self.navigate(NavDirection::Down, &settings)
                             }
                             Some(KeyCode::Left) | Some(KeyCode::A) => {
                                 if shift_pressed {
-                                    self.engine.interact(Interaction::Navigate(NavSignal::Prev));
+                                    self.navigate(NavDirection::Prev, &settings);
                                 } else {
-                                    self.engine.interact(Interaction::Navigate(NavSignal::Left));
+                                    self.navigate(NavDirection::Left, &settings);
                                 }
                             }
                             Some(KeyCode::Right) | Some(KeyCode::D) => {
                                 if shift_pressed {
-                                    self.engine.interact(Interaction::Navigate(NavSignal::Next));
+                                    self.navigate(NavDirection::Next, &settings);
                                 } else {
-                                    self.engine
-                                        .interact(Interaction::Navigate(NavSignal::Right));
+                                    self.navigate(NavDirection::Right, &settings);
                                 }
                             }
                             Some(KeyCode::Return)
@@ -243,14 +578,214 @@ impl BevyInteractionsEngine {
                 }
             }
         }
+
+        // Process gamepad input
+        if settings.gamepad && self.window_focused {
+            let delta_seconds = world.get_resource::<Time>().unwrap().delta_seconds();
+            self.update_gamepads(world, &settings, delta_seconds);
+        }
+
+        // Keep track of whether or not text is being typed into the UI
+        let mut text_focus = world.get_resource_mut::<UiTextFocus>().unwrap();
+        if self.engine.focused_text_input().is_some() && self.window_focused {
+            text_focus.0.insert(self.window_id);
+        } else {
+            text_focus.0.remove(&self.window_id);
+        }
+    }
+
+    /// Navigate in a direction, wrapping around to the other side of the UI if enabled
+    fn navigate(&mut self, direction: NavDirection, settings: &UiNavigationSettings) {
+        let signal = if settings.wrap_focus {
+            NavSignal::Jump(NavJump::Loop(direction))
+        } else {
+            match direction {
+                NavDirection::Up => NavSignal::Up,
+                NavDirection::Down => NavSignal::Down,
+                NavDirection::Left => NavSignal::Left,
+                NavDirection::Right => NavSignal::Right,
+                NavDirection::Prev => NavSignal::Prev,
+                NavDirection::Next => NavSignal::Next,
+                NavDirection::None => return,
+            }
+        };
+
+        self.engine.interact(Interaction::Navigate(signal));
+    }
+
+    /// Navigate with the d-pads and left sticks of the gamepads, and accept or cancel with the
+    /// south and east buttons
+    fn update_gamepads(
+        &mut self,
+        world: &World,
+        settings: &UiNavigationSettings,
+        delta_seconds: f32,
+    ) {
+        let gamepads = match world.get_resource::<Gamepads>() {
+            Some(gamepads) => gamepads,
+            None => return,
+        };
+        let buttons = world.get_resource::<Input<GamepadButton>>().unwrap();
+        let axes = world.get_resource::<Axis<GamepadAxis>>().unwrap();
+
+        // Gamepads can't type, but they can stop typing
+        if self.engine.focused_text_input().is_some() {
+            self.gamepad_direction = None;
+            if gamepads.iter().any(|&gamepad| {
+                buttons.just_pressed(GamepadButton(gamepad, GamepadButtonType::East))
+            }) {
+                self.engine
+                    .interact(Interaction::Navigate(NavSignal::FocusTextInput(().into())));
+            }
+            return;
+        }
+
+        // Find the direction that the gamepads are held in
+        let direction_of = |gamepad: Gamepad| {
+            let pressed = |button| buttons.pressed(GamepadButton(gamepad, button));
+            let stick_x = axes
+                .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickX))
+                .unwrap_or(0.);
+            let stick_y = axes
+                .get(GamepadAxis(gamepad, GamepadAxisType::LeftStickY))
+                .unwrap_or(0.);
+
+            if pressed(GamepadButtonType::DPadUp) || stick_y > settings.stick_threshold {
+                Some(NavDirection::Up)
+            } else if pressed(GamepadButtonType::DPadDown) || stick_y < -settings.stick_threshold {
+                Some(NavDirection::Down)
+            } else if pressed(GamepadButtonType::DPadLeft) || stick_x < -settings.stick_threshold {
+                Some(NavDirection::Left)
+            } else if pressed(GamepadButtonType::DPadRight) || stick_x > settings.stick_threshold {
+                Some(NavDirection::Right)
+            } else {
+                None
+            }
+        };
+        let direction = gamepads.iter().find_map(|&gamepad| direction_of(gamepad));
+
+        // Navigate when a direction is first held, and then repeatedly while it is held
+        match direction {
+            Some(direction) if Some(direction) != self.gamepad_direction => {
+                self.navigate(direction, settings);
+                self.gamepad_repeat_timer = settings.repeat_delay;
+            }
+            Some(direction) => {
+                self.gamepad_repeat_timer -= delta_seconds;
+                if self.gamepad_repeat_timer <= 0. {
+                    self.navigate(direction, settings);
+                    self.gamepad_repeat_timer += settings.repeat_interval;
+                }
+            }
+            None => (),
+        }
+        self.gamepad_direction = direction;
+
+        // Accept and cancel
+        for &gamepad in gamepads.iter() {
+            let south = GamepadButton(gamepad, GamepadButtonType::South);
+            let east = GamepadButton(gamepad, GamepadButtonType::East);
+
+            if buttons.just_pressed(south) {
+                self.engine
+                    .interact(Interaction::Navigate(NavSignal::Accept(true)));
+            } else if buttons.just_released(south) {
+                self.engine
+                    .interact(Interaction::Navigate(NavSignal::Accept(false)));
+            }
+
+            if buttons.just_pressed(east) {
+                self.engine
+                    .interact(Interaction::Navigate(NavSignal::Cancel(true)));
+            } else if buttons.just_released(east) {
+                self.engine
+                    .interact(Interaction::Navigate(NavSignal::Cancel(false)));
+            }
+        }
     }
 }
 
+/// Convert a position in a window to a position in the UI
+fn window_to_ui_position(
+    window: &Window,
+    position: bevy::math::Vec2,
+    target_size: bevy::math::UVec2,
+    ui_coords_mapping: &raui::prelude::CoordsMapping,
+) -> raui::prelude::Vec2 {
+    use raui::prelude::*;
+
+    // Map the window to the retro resolution, and then the retro resolution to the UI
+    let target_coords_mapping = CoordsMapping::new_scaling(
+        Rect {
+            left: 0.,
+            right: window.width(),
+            top: 0.,
+            bottom: window.height(),
+        },
+        CoordsMappingScaling::Stretch(Vec2 {
+            x: target_size.x as f32,
+            y: target_size.y as f32,
+        }),
+    );
+
+    let target_position = target_coords_mapping.real_to_virtual_vec2(
+        Vec2 {
+            x: position.x,
+            y: window.height() - position.y,
+        },
+        false,
+    );
+
+    ui_coords_mapping.real_to_virtual_vec2(target_position, false)
+}
+
 impl InteractionsEngine<DefaultInteractionsEngineResult, ()> for BevyInteractionsEngine {
     fn perform_interactions(
         &mut self,
         app: &mut raui::prelude::Application,
     ) -> Result<DefaultInteractionsEngineResult, ()> {
-        self.engine.perform_interactions(app)
+        // Send text editing events straight to the focused text input so that text changes and
+        // selection changes arrive in the order that they were made
+        if let Some(text_input) = self.engine.focused_text_input().cloned() {
+            for event in self.text_input_events.drain(..) {
+                match event {
+                    TextInputEvent::Change(change) => {
+                        app.send_message(&text_input, NavSignal::TextChange(change))
+                    }
+                    TextInputEvent::Select(change) => app.send_message(&text_input, change),
+                    TextInputEvent::Compose(text) => {
+                        app.send_message(&text_input, TextCompositionChange(text))
+                    }
+                }
+            }
+        } else {
+            self.text_input_events.clear();
+        }
+
+        for (widget, message) in self.drag_messages.drain(..) {
+            app.send_message(&widget, message);
+        }
+
+        self.selected_before_interactions = self.engine.selected_button().cloned();
+        let result = self.engine.perform_interactions(app);
+        self.modals.update_area(app);
+
+        // Drop the dragged widget on the button under the mouse
+        if let Some(drag) = self.finished_drag.take() {
+            app.send_message(&drag.source, UiDragMessage::Ended);
+            if let Some(target) = self.engine.selected_button() {
+                if *target != drag.source {
+                    app.send_message(
+                        target,
+                        UiDragMessage::Dropped {
+                            source: drag.source,
+                            payload: drag.payload,
+                        },
+                    );
+                }
+            }
+        }
+
+        result
     }
 }
@@ -7,10 +7,60 @@ use bevy_retrograde_core::prelude::AppBuilderRenderHookExt;
 mod resources;
 pub use resources::*;
 
+mod theme;
+pub use theme::*;
+
+mod handles;
+pub use handles::{new_ui_texture_handle, UiHandleExt};
+
+mod events;
+pub use events::*;
+
+mod prefab;
+pub use prefab::*;
+
+mod sounds;
+pub use sounds::*;
+
+mod animation;
+pub use animation::*;
+
+mod binding;
+pub use binding::{
+    ui_bound_value, ui_component, ui_resource, AppBuilderUiBindingExt, UiComponentMutation,
+    UiResourceMutation,
+};
+
+mod localization;
+pub use localization::{localized, UiTranslations};
+
+mod debug;
+pub use debug::UiDebugOverlay;
+
+mod opacity;
+
+mod cursor;
+pub use cursor::{CustomCursor, CustomCursorImage};
+
+mod modal;
+pub use modal::use_retro_modal;
+
+mod drag;
+pub use drag::{
+    use_retro_draggable, use_retro_drop_target, RetroDraggableProps, RetroDraggableState,
+    UiDragMessage, UiDropped,
+};
+
 mod render_hook;
 use render_hook::UiRenderHook;
 
+mod composition;
+pub use composition::{TextCompositionChange, UiTextComposition};
+
 pub(crate) mod interaction;
+pub use interaction::TextSelectionChange;
+
+pub mod widgets;
 
 pub use raui;
 
@@ -22,6 +72,35 @@ impl Plugin for RetroUiPlugin {
         app
             // Add the UI tree resource
             .init_resource::<UiTree>()
+            // Add the resource with the UI trees of other windows
+            .init_resource::<UiWindowTrees>()
+            // Add the UI text settings resource
+            .init_resource::<UiTextSettings>()
+            // Add the UI nine-patch resource
+            .init_resource::<UiNinePatches>()
+            // Add the UI texture atlas resource
+            .init_resource::<UiAtlases>()
+            // Add the UI scale mode resource
+            .init_resource::<UiScaleMode>()
+            // Add the UI resolution resource
+            .init_resource::<UiResolutions>()
+            // Add the UI translations resource
+            .init_resource::<UiTranslations>()
+            // Add the UI navigation settings resource
+            .init_resource::<UiNavigationSettings>()
+            // Add the UI processing resources
+            .init_resource::<UiProcessMode>()
+            .init_resource::<UiInvalidation>()
             .add_render_hook::<UiRenderHook>();
+
+        events::add_events(app);
+        theme::add_theme_systems(app);
+        prefab::add_prefab_assets(app);
+        sounds::add_sound_systems(app);
+        cursor::add_cursor_systems(app);
+        composition::add_composition_systems(app);
+        #[cfg(feature = "localization")]
+        localization::add_localization_systems(app);
+        debug::add_debug_systems(app);
     }
 }
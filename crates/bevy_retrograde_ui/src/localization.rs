@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+use bevy::utils::HashMap;
+
+#[cfg(feature = "localization")]
+use bevy::prelude::*;
+
+/// The prefix of UI text that is a localization key instead of the text itself
+const LOCALIZATION_KEY_PREFIX: &str = "l10n:";
+
+/// Get the text of a text box that shows the translation of a localization key
+///
+/// The key is translated with the [`UiTranslations`] resource when the text is drawn, so the text
+/// changes as soon as the translations do.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, *};
+/// fn start_button_label() -> WidgetNode {
+///     make_widget!(text_box)
+///         .with_props(TextBoxProps {
+///             text: localized("menu.start"),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+pub fn localized(key: &str) -> String {
+    format!("{}{}", LOCALIZATION_KEY_PREFIX, key)
+}
+
+/// This resource contains the translations of the localization keys in the UI text for the active
+/// language
+///
+/// Text made with [`localized`] is replaced with its translation when it is drawn, and text blocks
+/// are laid out again whenever their translation changes, so replacing the translations switches
+/// the language of the whole UI. Keys without a translation are drawn as the key itself, so that
+/// missing translations are easy to spot.
+///
+/// With the `localization` feature enabled, the translations are replaced with the ones from the
+/// Bevy Retrograde localization plugin whenever its `Localization` changes.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn switch_to_french(mut translations: ResMut<UiTranslations>) {
+///     translations.clear();
+///     translations.insert("menu.start", "Commencer");
+///     translations.insert("menu.quit", "Quitter");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiTranslations(pub HashMap<String, String>);
+
+impl UiTranslations {
+    /// Set the translation of a key
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, translation: V) {
+        self.0.insert(key.into(), translation.into());
+    }
+
+    /// Remove all of the translations
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Get the translation of a key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|x| x.as_str())
+    }
+
+    /// Get the text to draw for UI text, translating it if it is a localization key
+    pub(crate) fn resolve<'a>(&'a self, text: &'a str) -> Cow<'a, str> {
+        match text.strip_prefix(LOCALIZATION_KEY_PREFIX) {
+            Some(key) => Cow::Borrowed(self.get(key).unwrap_or(key)),
+            None => Cow::Borrowed(text),
+        }
+    }
+}
+
+/// Add the systems that keep the UI translations up to date with the localization plugin
+#[cfg(feature = "localization")]
+pub(crate) fn add_localization_systems(app: &mut AppBuilder) {
+    app.add_system_to_stage(CoreStage::PostUpdate, sync_localization.system());
+}
+
+/// Copy the translations of the localization plugin to the [`UiTranslations`]
+#[cfg(feature = "localization")]
+fn sync_localization(
+    localization: Res<bevy_retrograde_localization::Localization>,
+    mut translations: ResMut<UiTranslations>,
+) {
+    if localization.is_changed() {
+        translations.0 = localization
+            .iter()
+            .map(|(key, translation)| (key.to_string(), translation.to_string()))
+            .collect();
+    }
+}
@@ -0,0 +1,143 @@
+use raui::prelude::{
+    Application, Interaction, MessageData, NavSignal, Rect, Signal, Vec2, WidgetContext, WidgetId,
+};
+
+/// Signal sent by a modal when it is created and removed, which the interactions engine uses to
+/// block the widgets under it and to restore focus once it is gone
+#[derive(MessageData, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UiModalSignal {
+    Opened,
+    Closed,
+}
+
+/// Hook that makes a navigation container modal while it exists
+///
+/// The widget must also be a navigation container, with the `use_nav_container_active` hook. While
+/// it exists, keyboard and gamepad navigation is kept inside of it, and the mouse and touches
+/// can't interact with widgets outside of its area. Once it is removed, the button that was
+/// selected before it was created is selected again.
+///
+/// The [`retro_modal`][crate::widgets::retro_modal] widget uses this hook and dims the UI behind
+/// the modal.
+pub fn use_retro_modal(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        context.signals.write(NavSignal::Lock);
+        context.signals.write(UiModalSignal::Opened);
+    });
+
+    ctx.life_cycle.unmount(|context| {
+        context.signals.write(NavSignal::Unlock);
+        context.signals.write(UiModalSignal::Closed);
+    });
+}
+
+/// A modal that is open
+struct Modal {
+    id: WidgetId,
+    /// The button that was selected before the modal was opened
+    restore: Option<WidgetId>,
+}
+
+/// The modals that are open in a UI, from the bottom to the top
+#[derive(Default)]
+pub(crate) struct ModalStack {
+    modals: Vec<Modal>,
+    /// The area of the top modal as of the last layout, which the pointer can interact with
+    area: Option<Rect>,
+}
+
+impl ModalStack {
+    /// Open and close the modals that the signals of the UI open and close, returning the
+    /// navigation to do because of it
+    ///
+    /// `selected_before` is the button that was selected before the interactions engine handled the
+    /// signals, and `selected` is the button that is selected after.
+    pub fn handle_signals(
+        &mut self,
+        signals: &[Signal],
+        selected_before: Option<&WidgetId>,
+        selected: Option<&WidgetId>,
+    ) -> Vec<NavSignal> {
+        let mut navigation = Vec::new();
+
+        for (sender, message) in signals {
+            match message.as_any().downcast_ref::<UiModalSignal>() {
+                Some(UiModalSignal::Opened) => {
+                    self.modals.push(Modal {
+                        id: sender.clone(),
+                        restore: selected_before
+                            .filter(|button| !is_in(button, sender))
+                            .cloned(),
+                    });
+
+                    // Unselect buttons under the modal, unless a widget in the modal was focused
+                    if selected
+                        .map(|button| !is_in(button, sender))
+                        .unwrap_or(false)
+                    {
+                        navigation.push(NavSignal::Unselect);
+                    }
+                }
+                Some(UiModalSignal::Closed) => {
+                    if let Some(index) = self.modals.iter().position(|modal| modal.id == *sender) {
+                        let modal = self.modals.remove(index);
+                        if let Some(restore) = modal.restore {
+                            navigation.push(NavSignal::Select(restore.into()));
+                        }
+                    }
+                }
+                None => (),
+            }
+        }
+
+        if self.modals.is_empty() {
+            self.area = None;
+        }
+
+        navigation
+    }
+
+    /// Find the area of the top modal in the layout of the UI
+    pub fn update_area(&mut self, app: &Application) {
+        self.area = self.modals.last().and_then(|modal| {
+            app.layout_data()
+                .items
+                .get(&modal.id)
+                .map(|item| item.ui_space)
+        });
+    }
+
+    /// Get the interaction that a pointer interaction becomes while modals are open
+    ///
+    /// Moving the pointer outside of the top modal unselects the hovered button, and pressing it
+    /// there does nothing.
+    pub fn filter_pointer(&self, interaction: Interaction) -> Option<Interaction> {
+        let area = match self.area {
+            Some(area) => area,
+            None => return Some(interaction),
+        };
+        let inside = |position: &Vec2| {
+            position.x >= area.left
+                && position.x < area.right
+                && position.y >= area.top
+                && position.y < area.bottom
+        };
+
+        match &interaction {
+            Interaction::PointerMove(position) if !inside(position) => {
+                Some(Interaction::Navigate(NavSignal::Unselect))
+            }
+            Interaction::PointerDown(_, position) | Interaction::PointerUp(_, position)
+                if !inside(position) =>
+            {
+                None
+            }
+            _ => Some(interaction),
+        }
+    }
+}
+
+/// Get whether or not a widget is the given widget or one of its descendants
+fn is_in(id: &WidgetId, ancestor: &WidgetId) -> bool {
+    id.depth() >= ancestor.depth() && (0..ancestor.depth()).all(|i| id.part(i) == ancestor.part(i))
+}
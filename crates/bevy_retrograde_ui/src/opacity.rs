@@ -0,0 +1,67 @@
+use bevy::utils::HashMap;
+use raui::prelude::{ImageBoxMaterial, Scalar, WidgetId, WidgetUnit};
+
+/// The opacities of the [`retro_opacity`][crate::widgets::retro_opacity] widgets, passed to them
+/// in the process context
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UiOpacities(HashMap<WidgetId, Scalar>);
+
+impl UiOpacities {
+    /// Set the opacity of the subtree of a widget
+    pub fn insert(&mut self, widget: WidgetId, opacity: Scalar) {
+        self.0.insert(widget, opacity);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the opacity of a widget, which is the product of the opacities of the subtrees that it
+    /// is in
+    fn opacity_of(&self, id: &WidgetId) -> Scalar {
+        self.0
+            .iter()
+            .filter(|(ancestor, _)| {
+                id.depth() >= ancestor.depth()
+                    && (0..ancestor.depth()).all(|i| id.part(i) == ancestor.part(i))
+            })
+            .map(|(_, opacity)| opacity)
+            .product()
+    }
+
+    /// Multiply the alpha of the colors of the images and text in a rendered widget tree by their
+    /// opacity
+    pub fn apply(&self, unit: &mut WidgetUnit) {
+        match unit {
+            WidgetUnit::AreaBox(area_box) => self.apply(&mut area_box.slot),
+            WidgetUnit::ContentBox(content_box) => {
+                for item in &mut content_box.items {
+                    self.apply(&mut item.slot);
+                }
+            }
+            WidgetUnit::FlexBox(flex_box) => {
+                for item in &mut flex_box.items {
+                    self.apply(&mut item.slot);
+                }
+            }
+            WidgetUnit::GridBox(grid_box) => {
+                for item in &mut grid_box.items {
+                    self.apply(&mut item.slot);
+                }
+            }
+            WidgetUnit::SizeBox(size_box) => self.apply(&mut size_box.slot),
+            WidgetUnit::ImageBox(image_box) => {
+                let opacity = self.opacity_of(&image_box.id);
+                match &mut image_box.material {
+                    ImageBoxMaterial::Color(color) => color.color.a *= opacity,
+                    ImageBoxMaterial::Image(image) => image.tint.a *= opacity,
+                    _ => (),
+                }
+            }
+            WidgetUnit::TextBox(text_box) => {
+                text_box.color.a *= self.opacity_of(&text_box.id);
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use raui::prelude::{Application, PrefabValue};
+
+pub(crate) fn add_prefab_assets(app: &mut AppBuilder) {
+    app.init_resource::<UiTreePrefab>()
+        .init_resource::<UiComponentRegistry>()
+        .add_asset::<UiPrefab>()
+        .add_asset_loader(UiPrefabLoader);
+}
+
+/// A UI widget tree loaded from a `.ui` file
+///
+/// UI files are YAML files in RAUI's prefab format, which describes the widget tree with the names
+/// of the widget components and their props. Using a UI file as the [`UiTreePrefab`] shows it in
+/// the primary window, and when asset hot reloading is enabled the UI is updated whenever the file
+/// changes, so that UI layouts can be tweaked without recompiling the game.
+///
+/// The components and props in the file must be registered with RAUI. RAUI's own components and
+/// props are always registered, and custom ones can be registered with the
+/// [`UiComponentRegistry`].
+#[derive(TypeUuid, Debug, Clone)]
+#[uuid = "4e1e8d0a-3b8b-4f6e-9a51-0d0f51a6c2e7"]
+pub struct UiPrefab(pub PrefabValue);
+
+/// This resource contains the UI file that is shown in the primary window, if any
+///
+/// While this is set, it is shown instead of the [`UiTree`][crate::UiTree].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(asset_server: Res<AssetServer>, mut ui_prefab: ResMut<UiTreePrefab>) {
+///     ui_prefab.0 = Some(asset_server.load("ui/main_menu.ui"));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiTreePrefab(pub Option<Handle<UiPrefab>>);
+
+/// This resource contains the functions that register custom components and props with RAUI, so
+/// that they can be used in [`UiPrefab`] files
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::{raui::prelude::*, *};
+/// # fn health_bar(_ctx: WidgetContext) -> WidgetNode {
+/// #     Default::default()
+/// # }
+/// fn register_components(app: &mut Application) {
+///     app.register_component("health_bar", FnWidget::pointer(health_bar));
+/// }
+///
+/// fn setup(mut registry: ResMut<UiComponentRegistry>) {
+///     registry.register(register_components);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiComponentRegistry {
+    setups: Vec<fn(&mut Application)>,
+}
+
+impl UiComponentRegistry {
+    /// Add a function that registers components and props with the RAUI application of each window
+    pub fn register(&mut self, setup: fn(&mut Application)) {
+        self.setups.push(setup);
+    }
+
+    /// Get the registration functions
+    pub(crate) fn setups(&self) -> &[fn(&mut Application)] {
+        &self.setups
+    }
+}
+
+/// An error that occurs when loading a UI file
+#[derive(thiserror::Error, Debug)]
+pub enum UiPrefabLoaderError {
+    #[error("Could not parse UI file: {0}")]
+    Parse(#[from] serde_yaml::Error),
+}
+
+/// A UI file asset loader
+struct UiPrefabLoader;
+
+impl AssetLoader for UiPrefabLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move { Ok(load_ui_prefab(bytes, load_context).await?) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ui"]
+    }
+}
+
+async fn load_ui_prefab<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+) -> Result<(), UiPrefabLoaderError> {
+    let value: PrefabValue = serde_yaml::from_slice(bytes)?;
+
+    load_context.set_default_asset(LoadedAsset::new(UiPrefab(value)));
+
+    Ok(())
+}
@@ -1,16 +1,19 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
 
 use bevy::{
-    asset::{AssetPath, HandleId, LoadState},
+    app::{Events, ManualEventReader},
+    asset::{AssetEvent, AssetPath, HandleId, LoadState},
     core::Time,
-    math::{Mat4, Vec3},
+    log::{error, info},
+    math::{Mat4, UVec2, Vec3},
     prelude::{AssetServer, Assets, Handle, Mut, World},
     utils::HashSet,
+    window::{WindowId, Windows},
 };
 use bevy_retrograde_core::{
     graphics::{
         FrameContext, Program, RenderHook, RenderHookRenderableHandle, SceneFramebuffer, Surface,
-        Tess, TextureCache,
+        Tess, Texture, TextureCache,
     },
     luminance::{
         self,
@@ -23,21 +26,33 @@ use bevy_retrograde_core::{
         scissor::ScissorRegion,
         shader::Uniform,
         tess::View,
-        texture::{Dim2, GenMipmaps, MagFilter, MinFilter, Sampler, Wrap},
+        texture::{Dim2, MagFilter, MinFilter, Sampler, Wrap},
         Semantics, UniformInterface, Vertex,
     },
     prelude::{Color, Image},
 };
-use bevy_retrograde_text::{prelude::*, rasterize_text_block};
+use bevy_retrograde_text::{glyph_quad_mesh, layout_text_block, prelude::*};
 use raui::{
-    prelude::{Application, CoordsMapping, DefaultLayoutEngine, ProcessContext, Rect, Renderer},
+    prelude::{
+        content_box, make_widget, Application, DefaultLayoutEngine, ProcessContext, Rect, Renderer,
+        WidgetId, WidgetNode,
+    },
     renderer::tesselate::{
         prelude::TesselateRenderer,
         tesselation::{Batch, Tesselation, TesselationVerticesFormat},
     },
 };
 
-use crate::{interaction::BevyInteractionsEngine, UiTree};
+use crate::{
+    cursor::{cursor_widget, CursorDraw},
+    events::send_ui_signals,
+    handles::{get_ui_handle, load_ui_handle},
+    interaction::BevyInteractionsEngine,
+    opacity::UiOpacities,
+    CustomCursor, RetroUiTheme, UiAtlases, UiComponentRegistry, UiDebugOverlay, UiInvalidation,
+    UiNinePatches, UiPrefab, UiProcessMode, UiResolutions, UiScaleMode, UiTextSettings,
+    UiTranslations, UiTree, UiTreePrefab, UiWindowTrees,
+};
 
 trait AssetPathExt {
     fn format_as_load_path(&self) -> String;
@@ -56,11 +71,37 @@ impl<'a> AssetPathExt for AssetPath<'a> {
     }
 }
 
+/// A text block laid out by the UI render hook
+struct CachedText {
+    layout: TextLayout,
+    /// The handle ids of the font and fallback fonts that the text block was laid out with
+    font_ids: Vec<HandleId>,
+    /// The generation of the glyph atlas that the glyph quads were built from, or [`None`] if they
+    /// haven't been built yet
+    atlas_generation: Option<u32>,
+    /// The glyph quads, or [`None`] if the text block doesn't have any visible glyphs
+    tess: Option<Tess<UiVert, u32>>,
+    /// The number of frames since the text block was last drawn
+    unused_frames: u32,
+}
+
+/// The number of frames that text blocks are kept in the cache after they stop being drawn, so
+/// that text that is hidden and shown again doesn't have to be laid out again
+const TEXT_CACHE_FRAMES: u32 = 60;
+
+/// Put the custom cursor on top of a widget tree
+fn with_cursor(tree: WidgetNode) -> WidgetNode {
+    make_widget!(content_box)
+        .key("retro-ui-root")
+        .listed_slot(tree)
+        .listed_slot(make_widget!(cursor_widget).key("retro-ui-cursor"))
+        .into()
+}
+
 /// The render hook responsible for rendering the UI
 pub struct UiRenderHook {
     app: Application,
     current_ui_tesselation: Option<Tesselation>,
-    text_tess: Tess<UiVert>,
     shader_program: Program<(), (), UiUniformInterface>,
     /// Cache of image handles that the UI is using
     ///
@@ -70,15 +111,56 @@ pub struct UiRenderHook {
     handle_to_path: HashMap<HandleId, String>,
     /// Cache of fonts that the UI is using
     font_cache: HashSet<Handle<Font>>,
+    /// Fonts that text in the UI couldn't be laid out with because they hadn't loaded yet
+    pending_fonts: HashSet<Handle<Font>>,
+    font_event_reader: ManualEventReader<AssetEvent<Font>>,
+    /// The atlas that the glyphs of the text blocks are drawn from
+    glyph_atlas: GlyphAtlas,
+    glyph_atlas_texture: Texture<Dim2, NormRGBA8UI>,
+    /// Cache of the layouts and glyph quads of the text blocks drawn in recent frames
+    ///
+    /// Text blocks are only laid out again when they change, and their glyph quads are only
+    /// re-built when the glyph atlas is cleared.
+    text_cache: HashMap<TextRasterKey, CachedText>,
     interactions: BevyInteractionsEngine,
-    has_shown_clipping_warning: bool,
+    /// The window that this hook renders the UI of
+    window_id: WindowId,
+    /// Whether or not the widget tree must be applied to the app even if it hasn't changed, such as
+    /// when the window was just created
+    tree_needs_apply: bool,
+    /// The UI file that was last applied to the app, if any
+    applied_prefab: Option<Handle<UiPrefab>>,
+    prefab_event_reader: ManualEventReader<AssetEvent<UiPrefab>>,
+    /// The number of functions from the [`UiComponentRegistry`] that have been run on the app
+    applied_setups: usize,
+    /// The size in framebuffer pixels that the UI is laid out in this frame, which depends on the
+    /// [`UiResolution`][crate::UiResolution] of the tree
+    target_size: UVec2,
+    /// The framebuffer that the contents of rotated and skewed clip regions are drawn to before they
+    /// are composited onto the target framebuffer
+    clip_framebuffer: Option<SceneFramebuffer>,
+    /// The custom cursor as of the last frame
+    cursor_draw: CursorDraw,
+    /// The opacities of the widget subtrees as of the last time that the widgets were processed
+    opacities: UiOpacities,
+    /// Whether or not the debug overlay is drawn this frame
+    debug_overlay: bool,
+    /// The outlines of the debug overlay, in UI pixels, and their colors
+    debug_outlines: Vec<(ClipRect, [f32; 4])>,
+    /// The widget that was under the mouse as of the last frame that the debug overlay was shown
+    debug_widget_under_cursor: Option<WidgetId>,
 }
 
 impl RenderHook for UiRenderHook {
-    fn init(_window_id: bevy::window::WindowId, surface: &mut Surface) -> Box<dyn RenderHook>
+    fn init(window_id: WindowId, surface: &mut Surface) -> Box<dyn RenderHook>
     where
         Self: Sized,
     {
+        let glyph_atlas = GlyphAtlas::default();
+
+        // Create the glyph atlas texture, starting out empty
+        let glyph_atlas_texture = glyph_atlas.create_texture(surface);
+
         Box::new(Self {
             current_ui_tesselation: None,
             shader_program: surface
@@ -91,19 +173,29 @@ impl RenderHook for UiRenderHook {
                 )
                 .unwrap()
                 .program,
-            text_tess: surface
-                .new_tess()
-                .set_vertices(&QUAD_VERTS[..])
-                .set_mode(luminance::tess::Mode::TriangleFan)
-                .build()
-                .unwrap(),
 
             // Font & Image handle cache
             font_cache: Default::default(),
+            pending_fonts: Default::default(),
+            font_event_reader: Default::default(),
+            glyph_atlas,
+            glyph_atlas_texture,
+            text_cache: Default::default(),
             image_cache: Default::default(),
             handle_to_path: Default::default(),
-            interactions: Default::default(),
-            has_shown_clipping_warning: false,
+            interactions: BevyInteractionsEngine::new(window_id),
+            window_id,
+            tree_needs_apply: true,
+            applied_prefab: None,
+            prefab_event_reader: Default::default(),
+            applied_setups: 0,
+            target_size: UVec2::ZERO,
+            clip_framebuffer: None,
+            cursor_draw: Default::default(),
+            opacities: Default::default(),
+            debug_overlay: false,
+            debug_outlines: Default::default(),
+            debug_widget_under_cursor: None,
             app: {
                 let mut app = Application::new();
                 app.setup(raui::core::widget::setup);
@@ -121,43 +213,220 @@ impl RenderHook for UiRenderHook {
         texture_cache: &mut TextureCache,
         frame_context: &FrameContext,
     ) -> Vec<RenderHookRenderableHandle> {
+        // Lay out the UI in the low or high resolution target, depending on the tree
+        self.target_size = world
+            .get_resource::<UiResolutions>()
+            .unwrap()
+            .target_size(self.window_id, &frame_context.target_sizes);
+
+        // When fonts that text was waiting on load, or fonts are hot reloaded, lay out the UI again
+        // and drop the text laid out with the old fonts
+        let font_events = world.get_resource::<Events<AssetEvent<Font>>>().unwrap();
+        for event in self.font_event_reader.iter(font_events) {
+            match event {
+                AssetEvent::Created { handle } => {
+                    if self.pending_fonts.remove(handle) {
+                        self.app.mark_dirty();
+                    }
+                }
+                AssetEvent::Modified { handle } => {
+                    self.pending_fonts.remove(handle);
+                    self.text_cache
+                        .retain(|_, cached_text| !cached_text.font_ids.contains(&handle.id));
+                    // The atlas still has the glyphs of the old font, so start it over
+                    self.glyph_atlas.clear();
+                    self.app.mark_dirty();
+                }
+                AssetEvent::Removed { .. } => (),
+            }
+        }
+
+        let process_mode = *world.get_resource::<UiProcessMode>().unwrap();
+
         // Scope the borrow of the world and its resources
         let ui_tesselation = {
             // Update interactions
-            self.interactions
-                .update(world, frame_context.target_sizes.low);
+            self.interactions.update(world, self.target_size);
 
             // Get our bevy resources from the world
             let delta_time = world.get_resource::<Time>().unwrap().delta_seconds();
 
             // Get the app from the world ( we will re-insert it when we are done processing the app )
             world.resource_scope(|world: &mut World, ui_tree: Mut<UiTree>| {
-                // Update the widget tree if it has changed
-                if ui_tree.is_changed() {
-                    self.app.apply(ui_tree.0.clone());
+                // Update the widget tree if it has changed. The primary window shows the `UiTree`,
+                // and other windows show their trees from the `UiWindowTrees`.
+                let is_primary_window = world
+                    .get_resource::<Windows>()
+                    .unwrap()
+                    .get_primary()
+                    .map(|window| window.id() == self.window_id)
+                    .unwrap_or(false);
+                // Register any custom components that were added since the last frame
+                let registry = world.get_resource::<UiComponentRegistry>().unwrap();
+                for setup in &registry.setups()[self.applied_setups..] {
+                    self.app.setup(*setup);
+                }
+                self.applied_setups = registry.setups().len();
+
+                if is_primary_window {
+                    let ui_prefab = world.get_resource::<UiTreePrefab>().unwrap().0.clone();
+
+                    // Check whether the UI file has been switched, loaded, or hot reloaded
+                    let mut prefab_changed = ui_prefab != self.applied_prefab;
+                    let prefab_events = world
+                        .get_resource::<Events<AssetEvent<UiPrefab>>>()
+                        .unwrap();
+                    for event in self.prefab_event_reader.iter(prefab_events) {
+                        match event {
+                            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                                if Some(handle) == ui_prefab.as_ref() {
+                                    prefab_changed = true;
+                                }
+                            }
+                            AssetEvent::Removed { .. } => (),
+                        }
+                    }
+
+                    if let Some(handle) = &ui_prefab {
+                        if prefab_changed || self.tree_needs_apply {
+                            // If the file hasn't loaded yet, it is applied once it is created
+                            let prefabs = world.get_resource::<Assets<UiPrefab>>().unwrap();
+                            if let Some(prefab) = prefabs.get(handle) {
+                                match self.app.deserialize_node(prefab.0.clone()) {
+                                    Ok(node) => self.app.apply(with_cursor(node)),
+                                    Err(e) => error!("Could not load UI file: {:?}", e),
+                                }
+                                self.tree_needs_apply = false;
+                            }
+                        }
+                    } else if ui_tree.is_changed()
+                        || self.tree_needs_apply
+                        || self.applied_prefab.is_some()
+                    {
+                        self.app.apply(with_cursor(ui_tree.0.clone()));
+                        self.tree_needs_apply = false;
+                    }
+
+                    self.applied_prefab = ui_prefab;
+                } else {
+                    let window_trees = world.get_resource_mut::<UiWindowTrees>().unwrap();
+                    if window_trees.is_changed() || self.tree_needs_apply {
+                        self.app.apply(with_cursor(
+                            window_trees
+                                .get(self.window_id)
+                                .cloned()
+                                .unwrap_or_default(),
+                        ));
+                        self.tree_needs_apply = false;
+                    }
                 }
 
                 // Update delta time
                 self.app.animations_delta_time = delta_time;
 
-                // Run forced_process so that UI components run every frame in more of an "immediate
-                // mode" fashion.
-                //
-                // TODO: Maybe change this if it doesn't make sense
-                self.app.forced_process_with_context(
-                    // Add the Bevy world to the process context
-                    ProcessContext::new().insert_mut(world),
-                );
+                // Find where to draw the custom cursor, once its image has loaded
+                let mut cursor_draw = CursorDraw::default();
+                if let (true, Some(custom_cursor), Some((position, state))) = (
+                    is_primary_window,
+                    world.get_resource::<CustomCursor>(),
+                    self.interactions.cursor(),
+                ) {
+                    let image = custom_cursor.image(state);
+                    let asset_server = world.get_resource::<AssetServer>().unwrap();
+                    let handle: Handle<Image> = load_ui_handle(asset_server, &image.path);
+                    if let Some(texture) = texture_cache.get(&handle) {
+                        let size = texture.size();
+                        cursor_draw = CursorDraw {
+                            image: Some(image.path.clone()),
+                            position: raui::prelude::Vec2 {
+                                x: position.x - image.hotspot.x as f32,
+                                y: position.y - image.hotspot.y as f32,
+                            },
+                            size: raui::prelude::Vec2 {
+                                x: size[0] as f32,
+                                y: size[1] as f32,
+                            },
+                        };
+                    }
+                    self.image_cache.insert(handle);
+                }
+
+                // In retained mode, the widgets only run again when something that they show has
+                // changed
+                if cursor_draw != self.cursor_draw
+                    || world.is_resource_changed::<UiInvalidation>()
+                    || world.is_resource_changed::<RetroUiTheme>()
+                {
+                    self.app.mark_dirty();
+                }
+                self.cursor_draw = cursor_draw.clone();
+
+                let mut opacities = UiOpacities::default();
+                // Add the Bevy world, the custom cursor, and the subtree opacities to the process
+                // context
+                let process_context = ProcessContext::new()
+                    .insert_mut(world)
+                    .insert_mut(&mut cursor_draw)
+                    .insert_mut(&mut opacities);
+                let processed = match process_mode {
+                    // Run every widget every frame in more of an "immediate mode" fashion
+                    UiProcessMode::Forced => self.app.forced_process_with_context(process_context),
+                    UiProcessMode::Retained => self.app.process_with_context(process_context),
+                };
+                // The opacities are only found when the widgets run
+                if processed {
+                    self.opacities = opacities;
+                }
 
                 self.app
                     .interact(&mut self.interactions)
                     .expect("Couldn't run UI interactions");
-                self.app.consume_signals();
 
-                // For now we don't do image atlases
-                let atlases = HashMap::default();
+                // Send the signals from the UI widgets as Bevy events, running the widgets again
+                // next frame if they changed any bound data
+                let signals = self.app.consume_signals();
+                self.interactions.handle_signals(&signals);
+                if send_ui_signals(world, signals) {
+                    self.app.mark_dirty();
+                }
+
+                let ui_atlases = world.get_resource::<UiAtlases>().unwrap();
+                let asset_server = world.get_resource::<AssetServer>().unwrap();
+
+                // Map the images in atlases to their regions of the atlas textures
+                let mut atlases = HashMap::default();
+                for (name, atlas_path, region) in ui_atlases.iter() {
+                    let atlas_handle: Handle<Image> =
+                        asset_server.get_handle(HandleId::from(AssetPath::from(atlas_path)));
+
+                    // Load the atlas texture if loading has not started yet, and keep it loaded
+                    if let LoadState::NotLoaded = asset_server.get_load_state(&atlas_handle) {
+                        asset_server.load::<Image, _>(atlas_path);
+                    }
+                    self.handle_to_path
+                        .entry(atlas_handle.id)
+                        .or_insert_with(|| atlas_path.to_string());
+
+                    // The regions are in texture coordinates, so they can't be set until the
+                    // texture has loaded, but the image is still mapped to the atlas so that RAUI
+                    // doesn't try to load an image with the name of the atlas region
+                    let rect = if let Some(texture) = texture_cache.get(&atlas_handle) {
+                        let size = texture.size();
+                        Rect {
+                            left: region.x as f32 / size[0] as f32,
+                            right: (region.x + region.width) as f32 / size[0] as f32,
+                            top: region.y as f32 / size[1] as f32,
+                            bottom: (region.y + region.height) as f32 / size[1] as f32,
+                        }
+                    } else {
+                        Rect::default()
+                    };
+                    self.image_cache.insert(atlas_handle);
+                    atlases.insert(name.to_string(), (atlas_path.to_string(), rect));
+                }
 
-                // Collect image sizes from the textures in the texture cache
+                // Collect image sizes from the textures in the texture cache and the regions of the
+                // images in atlases
                 let image_sizes = texture_cache
                     .iter()
                     .filter_map(|(handle, texture)| {
@@ -171,21 +440,108 @@ impl RenderHook for UiRenderHook {
                             },
                         ))
                     })
+                    .chain(ui_atlases.iter().map(|(name, _, region)| {
+                        (
+                            name.to_string(),
+                            raui::prelude::Vec2 {
+                                x: region.width as f32,
+                                y: region.height as f32,
+                            },
+                        )
+                    }))
                     .collect();
 
-                // Get the coordinate mapping based on the size of the screen
-                let coords_mapping = CoordsMapping::new(Rect {
-                    left: 0.,
-                    top: 0.,
-                    right: frame_context.target_sizes.low.x as f32,
-                    bottom: frame_context.target_sizes.low.y as f32,
-                });
+                // Get the coordinate mapping based on the size of the screen and the UI scale mode
+                let coords_mapping = world
+                    .get_resource::<UiScaleMode>()
+                    .unwrap()
+                    .coords_mapping(self.target_size);
 
                 // Calculate app layout
                 self.app
                     .layout(&coords_mapping, &mut DefaultLayoutEngine)
                     .expect("Could not layout UI");
 
+                // Outline the widgets for the debug overlay
+                let debug_overlay = world.get_resource::<UiDebugOverlay>().unwrap();
+                self.debug_overlay = debug_overlay.enabled;
+                self.debug_outlines.clear();
+                if debug_overlay.enabled {
+                    let layout = self.app.layout_data();
+                    let outline = |id: Option<&WidgetId>, color: [f32; 4]| {
+                        let item = layout.items.get(id?)?;
+                        let rect = coords_mapping.virtual_to_real_rect(item.ui_space, false);
+                        Some((
+                            ClipRect {
+                                left: rect.left,
+                                top: rect.top,
+                                right: rect.right,
+                                bottom: rect.bottom,
+                            },
+                            color,
+                        ))
+                    };
+
+                    self.debug_outlines.extend(
+                        layout
+                            .items
+                            .keys()
+                            .filter_map(|id| outline(Some(id), DEBUG_LAYOUT_COLOR)),
+                    );
+                    self.debug_outlines.extend(
+                        outline(self.interactions.selected_button(), DEBUG_SELECTED_COLOR)
+                            .into_iter()
+                            .chain(outline(
+                                self.interactions.focused_text_input(),
+                                DEBUG_FOCUSED_COLOR,
+                            )),
+                    );
+
+                    // Log the smallest widget under the mouse when it changes
+                    if debug_overlay.log_widget_under_cursor {
+                        let widget_under_cursor =
+                            self.interactions.cursor().and_then(|(position, _)| {
+                                layout
+                                    .items
+                                    .iter()
+                                    .filter(|(_, item)| {
+                                        let rect = item.ui_space;
+                                        position.x >= rect.left
+                                            && position.x < rect.right
+                                            && position.y >= rect.top
+                                            && position.y < rect.bottom
+                                    })
+                                    .min_by(|(_, a), (_, b)| {
+                                        let area = |rect: Rect| {
+                                            (rect.right - rect.left) * (rect.bottom - rect.top)
+                                        };
+                                        area(a.ui_space)
+                                            .partial_cmp(&area(b.ui_space))
+                                            .unwrap_or(std::cmp::Ordering::Equal)
+                                    })
+                                    .map(|(id, _)| id.clone())
+                            });
+                        if widget_under_cursor != self.debug_widget_under_cursor {
+                            if let Some(id) = &widget_under_cursor {
+                                info!("UI widget under cursor: {:?}", id);
+                            }
+                            self.debug_widget_under_cursor = widget_under_cursor;
+                        }
+                    }
+                } else {
+                    self.debug_widget_under_cursor = None;
+                }
+
+                // Fade the subtrees that have an opacity, which only needs a copy of the tree if
+                // there are any
+                let faded_tree = if self.opacities.is_empty() {
+                    None
+                } else {
+                    let mut tree = self.app.rendered_tree().clone();
+                    self.opacities.apply(&mut tree);
+                    Some(tree)
+                };
+
                 // Tesselate the UI
                 let ui_tesselation = TesselateRenderer::new(
                     TesselationVerticesFormat::Interleaved,
@@ -194,7 +550,9 @@ impl RenderHook for UiRenderHook {
                     &image_sizes,
                 )
                 .render(
-                    self.app.rendered_tree(),
+                    faded_tree
+                        .as_ref()
+                        .unwrap_or_else(|| self.app.rendered_tree()),
                     &coords_mapping,
                     self.app.layout_data(),
                 )
@@ -223,7 +581,7 @@ impl RenderHook for UiRenderHook {
         world: &mut World,
         surface: &mut Surface,
         texture_cache: &mut TextureCache,
-        frame_context: &FrameContext,
+        _frame_context: &FrameContext,
         target_framebuffer: &SceneFramebuffer,
         // We only have one renderable for everything so we don't need to read this
         _renderables: &[RenderHookRenderableHandle],
@@ -232,69 +590,113 @@ impl RenderHook for UiRenderHook {
             current_ui_tesselation,
             shader_program,
             font_cache,
+            glyph_atlas,
+            glyph_atlas_texture,
+            text_cache,
             image_cache,
             handle_to_path,
-            text_tess,
-            has_shown_clipping_warning,
+            pending_fonts,
+            target_size,
+            debug_overlay,
+            debug_outlines,
+            clip_framebuffer,
             ..
         } = self;
 
         // Get world resources
         let asset_server = world.get_resource::<AssetServer>().unwrap();
         let font_assets = world.get_resource::<Assets<Font>>().unwrap();
+        let text_settings = world.get_resource::<UiTextSettings>().unwrap();
+        let translations = world.get_resource::<UiTranslations>().unwrap();
+        let nine_patches = world.get_resource::<UiNinePatches>().unwrap();
 
         // Get the UI tesselation
         let ui_tesselation = current_ui_tesselation.take().unwrap();
 
         // Collect vertices
-        let vertices = ui_tesselation
+        let mut vertices = ui_tesselation
             .vertices
             .as_interleaved()
             .unwrap()
             .iter()
-            .map(|vertice| UiVert {
-                pos: VertexPosition::new([vertice.position.x.floor(), vertice.position.y.floor()]),
-                uv: VertexUv::new([vertice.tex_coord.x, vertice.tex_coord.y]),
-                color: VertexColor::new([
+            .map(|vertice| QuadVert {
+                pos: [vertice.position.x, vertice.position.y],
+                uv: [vertice.tex_coord.x, vertice.tex_coord.y],
+                color: [
                     vertice.color.r,
                     vertice.color.g,
                     vertice.color.b,
                     vertice.color.a,
-                ]),
+                ],
             })
             .collect::<Vec<_>>();
+        let mut indices = ui_tesselation.indices;
+        let mut batches = ui_tesselation.batches;
+
+        // Split stretched nine-patch images into nine quads
+        apply_nine_patches(
+            &mut vertices,
+            &mut indices,
+            &mut batches,
+            nine_patches,
+            |image_path| {
+                let texture_handle: Handle<Image> = get_ui_handle(asset_server, image_path);
+                texture_cache.get(&texture_handle).map(|x| x.size())
+            },
+        );
+
+        // Draw the debug overlay on top of the rest of the UI
+        if *debug_overlay {
+            let clip_outlines = batches
+                .iter()
+                .filter_map(|batch| match batch {
+                    Batch::ClipPush(clip) => Some((
+                        ClipRect::from_transformed_box(
+                            &clip.matrix,
+                            [clip.box_size.x, clip.box_size.y],
+                        ),
+                        DEBUG_CLIP_COLOR,
+                    )),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            let first_index = indices.len();
+            for (rect, color) in debug_outlines.iter().chain(clip_outlines.iter()) {
+                push_debug_outline(&mut vertices, &mut indices, rect, *color);
+            }
+            batches.push(Batch::ColoredTriangles(first_index..indices.len()));
+        }
+
+        // Find the clip regions that can't be drawn with a scissor, adding the quads that they are
+        // composited through
+        let passes = split_clip_passes(&mut vertices, &mut indices, &batches);
 
         // Upload the vertices to the GPU
         let tess = surface
             .new_tess()
             .set_mode(luminance::tess::Mode::Triangle)
-            .set_vertices(vertices)
-            .set_indices(ui_tesselation.indices)
+            .set_vertices(
+                vertices
+                    .iter()
+                    .map(|vertice| UiVert {
+                        pos: VertexPosition::new([vertice.pos[0].floor(), vertice.pos[1].floor()]),
+                        uv: VertexUv::new(vertice.uv),
+                        color: VertexColor::new(vertice.color),
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .set_indices(indices)
             .build()
             .unwrap();
-        let batches = ui_tesselation.batches;
-
-        // Create the render state
-        let mut render_state = RenderState::default()
-            .set_blending(Blending {
-                equation: Equation::Additive,
-                src: Factor::SrcAlpha,
-                dst: Factor::SrcAlphaComplement,
-            })
-            .set_face_culling(Some(FaceCulling {
-                order: luminance::face_culling::FaceCullingOrder::CW,
-                mode: luminance::face_culling::FaceCullingMode::Back,
-            }))
-            .set_depth_test(None); // Disable depth test so the UI always renders on top
 
         // Get list of image handles used by the UI
         for image_path in batches.iter().filter_map(|x| match x {
             Batch::ImageTriangles(image, _) => Some(image),
             _ => None,
         }) {
-            // Get the texture handle
-            let texture_handle: Handle<Image> =
-                asset_server.get_handle(HandleId::from(AssetPath::from(image_path.as_str())));
+            // Get the texture handle, loading the texture if loading has not started yet
+            let texture_handle: Handle<Image> = load_ui_handle(asset_server, image_path);
 
             // Map the handle ID to the handle path if necessary
             //
@@ -304,11 +706,6 @@ impl RenderHook for UiRenderHook {
                 .entry(texture_handle.id)
                 .or_insert_with(|| image_path.clone());
 
-            // Load the texture if loading has not started yet
-            if let LoadState::NotLoaded = asset_server.get_load_state(&texture_handle) {
-                asset_server.load::<Image, _>(image_path.as_str());
-            }
-
             // Add the image to the image cache to keep the handle from getting dropped while the
             // UI is using it.
             image_cache.insert(texture_handle);
@@ -320,49 +717,73 @@ impl RenderHook for UiRenderHook {
             // We have the same issue with the fonts below.
         }
 
-        // Get list of font handles used by the UI
-        for font_path in batches.iter().filter_map(|x| match x {
-            Batch::ExternalText(_, batch) => Some(&batch.font),
-            _ => None,
-        }) {
-            // Get the font handle
-            let font_handle: Handle<Font> =
-                asset_server.get_handle(HandleId::from(AssetPath::from(font_path.as_str())));
-
-            // Load the font if loading has not started yet
-            if let LoadState::NotLoaded = asset_server.get_load_state(&font_handle) {
-                asset_server.load::<Font, _>(font_path.as_str());
-            }
+        // Get list of font handles used by the UI, including the fallback fonts
+        for font_path in batches
+            .iter()
+            .filter_map(|x| match x {
+                Batch::ExternalText(_, batch) => Some(&batch.font),
+                _ => None,
+            })
+            .chain(text_settings.fallback_fonts.iter())
+        {
+            // Get the font handle, loading the font if loading has not started yet
+            let font_handle: Handle<Font> = load_ui_handle(asset_server, font_path);
 
             font_cache.insert(font_handle);
         }
 
-        // Rasterize text blocks to textures
-        // TODO: Cache text block rasterizations and reuse if they haven't been changed
-        let mut text_block_textures = HashMap::new();
+        // Lay out the text blocks that have changed, re-using the layouts and glyph quads of text
+        // blocks that were drawn in recent frames
+        for cached_text in text_cache.values_mut() {
+            cached_text.unused_frames += 1;
+        }
+        let mut text_block_keys = HashMap::new();
+        let fallback_handles = text_settings
+            .fallback_fonts
+            .iter()
+            .map(|path| get_ui_handle(asset_server, path))
+            .collect::<Vec<Handle<Font>>>();
+        let fallback_fonts = fallback_handles
+            .iter()
+            .map(|x| font_assets.get(x))
+            .collect::<Option<Vec<_>>>();
         for (widget, batch) in batches.iter().filter_map(|x| match x {
             Batch::ExternalText(widget, batch) => Some((widget, batch)),
             _ => None,
         }) {
             // Get the font handle
-            let font_handle: Handle<Font> =
-                asset_server.get_handle(HandleId::from(AssetPath::from(batch.font.as_str())));
+            let font_handle: Handle<Font> = get_ui_handle(asset_server, &batch.font);
             // Load the font
-            let font = if let Some(font) = font_assets.get(font_handle) {
+            let font = if let Some(font) = font_assets.get(&font_handle) {
                 font
             } else {
+                pending_fonts.insert(font_handle);
+                continue;
+            };
+            // Wait for the fallback fonts to load
+            let fallback_fonts = if let Some(fallback_fonts) = &fallback_fonts {
+                fallback_fonts
+            } else {
+                pending_fonts.extend(
+                    fallback_handles
+                        .iter()
+                        .filter(|x| font_assets.get(*x).is_none())
+                        .cloned(),
+                );
                 continue;
             };
 
-            // Collect text info
+            // Collect text info, translating localization keys. Text blocks are cached by their
+            // translated text, so they are laid out again when the translation changes.
             let text = Text {
-                text: batch.text.clone(),
+                text: translations.resolve(&batch.text).into_owned(),
                 color: Color {
                     r: batch.color.r,
                     g: batch.color.g,
                     b: batch.color.b,
                     a: batch.color.a,
                 },
+                ..Default::default()
             };
             let text_block = TextBlock {
                 width: batch.box_size.x.round() as u32,
@@ -377,41 +798,292 @@ impl RenderHook for UiRenderHook {
                     raui::prelude::TextBoxVerticalAlign::Bottom => TextVerticalAlign::Bottom,
                 },
                 height: Some(batch.box_size.y.round() as u32),
+                letter_spacing: text_settings.letter_spacing,
+                line_height: text_settings.line_height,
+                overflow: text_settings.overflow,
+                max_lines: None,
             };
 
-            // Rasterize the text block
-            let image = rasterize_text_block(&text, font, Some(&text_block));
+            let key = TextRasterKey::new(&text, &font_handle, Some(&text_block))
+                .with_fallbacks(&fallback_handles);
+            text_block_keys.insert(widget.clone(), key);
+
+            // Re-use the cached text block if it hasn't changed, otherwise lay it out again
+            if let Some(cached_text) = text_cache.get_mut(&key) {
+                cached_text.unused_frames = 0;
+                continue;
+            }
+            text_cache.insert(
+                key,
+                CachedText {
+                    layout: layout_text_block(&text, font, fallback_fonts, Some(&text_block), None),
+                    font_ids: std::iter::once(font_handle.id)
+                        .chain(fallback_handles.iter().map(|x| x.id))
+                        .collect(),
+                    atlas_generation: None,
+                    tess: None,
+                    unused_frames: 0,
+                },
+            );
+        }
 
-            // Get raw pixels
-            let (sprite_width, sprite_height) = image.dimensions();
-            let image_size = [sprite_width, sprite_height];
-            let pixels = image.as_raw();
+        // Drop text blocks that haven't been drawn for a while
+        text_cache.retain(|_, cached_text| cached_text.unused_frames <= TEXT_CACHE_FRAMES);
+
+        // Add the glyphs of the text blocks that haven't been drawn from the current atlas yet. If
+        // the atlas fills up and is cleared, the glyphs of all of the text blocks drawn this frame
+        // have to be added again.
+        let generation = glyph_atlas.generation();
+        insert_atlas_glyphs(
+            glyph_atlas,
+            font_assets,
+            text_cache
+                .values()
+                .filter(|x| x.unused_frames == 0 && x.atlas_generation != Some(generation)),
+        );
+        if glyph_atlas.generation() != generation {
+            insert_atlas_glyphs(
+                glyph_atlas,
+                font_assets,
+                text_cache.values().filter(|x| x.unused_frames == 0),
+            );
+        }
 
-            // Upload the image to the GPU
-            let mut texture = surface
-                .new_texture::<Dim2, NormRGBA8UI>(image_size, 0, PIXELATED_SAMPLER)
-                .unwrap();
-            texture.upload_raw(GenMipmaps::No, pixels).unwrap();
+        // Upload any new glyphs to the atlas texture
+        glyph_atlas.upload_dirty_region(glyph_atlas_texture);
+
+        // Build the glyph quads of new text blocks, and of all of the text blocks if the atlas has
+        // been cleared since their quads were built
+        for cached_text in text_cache.values_mut() {
+            if cached_text.unused_frames == 0
+                && cached_text.atlas_generation != Some(glyph_atlas.generation())
+            {
+                cached_text.tess = build_text_tess(
+                    surface,
+                    &cached_text
+                        .layout
+                        .glyph_quads(glyph_atlas, &cached_text.font_ids),
+                );
+                cached_text.atlas_generation = Some(glyph_atlas.generation());
+            }
+        }
 
-            text_block_textures.insert(widget.clone(), texture);
+        let mut renderer = BatchRenderer {
+            shader_program,
+            glyph_atlas_texture,
+            tess: &tess,
+            asset_server,
+            texture_cache,
+            text_block_keys: &text_block_keys,
+            text_cache,
+            target_size: *target_size,
+            clip_stack: Vec::new(),
+        };
+        for pass in passes {
+            match pass {
+                UiPass::Direct(range) => {
+                    renderer.draw(surface, target_framebuffer, &batches[range], false)
+                }
+                UiPass::Clipped {
+                    batches: range,
+                    quad,
+                } => {
+                    // Re-create the clip framebuffer if the target framebuffer has been resized
+                    let size = target_framebuffer.size();
+                    if clip_framebuffer.as_ref().map(|x| x.size()) != Some(size) {
+                        *clip_framebuffer = Some(
+                            surface
+                                .new_framebuffer(size, 0, PIXELATED_SAMPLER)
+                                .expect("Could not create UI clip framebuffer"),
+                        );
+                    }
+                    let clip_framebuffer = clip_framebuffer.as_mut().unwrap();
+
+                    // Draw the contents of the clip region by themselves, and then draw them onto
+                    // the target through a quad in the shape of the clip region
+                    renderer.draw(surface, clip_framebuffer, &batches[range], true);
+                    renderer.composite(surface, target_framebuffer, clip_framebuffer, quad);
+                }
+            }
         }
+    }
+}
+
+/// A run of UI batches that are drawn together
+#[derive(Debug, Clone, PartialEq)]
+enum UiPass {
+    /// Batches that are drawn straight to the target framebuffer
+    Direct(Range<usize>),
+    /// The batches inside of a rotated or skewed clip region, which can't be clipped with a
+    /// scissor, so they are drawn to the clip framebuffer and composited onto the target framebuffer
+    /// through a quad in the shape of the clip region
+    Clipped {
+        batches: Range<usize>,
+        /// The indices of the quad
+        quad: Range<usize>,
+    },
+}
 
-        // The stack of clipping regions applied by RAUI
-        let mut clip_stack = Vec::new();
+/// Get whether or not a clip matrix keeps the edges of the clip region axis-aligned, so that it can
+/// be clipped with a scissor
+fn is_axis_aligned(matrix: &[f32; 16]) -> bool {
+    matrix[1].abs() < f32::EPSILON && matrix[4].abs() < f32::EPSILON
+}
+
+/// Split the UI batches into passes, adding the quads that the clip regions that aren't
+/// axis-aligned are composited through
+///
+/// Clip regions inside of those clip regions are clipped to their bounding box with a scissor.
+fn split_clip_passes(
+    vertices: &mut Vec<QuadVert>,
+    indices: &mut Vec<u32>,
+    batches: &[Batch],
+) -> Vec<UiPass> {
+    let mut passes = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < batches.len() {
+        let clip = match &batches[i] {
+            Batch::ClipPush(clip) if !is_axis_aligned(&clip.matrix) => clip,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        // Find the pop that matches the push
+        let mut depth = 0;
+        let mut end = batches.len();
+        for (j, batch) in batches.iter().enumerate().skip(i + 1) {
+            match batch {
+                Batch::ClipPush(_) => depth += 1,
+                Batch::ClipPop if depth == 0 => {
+                    end = j;
+                    break;
+                }
+                Batch::ClipPop => depth -= 1,
+                _ => (),
+            }
+        }
+
+        if start < i {
+            passes.push(UiPass::Direct(start..i));
+        }
+        let first_index = indices.len();
+        push_clip_quad(
+            vertices,
+            indices,
+            &clip.matrix,
+            [clip.box_size.x, clip.box_size.y],
+        );
+        passes.push(UiPass::Clipped {
+            batches: i + 1..end,
+            quad: first_index..indices.len(),
+        });
+
+        // Continue after the pop
+        i = end + 1;
+        start = i;
+    }
+
+    if start < batches.len() {
+        passes.push(UiPass::Direct(start..batches.len()));
+    }
+
+    passes
+}
+
+/// Get the render state that the UI is drawn with
+fn ui_render_state() -> RenderState {
+    RenderState::default()
+        // Blend the alpha separately so that the alpha of the clip framebuffer is right for
+        // compositing
+        .set_blending_separate(
+            Blending {
+                equation: Equation::Additive,
+                src: Factor::SrcAlpha,
+                dst: Factor::SrcAlphaComplement,
+            },
+            Blending {
+                equation: Equation::Additive,
+                src: Factor::One,
+                dst: Factor::SrcAlphaComplement,
+            },
+        )
+        .set_face_culling(Some(FaceCulling {
+            order: luminance::face_culling::FaceCullingOrder::CW,
+            mode: luminance::face_culling::FaceCullingMode::Back,
+        }))
+        .set_depth_test(None) // Disable depth test so the UI always renders on top
+}
+
+/// Draws runs of UI batches to a framebuffer
+struct BatchRenderer<'a> {
+    shader_program: &'a mut Program<(), (), UiUniformInterface>,
+    glyph_atlas_texture: &'a mut Texture<Dim2, NormRGBA8UI>,
+    tess: &'a Tess<UiVert, u32>,
+    asset_server: &'a AssetServer,
+    texture_cache: &'a mut TextureCache,
+    text_block_keys: &'a HashMap<WidgetId, TextRasterKey>,
+    text_cache: &'a HashMap<TextRasterKey, CachedText>,
+    target_size: UVec2,
+    /// The stack of clipping regions applied by RAUI, which carries over from one run of batches
+    /// to the next
+    clip_stack: Vec<ClipRect>,
+}
+
+impl<'a> BatchRenderer<'a> {
+    /// Get the render state with the scissor of the current clip region
+    fn render_state(&self, framebuffer_size: [u32; 2]) -> RenderState {
+        ui_render_state().set_scissor(
+            self.clip_stack
+                .last()
+                .map(|x| x.to_scissor_region(self.target_size, framebuffer_size)),
+        )
+    }
+
+    /// Draw the batches to the framebuffer, clearing it first if `clear` is `true`
+    fn draw(
+        &mut self,
+        surface: &mut Surface,
+        framebuffer: &SceneFramebuffer,
+        batches: &[Batch],
+        clear: bool,
+    ) {
+        let framebuffer_size = framebuffer.size();
+        let mut render_state = self.render_state(framebuffer_size);
+        let pipeline_state = if clear {
+            PipelineState::default().set_clear_color([0., 0., 0., 0.])
+        } else {
+            PipelineState::default().enable_clear_color(false)
+        };
+        let Self {
+            shader_program,
+            glyph_atlas_texture,
+            tess,
+            asset_server,
+            texture_cache,
+            text_block_keys,
+            text_cache,
+            target_size,
+            clip_stack,
+        } = self;
 
-        // Do the render
         surface
             .new_pipeline_gate()
             .pipeline(
-                // Render to the scene framebuffer
-                target_framebuffer,
-                &PipelineState::default().enable_clear_color(false),
+                framebuffer,
+                &pipeline_state,
                 |pipeline, mut shading_gate| {
+                    // All of the text is drawn from the same glyph atlas
+                    let bound_glyph_atlas =
+                        pipeline.bind_texture(&mut **glyph_atlas_texture).unwrap();
+
                     shading_gate.shade(
-                        shader_program,
+                        &mut **shader_program,
                         |mut interface, uniforms, mut render_gate| {
                             // Set the target size uniform
-                            let target_size = frame_context.target_sizes.low;
                             interface.set(
                                 &uniforms.target_size,
                                 [target_size.x as f32, target_size.y as f32],
@@ -424,13 +1096,12 @@ impl RenderHook for UiRenderHook {
                                         interface.set(&uniforms.widget_type, WIDGET_COLORED_TRIS);
 
                                         render_gate.render(&render_state, |mut tess_gate| {
-                                            tess_gate.render(tess.view(tris).unwrap())
+                                            tess_gate.render(tess.view(tris.clone()).unwrap())
                                         })?;
                                     }
                                     Batch::ImageTriangles(texture_path, tris) => {
-                                        let texture_handle = asset_server.get_handle(
-                                            HandleId::from(AssetPath::from(texture_path.as_str())),
-                                        );
+                                        let texture_handle: Handle<Image> =
+                                            get_ui_handle(asset_server, texture_path);
 
                                         // Get the texture using the image handle
                                         let texture = if let Some(texture) =
@@ -451,22 +1122,22 @@ impl RenderHook for UiRenderHook {
 
                                         // Render the block
                                         render_gate.render(&render_state, |mut tess_gate| {
-                                            tess_gate.render(tess.view(tris).unwrap())
+                                            tess_gate.render(tess.view(tris.clone()).unwrap())
                                         })?;
                                     }
                                     Batch::ExternalText(widget, batch) => {
-                                        // Get the texture
-                                        let texture = if let Some(tex) =
-                                            text_block_textures.get_mut(&widget)
+                                        // Get the glyph quads, skipping text blocks without
+                                        // any glyphs
+                                        let text_tess = if let Some(tess) = text_block_keys
+                                            .get(widget)
+                                            .and_then(|key| text_cache.get(key))
+                                            .and_then(|x| x.tess.as_ref())
                                         {
-                                            tex
+                                            tess
                                         } else {
                                             continue;
                                         };
 
-                                        // Bind our texture
-                                        let tex_size = texture.size();
-                                        let bound_texture = pipeline.bind_texture(texture).unwrap();
                                         interface.set(&uniforms.widget_type, WIDGET_TEXT);
 
                                         let m = batch.matrix;
@@ -481,81 +1152,47 @@ impl RenderHook for UiRenderHook {
                                                 [m[3], m[7], m[11], m[15]],
                                             ],
                                         );
-                                        // Set the text block size
-                                        interface.set(
-                                            &uniforms.text_box_size,
-                                            [tex_size[0] as f32, tex_size[1] as f32],
-                                        );
+                                        // The glyph quads are already in pixels
+                                        interface.set(&uniforms.text_box_size, [1., 1.]);
 
                                         // Set the texture uniform
-                                        interface.set(&uniforms.texture, bound_texture.binding());
+                                        interface
+                                            .set(&uniforms.texture, bound_glyph_atlas.binding());
 
                                         // Render the block
                                         render_gate.render(&render_state, |mut tess_gate| {
-                                            tess_gate.render(&*text_tess)
+                                            tess_gate.render(text_tess)
                                         })?;
                                     }
                                     Batch::FontTriangles(_, _, _) => {
                                         unimplemented!("Tesselated font rendering not implemented")
                                     }
                                     Batch::ClipPush(clip) => {
-                                        // Calculate clipping rectangle x and y
-                                        let matrix = Mat4::from_cols_array(&clip.matrix);
-
-                                        // tl, tr, bl, br == top_left, top_right, bottom_left, bottom_right
-                                        let tl = matrix.project_point3(Vec3::new(0.0, 0.0, 0.0));
-                                        let tr = matrix.project_point3(Vec3::new(
-                                            clip.box_size.x,
-                                            0.0,
-                                            0.0,
-                                        ));
-                                        let br = matrix.project_point3(Vec3::new(
-                                            clip.box_size.x,
-                                            clip.box_size.y,
-                                            0.0,
-                                        ));
-                                        let bl = matrix.project_point3(Vec3::new(
-                                            0.0,
-                                            clip.box_size.y,
-                                            0.0,
-                                        ));
-                                        let x1 = tl.x.min(tr.x).min(br.x).min(bl.x).round();
-                                        let y1 = tl.y.min(tr.y).min(br.y).min(bl.y).round();
-                                        let x2 = tl.x.max(tr.x).max(br.x).max(bl.x).round();
-                                        let y2 = tl.y.max(tr.y).max(br.y).max(bl.y).round();
-                                        let width = x2 - x1;
-                                        let height = y2 - y1;
-
-                                        // Set the clipping section for future renders
-                                        if !*has_shown_clipping_warning {
-                                            bevy::log::warn!(
-                                            "Detected UI elements that use clipping, there are \
-                                            some bugs under certain circumstances where the \
-                                            clipping region is incorrect. You may want to \
-                                            disable clipping if the UI element fails to \
-                                            render correctly"
-                                            );
-
-                                            *has_shown_clipping_warning = true;
+                                        // Clip to the part of the clip rect that is inside of
+                                        // the parent clip rect
+                                        let mut clip_rect = ClipRect::from_transformed_box(
+                                            &clip.matrix,
+                                            [clip.box_size.x, clip.box_size.y],
+                                        );
+                                        if let Some(parent) = clip_stack.last() {
+                                            clip_rect = clip_rect.intersect(parent);
                                         }
+                                        clip_stack.push(clip_rect);
 
-                                        let scissor_region = ScissorRegion {
-                                            x: x1 as u32,
-                                            y: y1 as u32,
-                                            width: width as u32,
-                                            height: height as u32,
-                                        };
-
-                                        render_state = render_state.set_scissor(scissor_region);
-                                        clip_stack.push(scissor_region);
+                                        render_state = render_state.set_scissor(
+                                            clip_rect
+                                                .to_scissor_region(*target_size, framebuffer_size),
+                                        );
                                     }
                                     Batch::ClipPop => {
-                                        // Pop the last item off the clip stack and set the scissor
-                                        // to the previous one
+                                        // Pop the last clip rect off the clip stack and go back to
+                                        // clipping with its parent
                                         clip_stack.pop();
 
                                         render_state =
-                                            render_state.set_scissor(clip_stack.last().cloned());
+                                            render_state.set_scissor(clip_stack.last().map(|x| {
+                                                x.to_scissor_region(*target_size, framebuffer_size)
+                                            }));
                                     }
                                     Batch::None => (),
                                 }
@@ -570,6 +1207,68 @@ impl RenderHook for UiRenderHook {
             .into_result()
             .expect("Could not render");
     }
+
+    /// Draw the contents of the clip framebuffer onto the target framebuffer through the quad with
+    /// the given indices
+    fn composite(
+        &mut self,
+        surface: &mut Surface,
+        framebuffer: &SceneFramebuffer,
+        clip_framebuffer: &mut SceneFramebuffer,
+        quad: Range<usize>,
+    ) {
+        let framebuffer_size = framebuffer.size();
+        // The clip framebuffer has premultiplied alpha, and the quad may have been flipped
+        let render_state = self
+            .render_state(framebuffer_size)
+            .set_blending(Blending {
+                equation: Equation::Additive,
+                src: Factor::One,
+                dst: Factor::SrcAlphaComplement,
+            })
+            .set_face_culling(Option::<FaceCulling>::None);
+        let Self {
+            shader_program,
+            tess,
+            target_size,
+            ..
+        } = self;
+
+        surface
+            .new_pipeline_gate()
+            .pipeline(
+                framebuffer,
+                &PipelineState::default().enable_clear_color(false),
+                |pipeline, mut shading_gate| {
+                    let bound_clip_texture = pipeline
+                        .bind_texture(clip_framebuffer.color_slot())
+                        .unwrap();
+
+                    shading_gate.shade(
+                        &mut **shader_program,
+                        |mut interface, uniforms, mut render_gate| {
+                            interface.set(
+                                &uniforms.target_size,
+                                [target_size.x as f32, target_size.y as f32],
+                            );
+                            interface.set(&uniforms.widget_type, WIDGET_CLIPPED);
+                            interface.set(&uniforms.clip_texture, bound_clip_texture.binding());
+                            interface.set(
+                                &uniforms.framebuffer_size,
+                                [framebuffer_size[0] as f32, framebuffer_size[1] as f32],
+                            );
+
+                            render_gate.render(&render_state, |mut tess_gate| {
+                                tess_gate.render(tess.view(quad).unwrap())
+                            })
+                        },
+                    )
+                },
+            )
+            .assume()
+            .into_result()
+            .expect("Could not render");
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Semantics)]
@@ -604,6 +1303,17 @@ struct UiUniformInterface {
     text_box_transform: Uniform<[[f32; 4]; 4]>,
     #[uniform(unbound)]
     text_box_size: Uniform<[f32; 2]>,
+
+    /// The texture of the clip framebuffer, which has the same pixel format as the scene
+    /// framebuffer
+    #[cfg(not(target_arch = "wasm32"))]
+    #[uniform(unbound)]
+    clip_texture: Uniform<TextureBinding<Dim2, luminance::pixel::Floating>>,
+    #[cfg(target_arch = "wasm32")]
+    #[uniform(unbound)]
+    clip_texture: Uniform<TextureBinding<Dim2, luminance::pixel::Unsigned>>,
+    #[uniform(unbound)]
+    framebuffer_size: Uniform<[f32; 2]>,
 }
 
 /// Uniform widget type constant
@@ -612,6 +1322,8 @@ const WIDGET_COLORED_TRIS: i32 = 0;
 const WIDGET_IMAGE_TRIS: i32 = 1;
 /// Uniform widget type constant
 const WIDGET_TEXT: i32 = 2;
+/// Uniform widget type constant
+const WIDGET_CLIPPED: i32 = 3;
 
 const PIXELATED_SAMPLER: Sampler = Sampler {
     wrap_r: Wrap::ClampToEdge,
@@ -622,26 +1334,343 @@ const PIXELATED_SAMPLER: Sampler = Sampler {
     depth_comparison: None,
 };
 
-// Quad vertices in a triangle fan
-const QUAD_VERTS: [UiVert; 4] = [
-    UiVert::new(
-        VertexPosition::new([0.0, 0.0]),
-        VertexUv::new([0.0, 0.0]),
-        VertexColor::new([1., 1., 1., 1.]),
-    ),
-    UiVert::new(
-        VertexPosition::new([1.0, 0.0]),
-        VertexUv::new([1.0, 0.0]),
-        VertexColor::new([1., 1., 1., 1.]),
-    ),
-    UiVert::new(
-        VertexPosition::new([1.0, 1.0]),
-        VertexUv::new([1.0, 1.0]),
-        VertexColor::new([1., 1., 1., 1.]),
-    ),
-    UiVert::new(
-        VertexPosition::new([0.0, 1.0]),
-        VertexUv::new([0.0, 1.0]),
-        VertexColor::new([1., 1., 1., 1.]),
-    ),
-];
+/// A clipping rectangle in UI pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ClipRect {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl ClipRect {
+    /// Get the rectangle that contains a box of the given size after it has been transformed
+    ///
+    /// Scissor regions can't be rotated, so rotated clip boxes clip to their bounding box.
+    fn from_transformed_box(matrix: &[f32; 16], box_size: [f32; 2]) -> Self {
+        let matrix = Mat4::from_cols_array(matrix);
+        let corners = [
+            matrix.project_point3(Vec3::new(0., 0., 0.)),
+            matrix.project_point3(Vec3::new(box_size[0], 0., 0.)),
+            matrix.project_point3(Vec3::new(box_size[0], box_size[1], 0.)),
+            matrix.project_point3(Vec3::new(0., box_size[1], 0.)),
+        ];
+
+        corners.iter().skip(1).fold(
+            ClipRect {
+                left: corners[0].x,
+                top: corners[0].y,
+                right: corners[0].x,
+                bottom: corners[0].y,
+            },
+            |rect, corner| ClipRect {
+                left: rect.left.min(corner.x),
+                top: rect.top.min(corner.y),
+                right: rect.right.max(corner.x),
+                bottom: rect.bottom.max(corner.y),
+            },
+        )
+    }
+
+    /// Get the part of the rectangle that is inside of another one
+    fn intersect(&self, other: &ClipRect) -> Self {
+        let left = self.left.max(other.left);
+        let top = self.top.max(other.top);
+
+        ClipRect {
+            left,
+            top,
+            // Keep the rectangle from having a negative size if they don't overlap
+            right: self.right.min(other.right).max(left),
+            bottom: self.bottom.min(other.bottom).max(top),
+        }
+    }
+
+    /// Get the scissor region of the rectangle in the framebuffer
+    ///
+    /// The UI is laid out in the resolution of its tree with its origin in the top left, but the
+    /// framebuffer may be scaled up and has its origin in the bottom left.
+    fn to_scissor_region(&self, target_size: UVec2, framebuffer_size: [u32; 2]) -> ScissorRegion {
+        let scale_x = framebuffer_size[0] as f32 / target_size.x.max(1) as f32;
+        let scale_y = framebuffer_size[1] as f32 / target_size.y.max(1) as f32;
+        let to_x = |x: f32| ((x * scale_x).round().max(0.) as u32).min(framebuffer_size[0]);
+        let to_y = |y: f32| {
+            ((framebuffer_size[1] as f32 - y * scale_y).round().max(0.) as u32)
+                .min(framebuffer_size[1])
+        };
+
+        let (x1, x2) = (to_x(self.left), to_x(self.right));
+        let (y1, y2) = (to_y(self.bottom), to_y(self.top));
+
+        ScissorRegion {
+            x: x1,
+            y: y1,
+            width: x2 - x1,
+            height: y2 - y1,
+        }
+    }
+}
+
+/// A UI vertex before it is uploaded to the GPU
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct QuadVert {
+    pos: [f32; 2],
+    uv: [f32; 2],
+    color: [f32; 4],
+}
+
+/// The color of the outlines of widget layout rects in the debug overlay
+const DEBUG_LAYOUT_COLOR: [f32; 4] = [0., 1., 0., 0.5];
+/// The color of the outlines of clipping regions in the debug overlay
+const DEBUG_CLIP_COLOR: [f32; 4] = [1., 0., 0., 0.8];
+/// The color of the outline of the selected button in the debug overlay
+const DEBUG_SELECTED_COLOR: [f32; 4] = [1., 1., 0., 1.];
+/// The color of the outline of the focused text input in the debug overlay
+const DEBUG_FOCUSED_COLOR: [f32; 4] = [0., 1., 1., 1.];
+
+/// Add a one pixel wide outline around a rectangle as colored quads
+fn push_debug_outline(
+    vertices: &mut Vec<QuadVert>,
+    indices: &mut Vec<u32>,
+    rect: &ClipRect,
+    color: [f32; 4],
+) {
+    let ClipRect {
+        left,
+        top,
+        right,
+        bottom,
+    } = *rect;
+
+    for &[x0, y0, x1, y1] in &[
+        [left, top, right, top + 1.],
+        [left, bottom - 1., right, bottom],
+        [left, top, left + 1., bottom],
+        [right - 1., top, right, bottom],
+    ] {
+        let first_vertex = vertices.len() as u32;
+        for &pos in &[[x0, y0], [x1, y0], [x1, y1], [x0, y1]] {
+            vertices.push(QuadVert {
+                pos,
+                uv: [0., 0.],
+                color,
+            });
+        }
+
+        // Two triangles, in the same winding as the rest of the UI
+        indices.extend(&[
+            first_vertex,
+            first_vertex + 1,
+            first_vertex + 2,
+            first_vertex,
+            first_vertex + 2,
+            first_vertex + 3,
+        ]);
+    }
+}
+
+/// Add a quad covering a clip box after it has been transformed
+fn push_clip_quad(
+    vertices: &mut Vec<QuadVert>,
+    indices: &mut Vec<u32>,
+    matrix: &[f32; 16],
+    box_size: [f32; 2],
+) {
+    let matrix = Mat4::from_cols_array(matrix);
+    let first_vertex = vertices.len() as u32;
+    for &[x, y] in &[
+        [0., 0.],
+        [box_size[0], 0.],
+        [box_size[0], box_size[1]],
+        [0., box_size[1]],
+    ] {
+        let corner = matrix.project_point3(Vec3::new(x, y, 0.));
+        vertices.push(QuadVert {
+            pos: [corner.x, corner.y],
+            uv: [0., 0.],
+            color: [1., 1., 1., 1.],
+        });
+    }
+
+    indices.extend(&[
+        first_vertex,
+        first_vertex + 1,
+        first_vertex + 2,
+        first_vertex,
+        first_vertex + 2,
+        first_vertex + 3,
+    ]);
+}
+
+/// Replace the quads of stretched nine-patch images with nine quads each, so that the borders of
+/// the images keep their size
+fn apply_nine_patches<F: FnMut(&str) -> Option<[u32; 2]>>(
+    vertices: &mut Vec<QuadVert>,
+    indices: &mut Vec<u32>,
+    batches: &mut [Batch],
+    nine_patches: &UiNinePatches,
+    mut image_size: F,
+) {
+    for batch in batches.iter_mut() {
+        let (image_path, tris) = match batch {
+            Batch::ImageTriangles(image_path, tris) => (image_path, tris),
+            _ => continue,
+        };
+        let nine_patch = if let Some(nine_patch) = nine_patches.get(image_path) {
+            nine_patch
+        } else {
+            continue;
+        };
+        // Skip images that haven't loaded yet
+        let size = if let Some(size) = image_size(image_path) {
+            size
+        } else {
+            continue;
+        };
+
+        // Stretched images are drawn as a single quad made of two triangles
+        if tris.end - tris.start != 6 {
+            continue;
+        }
+        let quad = indices[tris.clone()]
+            .iter()
+            .map(|&i| vertices[i as usize])
+            .collect::<Vec<_>>();
+        let corner_sum = |x: &&QuadVert| x.pos[0] + x.pos[1];
+        let compare = |a: &&QuadVert, b: &&QuadVert| {
+            corner_sum(a)
+                .partial_cmp(&corner_sum(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+        let top_left = *quad.iter().min_by(compare).unwrap();
+        let bottom_right = *quad.iter().max_by(compare).unwrap();
+
+        // Skip rotated images, which can't be split along their axes
+        if !quad.iter().all(|x| {
+            (x.pos[0] == top_left.pos[0] || x.pos[0] == bottom_right.pos[0])
+                && (x.pos[1] == top_left.pos[1] || x.pos[1] == bottom_right.pos[1])
+        }) {
+            continue;
+        }
+
+        // Get the positions and texture coordinates of the lines that split the image, shrinking
+        // the borders if the image is drawn smaller than them
+        let split =
+            |min: f32, max: f32, uv_min: f32, uv_max: f32, start: u32, end: u32, size: u32| {
+                let border_scale = ((max - min) / (start + end).max(1) as f32).min(1.);
+                let uv_direction = (uv_max - uv_min).signum();
+                (
+                    [
+                        min,
+                        min + start as f32 * border_scale,
+                        max - end as f32 * border_scale,
+                        max,
+                    ],
+                    [
+                        uv_min,
+                        uv_min + uv_direction * start as f32 / size as f32,
+                        uv_max - uv_direction * end as f32 / size as f32,
+                        uv_max,
+                    ],
+                )
+            };
+        let (xs, us) = split(
+            top_left.pos[0],
+            bottom_right.pos[0],
+            top_left.uv[0],
+            bottom_right.uv[0],
+            nine_patch.left,
+            nine_patch.right,
+            size[0].max(1),
+        );
+        let (ys, vs) = split(
+            top_left.pos[1],
+            bottom_right.pos[1],
+            top_left.uv[1],
+            bottom_right.uv[1],
+            nine_patch.top,
+            nine_patch.bottom,
+            size[1].max(1),
+        );
+
+        // Add a 4x4 grid of vertices and the nine quads between them
+        let first_vertex = vertices.len() as u32;
+        for row in 0..4 {
+            for column in 0..4 {
+                vertices.push(QuadVert {
+                    pos: [xs[column], ys[row]],
+                    uv: [us[column], vs[row]],
+                    color: top_left.color,
+                });
+            }
+        }
+        let first_index = indices.len();
+        for row in 0..3 {
+            for column in 0..3 {
+                let top_left = first_vertex + row * 4 + column;
+                let (top_right, bottom_right, bottom_left) =
+                    (top_left + 1, top_left + 5, top_left + 4);
+
+                // Two triangles per quad, in the same winding as the rest of the UI
+                indices.extend(&[
+                    top_left,
+                    top_right,
+                    bottom_right,
+                    top_left,
+                    bottom_right,
+                    bottom_left,
+                ]);
+            }
+        }
+
+        *tris = first_index..indices.len();
+    }
+}
+
+/// Add the glyphs used by text blocks to the glyph atlas
+fn insert_atlas_glyphs<'a, I: Iterator<Item = &'a CachedText>>(
+    glyph_atlas: &mut GlyphAtlas,
+    font_assets: &Assets<Font>,
+    texts: I,
+) {
+    let mut atlas_glyphs = Vec::new();
+    for cached_text in texts {
+        if let Some(fonts) = cached_text
+            .font_ids
+            .iter()
+            .map(|&id| font_assets.get(id).map(|font| (id, font)))
+            .collect::<Option<Vec<_>>>()
+        {
+            atlas_glyphs.extend(cached_text.layout.atlas_glyphs(&fonts));
+        }
+    }
+
+    if !atlas_glyphs.is_empty() {
+        glyph_atlas.insert_glyphs(&atlas_glyphs);
+    }
+}
+
+/// Upload the glyph quads of a text block to the GPU
+fn build_text_tess(surface: &mut Surface, quads: &[GlyphQuad]) -> Option<Tess<UiVert, u32>> {
+    if quads.is_empty() {
+        return None;
+    }
+
+    let (vertices, indices) = glyph_quad_mesh(quads, |pos, uv, color| {
+        UiVert::new(
+            VertexPosition::new(pos),
+            VertexUv::new(uv),
+            VertexColor::new(color),
+        )
+    });
+
+    Some(
+        surface
+            .new_tess()
+            .set_mode(luminance::tess::Mode::Triangle)
+            .set_vertices(vertices)
+            .set_indices(indices)
+            .build()
+            .expect("Could not create text tesselation"),
+    )
+}
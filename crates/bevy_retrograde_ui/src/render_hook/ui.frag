@@ -3,10 +3,13 @@ varying vec2 uv;
 
 uniform sampler2D texture;
 uniform int widget_type;
+uniform sampler2D clip_texture;
+uniform vec2 framebuffer_size;
 
 const int WIDGET_COLORED_TRIS = 0;
 const int WIDGET_IMAGE_TRIS = 1;
 const int WIDGET_TEXT = 2;
+const int WIDGET_CLIPPED = 3;
 
 
 void main() {
@@ -16,5 +19,8 @@ void main() {
     gl_FragColor = color;
   } else if (widget_type == WIDGET_TEXT) {
     gl_FragColor = color * texture2D(texture, uv);
+  } else if (widget_type == WIDGET_CLIPPED) {
+    // Copy the pixel of the clip framebuffer under the clip region
+    gl_FragColor = texture2D(clip_texture, gl_FragCoord.xy / framebuffer_size);
   }
 }
@@ -13,10 +13,12 @@ uniform mat4 text_box_transform;
 const int WIDGET_COLORED_TRIS = 0;
 const int WIDGET_IMAGE_TRIS = 1;
 const int WIDGET_TEXT = 2;
+const int WIDGET_CLIPPED = 3;
 const vec4 y_invert = vec4(1., -1., 1., 1.);
 
 void main() {
-  if (widget_type == WIDGET_COLORED_TRIS || widget_type == WIDGET_IMAGE_TRIS) {
+  if (widget_type == WIDGET_COLORED_TRIS || widget_type == WIDGET_IMAGE_TRIS ||
+      widget_type == WIDGET_CLIPPED) {
     gl_Position = vec4(v_pos / target_size * 2.0 - 1., 0., 1.) * y_invert;
   } else if (widget_type == WIDGET_TEXT) {
     vec4 base_pos = vec4(v_pos * text_box_size, 0., 1.) * text_box_transform;
@@ -1,5 +1,404 @@
-use raui::prelude::WidgetNode;
+use bevy::{math::UVec2, utils::HashMap, window::WindowId};
+use bevy_retrograde_core::prelude::CameraTargetSizes;
+use bevy_retrograde_text::prelude::TextOverflow;
+use raui::prelude::{CoordsMapping, CoordsMappingScaling, Rect, WidgetNode};
 
 /// This resource contains Bevy Retrograde's UI widget tree
+///
+/// This is the UI of the primary window. Other windows get their own UI from the
+/// [`UiWindowTrees`].
 #[derive(Debug, Clone, Default)]
 pub struct UiTree(pub WidgetNode);
+
+/// This resource contains the UI widget trees of windows other than the primary window
+///
+/// Each window has its own UI with its own navigation and keyboard focus, so that secondary windows
+/// can have their own HUD or menus. Windows without a tree don't show any UI.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{prelude::*, window::WindowCreated};
+/// # use bevy_retrograde_ui::*;
+/// # fn map_widget(_ctx: raui::prelude::WidgetContext) -> raui::prelude::WidgetNode {
+/// #     Default::default()
+/// # }
+/// fn setup_map_window(
+///     mut window_created_events: EventReader<WindowCreated>,
+///     mut window_trees: ResMut<UiWindowTrees>,
+/// ) {
+///     for event in window_created_events.iter() {
+///         if !event.id.is_primary() {
+///             window_trees.insert(event.id, raui::prelude::make_widget!(map_widget).into());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiWindowTrees(pub HashMap<WindowId, WidgetNode>);
+
+impl UiWindowTrees {
+    /// Set the widget tree of a window
+    pub fn insert(&mut self, window_id: WindowId, tree: WidgetNode) {
+        self.0.insert(window_id, tree);
+    }
+
+    /// Remove the widget tree of a window
+    pub fn remove(&mut self, window_id: WindowId) {
+        self.0.remove(&window_id);
+    }
+
+    /// Get the widget tree of a window
+    pub fn get(&self, window_id: WindowId) -> Option<&WidgetNode> {
+        self.0.get(&window_id)
+    }
+}
+
+/// This resource contains the text settings applied to all of the text in the UI
+///
+/// The layout settings correspond to the fields of the same name on the
+/// [`TextBlock`][bevy_retrograde_text::prelude::TextBlock] component.
+#[derive(Debug, Clone, Default)]
+pub struct UiTextSettings {
+    /// Extra space in pixels added between each glyph
+    pub letter_spacing: i32,
+    /// The distance in pixels from the top of one line to the top of the next, or [`None`] to use
+    /// the height of the font
+    pub line_height: Option<u32>,
+    /// What to do with text that doesn't fit in its text box
+    pub overflow: TextOverflow,
+    /// The asset paths of fonts used, in order, for any characters that are missing from the font
+    /// of a text box
+    pub fallback_fonts: Vec<String>,
+}
+
+/// This resource contains the nine-patch borders of UI images, by asset path
+///
+/// Images that are drawn from a [`UiAtlases`] atlas can't be nine-patches.
+///
+/// Nine-patch images are split into a 3x3 grid by their borders. When an image box stretches a
+/// nine-patch image, the corners keep their size, the edges stretch along one axis, and only the
+/// middle stretches along both, so that bordered panels and buttons can be any size without
+/// blurring or distorting their borders.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut nine_patches: ResMut<UiNinePatches>) {
+///     nine_patches.insert("ui/panel.png", NinePatch::uniform(4));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiNinePatches(pub HashMap<String, NinePatch>);
+
+impl UiNinePatches {
+    /// Set the nine-patch borders of an image
+    pub fn insert<P: Into<String>>(&mut self, image_path: P, nine_patch: NinePatch) {
+        self.0.insert(image_path.into(), nine_patch);
+    }
+
+    /// Get the nine-patch borders of an image
+    pub fn get(&self, image_path: &str) -> Option<NinePatch> {
+        self.0.get(image_path).copied()
+    }
+}
+
+/// The size in image pixels of the borders of a nine-patch image
+///
+/// See [`UiNinePatches`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NinePatch {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl NinePatch {
+    /// Create a nine-patch with the same border size on every side
+    pub fn uniform(border: u32) -> Self {
+        Self {
+            left: border,
+            right: border,
+            top: border,
+            bottom: border,
+        }
+    }
+}
+
+/// This resource contains the texture atlases that UI images can be drawn from
+///
+/// Each image in an atlas is given a name, which can be used as the image id of RAUI image boxes in
+/// place of an asset path. Keeping all of the images of a UI skin in one atlas texture means that
+/// the texture doesn't have to be re-bound for every image. Atlas textures are loaded as soon as
+/// they are added.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut atlases: ResMut<UiAtlases>) {
+///     atlases.insert("button", "ui/skin.png", UiAtlasRegion::new(0, 0, 32, 16));
+///     atlases.insert("button_hovered", "ui/skin.png", UiAtlasRegion::new(0, 16, 32, 16));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiAtlases {
+    images: HashMap<String, (String, UiAtlasRegion)>,
+}
+
+impl UiAtlases {
+    /// Add an image in an atlas, with the asset path of the atlas texture and the region of the
+    /// texture that the image is in
+    pub fn insert<N: Into<String>, P: Into<String>>(
+        &mut self,
+        name: N,
+        atlas_path: P,
+        region: UiAtlasRegion,
+    ) {
+        self.images.insert(name.into(), (atlas_path.into(), region));
+    }
+
+    /// Remove an image from its atlas
+    pub fn remove(&mut self, name: &str) {
+        self.images.remove(name);
+    }
+
+    /// Get the asset path of the atlas texture that an image is in, and the region of the texture
+    pub fn get(&self, name: &str) -> Option<(&str, UiAtlasRegion)> {
+        self.images
+            .get(name)
+            .map(|(atlas_path, region)| (atlas_path.as_str(), *region))
+    }
+
+    /// Iterate over the names of the images in atlases, along with their atlas paths and regions
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, UiAtlasRegion)> {
+        self.images
+            .iter()
+            .map(|(name, (atlas_path, region))| (name.as_str(), atlas_path.as_str(), *region))
+    }
+}
+
+/// The region in pixels of an atlas texture that an image is in
+///
+/// See [`UiAtlases`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UiAtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl UiAtlasRegion {
+    /// Create a region from its position and size
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// This resource configures how the UI is navigated with the keyboard and gamepads
+///
+/// The arrow keys, WASD, and the gamepad d-pads and left sticks move between navigable widgets,
+/// while enter, space, and the south gamepad button accept, and escape and the east gamepad button
+/// cancel.
+#[derive(Debug, Clone)]
+pub struct UiNavigationSettings {
+    /// Whether or not moving past the last widget in a direction wraps around to the first
+    pub wrap_focus: bool,
+    /// Whether or not gamepads can navigate the UI
+    pub gamepad: bool,
+    /// How far a gamepad stick must be pushed to navigate, from `0.0` to `1.0`
+    pub stick_threshold: f32,
+    /// The number of seconds that a gamepad direction must be held before it navigates again
+    pub repeat_delay: f32,
+    /// The number of seconds between navigating again while a gamepad direction is held
+    pub repeat_interval: f32,
+    /// How far in UI units the mouse must move while pressed on a draggable widget to start
+    /// dragging it, and how far a touch must move to scroll instead of tapping
+    pub drag_threshold: f32,
+}
+
+impl Default for UiNavigationSettings {
+    fn default() -> Self {
+        Self {
+            wrap_focus: true,
+            gamepad: true,
+            stick_threshold: 0.5,
+            repeat_delay: 0.4,
+            repeat_interval: 0.1,
+            drag_threshold: 4.,
+        }
+    }
+}
+
+/// The resolution that a UI tree is laid out and drawn at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiResolution {
+    /// The retro resolution of the camera, so that the UI is as pixelated as the rest of the game
+    Low,
+    /// The resolution that the retro resolution is scaled up to, which is the smallest whole
+    /// multiple of it that covers the window
+    ///
+    /// Text and images in the UI are drawn with smaller pixels than the game world, so that the UI
+    /// is crisper and easier to read. Use the [`UiScaleMode`] to make the UI larger.
+    High,
+}
+
+impl Default for UiResolution {
+    fn default() -> Self {
+        UiResolution::Low
+    }
+}
+
+/// This resource sets the [`UiResolution`] of the UI tree of each window
+///
+/// Windows that haven't been given a resolution use the low, retro resolution.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{prelude::*, window::WindowId};
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut resolutions: ResMut<UiResolutions>) {
+///     // Draw the UI of the primary window with crisp text over the chunky pixels of the game
+///     resolutions.insert(WindowId::primary(), UiResolution::High);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiResolutions(pub HashMap<WindowId, UiResolution>);
+
+impl UiResolutions {
+    /// Set the resolution of the UI tree of a window
+    pub fn insert(&mut self, window_id: WindowId, resolution: UiResolution) {
+        self.0.insert(window_id, resolution);
+    }
+
+    /// Get the resolution of the UI tree of a window
+    pub fn get(&self, window_id: WindowId) -> UiResolution {
+        self.0.get(&window_id).copied().unwrap_or_default()
+    }
+
+    /// Get the size of the UI of a window from the target sizes of the camera
+    pub(crate) fn target_size(
+        &self,
+        window_id: WindowId,
+        target_sizes: &CameraTargetSizes,
+    ) -> UVec2 {
+        match self.get(window_id) {
+            UiResolution::Low => target_sizes.low,
+            UiResolution::High => target_sizes.high,
+        }
+    }
+}
+
+/// This resource sets when the widgets of the UI are run
+///
+/// In the default [`Forced`][UiProcessMode::Forced] mode, every widget runs every frame, so widgets
+/// that read the Bevy world with [`ui_resource`][crate::ui_resource] and
+/// [`ui_component`][crate::ui_component] are always up to date. The
+/// [`Retained`][UiProcessMode::Retained] mode skips running the widgets on frames where nothing in
+/// the UI has changed, which is cheaper for large UIs that rarely change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiProcessMode {
+    /// Run every widget every frame
+    Forced,
+    /// Only run the widgets when the UI has changed
+    ///
+    /// The widgets run when the UI tree, the theme, or the state of a widget changes, when the
+    /// player interacts with the UI, when widgets are animating, and when widgets change bound
+    /// resources or components with binding mutation signals. Anything else that a widget shows
+    /// from the Bevy world has to be refreshed by calling [`UiInvalidation::invalidate`] when it
+    /// changes.
+    Retained,
+}
+
+impl Default for UiProcessMode {
+    fn default() -> Self {
+        UiProcessMode::Forced
+    }
+}
+
+/// This resource is used to run the widgets of the UI again in the
+/// [`Retained`][UiProcessMode::Retained] process mode
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::*;
+/// struct Score(u32);
+///
+/// fn refresh_score_label(score: Res<Score>, mut invalidation: ResMut<UiInvalidation>) {
+///     // Widgets that show the score with `ui_resource` need to run again when it changes
+///     if score.is_changed() {
+///         invalidation.invalidate();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiInvalidation;
+
+impl UiInvalidation {
+    /// Run the widgets of the UI again on the next frame
+    ///
+    /// This is done by mutably borrowing the resource, which Bevy's change detection picks up, so
+    /// it does nothing itself.
+    pub fn invalidate(&mut self) {}
+}
+
+/// This resource controls how the UI is scaled to the resolution of the UI tree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UiScaleMode {
+    /// One UI unit is one pixel of the UI resolution
+    MatchTarget,
+    /// The UI is laid out at a fixed resolution that is scaled to fit the UI resolution
+    ///
+    /// The UI keeps its aspect ratio, so when the UI resolution is wider or taller than the fixed
+    /// resolution, the extra space is added to the width or height of the UI.
+    FixedResolution { width: f32, height: f32 },
+    /// Each UI unit is this many pixels of the UI resolution
+    PixelScale(f32),
+}
+
+impl Default for UiScaleMode {
+    fn default() -> Self {
+        UiScaleMode::MatchTarget
+    }
+}
+
+impl UiScaleMode {
+    /// Get the mapping from UI coordinates to the resolution of the UI tree
+    pub(crate) fn coords_mapping(&self, target_size: UVec2) -> CoordsMapping {
+        let target_area = Rect {
+            left: 0.,
+            top: 0.,
+            right: target_size.x as f32,
+            bottom: target_size.y as f32,
+        };
+
+        match *self {
+            UiScaleMode::MatchTarget => CoordsMapping::new(target_area),
+            UiScaleMode::FixedResolution { width, height } => {
+                let target_aspect = target_size.x as f32 / target_size.y as f32;
+                let scaling = if target_aspect > width / height {
+                    CoordsMappingScaling::FitVertical(height)
+                } else {
+                    CoordsMappingScaling::FitHorizontal(width)
+                };
+                CoordsMapping::new_scaling(target_area, scaling)
+            }
+            UiScaleMode::PixelScale(scale) => {
+                CoordsMapping::new_scaling(target_area, CoordsMappingScaling::Constant(scale))
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use raui::prelude::{
+    ButtonNotifyMessage, ButtonProps, Deserialize, MessageData, NavSignal, Props, PropsData,
+    Serialize, WidgetContext, WidgetId,
+};
+
+use crate::AppBuilderUiSignalExt;
+
+pub(crate) fn add_sound_systems(app: &mut AppBuilder) {
+    app.add_ui_signal_event::<UiSound>();
+
+    #[cfg(feature = "audio")]
+    app.add_system_to_stage(CoreStage::PostUpdate, play_ui_sounds.system());
+}
+
+/// The kinds of sounds that the UI plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UiSoundKind {
+    /// A button was hovered over with the mouse or navigated to
+    Hover,
+    /// A button was clicked
+    Click,
+    /// A text input was focused
+    Focus,
+    /// The player tried to do something that isn't allowed, such as typing past the maximum length
+    /// of a text input
+    Invalid,
+}
+
+/// The asset paths of the sounds that the UI plays, set in the
+/// [`RetroUiTheme`][crate::RetroUiTheme]
+///
+/// Sounds that are [`None`] aren't played.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetroUiSounds {
+    pub hover: Option<String>,
+    pub click: Option<String>,
+    pub focus: Option<String>,
+    pub invalid: Option<String>,
+}
+
+impl RetroUiSounds {
+    /// Get the asset path of the sound of the given kind
+    pub fn get(&self, kind: UiSoundKind) -> Option<&str> {
+        match kind {
+            UiSoundKind::Hover => self.hover.as_deref(),
+            UiSoundKind::Click => self.click.as_deref(),
+            UiSoundKind::Focus => self.focus.as_deref(),
+            UiSoundKind::Invalid => self.invalid.as_deref(),
+        }
+    }
+}
+
+/// Props that change the sounds of a single widget with the [`use_ui_sounds`] hook
+///
+/// Sounds that are [`None`] use the sounds of the [`RetroUiTheme`][crate::RetroUiTheme].
+#[derive(PropsData, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetroUiSoundProps {
+    #[serde(default)]
+    pub hover: Option<String>,
+    #[serde(default)]
+    pub click: Option<String>,
+    #[serde(default)]
+    pub focus: Option<String>,
+    #[serde(default)]
+    pub invalid: Option<String>,
+    /// Whether or not the widget doesn't play any sounds
+    #[serde(default)]
+    pub muted: bool,
+}
+
+/// Bevy event sent when a UI widget plays a sound
+///
+/// With the `audio` feature enabled, the sound is played on the
+/// [`UI_BUS`][bevy_retrograde_audio::UI_BUS] of the audio plugin. Without it, these events can be
+/// used to play the UI sounds some other way.
+#[derive(MessageData, Debug, Clone)]
+pub struct UiSound {
+    /// The widget that played the sound
+    pub widget: WidgetId,
+    /// The kind of sound
+    pub kind: UiSoundKind,
+    /// The asset path of the sound set in the widget's [`RetroUiSoundProps`], or [`None`] to play
+    /// the sound of the theme
+    pub sound: Option<String>,
+}
+
+impl UiSound {
+    /// Create a sound for a widget, using the sound from its [`RetroUiSoundProps`] if it has one
+    ///
+    /// Returns [`None`] if the widget is muted. The sound is played by writing it to the widget's
+    /// signals, which lets custom widgets play sounds, such as the [`Invalid`][UiSoundKind::Invalid]
+    /// sound when they reject some input.
+    pub fn for_widget(widget: &WidgetId, props: &Props, kind: UiSoundKind) -> Option<Self> {
+        let sound_props = props.read_cloned_or_default::<RetroUiSoundProps>();
+        if sound_props.muted {
+            return None;
+        }
+
+        Some(Self {
+            widget: widget.to_owned(),
+            kind,
+            sound: match kind {
+                UiSoundKind::Hover => sound_props.hover,
+                UiSoundKind::Click => sound_props.click,
+                UiSoundKind::Focus => sound_props.focus,
+                UiSoundKind::Invalid => sound_props.invalid,
+            },
+        })
+    }
+}
+
+/// Hook that makes a button play the [`Hover`][UiSoundKind::Hover] sound when it is selected and
+/// the [`Click`][UiSoundKind::Click] sound when it is clicked
+///
+/// Like [`use_ui_button_events`][crate::use_ui_button_events], this works both for widgets that are
+/// buttons themselves and for widgets that are notified by a button. The
+/// [`widgets`][crate::widgets] that are buttons always play sounds.
+pub fn use_ui_sounds(ctx: &mut WidgetContext) {
+    ctx.life_cycle.change(|context| {
+        for msg in context.messenger.messages {
+            let kind = if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                if msg.select_start() {
+                    UiSoundKind::Hover
+                } else if msg.trigger_stop() {
+                    UiSoundKind::Click
+                } else {
+                    continue;
+                }
+            } else if let Some(signal) = msg.as_any().downcast_ref::<NavSignal>() {
+                // The state still has the button props from before this change
+                let button = context.state.read_cloned_or_default::<ButtonProps>();
+                match signal {
+                    NavSignal::Select(_) if !button.selected => UiSoundKind::Hover,
+                    NavSignal::Accept(false) if button.trigger => UiSoundKind::Click,
+                    _ => continue,
+                }
+            } else {
+                continue;
+            };
+
+            if let Some(sound) = UiSound::for_widget(context.id, context.props, kind) {
+                context.signals.write(sound);
+            }
+        }
+    });
+}
+
+/// Play the UI sounds through the audio plugin
+#[cfg(feature = "audio")]
+fn play_ui_sounds(
+    mut events: EventReader<UiSound>,
+    theme: Res<crate::RetroUiTheme>,
+    asset_server: Res<AssetServer>,
+    mut sound_controller: bevy_retrograde_audio::SoundController,
+    mut sounds: Local<bevy::utils::HashMap<String, bevy_retrograde_audio::Sound>>,
+) {
+    for event in events.iter() {
+        let path = match event
+            .sound
+            .as_deref()
+            .or_else(|| theme.sounds.get(event.kind))
+        {
+            Some(path) => path,
+            None => continue,
+        };
+
+        // Create each sound the first time it is played
+        let sound = *sounds
+            .entry(path.to_string())
+            .or_insert_with(|| sound_controller.create_sound(&asset_server.load(path)));
+
+        sound_controller.play_sound_on_bus(
+            sound,
+            bevy_retrograde_audio::UI_BUS,
+            Default::default(),
+        );
+    }
+}
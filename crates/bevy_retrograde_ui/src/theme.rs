@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use bevy_retrograde_core::prelude::Color;
+use raui::prelude::{
+    ImageBoxImage, TextBoxFont, ThemeProps, ThemedImageMaterial, ThemedTextMaterial,
+};
+
+use crate::{NinePatch, RetroUiSounds, UiNinePatches};
+
+pub(crate) fn add_theme_systems(app: &mut AppBuilder) {
+    app.init_resource::<RetroUiTheme>()
+        .add_system_to_stage(CoreStage::PostUpdate, update_theme_nine_patches.system());
+}
+
+/// This resource contains the style of the bundled [`widgets`][crate::widgets]
+///
+/// Changing the theme restyles every bundled widget, so that the look of a whole game UI can be set
+/// in one place. Props set on a widget take priority over the theme.
+///
+/// The theme images are registered as nine-patches in the [`UiNinePatches`] resource, and
+/// [`raui_theme_props`][Self::raui_theme_props] creates RAUI theme props from the theme for RAUI's
+/// own "paper" widgets.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::Color;
+/// # use bevy_retrograde_ui::*;
+/// fn setup(mut theme: ResMut<RetroUiTheme>) {
+///     theme.font = "cozette.bdf".into();
+///     theme.panel = Some(RetroUiThemeImage::new("ui/panel.png", NinePatch::uniform(4)));
+///     theme.palette.accent = Color::new(1.0, 0.8, 0.2, 1.0);
+///     theme.sounds.click = Some("ui/click.ogg".into());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetroUiTheme {
+    /// The asset path of the font of the widgets
+    pub font: String,
+    /// The width in pixels of each character of the font
+    ///
+    /// Widgets that position things between characters, such as the cursor of a text input, need
+    /// this, so the font should be monospace, which most retro fonts are.
+    pub glyph_width: f32,
+    /// The background image of panels
+    pub panel: Option<RetroUiThemeImage>,
+    /// The image of buttons that aren't pressed
+    pub button_up: Option<RetroUiThemeImage>,
+    /// The image of buttons while they are pressed
+    pub button_down: Option<RetroUiThemeImage>,
+    /// The colors of the widgets
+    pub palette: RetroUiPalette,
+    /// The amount that the padding inside of the widgets is multiplied by
+    pub padding_scale: f32,
+    /// The sounds that the widgets play
+    pub sounds: RetroUiSounds,
+}
+
+impl Default for RetroUiTheme {
+    fn default() -> Self {
+        Self {
+            font: String::new(),
+            glyph_width: 6.,
+            panel: None,
+            button_up: None,
+            button_down: None,
+            palette: Default::default(),
+            padding_scale: 1.,
+            sounds: Default::default(),
+        }
+    }
+}
+
+impl RetroUiTheme {
+    /// Get the amount of padding in pixels for the given base amount, scaled by the padding scale
+    pub fn padding(&self, base: f32) -> f32 {
+        (base * self.padding_scale).round()
+    }
+
+    /// Get the font of the theme for a RAUI text box
+    pub fn text_box_font(&self) -> TextBoxFont {
+        TextBoxFont {
+            name: self.font.clone(),
+            // Fonts in Bevy Retrograde don't have sizes
+            size: 1.,
+        }
+    }
+
+    /// Create RAUI theme props that style RAUI's "paper" widgets with this theme
+    ///
+    /// The panel image is the default content background, and the button images are the
+    /// `button-up` and `button-down` content background variants.
+    pub fn raui_theme_props(&self) -> ThemeProps {
+        let mut theme = ThemeProps::default();
+
+        for (variant, image) in [
+            ("", &self.panel),
+            ("button-up", &self.button_up),
+            ("button-down", &self.button_down),
+        ]
+        .iter()
+        {
+            if let Some(image) = image {
+                theme.content_backgrounds.insert(
+                    variant.to_string(),
+                    ThemedImageMaterial::Image(image.image_box_image()),
+                );
+            }
+        }
+
+        theme.text_variants.insert(
+            String::new(),
+            ThemedTextMaterial {
+                font: self.text_box_font(),
+                ..Default::default()
+            },
+        );
+
+        theme
+    }
+}
+
+/// A nine-patch image used by the [`RetroUiTheme`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetroUiThemeImage {
+    /// The asset path of the image
+    pub path: String,
+    /// The nine-patch borders of the image
+    pub nine_patch: NinePatch,
+}
+
+impl RetroUiThemeImage {
+    /// Create a theme image from its asset path and nine-patch borders
+    pub fn new<P: Into<String>>(path: P, nine_patch: NinePatch) -> Self {
+        Self {
+            path: path.into(),
+            nine_patch,
+        }
+    }
+
+    /// Get the image for a RAUI image box
+    ///
+    /// The image is stretched by the image box, and the theme's nine-patch borders keep the
+    /// borders of the image from stretching.
+    pub fn image_box_image(&self) -> ImageBoxImage {
+        ImageBoxImage {
+            id: self.path.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The colors of a [`RetroUiTheme`]
+#[derive(Debug, Clone)]
+pub struct RetroUiPalette {
+    /// The color of text
+    pub text: Color,
+    /// The color of less important text, such as placeholders
+    pub text_muted: Color,
+    /// The color of highlights, such as the frame around the selected widget
+    pub accent: Color,
+    /// The color behind selected text
+    pub selection: Color,
+    /// The color of backgrounds that don't have an image
+    pub background: Color,
+    /// The color of the empty part of scrollbars and progress bars
+    pub track: Color,
+    /// The color of the filled part of scrollbars and progress bars
+    pub fill: Color,
+}
+
+impl Default for RetroUiPalette {
+    fn default() -> Self {
+        Self {
+            text: Color::new(1., 1., 1., 1.),
+            text_muted: Color::new(0.5, 0.5, 0.5, 1.),
+            accent: Color::new(1., 1., 1., 1.),
+            selection: Color::new(0.2, 0.4, 0.8, 1.),
+            background: Color::new(0.1, 0.1, 0.1, 1.),
+            track: Color::new(0.1, 0.1, 0.1, 1.),
+            fill: Color::new(0.6, 0.6, 0.6, 1.),
+        }
+    }
+}
+
+/// Register the theme images as nine-patches when the theme changes
+fn update_theme_nine_patches(theme: Res<RetroUiTheme>, mut nine_patches: ResMut<UiNinePatches>) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    for image in [&theme.panel, &theme.button_up, &theme.button_down]
+        .iter()
+        .filter_map(|x| x.as_ref())
+    {
+        nine_patches.insert(image.path.clone(), image.nine_patch);
+    }
+}
@@ -0,0 +1,2297 @@
+//! Ready-made RAUI widgets for Bevy Retrograde
+//!
+//! These widgets are built for the keyboard, mouse, and gamepad input that Bevy Retrograde feeds
+//! to RAUI. Like the rest of your RAUI widgets, it's best to import them in a separate module so
+//! that the RAUI types don't get mixed up with the Bevy types.
+//!
+//! The widgets are styled by the [`RetroUiTheme`] resource, and anything left unset in their props
+//! comes from the theme.
+
+use bevy::prelude::World;
+use raui::prelude::*;
+
+use crate::{
+    lerp_vec2, modal::use_retro_modal, opacity::UiOpacities, start_ui_animation,
+    ui_animation_progress, ui_bound_value, use_retro_draggable, use_retro_drop_target,
+    use_ui_button_events, use_ui_sounds, Easing, RetroDraggableState, RetroUiSoundProps,
+    RetroUiTheme, RetroUiThemeImage, TextCompositionChange, TextSelectionChange, UiSound,
+    UiSoundKind, UiValue, UiValueChanged,
+};
+
+/// Get the UI theme from the Bevy world in the process context
+fn get_theme(ctx: &mut WidgetContext) -> RetroUiTheme {
+    ctx.process_context
+        .get_mut::<World>()
+        .and_then(|world| world.get_resource::<RetroUiTheme>())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Convert a Bevy Retrograde color to a RAUI color
+fn raui_color(color: bevy_retrograde_core::prelude::Color) -> Color {
+    Color {
+        r: color.r,
+        g: color.g,
+        b: color.b,
+        a: color.a,
+    }
+}
+
+/// The props of a [`retro_text_input`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroTextInputProps {
+    /// The text that the input starts out with
+    pub text: String,
+    /// The text shown while the input is empty
+    pub placeholder: String,
+    /// The font of the text, or [`None`] to use the theme font
+    pub font: Option<TextBoxFont>,
+    /// The color of the text and the cursor, or [`None`] to use the theme text color
+    pub color: Option<Color>,
+    /// The color of the placeholder text, or [`None`] to use the theme muted text color
+    pub placeholder_color: Option<Color>,
+    /// The color drawn behind selected text, or [`None`] to use the theme selection color
+    pub selection_color: Option<Color>,
+    /// The width in pixels of each character of the font, or [`None`] to use the theme glyph width
+    ///
+    /// The cursor and selection are positioned by counting characters, so the font should be
+    /// monospace, which most retro fonts are.
+    pub glyph_width: Option<Scalar>,
+    /// The maximum number of characters that can be entered, if any
+    pub max_length: Option<usize>,
+}
+
+/// The state of a [`retro_text_input`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroTextInputState {
+    /// The text that has been entered
+    pub text: String,
+    /// The position of the cursor in characters
+    pub cursor: usize,
+    /// The other end of the selection from the cursor, if any text is selected
+    pub selection_anchor: Option<usize>,
+    /// Whether or not the input has keyboard focus
+    pub focused: bool,
+    /// The text being composed with an input method, which is shown at the cursor until it is
+    /// committed
+    #[serde(default)]
+    pub composition: String,
+}
+
+impl RetroTextInputState {
+    /// Get the range of selected characters, if any text is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.selection_anchor?;
+        if anchor == self.cursor {
+            None
+        } else {
+            Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+        }
+    }
+
+    /// Get the selected text
+    pub fn selected_text(&self) -> &str {
+        match self.selection() {
+            Some((start, end)) => {
+                &self.text[byte_index(&self.text, start)..byte_index(&self.text, end)]
+            }
+            None => "",
+        }
+    }
+
+    /// Delete the selected text, returning whether or not any text was selected
+    fn delete_selection(&mut self) -> bool {
+        if let Some((start, end)) = self.selection() {
+            let range = byte_index(&self.text, start)..byte_index(&self.text, end);
+            self.text.replace_range(range, "");
+            self.cursor = start;
+            self.selection_anchor = None;
+            true
+        } else {
+            self.selection_anchor = None;
+            false
+        }
+    }
+
+    /// Move the cursor, extending the selection if `select` is `true`
+    fn move_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = position;
+    }
+}
+
+/// Get the byte index of a character position in a string
+fn byte_index(text: &str, position: usize) -> usize {
+    text.char_indices()
+        .nth(position)
+        .map(|(index, _)| index)
+        .unwrap_or_else(|| text.len())
+}
+
+/// Add these props to a [`retro_text_input`] to send [`RetroTextInputMessage`]s to a widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroTextInputNotifyProps(
+    #[serde(default)]
+    #[serde(skip_serializing_if = "WidgetIdOrRef::is_none")]
+    pub WidgetIdOrRef,
+);
+
+/// The message sent by a [`retro_text_input`] when its text changes or it is submitted
+#[derive(MessageData, Debug, Clone)]
+pub struct RetroTextInputMessage {
+    /// The ID of the text input
+    pub sender: WidgetId,
+    /// The new state of the text input
+    pub state: RetroTextInputState,
+    /// Whether or not the input was submitted by pressing enter
+    pub submitted: bool,
+}
+
+/// A message that edits the text of a [`retro_text_input`] even when it isn't focused, such as the
+/// keys pressed on a [`retro_virtual_keyboard`]
+#[derive(MessageData, Debug, Clone)]
+pub struct RetroTextInputEdit(pub NavTextChange);
+
+/// Get the text change of a message to a text input, if it is one that the input should apply
+fn text_change(msg: &dyn std::any::Any, focused: bool) -> Option<&NavTextChange> {
+    if let Some(NavSignal::TextChange(change)) = msg.downcast_ref::<NavSignal>() {
+        // Text typed on the keyboard only goes to the input while it is focused
+        focused.then(|| change)
+    } else {
+        msg.downcast_ref::<RetroTextInputEdit>().map(|edit| &edit.0)
+    }
+}
+
+/// Hook that edits the [`RetroTextInputState`] of a text input in response to the text changes
+/// sent by the Bevy Retrograde interactions engine and [`RetroTextInputEdit`] messages
+#[pre_hooks(use_nav_text_input, use_ui_sounds)]
+pub fn use_retro_text_input(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<RetroTextInputProps>();
+        let cursor = props.text.chars().count();
+        let _ = context.state.write(RetroTextInputState {
+            text: props.text,
+            cursor,
+            ..Default::default()
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<RetroTextInputProps>();
+        let mut state = context
+            .state
+            .read_cloned_or_default::<RetroTextInputState>();
+        let previous_text = state.text.clone();
+        let mut changed = false;
+        let mut submitted = false;
+
+        for msg in context.messenger.messages {
+            // Focus the input when it is clicked
+            if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                if msg.trigger_start() && !state.focused {
+                    context
+                        .signals
+                        .write(NavSignal::FocusTextInput(context.id.to_owned().into()));
+                }
+            } else if let Some(NavSignal::FocusTextInput(idref)) =
+                msg.as_any().downcast_ref::<NavSignal>()
+            {
+                if idref.is_some() && !state.focused {
+                    if let Some(sound) =
+                        UiSound::for_widget(context.id, context.props, UiSoundKind::Focus)
+                    {
+                        context.signals.write(sound);
+                    }
+                }
+                state.focused = idref.is_some();
+                state.selection_anchor = None;
+                state.composition.clear();
+                changed = true;
+            } else if let Some(text_change) = text_change(msg.as_any(), state.focused) {
+                let len = state.text.chars().count();
+
+                match text_change {
+                    NavTextChange::InsertCharacter(c) => {
+                        state.delete_selection();
+                        if props
+                            .max_length
+                            .map(|max| state.text.chars().count() < max)
+                            .unwrap_or(true)
+                        {
+                            let index = byte_index(&state.text, state.cursor);
+                            state.text.insert(index, *c);
+                            state.cursor += 1;
+                        } else if let Some(sound) =
+                            UiSound::for_widget(context.id, context.props, UiSoundKind::Invalid)
+                        {
+                            context.signals.write(sound);
+                        }
+                    }
+                    NavTextChange::MoveCursorLeft => match state.selection() {
+                        Some((start, _)) => state.move_cursor(start, false),
+                        None => state.move_cursor(state.cursor.saturating_sub(1), false),
+                    },
+                    NavTextChange::MoveCursorRight => match state.selection() {
+                        Some((_, end)) => state.move_cursor(end, false),
+                        None => state.move_cursor((state.cursor + 1).min(len), false),
+                    },
+                    NavTextChange::MoveCursorStart => state.move_cursor(0, false),
+                    NavTextChange::MoveCursorEnd => state.move_cursor(len, false),
+                    NavTextChange::DeleteLeft => {
+                        if !state.delete_selection() && state.cursor > 0 {
+                            state.cursor -= 1;
+                            state.text.remove(byte_index(&state.text, state.cursor));
+                        }
+                    }
+                    NavTextChange::DeleteRight => {
+                        if !state.delete_selection() && state.cursor < len {
+                            state.text.remove(byte_index(&state.text, state.cursor));
+                        }
+                    }
+                    NavTextChange::NewLine => submitted = true,
+                }
+                changed = true;
+            } else if let Some(TextCompositionChange(composition)) =
+                msg.as_any().downcast_ref::<TextCompositionChange>()
+            {
+                if state.focused {
+                    state.composition = composition.clone();
+                    changed = true;
+                }
+            } else if let Some(selection_change) =
+                msg.as_any().downcast_ref::<TextSelectionChange>()
+            {
+                if !state.focused {
+                    continue;
+                }
+                let len = state.text.chars().count();
+
+                match selection_change {
+                    TextSelectionChange::SelectLeft => {
+                        state.move_cursor(state.cursor.saturating_sub(1), true)
+                    }
+                    TextSelectionChange::SelectRight => {
+                        state.move_cursor((state.cursor + 1).min(len), true)
+                    }
+                    TextSelectionChange::SelectStart => state.move_cursor(0, true),
+                    TextSelectionChange::SelectEnd => state.move_cursor(len, true),
+                    TextSelectionChange::SelectAll => {
+                        state.selection_anchor = Some(0);
+                        state.cursor = len;
+                    }
+                }
+                changed = true;
+            }
+        }
+
+        if state.text != previous_text {
+            context.signals.write(UiValueChanged {
+                widget: context.id.to_owned(),
+                value: UiValue::Text(state.text.clone()),
+            });
+        }
+
+        if changed {
+            if let Ok(notify) = context.props.read::<RetroTextInputNotifyProps>() {
+                if let Some(to) = notify.0.read() {
+                    context.messenger.write(
+                        to,
+                        RetroTextInputMessage {
+                            sender: context.id.to_owned(),
+                            state: state.clone(),
+                            submitted,
+                        },
+                    );
+                }
+            }
+            let _ = context.state.write(state);
+        }
+    });
+}
+
+/// A single-line text input that can be clicked or navigated to and typed into
+///
+/// The text is edited with the keyboard: the arrow keys, home, and end move the cursor, holding
+/// shift while moving the cursor selects text, ctrl + A selects all of the text, backspace and
+/// delete remove text, enter submits the input, and escape unfocuses it. Text typed with an input
+/// method, such as Japanese, is shown at the cursor while it is composed. Add
+/// [`RetroTextInputNotifyProps`] to get a [`RetroTextInputMessage`] whenever the text changes, or
+/// read the [`UiValueChanged`] Bevy events that it sends.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn name_input(ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_text_input)
+///         .with_props(NavItemActive)
+///         .with_props(RetroTextInputNotifyProps(ctx.id.to_owned().into()))
+///         .with_props(RetroTextInputProps {
+///             placeholder: "Your name".into(),
+///             max_length: Some(12),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_text_input)]
+pub fn retro_text_input(mut ctx: WidgetContext) -> WidgetNode {
+    let props = ctx.props.read_cloned_or_default::<RetroTextInputProps>();
+    let state = ctx.state.read_cloned_or_default::<RetroTextInputState>();
+    let theme = get_theme(&mut ctx);
+    let color = props
+        .color
+        .unwrap_or_else(|| raui_color(theme.palette.text));
+
+    // Blink the cursor twice a second
+    let cursor_visible = state.focused
+        && ctx
+            .process_context
+            .get_mut::<World>()
+            .and_then(|world| world.get_resource::<bevy::core::Time>())
+            .map(|time| time.seconds_since_startup().fract() < 0.5)
+            .unwrap_or(true);
+
+    // Create a box of color that spans the height of the input from the given character
+    let glyph_width = props.glyph_width.unwrap_or(theme.glyph_width);
+    let color_box = |position: usize, width: Scalar, color: Color| {
+        let left = position as Scalar * glyph_width;
+        make_widget!(image_box)
+            .with_props(ImageBoxProps {
+                material: ImageBoxMaterial::Color(ImageBoxColor {
+                    color,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .with_props(ContentBoxItemLayout {
+                anchors: Rect {
+                    left: 0.,
+                    right: 0.,
+                    top: 0.,
+                    bottom: 1.,
+                },
+                margin: Rect {
+                    left,
+                    right: -(left + width),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+    };
+
+    let mut content = make_widget!(content_box);
+    let selection_color = props
+        .selection_color
+        .unwrap_or_else(|| raui_color(theme.palette.selection));
+
+    if let Some((start, end)) = state.selection().filter(|_| state.focused) {
+        content = content.listed_slot(color_box(
+            start,
+            (end - start) as Scalar * glyph_width,
+            selection_color,
+        ));
+    }
+
+    // Show the text being composed at the cursor, highlighted like a selection
+    let composition_length = if state.focused {
+        state.composition.chars().count()
+    } else {
+        0
+    };
+    let mut text = state.text;
+    if composition_length > 0 {
+        content = content.listed_slot(color_box(
+            state.cursor,
+            composition_length as Scalar * glyph_width,
+            selection_color,
+        ));
+        text.insert_str(byte_index(&text, state.cursor), &state.composition);
+    }
+
+    let (text, text_color) = if text.is_empty() {
+        (
+            props.placeholder,
+            props
+                .placeholder_color
+                .unwrap_or_else(|| raui_color(theme.palette.text_muted)),
+        )
+    } else {
+        (text, color)
+    };
+    content = content.listed_slot(make_widget!(text_box).with_props(TextBoxProps {
+        text,
+        font: props.font.unwrap_or_else(|| theme.text_box_font()),
+        color: text_color,
+        width: TextBoxSizeValue::Fill,
+        height: TextBoxSizeValue::Fill,
+        horizontal_align: TextBoxHorizontalAlign::Left,
+        vertical_align: TextBoxVerticalAlign::Middle,
+        ..Default::default()
+    }));
+
+    if cursor_visible {
+        content = content.listed_slot(color_box(state.cursor + composition_length, 1., color));
+    }
+
+    make_widget!(button)
+        .merge_props(ctx.props.clone())
+        .with_props(ButtonNotifyProps(ctx.id.to_owned().into()))
+        .named_slot("content", content)
+        .into()
+}
+
+/// The props of a [`retro_scroll_box`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroScrollBoxProps {
+    /// The thickness of the scrollbars in pixels, or [`None`] to scale the default thickness by the
+    /// theme padding scale
+    pub scrollbar_size: Option<Scalar>,
+    /// The color of the scrollbar tracks, or [`None`] to use the theme track color
+    pub track_color: Option<Color>,
+    /// The color of the scrollbar thumbs, or [`None`] to use the theme fill color
+    pub thumb_color: Option<Color>,
+}
+
+/// A box that scrolls its `content` slot with the mouse wheel, touch drags, and navigation
+///
+/// The content is clipped to the box, and thin scrollbars that suit low-resolution UIs are drawn
+/// along its right and bottom sides. The scrollbars can also be dragged with the mouse.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn credits(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_scroll_box)
+///         .named_slot(
+///             "content",
+///             make_widget!(text_box).with_props(TextBoxProps {
+///                 text: "A very long list of names".into(),
+///                 height: TextBoxSizeValue::Exact(400.),
+///                 ..Default::default()
+///             }),
+///         )
+///         .into()
+/// }
+/// ```
+pub fn retro_scroll_box(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        key,
+        props,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    let scroll_box_props = props.read_cloned_or_default::<RetroScrollBoxProps>();
+    let color_material = |color| {
+        ImageBoxMaterial::Color(ImageBoxColor {
+            color,
+            ..Default::default()
+        })
+    };
+
+    // Clip the content so that it doesn't draw outside of the box as it scrolls
+    make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .with_props(ContentBoxProps {
+            clipping: true,
+            ..Default::default()
+        })
+        .listed_slot(
+            make_widget!(nav_scroll_box)
+                .key("scroll-box")
+                .merge_props(props.clone())
+                .named_slot("content", content)
+                .named_slot(
+                    "scrollbars",
+                    make_widget!(nav_scroll_box_side_scrollbars)
+                        .key("scrollbars")
+                        .with_props(SideScrollbarsProps {
+                            size: scroll_box_props
+                                .scrollbar_size
+                                .unwrap_or_else(|| theme.padding(3.)),
+                            back_material: Some(color_material(
+                                scroll_box_props
+                                    .track_color
+                                    .unwrap_or_else(|| raui_color(theme.palette.track)),
+                            )),
+                            front_material: color_material(
+                                scroll_box_props
+                                    .thumb_color
+                                    .unwrap_or_else(|| raui_color(theme.palette.fill)),
+                            ),
+                        }),
+                ),
+        )
+        .into()
+}
+
+/// Add this to a [`retro_focus_frame`] to select it as soon as it is created, so that a menu can be
+/// navigated with the keyboard or a gamepad without having to click it first
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroInitialFocus;
+
+/// The props of a [`retro_focus_frame`] widget
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroFocusFrameProps {
+    /// The color of the frame, or [`None`] to use the theme accent color
+    pub color: Option<Color>,
+    /// The thickness of the frame in pixels, or [`None`] to scale the default thickness by the
+    /// theme padding scale
+    pub thickness: Option<Scalar>,
+}
+
+/// Hook that selects a widget as soon as it is created if it has the [`RetroInitialFocus`] props
+fn use_retro_initial_focus(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        if context.props.has::<RetroInitialFocus>() {
+            context
+                .signals
+                .write(NavSignal::Select(context.id.to_owned().into()));
+        }
+    });
+}
+
+/// Create a box of the given material along each edge of a content box, to draw a frame around it
+fn outline(material: ImageBoxMaterial, thickness: Scalar) -> Vec<WidgetComponent> {
+    [
+        (
+            Rect {
+                left: 0.,
+                right: 1.,
+                top: 0.,
+                bottom: 0.,
+            },
+            Rect {
+                bottom: -thickness,
+                ..Default::default()
+            },
+        ),
+        (
+            Rect {
+                left: 0.,
+                right: 1.,
+                top: 1.,
+                bottom: 1.,
+            },
+            Rect {
+                top: -thickness,
+                ..Default::default()
+            },
+        ),
+        (
+            Rect {
+                left: 0.,
+                right: 0.,
+                top: 0.,
+                bottom: 1.,
+            },
+            Rect {
+                right: -thickness,
+                ..Default::default()
+            },
+        ),
+        (
+            Rect {
+                left: 1.,
+                right: 1.,
+                top: 0.,
+                bottom: 1.,
+            },
+            Rect {
+                left: -thickness,
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .map(|(anchors, margin)| {
+        make_widget!(image_box)
+            .with_props(ImageBoxProps {
+                material: material.clone(),
+                ..Default::default()
+            })
+            .with_props(ContentBoxItemLayout {
+                anchors: *anchors,
+                margin: *margin,
+                ..Default::default()
+            })
+    })
+    .collect()
+}
+
+/// Create the frame that a button draws around itself while it is selected
+fn selection_outline(
+    theme: &RetroUiTheme,
+    color: Option<Color>,
+    thickness: Option<Scalar>,
+) -> Vec<WidgetComponent> {
+    outline(
+        ImageBoxMaterial::Color(ImageBoxColor {
+            color: color.unwrap_or_else(|| raui_color(theme.palette.accent)),
+            ..Default::default()
+        }),
+        thickness.unwrap_or_else(|| theme.padding(1.).max(1.)),
+    )
+}
+
+/// A button that draws a frame around its `content` slot while it is selected
+///
+/// Widgets are selected by hovering over them with the mouse, or by navigating to them with the
+/// keyboard or a gamepad, so the frame shows players where they are in a menu. Like other buttons,
+/// the frame must have the `NavItemActive` props to be navigable, and it sends
+/// `ButtonNotifyMessage`s to the widget in its `ButtonNotifyProps`. It also sends
+/// [`UiButtonClicked`][crate::UiButtonClicked] Bevy events when it is clicked.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn menu_item(ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_focus_frame)
+///         .with_props(NavItemActive)
+///         .with_props(RetroInitialFocus)
+///         .with_props(ButtonNotifyProps(ctx.id.to_owned().into()))
+///         .named_slot(
+///             "content",
+///             make_widget!(text_box).with_props(TextBoxProps {
+///                 text: "Start".into(),
+///                 ..Default::default()
+///             }),
+///         )
+///         .into()
+/// }
+/// ```
+#[pre_hooks(
+    use_button,
+    use_ui_button_events,
+    use_ui_sounds,
+    use_retro_initial_focus
+)]
+pub fn retro_focus_frame(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        key,
+        props,
+        state,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    let ButtonProps { selected, .. } = state.read_cloned_or_default();
+    let frame_props = props.read_cloned_or_default::<RetroFocusFrameProps>();
+
+    let mut frame = make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(content);
+
+    if selected {
+        for edge in selection_outline(&theme, frame_props.color, frame_props.thickness) {
+            frame = frame.listed_slot(edge);
+        }
+    }
+
+    frame.into()
+}
+
+/// Create an image box with a theme image, or with a plain color if the theme doesn't have the
+/// image
+fn theme_image_box(image: &Option<RetroUiThemeImage>, color: Color) -> WidgetComponent {
+    make_widget!(image_box).with_props(ImageBoxProps {
+        material: match image {
+            Some(image) => ImageBoxMaterial::Image(image.image_box_image()),
+            None => ImageBoxMaterial::Color(ImageBoxColor {
+                color,
+                ..Default::default()
+            }),
+        },
+        ..Default::default()
+    })
+}
+
+/// Create a single line of text in the theme font
+fn theme_label(
+    theme: &RetroUiTheme,
+    text: String,
+    horizontal_align: TextBoxHorizontalAlign,
+) -> WidgetComponent {
+    make_widget!(text_box).with_props(TextBoxProps {
+        text,
+        font: theme.text_box_font(),
+        color: raui_color(theme.palette.text),
+        horizontal_align,
+        vertical_align: TextBoxVerticalAlign::Middle,
+        ..Default::default()
+    })
+}
+
+/// Get the index in a key such as `tab-2`, if the key has the given prefix
+fn indexed_key(key: &str, prefix: &str) -> Option<usize> {
+    key.strip_prefix(prefix)?.strip_prefix('-')?.parse().ok()
+}
+
+/// Create a bar that is filled from left to right by the given fraction, with the fill inset from
+/// the edges of the track
+fn fill_bar(track: Color, fill: Color, fraction: Scalar, inset: Scalar) -> WidgetComponent {
+    let fraction = fraction.max(0.).min(1.);
+    let mut bar = make_widget!(content_box).listed_slot(theme_image_box(&None, track));
+
+    // An empty fill would be inverted by its inset
+    if fraction > 0. {
+        bar = bar.listed_slot(
+            theme_image_box(&None, fill).with_props(ContentBoxItemLayout {
+                anchors: Rect {
+                    left: 0.,
+                    right: fraction,
+                    top: 0.,
+                    bottom: 1.,
+                },
+                margin: Rect {
+                    left: inset,
+                    right: inset,
+                    top: inset,
+                    bottom: inset,
+                },
+                ..Default::default()
+            }),
+        );
+    }
+
+    bar
+}
+
+/// Create a button inside of one of the composite widgets, which notifies the composite widget
+///
+/// The button always takes part in navigation, and it shares the sounds of the composite widget.
+fn inner_button(
+    widget: WidgetComponent,
+    key: String,
+    parent: &WidgetId,
+    props: &Props,
+) -> WidgetComponent {
+    widget
+        .key(key)
+        .with_props(NavItemActive)
+        .with_props(ButtonNotifyProps(parent.to_owned().into()))
+        .with_props(props.read_cloned_or_default::<RetroUiSoundProps>())
+}
+
+/// The props of a [`retro_button`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroButtonProps {
+    /// The label of the button, which is shown when the button doesn't have a `content` slot
+    pub text: String,
+    /// Whether or not the button is drawn pressed even when it isn't, such as the selected tab of a
+    /// [`retro_tab_bar`]
+    pub down: bool,
+}
+
+/// A button drawn with the `button_up` and `button_down` images of the theme
+///
+/// The button shows the `text` of its [`RetroButtonProps`], or its `content` slot if it has one,
+/// and draws a frame around itself while it is selected, like a [`retro_focus_frame`]. Like other
+/// buttons, it must have the `NavItemActive` props to be pressed, and it sends
+/// [`UiButtonClicked`][crate::UiButtonClicked] Bevy events when it is clicked.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn start_button(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_button)
+///         .key("start-button")
+///         .with_props(NavItemActive)
+///         .with_props(RetroInitialFocus)
+///         .with_props(RetroButtonProps {
+///             text: "Start".into(),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(
+    use_button,
+    use_ui_button_events,
+    use_ui_sounds,
+    use_retro_initial_focus
+)]
+pub fn retro_button(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        key,
+        props,
+        state,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    let ButtonProps {
+        selected, trigger, ..
+    } = state.read_cloned_or_default();
+    let button_props = props.read_cloned_or_default::<RetroButtonProps>();
+    let down = trigger || button_props.down;
+
+    let (image, color) = if down {
+        (&theme.button_down, theme.palette.track)
+    } else {
+        (&theme.button_up, theme.palette.background)
+    };
+    let content = if content.is_none() {
+        theme_label(&theme, button_props.text, TextBoxHorizontalAlign::Center).into()
+    } else {
+        content
+    };
+    let padding = theme.padding(2.);
+
+    let mut button = make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(theme_image_box(image, raui_color(color)))
+        .listed_slot(content.with_props(ContentBoxItemLayout {
+            margin: Rect {
+                left: padding,
+                right: padding,
+                top: padding,
+                bottom: padding,
+            },
+            ..Default::default()
+        }));
+
+    if selected {
+        for edge in selection_outline(&theme, None, None) {
+            button = button.listed_slot(edge);
+        }
+    }
+
+    button.into()
+}
+
+/// The props of a [`retro_toggle`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroToggleProps {
+    /// Whether or not the toggle starts out checked
+    pub checked: bool,
+    /// The label next to the check box
+    pub text: String,
+}
+
+/// The state of a [`retro_toggle`] widget
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroToggleState {
+    /// Whether or not the toggle is checked
+    pub checked: bool,
+}
+
+/// Hook that flips the [`RetroToggleState`] of a [`retro_toggle`] when it is clicked
+fn use_retro_toggle(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context.props.read_cloned_or_default::<RetroToggleProps>();
+        let _ = context.state.write(RetroToggleState {
+            checked: props.checked,
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let mut state = context.state.read_cloned_or_default::<RetroToggleState>();
+
+        for msg in context.messenger.messages {
+            if let Some(msg) = msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                if msg.trigger_stop() {
+                    state.checked = !state.checked;
+                    context.signals.write(UiValueChanged {
+                        widget: context.id.to_owned(),
+                        value: UiValue::Bool(state.checked),
+                    });
+                    let _ = context.state.write(state);
+                }
+            }
+        }
+    });
+}
+
+/// A check box with a label that is switched on and off by clicking it
+///
+/// The toggle sends a [`UiValueChanged`] Bevy event with a [`UiValue::Bool`] when it is switched.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn fullscreen_toggle(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_toggle)
+///         .key("fullscreen")
+///         .with_props(RetroToggleProps {
+///             checked: true,
+///             text: "Fullscreen".into(),
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_toggle)]
+pub fn retro_toggle(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        id,
+        key,
+        props,
+        state,
+        ..
+    } = ctx;
+    let toggle_props = props.read_cloned_or_default::<RetroToggleProps>();
+    let RetroToggleState { checked } = state.read_cloned_or_default();
+
+    let size = theme.glyph_width;
+    let inset = theme.padding(1.).max(1.);
+
+    // Center a square check box vertically
+    let mut check_box = make_widget!(content_box)
+        .with_props(FlexBoxItemLayout {
+            basis: Some(size),
+            grow: 0.,
+            shrink: 0.,
+            margin: Rect {
+                right: theme.padding(2.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .listed_slot(
+            theme_image_box(&None, raui_color(theme.palette.track)).with_props(
+                ContentBoxItemLayout {
+                    anchors: Rect {
+                        left: 0.,
+                        right: 1.,
+                        top: 0.5,
+                        bottom: 0.5,
+                    },
+                    margin: Rect {
+                        top: -size / 2.,
+                        bottom: -size / 2.,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ),
+        );
+    if checked {
+        check_box = check_box.listed_slot(
+            theme_image_box(&None, raui_color(theme.palette.fill)).with_props(
+                ContentBoxItemLayout {
+                    anchors: Rect {
+                        left: 0.,
+                        right: 1.,
+                        top: 0.5,
+                        bottom: 0.5,
+                    },
+                    margin: Rect {
+                        left: inset,
+                        right: inset,
+                        top: inset - size / 2.,
+                        bottom: inset - size / 2.,
+                    },
+                    ..Default::default()
+                },
+            ),
+        );
+    }
+
+    inner_button(
+        make_widget!(retro_focus_frame).merge_props(props.clone()),
+        key.to_owned(),
+        id,
+        props,
+    )
+    .named_slot(
+        "content",
+        make_widget!(horizontal_box)
+            .listed_slot(check_box)
+            .listed_slot(theme_label(
+                &theme,
+                toggle_props.text,
+                TextBoxHorizontalAlign::Left,
+            )),
+    )
+    .into()
+}
+
+/// The props of a [`retro_slider`] widget
+#[derive(PropsData, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroSliderProps {
+    /// The value that the slider starts out with
+    pub value: Scalar,
+    /// The smallest value of the slider
+    pub min: Scalar,
+    /// The largest value of the slider
+    pub max: Scalar,
+    /// How much the value changes with each press of the slider's buttons
+    pub step: Scalar,
+}
+
+impl Default for RetroSliderProps {
+    fn default() -> Self {
+        Self {
+            value: 0.,
+            min: 0.,
+            max: 1.,
+            step: 0.1,
+        }
+    }
+}
+
+/// The state of a [`retro_slider`] widget
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroSliderState {
+    /// The value of the slider
+    pub value: Scalar,
+}
+
+/// Hook that steps the [`RetroSliderState`] of a [`retro_slider`] when its buttons are clicked
+fn use_retro_slider(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context.props.read_cloned_or_default::<RetroSliderProps>();
+        let _ = context.state.write(RetroSliderState {
+            value: props.value.max(props.min).min(props.max),
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let props = context.props.read_cloned_or_default::<RetroSliderProps>();
+        let mut state = context.state.read_cloned_or_default::<RetroSliderState>();
+
+        for msg in context.messenger.messages {
+            let msg = match msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                Some(msg) if msg.trigger_stop() => msg,
+                _ => continue,
+            };
+            let step = match msg.sender.key() {
+                "decrease" => -props.step,
+                "increase" => props.step,
+                _ => continue,
+            };
+
+            let value = (state.value + step).max(props.min).min(props.max);
+            if value != state.value {
+                state.value = value;
+                context.signals.write(UiValueChanged {
+                    widget: context.id.to_owned(),
+                    value: UiValue::Number(value),
+                });
+                let _ = context.state.write(state);
+            } else if let Some(sound) =
+                UiSound::for_widget(context.id, context.props, UiSoundKind::Invalid)
+            {
+                context.signals.write(sound);
+            }
+        }
+    });
+}
+
+/// A bar with buttons on either side that step its value down and up
+///
+/// Stepping with buttons, instead of dragging a handle, lets the slider be used the same way with
+/// the keyboard, a gamepad, the mouse, or a touch screen. The slider sends a [`UiValueChanged`]
+/// Bevy event with a [`UiValue::Number`] when its value changes, and plays the
+/// [`Invalid`][UiSoundKind::Invalid] sound when it is already at the end that it is stepped
+/// towards.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn volume_slider(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_slider)
+///         .key("volume")
+///         .with_props(RetroSliderProps {
+///             value: 0.8,
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_slider)]
+pub fn retro_slider(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        id,
+        key,
+        props,
+        state,
+        ..
+    } = ctx;
+    let slider_props = props.read_cloned_or_default::<RetroSliderProps>();
+    let RetroSliderState { value } = state.read_cloned_or_default();
+
+    let range = slider_props.max - slider_props.min;
+    let fraction = if range > 0. {
+        (value - slider_props.min) / range
+    } else {
+        1.
+    };
+    let button_size = theme.glyph_width + theme.padding(4.);
+    let button_layout = || FlexBoxItemLayout {
+        basis: Some(button_size),
+        grow: 0.,
+        shrink: 0.,
+        ..Default::default()
+    };
+    let inset = theme.padding(1.).max(1.);
+
+    let mut decrease = inner_button(make_widget!(retro_button), "decrease".into(), id, props)
+        .with_props(button_layout())
+        .with_props(RetroButtonProps {
+            text: "<".into(),
+            ..Default::default()
+        });
+    if props.has::<RetroInitialFocus>() {
+        decrease = decrease.with_props(RetroInitialFocus);
+    }
+
+    make_widget!(horizontal_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(decrease)
+        .listed_slot(
+            fill_bar(
+                raui_color(theme.palette.track),
+                raui_color(theme.palette.fill),
+                fraction,
+                inset,
+            )
+            .key("track"),
+        )
+        .listed_slot(
+            inner_button(make_widget!(retro_button), "increase".into(), id, props)
+                .with_props(button_layout())
+                .with_props(RetroButtonProps {
+                    text: ">".into(),
+                    ..Default::default()
+                }),
+        )
+        .into()
+}
+
+/// The props of a [`retro_dropdown`] widget
+#[derive(PropsData, Debug, Clone, Serialize, Deserialize)]
+pub struct RetroDropdownProps {
+    /// The options that can be chosen
+    pub options: Vec<String>,
+    /// The index of the option that is chosen to start out with
+    pub selected: usize,
+    /// The height in pixels of each option in the list of options
+    pub option_height: Scalar,
+}
+
+impl Default for RetroDropdownProps {
+    fn default() -> Self {
+        Self {
+            options: Vec::new(),
+            selected: 0,
+            option_height: 12.,
+        }
+    }
+}
+
+/// The state of a [`retro_dropdown`] widget
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroDropdownState {
+    /// The index of the chosen option
+    pub selected: usize,
+    /// Whether or not the list of options is open
+    pub open: bool,
+}
+
+/// Hook that opens the list of options of a [`retro_dropdown`] and chooses the option that is
+/// clicked
+fn use_retro_dropdown(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context.props.read_cloned_or_default::<RetroDropdownProps>();
+        let _ = context.state.write(RetroDropdownState {
+            selected: props.selected,
+            open: false,
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let mut state = context.state.read_cloned_or_default::<RetroDropdownState>();
+        let mut changed = false;
+
+        for msg in context.messenger.messages {
+            let msg = match msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                Some(msg) if msg.trigger_stop() => msg,
+                _ => continue,
+            };
+
+            if msg.sender.key() == "button" {
+                state.open = !state.open;
+            } else if let Some(index) = indexed_key(msg.sender.key(), "option") {
+                state.open = false;
+                if index != state.selected {
+                    state.selected = index;
+                    context.signals.write(UiValueChanged {
+                        widget: context.id.to_owned(),
+                        value: UiValue::Number(index as f32),
+                    });
+                }
+            } else {
+                continue;
+            }
+            changed = true;
+        }
+
+        if changed {
+            let _ = context.state.write(state);
+        }
+    });
+}
+
+/// A button that opens a list of options to choose one from
+///
+/// The dropdown sends a [`UiValueChanged`] Bevy event with the index of the chosen option as a
+/// [`UiValue::Number`]. The list of options opens below the dropdown and is drawn over the widgets
+/// before it, so the dropdown should come after the widgets that it may cover.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn difficulty_dropdown(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_dropdown)
+///         .key("difficulty")
+///         .with_props(RetroDropdownProps {
+///             options: vec!["Easy".into(), "Normal".into(), "Hard".into()],
+///             selected: 1,
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_dropdown)]
+pub fn retro_dropdown(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext {
+        id,
+        key,
+        props,
+        state,
+        ..
+    } = ctx;
+    let dropdown_props = props.read_cloned_or_default::<RetroDropdownProps>();
+    let RetroDropdownState { selected, open } = state.read_cloned_or_default();
+
+    let mut button = inner_button(make_widget!(retro_button), "button".into(), id, props)
+        .with_props(RetroButtonProps {
+            text: dropdown_props
+                .options
+                .get(selected)
+                .cloned()
+                .unwrap_or_default(),
+            down: open,
+        });
+    if props.has::<RetroInitialFocus>() {
+        button = button.with_props(RetroInitialFocus);
+    }
+
+    let mut dropdown = make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(button);
+
+    if open {
+        let mut list = make_widget!(vertical_box);
+        for (i, option) in dropdown_props.options.into_iter().enumerate() {
+            let mut option = inner_button(
+                make_widget!(retro_focus_frame),
+                format!("option-{}", i),
+                id,
+                props,
+            )
+            .named_slot(
+                "content",
+                theme_label(&theme, option, TextBoxHorizontalAlign::Left).with_props(
+                    ContentBoxItemLayout {
+                        margin: Rect {
+                            left: theme.padding(2.),
+                            right: theme.padding(2.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                ),
+            );
+            // Move the selection into the list as it opens
+            if i == selected {
+                option = option.with_props(RetroInitialFocus);
+            }
+            list = list.listed_slot(option);
+        }
+
+        let count = dropdown_props.options.len() as Scalar;
+        dropdown = dropdown.listed_slot(
+            make_widget!(content_box)
+                .key("options")
+                .with_props(ContentBoxItemLayout {
+                    anchors: Rect {
+                        left: 0.,
+                        right: 1.,
+                        top: 1.,
+                        bottom: 1.,
+                    },
+                    margin: Rect {
+                        bottom: -(dropdown_props.option_height * count),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .listed_slot(theme_image_box(
+                    &theme.panel,
+                    raui_color(theme.palette.background),
+                ))
+                .listed_slot(list),
+        );
+    }
+
+    dropdown.into()
+}
+
+/// The props of a [`retro_tab_bar`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroTabBarProps {
+    /// The labels of the tabs
+    pub tabs: Vec<String>,
+    /// The index of the tab that is selected to start out with
+    pub selected: usize,
+}
+
+/// The state of a [`retro_tab_bar`] widget
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroTabBarState {
+    /// The index of the selected tab
+    pub selected: usize,
+}
+
+/// Hook that selects the tab of a [`retro_tab_bar`] that is clicked
+fn use_retro_tab_bar(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context.props.read_cloned_or_default::<RetroTabBarProps>();
+        let _ = context.state.write(RetroTabBarState {
+            selected: props.selected,
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let mut state = context.state.read_cloned_or_default::<RetroTabBarState>();
+
+        for msg in context.messenger.messages {
+            let msg = match msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                Some(msg) if msg.trigger_stop() => msg,
+                _ => continue,
+            };
+
+            if let Some(index) = indexed_key(msg.sender.key(), "tab") {
+                if index != state.selected {
+                    context.signals.write(UiValueChanged {
+                        widget: context.id.to_owned(),
+                        value: UiValue::Number(index as f32),
+                    });
+                    state.selected = index;
+                    let _ = context.state.write(state);
+                }
+            }
+        }
+    });
+}
+
+/// A row of buttons, one for each tab, of which the selected one is drawn pressed
+///
+/// The tab bar sends a [`UiValueChanged`] Bevy event with the index of the selected tab as a
+/// [`UiValue::Number`] when another tab is selected.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn settings_tabs(_ctx: WidgetContext) -> WidgetNode {
+///     make_widget!(retro_tab_bar)
+///         .key("settings-tabs")
+///         .with_props(RetroTabBarProps {
+///             tabs: vec!["Video".into(), "Audio".into(), "Controls".into()],
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_tab_bar)]
+pub fn retro_tab_bar(ctx: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        id,
+        key,
+        props,
+        state,
+        ..
+    } = ctx;
+    let tab_bar_props = props.read_cloned_or_default::<RetroTabBarProps>();
+    let RetroTabBarState { selected } = state.read_cloned_or_default();
+    let initial_focus = props.has::<RetroInitialFocus>();
+
+    let mut tab_bar = make_widget!(horizontal_box)
+        .key(key)
+        .merge_props(props.clone());
+    for (i, text) in tab_bar_props.tabs.into_iter().enumerate() {
+        let mut tab = inner_button(make_widget!(retro_button), format!("tab-{}", i), id, props)
+            .with_props(RetroButtonProps {
+                text,
+                down: i == selected,
+            });
+        if initial_focus && i == selected {
+            tab = tab.with_props(RetroInitialFocus);
+        }
+        tab_bar = tab_bar.listed_slot(tab);
+    }
+
+    tab_bar.into()
+}
+
+/// How a [`retro_progress_bar`] is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RetroProgressBarStyle {
+    /// A single bar that fills up smoothly
+    Smooth,
+    /// A row of separate segments that are each either full or empty, like a row of hearts
+    ///
+    /// A segment is drawn full as soon as any of it is filled, so that a bar of health only looks
+    /// empty once the health is gone.
+    Segmented {
+        /// The number of segments
+        segments: u32,
+        /// The space between the segments
+        gap: Scalar,
+    },
+    /// A bar that fills up smoothly, with pixel notches that split it into equal parts
+    Notched {
+        /// The number of parts that the notches split the bar into
+        parts: u32,
+    },
+}
+
+impl Default for RetroProgressBarStyle {
+    fn default() -> Self {
+        RetroProgressBarStyle::Smooth
+    }
+}
+
+/// The props of a [`retro_progress_bar`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroProgressBarProps {
+    /// How full the bar is, from `0.0` to `1.0`
+    pub value: Scalar,
+    /// The name of a bound value that the bar shows instead of its `value`, if any
+    ///
+    /// Values are bound with
+    /// [`add_ui_resource_value`][crate::AppBuilderUiBindingExt::add_ui_resource_value] and
+    /// [`add_ui_component_value`][crate::AppBuilderUiBindingExt::add_ui_component_value], and
+    /// should be from `0.0` to `1.0`.
+    #[serde(default)]
+    pub binding: Option<String>,
+    /// The entity that a bound component value is read from, from
+    /// [`Entity::to_bits`][bevy::prelude::Entity::to_bits], or [`None`] to read it from the first
+    /// entity with the component
+    #[serde(default)]
+    pub entity: Option<u64>,
+    /// How the bar is drawn
+    #[serde(default)]
+    pub style: RetroProgressBarStyle,
+    /// The color of the empty part of the bar, or [`None`] to use the theme track color
+    pub track_color: Option<Color>,
+    /// The color of the filled part of the bar, or [`None`] to use the theme fill color
+    pub fill_color: Option<Color>,
+}
+
+/// A bar that fills up from left to right, for health bars, loading screens, and the like
+///
+/// In the [`Retained`][crate::UiProcessMode::Retained] process mode, a bar with a binding only
+/// changes when the UI is invalidated.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*, *};
+/// struct Health {
+///     current: u32,
+///     max: u32,
+/// }
+///
+/// fn health_bar() -> WidgetNode {
+///     make_widget!(retro_progress_bar)
+///         .with_props(RetroProgressBarProps {
+///             binding: Some("player-health".into()),
+///             style: RetroProgressBarStyle::Segmented {
+///                 segments: 10,
+///                 gap: 1.,
+///             },
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+///
+/// fn main() {
+///     App::build().add_ui_component_value("player-health", |health: &Health| {
+///         health.current as f32 / health.max as f32
+///     });
+/// }
+/// ```
+pub fn retro_progress_bar(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let bar_props = ctx.props.read_cloned_or_default::<RetroProgressBarProps>();
+    let value = bar_props
+        .binding
+        .as_deref()
+        .and_then(|binding| {
+            ui_bound_value(
+                &mut ctx,
+                binding,
+                bar_props.entity.map(bevy::prelude::Entity::from_bits),
+            )
+        })
+        .unwrap_or(bar_props.value)
+        .max(0.)
+        .min(1.);
+    let track = bar_props
+        .track_color
+        .unwrap_or_else(|| raui_color(theme.palette.track));
+    let fill = bar_props
+        .fill_color
+        .unwrap_or_else(|| raui_color(theme.palette.fill));
+    let inset = theme.padding(1.).max(1.);
+    let WidgetContext { key, props, .. } = ctx;
+
+    let bar = match bar_props.style {
+        RetroProgressBarStyle::Smooth => fill_bar(track, fill, value, inset),
+        RetroProgressBarStyle::Segmented { segments, gap } => {
+            let segments = segments.max(1);
+            // Leave out a little of the value so that rounding errors don't fill another segment
+            let filled = (value * segments as Scalar - 0.001).ceil() as u32;
+            let mut bar = make_widget!(content_box);
+
+            for i in 0..segments {
+                bar = bar.listed_slot(
+                    fill_bar(track, fill, if i < filled { 1. } else { 0. }, inset)
+                        .key(format!("segment-{}", i))
+                        .with_props(ContentBoxItemLayout {
+                            anchors: Rect {
+                                left: i as Scalar / segments as Scalar,
+                                right: (i + 1) as Scalar / segments as Scalar,
+                                top: 0.,
+                                bottom: 1.,
+                            },
+                            margin: Rect {
+                                left: if i == 0 { 0. } else { gap / 2. },
+                                right: if i + 1 == segments { 0. } else { gap / 2. },
+                                top: 0.,
+                                bottom: 0.,
+                            },
+                            ..Default::default()
+                        }),
+                );
+            }
+
+            bar
+        }
+        RetroProgressBarStyle::Notched { parts } => {
+            let mut bar = fill_bar(track, fill, value, inset);
+
+            // Draw a pixel wide notch of the track color between each of the parts
+            for i in 1..parts {
+                let position = i as Scalar / parts as Scalar;
+                bar = bar.listed_slot(
+                    theme_image_box(&None, track)
+                        .key(format!("notch-{}", i))
+                        .with_props(ContentBoxItemLayout {
+                            anchors: Rect {
+                                left: position,
+                                right: position,
+                                top: 0.,
+                                bottom: 1.,
+                            },
+                            margin: Rect {
+                                left: 0.,
+                                right: -1.,
+                                top: inset,
+                                bottom: inset,
+                            },
+                            ..Default::default()
+                        }),
+                );
+            }
+
+            bar
+        }
+    };
+
+    bar.key(key).merge_props(props.clone()).into()
+}
+
+/// The props of a [`retro_virtual_keyboard`] widget
+#[derive(PropsData, Debug, Clone, Serialize, Deserialize)]
+pub struct RetroVirtualKeyboardProps {
+    /// The text input that the keyboard types into
+    #[serde(default)]
+    #[serde(skip_serializing_if = "WidgetIdOrRef::is_none")]
+    pub target: WidgetIdOrRef,
+    /// The rows of character keys
+    pub rows: Vec<String>,
+    /// The label of the space key
+    pub space_label: String,
+    /// The label of the key that deletes the character before the cursor
+    pub delete_label: String,
+    /// The label of the key that submits the text input
+    pub done_label: String,
+}
+
+impl Default for RetroVirtualKeyboardProps {
+    fn default() -> Self {
+        Self {
+            target: Default::default(),
+            rows: vec![
+                "1234567890".into(),
+                "QWERTYUIOP".into(),
+                "ASDFGHJKL-".into(),
+                "ZXCVBNM.,!".into(),
+            ],
+            space_label: "SPACE".into(),
+            delete_label: "DEL".into(),
+            done_label: "OK".into(),
+        }
+    }
+}
+
+/// Get the character of a [`retro_virtual_keyboard`] key from its name
+fn character_key(rows: &[String], key: &str) -> Option<char> {
+    let mut position = key.strip_prefix("char-")?.split('-');
+    let row = position.next()?.parse::<usize>().ok()?;
+    let column = position.next()?.parse::<usize>().ok()?;
+
+    rows.get(row)?.chars().nth(column)
+}
+
+/// Hook that sends the keys pressed on a [`retro_virtual_keyboard`] to its target text input
+fn use_retro_virtual_keyboard(ctx: &mut WidgetContext) {
+    ctx.life_cycle.change(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<RetroVirtualKeyboardProps>();
+        let target = match props.target.read() {
+            Some(target) => target,
+            None => return,
+        };
+
+        for msg in context.messenger.messages {
+            let msg = match msg.as_any().downcast_ref::<ButtonNotifyMessage>() {
+                Some(msg) if msg.trigger_stop() => msg,
+                _ => continue,
+            };
+
+            // The keys are named after what they do, or after where the character keys are
+            let change = match msg.sender.key() {
+                "space" => NavTextChange::InsertCharacter(' '),
+                "delete" => NavTextChange::DeleteLeft,
+                "done" => NavTextChange::NewLine,
+                key => match character_key(&props.rows, key) {
+                    Some(c) => NavTextChange::InsertCharacter(c),
+                    None => continue,
+                },
+            };
+            context
+                .messenger
+                .write(target.clone(), RetroTextInputEdit(change));
+        }
+    });
+}
+
+/// An on-screen keyboard that can be typed on with a gamepad, touch screen, or mouse
+///
+/// Each key is a [`retro_focus_frame`] that types into the text input in the `target` of the
+/// [`RetroVirtualKeyboardProps`], so that players can enter text on platforms without a physical
+/// keyboard. The text input doesn't need to be focused. Add [`RetroInitialFocus`] to the keyboard
+/// to select its first key as soon as it is created.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn name_entry(ctx: WidgetContext) -> WidgetNode {
+///     let input = WidgetRef::default();
+///
+///     make_widget!(vertical_box)
+///         .listed_slot(
+///             make_widget!(retro_text_input)
+///                 .key("name")
+///                 .idref(input.clone())
+///                 .with_props(NavItemActive),
+///         )
+///         .listed_slot(
+///             make_widget!(retro_virtual_keyboard)
+///                 .with_props(RetroInitialFocus)
+///                 .with_props(RetroVirtualKeyboardProps {
+///                     target: input.into(),
+///                     ..Default::default()
+///                 }),
+///         )
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_virtual_keyboard)]
+pub fn retro_virtual_keyboard(mut ctx: WidgetContext) -> WidgetNode {
+    let theme = get_theme(&mut ctx);
+    let WidgetContext { id, key, props, .. } = ctx;
+    let keyboard_props = props.read_cloned_or_default::<RetroVirtualKeyboardProps>();
+    let initial_focus = props.has::<RetroInitialFocus>();
+
+    let key_widget = |name: String, label: String, focused: bool| {
+        let mut key = make_widget!(retro_focus_frame)
+            .key(name)
+            .with_props(NavItemActive)
+            .with_props(ButtonNotifyProps(id.to_owned().into()))
+            .named_slot(
+                "content",
+                make_widget!(text_box).with_props(TextBoxProps {
+                    text: label,
+                    font: theme.text_box_font(),
+                    color: raui_color(theme.palette.text),
+                    horizontal_align: TextBoxHorizontalAlign::Center,
+                    vertical_align: TextBoxVerticalAlign::Middle,
+                    ..Default::default()
+                }),
+            );
+        if focused {
+            key = key.with_props(RetroInitialFocus);
+        }
+        WidgetNode::from(key)
+    };
+
+    let mut keyboard = make_widget!(vertical_box)
+        .key(key)
+        .merge_props(props.clone());
+    for (i, row) in keyboard_props.rows.iter().enumerate() {
+        let mut row_widget = make_widget!(horizontal_box).key(format!("row-{}", i));
+        for (j, c) in row.chars().enumerate() {
+            row_widget = row_widget.listed_slot(key_widget(
+                format!("char-{}-{}", i, j),
+                c.to_string(),
+                initial_focus && i == 0 && j == 0,
+            ));
+        }
+        keyboard = keyboard.listed_slot(row_widget);
+    }
+    keyboard = keyboard.listed_slot(
+        make_widget!(horizontal_box)
+            .key("row-actions")
+            .listed_slot(key_widget(
+                "space".into(),
+                keyboard_props.space_label,
+                false,
+            ))
+            .listed_slot(key_widget(
+                "delete".into(),
+                keyboard_props.delete_label,
+                false,
+            ))
+            .listed_slot(key_widget("done".into(), keyboard_props.done_label, false)),
+    );
+
+    keyboard.into()
+}
+
+/// A button that can be dragged with the mouse and dropped on a [`retro_drop_target`]
+///
+/// The `content` slot follows the mouse while it is dragged. Set the data that is dropped with the
+/// [`RetroDraggableProps`][crate::RetroDraggableProps]. Like other buttons, it must have the
+/// `NavItemActive` props to be pressed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*, RetroDraggableProps};
+/// fn inventory_item(item_id: &str) -> WidgetNode {
+///     make_widget!(retro_draggable)
+///         .with_props(NavItemActive)
+///         .with_props(RetroDraggableProps {
+///             payload: item_id.into(),
+///         })
+///         .named_slot(
+///             "content",
+///             make_widget!(image_box).with_props(ImageBoxProps {
+///                 material: ImageBoxMaterial::Image(ImageBoxImage {
+///                     id: "items/sword.png".into(),
+///                     ..Default::default()
+///                 }),
+///                 ..Default::default()
+///             }),
+///         )
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_button, use_retro_draggable, use_ui_sounds)]
+pub fn retro_draggable(ctx: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        key,
+        props,
+        state,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    let RetroDraggableState { offset, .. } = state.read_cloned_or_default();
+
+    make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(content.with_props(ContentBoxItemLayout {
+            // Move the content with the mouse
+            margin: Rect {
+                left: offset.x,
+                right: -offset.x,
+                top: offset.y,
+                bottom: -offset.y,
+            },
+            ..Default::default()
+        }))
+        .into()
+}
+
+/// A button that [`retro_draggable`] widgets can be dropped on
+///
+/// It sends a [`UiDropped`][crate::UiDropped] Bevy event when a widget is dropped on it. Otherwise
+/// it is a [`retro_focus_frame`], so the frame shows players where they are about to drop, and like
+/// other buttons it must have the `NavItemActive` props for widgets to be dropped on it.
+#[pre_hooks(use_retro_drop_target)]
+pub fn retro_drop_target(ctx: WidgetContext) -> WidgetNode {
+    retro_focus_frame(ctx)
+}
+
+/// The props of a [`retro_opacity`] widget
+#[derive(PropsData, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroOpacityProps {
+    /// The opacity of the content, from `0.0` for transparent to `1.0` for opaque
+    pub opacity: Scalar,
+}
+
+impl Default for RetroOpacityProps {
+    fn default() -> Self {
+        Self { opacity: 1. }
+    }
+}
+
+/// A widget that makes its `content` slot, and everything in it, partly transparent
+///
+/// The opacity multiplies the alpha of the colors of all of the images and text in the content, so
+/// that a whole menu or HUD can be faded in and out as a unit. Opacity widgets inside of each other
+/// multiply their opacities.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn faded_hud(hud: WidgetNode) -> WidgetNode {
+///     make_widget!(retro_opacity)
+///         .with_props(RetroOpacityProps { opacity: 0.5 })
+///         .named_slot("content", hud)
+///         .into()
+/// }
+/// ```
+pub fn retro_opacity(mut ctx: WidgetContext) -> WidgetNode {
+    let RetroOpacityProps { opacity } = ctx.props.read_cloned_or_default();
+
+    // The render hook fades the subtree of this widget when the UI is drawn
+    if opacity < 1. {
+        if let Some(opacities) = ctx.process_context.get_mut::<UiOpacities>() {
+            opacities.insert(ctx.id.to_owned(), opacity.max(0.));
+        }
+    }
+
+    let WidgetContext {
+        key,
+        props,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(content)
+        .into()
+}
+
+/// How a [`retro_transition`] moves its content in or out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RetroTransitionKind {
+    /// Slide in from, or out to, this offset in UI units
+    Slide(Vec2),
+    /// Grow from, or shrink to, nothing
+    Pop,
+    /// Fade in from, or out to, transparent
+    Fade,
+    /// Appear or disappear without animating
+    Cut,
+}
+
+impl Default for RetroTransitionKind {
+    fn default() -> Self {
+        RetroTransitionKind::Slide(Vec2 { x: 0., y: 16. })
+    }
+}
+
+/// The props of a [`retro_transition`] widget
+#[derive(PropsData, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetroTransitionProps {
+    /// Whether or not the content is shown
+    pub visible: bool,
+    /// How the content comes in when it is shown
+    pub enter: RetroTransitionKind,
+    /// How the content goes out when it is hidden
+    pub exit: RetroTransitionKind,
+    /// How long the transition takes in seconds
+    pub duration: Scalar,
+    /// The easing curve of the transition
+    pub easing: Easing,
+}
+
+impl Default for RetroTransitionProps {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            enter: Default::default(),
+            exit: Default::default(),
+            duration: 0.25,
+            easing: Default::default(),
+        }
+    }
+}
+
+/// Whether or not a [`retro_transition`] was visible as of its last change
+#[derive(PropsData, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+struct RetroTransitionState {
+    visible: bool,
+}
+
+/// The name of the animation of a [`retro_transition`]
+const TRANSITION_ANIMATION: &str = "transition";
+
+/// Hook that starts the transition of a [`retro_transition`] when it is created and when its
+/// visibility changes
+fn use_retro_transition(ctx: &mut WidgetContext) {
+    ctx.life_cycle.mount(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<RetroTransitionProps>();
+        if props.visible {
+            start_ui_animation(&context.animator, TRANSITION_ANIMATION, props.duration);
+        }
+        let _ = context.state.write(RetroTransitionState {
+            visible: props.visible,
+        });
+    });
+
+    ctx.life_cycle.change(|context| {
+        let props = context
+            .props
+            .read_cloned_or_default::<RetroTransitionProps>();
+        let state = context
+            .state
+            .read_cloned_or_default::<RetroTransitionState>();
+        if props.visible != state.visible {
+            start_ui_animation(&context.animator, TRANSITION_ANIMATION, props.duration);
+            let _ = context.state.write(RetroTransitionState {
+                visible: props.visible,
+            });
+        }
+    });
+}
+
+/// A widget that animates its `content` slot in when it is created or shown, and out when it is
+/// hidden, for panels and menus
+///
+/// Set `visible` in the [`RetroTransitionProps`] to `false` to play the exit transition, instead of
+/// removing the widget from the tree, which would remove the content right away.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*, Easing};
+/// fn pause_menu(open: bool, menu: WidgetNode) -> WidgetNode {
+///     make_widget!(retro_transition)
+///         .with_props(RetroTransitionProps {
+///             visible: open,
+///             enter: RetroTransitionKind::Pop,
+///             exit: RetroTransitionKind::Slide(Vec2 { x: 0., y: -32. }),
+///             easing: Easing::BackOut,
+///             ..Default::default()
+///         })
+///         .named_slot("content", menu)
+///         .into()
+/// }
+/// ```
+#[pre_hooks(use_retro_transition)]
+pub fn retro_transition(ctx: WidgetContext) -> WidgetNode {
+    let props = ctx.props.read_cloned_or_default::<RetroTransitionProps>();
+    let progress = ui_animation_progress(&ctx, TRANSITION_ANIMATION, props.easing);
+
+    // How much of the content is shown, from `0.0` to `1.0`. The state is written once the widget
+    // is mounted, so until then the enter transition hasn't started.
+    let (visible, shown) = match ctx.state.read_cloned::<RetroTransitionState>() {
+        Ok(RetroTransitionState { visible }) => {
+            (visible, if visible { progress } else { 1. - progress })
+        }
+        Err(_) => (props.visible, 0.),
+    };
+    let kind = if visible { props.enter } else { props.exit };
+
+    let WidgetContext {
+        key,
+        props,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    let mut widget = make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone());
+    let transform = match kind {
+        RetroTransitionKind::Slide(offset) => Transform {
+            translation: lerp_vec2(offset, Vec2::default(), shown),
+            ..Default::default()
+        },
+        RetroTransitionKind::Pop => Transform {
+            pivot: Vec2 { x: 0.5, y: 0.5 },
+            scale: Vec2 { x: shown, y: shown },
+            ..Default::default()
+        },
+        RetroTransitionKind::Fade | RetroTransitionKind::Cut => Default::default(),
+    };
+    let opacity = match kind {
+        RetroTransitionKind::Fade => shown,
+        _ => 1.,
+    };
+    let hidden = match kind {
+        RetroTransitionKind::Cut => !visible,
+        _ => shown <= 0.,
+    };
+
+    if !hidden {
+        widget = widget
+            .with_props(ContentBoxProps {
+                transform,
+                ..Default::default()
+            })
+            .listed_slot(
+                make_widget!(retro_opacity)
+                    .key("opacity")
+                    .with_props(RetroOpacityProps { opacity })
+                    .named_slot("content", content),
+            );
+    }
+
+    widget.into()
+}
+
+/// How a [`retro_texture`] is scaled to fit its widget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetroTextureScaling {
+    /// Stretch the texture to the size of the widget
+    Stretch,
+    /// Scale the texture as large as it fits in the widget, keeping its aspect ratio
+    Fit,
+}
+
+impl Default for RetroTextureScaling {
+    fn default() -> Self {
+        RetroTextureScaling::Fit
+    }
+}
+
+/// The props of a [`retro_texture`] widget
+#[derive(PropsData, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetroTextureProps {
+    /// The ID of the texture, from [`UiHandleExt::ui_id`][crate::UiHandleExt::ui_id]
+    pub texture: String,
+    /// How the texture is scaled to fit the widget
+    pub scaling: RetroTextureScaling,
+}
+
+/// A widget that shows a texture that changes while the game runs, such as a minimap, a character
+/// preview, or a picture-in-picture view
+///
+/// The texture can be an [`Image`][bevy_retrograde_core::prelude::Image] asset that is drawn to
+/// every frame, which is uploaded to the GPU again whenever it changes, or a texture that a render
+/// hook renders to itself, using a handle from
+/// [`new_ui_texture_handle`][crate::new_ui_texture_handle].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_core::prelude::Image;
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*, UiHandleExt};
+/// fn minimap(minimap_image: &Handle<Image>) -> WidgetNode {
+///     make_widget!(retro_texture)
+///         .with_props(RetroTextureProps {
+///             texture: minimap_image.ui_id(),
+///             ..Default::default()
+///         })
+///         .into()
+/// }
+/// ```
+pub fn retro_texture(ctx: WidgetContext) -> WidgetNode {
+    let WidgetContext { key, props, .. } = ctx;
+    let texture_props = props.read_cloned_or_default::<RetroTextureProps>();
+
+    make_widget!(image_box)
+        .key(key)
+        .merge_props(props.clone())
+        .with_props(ImageBoxProps {
+            material: ImageBoxMaterial::Image(ImageBoxImage {
+                id: texture_props.texture,
+                ..Default::default()
+            }),
+            content_keep_aspect_ratio: match texture_props.scaling {
+                RetroTextureScaling::Stretch => None,
+                RetroTextureScaling::Fit => Some(ImageBoxAspectRatio {
+                    horizontal_alignment: 0.5,
+                    vertical_alignment: 0.5,
+                }),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// The props of a [`retro_modal`] widget
+#[derive(PropsData, Debug, Clone, Serialize, Deserialize)]
+pub struct RetroModalProps {
+    /// The color drawn over the UI behind the modal
+    pub dim_color: Color,
+    /// Where the content of the modal is placed, which is the only area that the mouse can
+    /// interact with while the modal is open
+    pub layout: ContentBoxItemLayout,
+}
+
+impl Default for RetroModalProps {
+    fn default() -> Self {
+        Self {
+            dim_color: Color {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 0.5,
+            },
+            layout: Default::default(),
+        }
+    }
+}
+
+/// The navigation container around the content of a [`retro_modal`]
+#[pre_hooks(use_nav_container_active, use_retro_modal)]
+fn retro_modal_panel(ctx: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        key, named_slots, ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+
+    make_widget!(content_box)
+        .key(key)
+        .listed_slot(content)
+        .into()
+}
+
+/// A widget that covers the UI in a dim color and shows its `content` slot on top, such as a
+/// dialog that has to be answered before the game goes on
+///
+/// While the modal exists, keyboard and gamepad navigation stays inside of its content, and the
+/// mouse can only interact with the area of the content. The modal is closed by removing it from
+/// the UI, such as when a button in the content is clicked, and the button that was selected
+/// before it was opened is then selected again. Give a button in the content the
+/// [`RetroInitialFocus`] props to select it when the modal opens.
+///
+/// The modal should be the last widget in a content box that covers the screen, so that it is
+/// drawn over everything else.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy_retrograde_ui::{raui::prelude::*, widgets::*};
+/// fn quit_dialog(dialog: WidgetNode) -> WidgetNode {
+///     make_widget!(retro_modal)
+///         .key("quit-dialog")
+///         .with_props(RetroModalProps {
+///             layout: ContentBoxItemLayout {
+///                 anchors: Rect {
+///                     left: 0.5,
+///                     right: 0.5,
+///                     top: 0.5,
+///                     bottom: 0.5,
+///                 },
+///                 margin: Rect {
+///                     left: -60.,
+///                     right: -60.,
+///                     top: -30.,
+///                     bottom: -30.,
+///                 },
+///                 ..Default::default()
+///             },
+///             ..Default::default()
+///         })
+///         .named_slot("content", dialog)
+///         .into()
+/// }
+/// ```
+pub fn retro_modal(ctx: WidgetContext) -> WidgetNode {
+    let WidgetContext {
+        key,
+        props,
+        named_slots,
+        ..
+    } = ctx;
+    unpack_named_slots!(named_slots => content);
+    let modal_props = props.read_cloned_or_default::<RetroModalProps>();
+
+    make_widget!(content_box)
+        .key(key)
+        .merge_props(props.clone())
+        .listed_slot(
+            make_widget!(image_box)
+                .key("backdrop")
+                .with_props(ImageBoxProps {
+                    material: ImageBoxMaterial::Color(ImageBoxColor {
+                        color: modal_props.dim_color,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+        )
+        .listed_slot(
+            make_widget!(retro_modal_panel)
+                .key("panel")
+                .with_props(modal_props.layout)
+                .named_slot("content", content),
+        )
+        .into()
+}
@@ -30,5 +30,5 @@ fn setup(asset_server: Res<AssetServer>, mut sound_controller: SoundController)
     sound_controller.play_sound_with_settings(
         music,
         PlaySoundSettings::new().loop_start(LoopStart::Custom(0.)),
-    )
+    );
 }
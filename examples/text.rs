@@ -36,12 +36,10 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Bevy Retrograde reads the BDF font format
     let font = asset_server.load("cozette.bdf");
 
-    // Spawn a single line of text
+    // Spawn a single line of text. Markup can be used to change the color of parts of the text and
+    // to animate them with effects.
     commands.spawn().insert_bundle(TextBundle {
-        text: Text {
-            text: "The Beginning".into(),
-            ..Default::default()
-        },
+        text: Text::from_markup("The [color=#4488ff][wave]Beginning[/wave][/color]").unwrap(),
         font: font.clone(),
         transform: Transform::from_xyz(0., -110., 0.),
         ..Default::default()
@@ -57,6 +55,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             text: Text {
                 text: long_text.into(),
                 color: Color::new(1., 0., 0., 1.),
+                ..Default::default()
             },
             font: font.clone(),
             ..Default::default()
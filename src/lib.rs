@@ -59,10 +59,31 @@
 //! - Sprites are pixel-perfectly aligned by default but can be set to non-perfect on a per-sprite
 //!   basis
 //! - [LDtk](https://ldtk.io) map loading and rendering
+//! - [Tiled](https://www.mapeditor.org/) map loading and rendering
 //! - An integration with the [RAUI] UI library for building in-game user interfaces and HUD
 //! - Physics and collision detection powered by [Heron] and [Rapier] with automatic generation of
 //!   convex collision shapes from sprite images
-//! - Text rendering of BDF fonts
+//! - Text rendering of BDF, TTF/OTF, and BMFont fonts
+//! - Audio playback of OGG, FLAC, WAV, and MP3 files, with each format enabled by its own
+//!   `audio-*` feature
+//! - Named input actions and axes bound to keyboard, mouse, gamepad, and touch inputs, and mouse
+//!   picking of sprites
+//! - Saving and loading game data on desktop and web
+//! - Localization of text and UI from per-language translation files, enabled with the
+//!   `localization` feature
+//! - Game states with per-state asset loading and a loading screen, and asset preloading with
+//!   progress tracking, enabled with the `state` feature
+//! - Sprite sheet animation state machines, enabled with the `animation` feature
+//! - Tweening of sprite positions, cameras, and audio volumes with easing functions, and entities
+//!   that follow paths of waypoints, enabled with the `tween` feature
+//! - Timelines for simple cutscenes that move entities, play animations and sounds, show dialogs,
+//!   and fade the screen, enabled with the `timeline` feature
+//! - WebSocket networking on desktop and web, and UDP on desktop, for features like online
+//!   leaderboards, enabled with the `network` feature
+//! - An in-game diagnostics overlay and renderer timings reported through Bevy's diagnostics,
+//!   enabled with the `diagnostics` feature
+//! - An [egui] overlay for dev tools, with a built-in entity inspector, enabled with the `egui`
+//!   feature
 //! - Custom shaders for post-processing, including a built-in CRT shader
 //! - Render hooks allowing you to drop down into raw [Luminance] calls for custom rendering
 //!
@@ -73,6 +94,8 @@
 //!
 //! [RAUI]: https://raui-labs.github.io/raui/
 //!
+//! [egui]: https://github.com/emilk/egui
+//!
 //! [Heron]: https://github.com/jcornaz/heron
 //!
 //! [Rapier]: https://rapier.rs/
@@ -207,15 +230,51 @@ impl bevy::app::PluginGroup for RetroPlugins {
         #[cfg(feature = "audio")]
         group.add(audio::RetroAudioPlugin);
 
+        #[cfg(feature = "input")]
+        group.add(input::RetroInputPlugin);
+
+        #[cfg(feature = "save")]
+        group.add(save::RetroSavePlugin);
+
+        #[cfg(feature = "localization")]
+        group.add(localization::RetroLocalizationPlugin);
+
+        #[cfg(feature = "state")]
+        group.add(state::RetroStatePlugin);
+
+        #[cfg(feature = "animation")]
+        group.add(animation::RetroAnimationPlugin);
+
+        #[cfg(feature = "tween")]
+        group.add(tween::RetroTweenPlugin);
+
+        #[cfg(feature = "timeline")]
+        group.add(timeline::RetroTimelinePlugin);
+
+        #[cfg(feature = "network")]
+        group.add(net::RetroNetPlugin);
+
         #[cfg(feature = "ldtk")]
         group.add(ldtk::LdtkPlugin);
 
+        #[cfg(feature = "tiled")]
+        group.add(tiled::TiledPlugin);
+
         #[cfg(feature = "epaint")]
         group.add(epaint::RetroEpaintPlugin);
 
+        #[cfg(feature = "egui")]
+        group.add(epaint::RetroEguiPlugin);
+
         #[cfg(feature = "text")]
         group.add(text::RetroTextPlugin);
 
+        #[cfg(feature = "diagnostics")]
+        group.add(core::diagnostics::RetroRenderDiagnosticsPlugin);
+
+        #[cfg(feature = "diagnostics")]
+        group.add(text::diagnostics::RetroDiagnosticsOverlayPlugin);
+
         #[cfg(feature = "physics")]
         group.add(physics::RetroPhysicsPlugin);
 
@@ -234,12 +293,45 @@ pub mod prelude {
     #[cfg(feature = "audio")]
     pub use bevy_retrograde_audio::*;
 
+    #[cfg(feature = "input")]
+    pub use bevy_retrograde_input::*;
+
+    #[cfg(feature = "save")]
+    pub use bevy_retrograde_save::*;
+
+    #[cfg(feature = "localization")]
+    pub use bevy_retrograde_localization::*;
+
+    #[cfg(feature = "state")]
+    pub use bevy_retrograde_state::*;
+
+    #[cfg(feature = "animation")]
+    pub use bevy_retrograde_animation::*;
+
+    #[cfg(feature = "tween")]
+    pub use bevy_retrograde_tween::*;
+
+    #[cfg(feature = "timeline")]
+    pub use bevy_retrograde_timeline::*;
+
+    #[cfg(feature = "network")]
+    pub use bevy_retrograde_net::*;
+
     #[cfg(feature = "text")]
     pub use bevy_retrograde_text::prelude::*;
 
+    #[cfg(feature = "diagnostics")]
+    pub use bevy_retrograde_core::diagnostics::*;
+
+    #[cfg(feature = "diagnostics")]
+    pub use bevy_retrograde_text::diagnostics::*;
+
     #[cfg(feature = "ldtk")]
     pub use bevy_retrograde_ldtk::*;
 
+    #[cfg(feature = "tiled")]
+    pub use bevy_retrograde_tiled::*;
+
     #[cfg(feature = "ui")]
     pub use bevy_retrograde_ui::*;
 
@@ -262,6 +354,38 @@ pub use bevy_retrograde_macros::impl_deref;
 #[doc(inline)]
 pub use bevy_retrograde_audio as audio;
 
+#[cfg(feature = "input")]
+#[doc(inline)]
+pub use bevy_retrograde_input as input;
+
+#[cfg(feature = "save")]
+#[doc(inline)]
+pub use bevy_retrograde_save as save;
+
+#[cfg(feature = "localization")]
+#[doc(inline)]
+pub use bevy_retrograde_localization as localization;
+
+#[cfg(feature = "state")]
+#[doc(inline)]
+pub use bevy_retrograde_state as state;
+
+#[cfg(feature = "animation")]
+#[doc(inline)]
+pub use bevy_retrograde_animation as animation;
+
+#[cfg(feature = "tween")]
+#[doc(inline)]
+pub use bevy_retrograde_tween as tween;
+
+#[cfg(feature = "timeline")]
+#[doc(inline)]
+pub use bevy_retrograde_timeline as timeline;
+
+#[cfg(feature = "network")]
+#[doc(inline)]
+pub use bevy_retrograde_net as net;
+
 #[cfg(feature = "text")]
 #[doc(inline)]
 pub use bevy_retrograde_text as text;
@@ -273,6 +397,9 @@ pub use bevy_retrograde_physics as physics;
 #[cfg(feature = "ldtk")]
 pub use bevy_retrograde_ldtk as ldtk;
 
+#[cfg(feature = "tiled")]
+pub use bevy_retrograde_tiled as tiled;
+
 #[cfg(feature = "ui")]
 #[doc(inline)]
 pub use bevy_retrograde_ui as ui;